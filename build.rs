@@ -0,0 +1,44 @@
+//! Embed the locked rquickjs version for `get_converter_info`, and regenerate the C
+//! header of the `capi` feature
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let version = lock
+        .split("[[package]]")
+        .find_map(|package| {
+            let mut fields = package.lines().map(str::trim);
+            fields.find(|line| *line == "name = \"rquickjs\"")?;
+            fields
+                .find_map(|line| line.strip_prefix("version = "))
+                .map(|version| version.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=RQUICKJS_VERSION={}", version);
+
+    if std::env::var_os("CARGO_FEATURE_CAPI").is_some() {
+        generate_c_header();
+    }
+}
+
+/// Rewrite `include/tex2typst.h` from `src/capi.rs` with the cbindgen CLI.
+///
+/// The header is checked in, so a machine without cbindgen builds against the
+/// committed copy.
+fn generate_c_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let status = std::process::Command::new("cbindgen")
+        .args(["--config", "cbindgen.toml", "--crate", "tex2typst"])
+        .args(["--output", "include/tex2typst.h"])
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => println!(
+            "cargo:warning=cbindgen failed ({}); include/tex2typst.h left as is",
+            status
+        ),
+        // Not installed
+        Err(_) => {}
+    }
+}
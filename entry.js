@@ -1,5 +1,13 @@
-import { tex2typst, typst2tex } from "tex2typst";
+import { tex2typst, typst2tex, parseTex, parseTypst } from "tex2typst";
+import { version } from "tex2typst/package.json";
 
 // Export both conversion functions to the global scope
 globalThis.tex2typst = tex2typst;
 globalThis.typst2tex = typst2tex;
+
+// Export the parsers so the intermediate AST can be inspected
+globalThis.parseTex = parseTex;
+globalThis.parseTypst = parseTypst;
+
+// Version of the upstream library, reported by `get_js_bundle_version()`
+globalThis.tex2typstVersion = version;
@@ -0,0 +1,100 @@
+/* tex2typst C API (`capi` cargo feature).
+ *
+ * All strings are NUL-terminated UTF-8. Strings returned through `out` and `err`
+ * belong to the caller and are released with t2t_free_string; the string from
+ * t2t_version is static. Each calling thread gets its own converter engine. */
+
+#ifndef TEX2TYPST_H
+#define TEX2TYPST_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The conversion succeeded and `*out` holds the result
+ */
+#define T2T_OK 0
+
+/**
+ * `tex` or `out` is NULL, or a string argument is not valid UTF-8
+ */
+#define T2T_ERR_ARGUMENT 1
+
+/**
+ * `options_json` is not a JSON object of known options
+ */
+#define T2T_ERR_OPTIONS 2
+
+/**
+ * The converter rejected the input
+ */
+#define T2T_ERR_CONVERSION 3
+
+/**
+ * The JS engine could not be created or failed internally
+ */
+#define T2T_ERR_ENGINE 4
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Convert the LaTeX/TeX math `tex` to Typst.
+ *
+ * `options_json` is NULL or a JSON object of `tex2typst` options with their
+ * Python names, e.g. `{"frac_to_slash": false}`. Returns `T2T_OK` and stores
+ * the result in `*out`, or returns one of the `T2T_ERR_*` codes and stores the
+ * message in `*err`. Whichever string is set is owned by the caller and must
+ * be released with `t2t_free_string`; the other is set to NULL.
+ *
+ * # Safety
+ *
+ * `tex` and `options_json` must each be NULL or point to a NUL-terminated
+ * string. `out` must be NULL or valid for a pointer write, and so must `err`.
+ */
+int t2t_convert_tex(const char *tex, const char *options_json, char **out, char **err);
+
+/**
+ * Convert `tex` like `t2t_convert_tex`, but into the caller's buffer `out_buf` of
+ * `out_len` bytes, for hosts that cannot free memory allocated by the library.
+ *
+ * Returns the length of the result in bytes, without the terminating NUL, and
+ * copies as much of it as fits, always NUL-terminated when `out_len` is not 0. As
+ * with `snprintf`, a return value of `out_len` or more means the output was cut
+ * short; call again with a larger buffer, or with `out_buf` NULL and `out_len` 0
+ * to only measure it. On failure returns the negated `T2T_ERR_*` code, e.g.
+ * `-T2T_ERR_CONVERSION`; results longer than `INT_MAX` bytes give
+ * `-T2T_ERR_ARGUMENT`. Use `t2t_convert_tex` for the error message.
+ *
+ * # Safety
+ *
+ * `tex` and `options_json` must each be NULL or point to a NUL-terminated
+ * string. `out_buf` must be valid for writes of `out_len` bytes, or NULL with
+ * `out_len` 0.
+ */
+int t2t_convert_tex_into(const char *tex, const char *options_json, char *out_buf, size_t out_len);
+
+/**
+ * Release a string returned through `out` or `err`; NULL is ignored.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a string from `t2t_convert_tex` that was not freed yet.
+ */
+void t2t_free_string(char *value);
+
+/**
+ * The library version as a static string; do not free it
+ */
+const char *t2t_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TEX2TYPST_H */
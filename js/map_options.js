@@ -0,0 +1,181 @@
+// Support for the `symbolMap` and `environmentMap` options of `tex2typst`,
+// evaluated after the bundle.
+//
+// Mapped commands and environments are swapped for private-use placeholder
+// characters, which the converter passes through like a symbol, and the
+// placeholders in the output are then replaced by their Typst.
+(() => {
+  if (typeof tex2typst !== "function") {
+    return;
+  }
+  const base = tex2typst;
+  const hasOwn = Object.prototype.hasOwnProperty;
+
+  // Index of the `\end{name}` closing the environment whose body starts at
+  // `from`, or -1 if it is not closed
+  const environmentEnd = (tex, name, from) => {
+    const begin = "\\begin{" + name + "}";
+    const end = "\\end{" + name + "}";
+    let depth = 1;
+    let i = from;
+    for (;;) {
+      const nextEnd = tex.indexOf(end, i);
+      if (nextEnd < 0) {
+        return -1;
+      }
+      const nextBegin = tex.indexOf(begin, i);
+      if (nextBegin >= 0 && nextBegin < nextEnd) {
+        depth++;
+        i = nextBegin + begin.length;
+      } else if (--depth === 0) {
+        return nextEnd;
+      } else {
+        i = nextEnd + end.length;
+      }
+    }
+  };
+
+  // Split an environment body into rows (`\\`) of cells (`&`), ignoring those
+  // inside braces and nested environments
+  const splitCells = (body) => {
+    const rows = [[]];
+    let depth = 0;
+    let cell = 0;
+    let i = 0;
+    const endCell = (at) => {
+      rows[rows.length - 1].push(body.slice(cell, at));
+    };
+    while (i < body.length) {
+      const c = body[i];
+      if (c === "\\") {
+        const command = /^\\([a-zA-Z]+|.?)/.exec(body.slice(i))[0];
+        if (command === "\\\\" && depth === 0) {
+          endCell(i);
+          rows.push([]);
+          cell = i + 2;
+        } else if (command === "\\begin") {
+          depth++;
+        } else if (command === "\\end") {
+          depth--;
+        }
+        i += command.length;
+        continue;
+      }
+      if (c === "{") {
+        depth++;
+      } else if (c === "}") {
+        depth--;
+      } else if (c === "&" && depth === 0) {
+        endCell(i);
+        cell = i + 1;
+      }
+      i++;
+    }
+    endCell(body.length);
+    // A trailing `\\` does not start another row
+    const last = rows[rows.length - 1];
+    if (rows.length > 1 && last.length === 1 && last[0].trim() === "") {
+      rows.pop();
+    }
+    return rows;
+  };
+
+  globalThis.tex2typst = function (tex, options) {
+    const symbolMap = options && options.symbolMap;
+    const environmentMap = options && options.environmentMap;
+    if (!symbolMap && !environmentMap) {
+      return base(tex, options);
+    }
+    const rest = Object.assign({}, options);
+    delete rest.symbolMap;
+    delete rest.environmentMap;
+    tex = String(tex);
+
+    const typstByPlaceholder = new Map();
+    let next = 0xf8ff;
+    // `atomic` Typst, such as a function call, is never put in parentheses
+    const placeholder = (typst, atomic) => {
+      // Count down from the end of the private-use area, skipping characters
+      // the input already holds (the siunitx option uses its start)
+      while (next >= 0xe000 && tex.includes(String.fromCharCode(next))) {
+        next--;
+      }
+      if (next < 0xe000) {
+        throw new Error("too many mapped symbols and environments in one input");
+      }
+      const char = String.fromCharCode(next--);
+      typstByPlaceholder.set(char, { typst, atomic });
+      return char;
+    };
+
+    let input = tex;
+    if (environmentMap) {
+      // Bodies are converted on their own, with the same options, so mapped
+      // environments and symbols nested in them are handled too
+      const convertCell = (cell) =>
+        cell.trim() === "" ? "" : globalThis.tex2typst(cell, options);
+      const pattern = /\\begin\{([^{}]*)\}/g;
+      let result = "";
+      let copied = 0;
+      let match;
+      while ((match = pattern.exec(input)) !== null) {
+        const name = match[1];
+        if (!hasOwn.call(environmentMap, name)) {
+          continue;
+        }
+        const end = environmentEnd(input, name, pattern.lastIndex);
+        if (end < 0) {
+          // Left for the converter to report
+          continue;
+        }
+        const cells = splitCells(input.slice(pattern.lastIndex, end))
+          .map((row) => row.map(convertCell).join(", "))
+          .join("; ");
+        result += input.slice(copied, match.index);
+        result += placeholder(environmentMap[name] + "(" + cells + ")", true);
+        copied = end + ("\\end{" + name + "}").length;
+        pattern.lastIndex = copied;
+      }
+      input = result + input.slice(copied);
+    }
+    if (symbolMap) {
+      // Keys may be given with or without their backslash
+      const typstByName = new Map();
+      for (const key of Object.keys(symbolMap)) {
+        typstByName.set(key.startsWith("\\") ? key.slice(1) : key, symbolMap[key]);
+      }
+      const placeholders = new Map();
+      input = input.replace(/\\([a-zA-Z]+|.)/g, (match, name) => {
+        if (!typstByName.has(name)) {
+          return match;
+        }
+        if (!placeholders.has(name)) {
+          placeholders.set(name, placeholder(typstByName.get(name), false));
+        }
+        return placeholders.get(name);
+      });
+    }
+    if (typstByPlaceholder.size === 0) {
+      return base(tex, rest);
+    }
+
+    const output = base(input, rest);
+    let result = "";
+    for (let i = 0; i < output.length; i++) {
+      const replacement = typstByPlaceholder.get(output[i]);
+      if (replacement === undefined) {
+        result += output[i];
+        continue;
+      }
+      const { typst, atomic } = replacement;
+      // Group multi-term Typst that is an operand of `/`, `^` or `_`
+      const operand = (c) => c !== undefined && "/^_".includes(c);
+      const grouped =
+        !atomic &&
+        typst.includes(" ") &&
+        (operand(output[i - 1]) || operand(output[i + 1]));
+      result += grouped ? "(" + typst + ")" : typst;
+    }
+    return result;
+  };
+})();
@@ -0,0 +1,185 @@
+// Support for the `handlePhysics` option of `tex2typst`, evaluated after the
+// bundle.
+//
+// Macros of the LaTeX `physics` package are rewritten into plain TeX the
+// converter knows before the input reaches it. Macros of the input's own
+// `customTexMacros` are left alone, so those definitions win.
+(() => {
+  if (typeof tex2typst !== "function") {
+    return;
+  }
+  const base = tex2typst;
+  const hasOwn = Object.prototype.hasOwnProperty;
+
+  // Macros with a fixed number of braced arguments, as (count, TeX with #1, #2)
+  const FIXED = {
+    abs: [1, "\\left| #1 \\right|"],
+    norm: [1, "\\left\\| #1 \\right\\|"],
+    vb: [1, "\\mathbf{#1}"],
+    vu: [1, "\\hat{\\mathbf{#1}}"],
+    expval: [1, "\\left\\langle #1 \\right\\rangle"],
+    bra: [1, "\\left\\langle #1 \\right|"],
+    ket: [1, "\\left| #1 \\right\\rangle"],
+    comm: [2, "\\left[ #1, #2 \\right]"],
+    order: [1, "\\mathcal{O}\\left( #1 \\right)"],
+    dd: [0, "\\mathrm{d}"],
+    grad: [0, "\\nabla"],
+    curl: [0, "\\nabla \\times"],
+    tr: [0, "\\operatorname{tr}"],
+    Tr: [0, "\\operatorname{Tr}"],
+  };
+  // Opening delimiters `\qty` accepts and their `\left`/`\right` pairs
+  const QTY = [
+    ["(", ")", "\\left(", "\\right)"],
+    ["[", "]", "\\left[", "\\right]"],
+    ["\\{", "\\}", "\\left\\{", "\\right\\}"],
+    ["{", "}", "\\left\\{", "\\right\\}"],
+    ["|", "|", "\\left|", "\\right|"],
+  ];
+
+  const expand = (tex, macros) => {
+    const skipSpaces = (i) => {
+      while (i < tex.length && /\s/.test(tex[i])) {
+        i++;
+      }
+      return i;
+    };
+    // The argument between `open` and `close` at `i` as [body, index after it],
+    // or null; like delimiters nest unless they are the same
+    const delimited = (i, open, close) => {
+      if (!tex.startsWith(open, i)) {
+        return null;
+      }
+      let depth = 0;
+      for (let k = i; k < tex.length; ) {
+        if (open !== close && tex.startsWith(open, k)) {
+          depth++;
+          k += open.length;
+        } else if (tex.startsWith(close, k) && (k > i || open !== close)) {
+          if (open === close || --depth === 0) {
+            return [tex.slice(i + open.length, k), k + close.length];
+          }
+          k += close.length;
+        } else if (tex[k] === "\\") {
+          k += 2;
+        } else {
+          k += open === close && k === i ? open.length : 1;
+        }
+      }
+      return null;
+    };
+    const braced = (i) => delimited(skipSpaces(i), "{", "}");
+    // Up to `max` braced arguments from `i`, at least `min`, as [bodies, index]
+    const arguments_ = (i, min, max) => {
+      const args = [];
+      while (args.length < max) {
+        const arg = braced(i);
+        if (arg === null) {
+          break;
+        }
+        args.push(expand(arg[0], macros));
+        i = arg[1];
+      }
+      return args.length < min ? null : [args, i];
+    };
+
+    // TeX for `name` with its arguments from `i`, as [TeX, index], or null to
+    // leave the command as it is
+    const rewrite = (name, i) => {
+      if (hasOwn.call(FIXED, name)) {
+        const [count, template] = FIXED[name];
+        const read = arguments_(i, count, count);
+        if (read === null) {
+          return null;
+        }
+        const [args, next] = read;
+        return [template.replace(/#(\d)/g, (_, n) => args[n - 1]), next];
+      }
+      if (name === "qty") {
+        const start = skipSpaces(i);
+        for (const [open, close, left, right] of QTY) {
+          const arg = delimited(start, open, close);
+          if (arg !== null) {
+            return [left + " " + expand(arg[0], macros) + " " + right, arg[1]];
+          }
+        }
+        return null;
+      }
+      if (name === "braket") {
+        const read = arguments_(i, 1, 2);
+        if (read === null) {
+          return null;
+        }
+        const [[bra, ket], next] = read;
+        return ["\\langle " + bra + " | " + (ket === undefined ? bra : ket) + " \\rangle", next];
+      }
+      if (name === "dv" || name === "pdv") {
+        const d = name === "dv" ? "\\mathrm{d}" : "\\partial";
+        // An optional `[n]` gives the order
+        let order = "";
+        let j = skipSpaces(i);
+        const optional = delimited(j, "[", "]");
+        if (optional !== null) {
+          order = optional[0].trim();
+          j = optional[1];
+        }
+        const read = arguments_(j, 1, name === "pdv" ? 3 : 2);
+        if (read === null) {
+          return null;
+        }
+        const [args, next] = read;
+        const power = order ? "^{" + order + "}" : "";
+        if (args.length === 1) {
+          return ["\\frac{" + d + power + "}{" + d + " " + args[0] + power + "}", next];
+        }
+        if (args.length === 3) {
+          const numerator = d + "^{2} " + args[0];
+          return [
+            "\\frac{" + numerator + "}{" + d + " " + args[1] + " " + d + " " + args[2] + "}",
+            next,
+          ];
+        }
+        return [
+          "\\frac{" + d + power + " " + args[0] + "}{" + d + " " + args[1] + power + "}",
+          next,
+        ];
+      }
+      return null;
+    };
+
+    let out = "";
+    let i = 0;
+    while (i < tex.length) {
+      const command = /^\\([a-zA-Z]+|.?)/.exec(tex.slice(i));
+      if (command === null) {
+        out += tex[i++];
+        continue;
+      }
+      const name = command[1];
+      let next = i + command[0].length;
+      // Starred forms only change the delimiter sizing
+      const starred = tex[next] === "*" && /^[a-zA-Z]+$/.test(name);
+      const replacement =
+        hasOwn.call(macros, "\\" + name) || hasOwn.call(macros, name)
+          ? null
+          : rewrite(name, starred ? next + 1 : next);
+      if (replacement === null) {
+        out += command[0];
+        i = next;
+        continue;
+      }
+      out += replacement[0] + " ";
+      i = replacement[1];
+    }
+    return out;
+  };
+
+  globalThis.tex2typst = function (tex, options) {
+    if (!options || !options.handlePhysics) {
+      return base(tex, options);
+    }
+    const rest = Object.assign({}, options);
+    delete rest.handlePhysics;
+    return base(expand(String(tex), rest.customTexMacros || {}), rest);
+  };
+})();
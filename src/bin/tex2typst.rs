@@ -0,0 +1,300 @@
+//! `tex2typst` command: convert math given as arguments, on stdin or in a JSON Lines
+//! file, one result per line on stdout (`cli` feature).
+
+use _tex2typst_core::{ConvertError, Converter, Options};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: tex2typst [OPTIONS] [EXPR]...
+       tex2typst [OPTIONS] --stdin
+       tex2typst [OPTIONS] --batch FILE.jsonl
+
+Convert LaTeX/TeX math to Typst (or back with --reverse), one result per line.
+Put expressions starting with '-' after a '--' argument.
+
+Input:
+      --stdin                 Convert every line read from stdin
+      --batch FILE            Convert every record of a JSON Lines file: a string, or an
+                              object with a \"tex\" (\"typst\" with --reverse) field
+  -r, --reverse               Convert Typst to LaTeX/TeX
+      --errors fail|skip      On a failed conversion stop with exit code 1 (fail, the
+                              default), or report it on stderr, print an empty line and
+                              keep going (skip)
+
+Options (each --FLAG has a --no-FLAG form):
+      --non-strict            Allow non-strict parsing
+      --prefer-shorthands     Prefer shorthand notation
+      --keep-spaces           Preserve spaces in output
+      --frac-to-slash         Convert fractions to slash notation
+      --infty-to-oo           Convert \\infty to oo
+      --optimize              Optimize output
+      --ignore-unknown-commands
+                              Pass unrecognized commands through verbatim
+      --handle-physics        Rewrite physics package macros (\\dv, \\abs, ...)
+      --output-unicode        Emit Unicode characters (α, ×) instead of symbol names
+      --block-math-mode       Use block math mode (--reverse)
+      --handle-text-mode MODE How to treat \\text{...}: verbatim, convert or strip
+      --custom-macro NAME=BODY
+                              Define a TeX macro, or a Typst macro with --reverse;
+                              repeatable
+
+  -h, --help                  Print this help
+  -V, --version               Print the version
+";
+
+/// Accessor of a boolean field of `Options`
+type Flag = fn(&mut Options) -> &mut Option<bool>;
+
+/// Boolean options as (flag, field)
+const FLAGS: &[(&str, Flag)] = &[
+    ("non-strict", |o| &mut o.non_strict),
+    ("prefer-shorthands", |o| &mut o.prefer_shorthands),
+    ("keep-spaces", |o| &mut o.keep_spaces),
+    ("frac-to-slash", |o| &mut o.frac_to_slash),
+    ("infty-to-oo", |o| &mut o.infty_to_oo),
+    ("optimize", |o| &mut o.optimize),
+    ("ignore-unknown-commands", |o| {
+        &mut o.ignore_unknown_commands
+    }),
+    ("handle-physics", |o| &mut o.handle_physics),
+    ("output-unicode", |o| &mut o.output_unicode),
+    ("block-math-mode", |o| &mut o.block_math_mode),
+];
+
+enum Input {
+    Args(Vec<String>),
+    Stdin,
+    Batch(String),
+}
+
+struct Cli {
+    input: Input,
+    reverse: bool,
+    skip_errors: bool,
+    options: Options,
+}
+
+/// What `main` should do after parsing the command line
+enum Parsed {
+    Run(Box<Cli>),
+    Print(String),
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Parsed, String> {
+    let mut args = args.into_iter();
+    let mut expressions = Vec::new();
+    let mut stdin = false;
+    let mut batch = None;
+    let mut reverse = false;
+    let mut skip_errors = false;
+    let mut options = Options::default();
+    let mut macros = HashMap::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            expressions.extend(args.by_ref());
+            break;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            expressions.push(arg);
+            continue;
+        }
+        // `--name=value` and `--name value` are both accepted
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match name {
+            "-h" | "--help" => return Ok(Parsed::Print(USAGE.to_string())),
+            "-V" | "--version" => {
+                return Ok(Parsed::Print(format!(
+                    "tex2typst {}\n",
+                    env!("CARGO_PKG_VERSION")
+                )));
+            }
+            "--stdin" => stdin = true,
+            "--batch" => batch = Some(value(name)?),
+            "-r" | "--reverse" => reverse = true,
+            "--errors" => match value(name)?.as_str() {
+                "fail" => skip_errors = false,
+                "skip" => skip_errors = true,
+                other => {
+                    return Err(format!(
+                        "Invalid value {:?} for --errors, expected fail or skip",
+                        other
+                    ));
+                }
+            },
+            "--handle-text-mode" => options.handle_text_mode = Some(value(name)?),
+            "--custom-macro" => {
+                let definition = value(name)?;
+                let (macro_name, body) = definition.split_once('=').ok_or_else(|| {
+                    format!("--custom-macro expects NAME=BODY, got {:?}", definition)
+                })?;
+                macros.insert(macro_name.to_string(), body.to_string());
+            }
+            _ => {
+                let flag = name.strip_prefix("--").unwrap_or(name);
+                let (flag, enabled) = match flag.strip_prefix("no-") {
+                    Some(flag) => (flag, false),
+                    None => (flag, true),
+                };
+                let Some((_, option)) = FLAGS.iter().find(|(known, _)| *known == flag) else {
+                    return Err(format!("Unknown option '{}'", name));
+                };
+                if inline_value.is_some() {
+                    return Err(format!("{} does not take a value", name));
+                }
+                *option(&mut options) = Some(enabled);
+            }
+        }
+    }
+
+    if !macros.is_empty() {
+        if reverse {
+            options.custom_typst_macros = Some(macros);
+        } else {
+            options.custom_tex_macros = Some(macros);
+        }
+    }
+    let input = match (stdin, batch, expressions.is_empty()) {
+        (false, None, false) => Input::Args(expressions),
+        (true, None, true) => Input::Stdin,
+        (false, Some(path), true) => Input::Batch(path),
+        (false, None, true) => return Err("No input; pass expressions, --stdin or --batch".into()),
+        _ => return Err("Expressions, --stdin and --batch cannot be combined".into()),
+    };
+    Ok(Parsed::Run(Box::new(Cli {
+        input,
+        reverse,
+        skip_errors,
+        options,
+    })))
+}
+
+/// The expression of JSON Lines record `line`
+fn batch_record(line: &str, field: &str) -> Result<String, String> {
+    let record: serde_json::Value =
+        serde_json::from_str(line).map_err(|e| format!("invalid JSON: {}", e))?;
+    match &record {
+        serde_json::Value::String(text) => Ok(text.clone()),
+        serde_json::Value::Object(object) => match object.get(field) {
+            Some(serde_json::Value::String(text)) => Ok(text.clone()),
+            Some(_) => Err(format!("field '{}' is not a string", field)),
+            None => Err(format!("missing field '{}'", field)),
+        },
+        _ => Err(format!(
+            "expected a string or an object with a '{}' field",
+            field
+        )),
+    }
+}
+
+/// Error raised while running; `Conversion` failures may be skipped
+enum Failure {
+    Conversion(String),
+    Fatal(String),
+}
+
+impl From<ConvertError> for Failure {
+    fn from(error: ConvertError) -> Self {
+        match error {
+            // The JS stack trace is no use on a terminal
+            ConvertError::Conversion { exception, .. } => {
+                Failure::Conversion(format!("Conversion failed: {}", exception.message))
+            }
+            _ => Failure::Fatal(error.to_string()),
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let converter = Converter::new().map_err(|e| e.to_string())?;
+    let convert = |input: &str| -> Result<String, Failure> {
+        Ok(if cli.reverse {
+            converter.typst_to_tex(input, &cli.options)?
+        } else {
+            converter.tex_to_typst(input, &cli.options)?
+        })
+    };
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    // Streamed output is flushed per line so pipelines see results as they come
+    let flush = matches!(cli.input, Input::Stdin);
+    let mut emit = |label: &str, result: Result<String, Failure>| -> Result<(), String> {
+        let line = match result {
+            Ok(line) => line,
+            Err(Failure::Conversion(message)) if cli.skip_errors => {
+                eprintln!("tex2typst: {}: {}", label, message);
+                String::new()
+            }
+            Err(Failure::Conversion(message)) | Err(Failure::Fatal(message)) => {
+                return Err(format!("{}: {}", label, message));
+            }
+        };
+        writeln!(out, "{}", line).map_err(|e| e.to_string())?;
+        if flush {
+            out.flush().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    };
+
+    match &cli.input {
+        Input::Args(expressions) => {
+            for (index, expression) in expressions.iter().enumerate() {
+                emit(&format!("argument {}", index + 1), convert(expression))?;
+            }
+        }
+        Input::Stdin => {
+            for (index, line) in std::io::stdin().lock().lines().enumerate() {
+                let line = line.map_err(|e| format!("reading stdin: {}", e))?;
+                emit(&format!("line {}", index + 1), convert(&line))?;
+            }
+        }
+        Input::Batch(path) => {
+            let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+            let field = if cli.reverse { "typst" } else { "tex" };
+            for (index, line) in std::io::BufReader::new(file).lines().enumerate() {
+                let line = line.map_err(|e| format!("{}: {}", path, e))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let result = batch_record(&line, field)
+                    .map_err(Failure::Conversion)
+                    .and_then(|input| convert(&input));
+                emit(&format!("{}:{}", path, index + 1), result)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = match parse_args(std::env::args().skip(1)) {
+        Ok(Parsed::Run(cli)) => cli,
+        Ok(Parsed::Print(text)) => {
+            print!("{}", text);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("tex2typst: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(*cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("tex2typst: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
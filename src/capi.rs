@@ -0,0 +1,329 @@
+//! C ABI for embedding the converter in non-Python hosts (`capi` feature).
+//!
+//! The declarations live in `include/tex2typst.h`, regenerated by `build.rs` with
+//! cbindgen when it is installed. Every thread calling in gets its own engine,
+//! created on its first conversion.
+
+use crate::core::{ConvertError, Converter, Options};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// The conversion succeeded and `*out` holds the result
+pub const T2T_OK: c_int = 0;
+/// `tex` or `out` is NULL, or a string argument is not valid UTF-8
+pub const T2T_ERR_ARGUMENT: c_int = 1;
+/// `options_json` is not a JSON object of known options
+pub const T2T_ERR_OPTIONS: c_int = 2;
+/// The converter rejected the input
+pub const T2T_ERR_CONVERSION: c_int = 3;
+/// The JS engine could not be created or failed internally
+pub const T2T_ERR_ENGINE: c_int = 4;
+
+const VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("package version contains a NUL byte"),
+    };
+
+thread_local! {
+    static CONVERTER: RefCell<Option<Converter>> = const { RefCell::new(None) };
+}
+
+/// An error code and its message
+type Failure = (c_int, String);
+
+fn engine_failure(error: ConvertError) -> Failure {
+    let code = match error {
+        ConvertError::InvalidOptions(_) => T2T_ERR_OPTIONS,
+        ConvertError::Conversion { .. } => T2T_ERR_CONVERSION,
+        ConvertError::Engine(_) | ConvertError::Runtime(_) | ConvertError::OutOfMemory(_) => {
+            T2T_ERR_ENGINE
+        }
+    };
+    (code, error.to_string())
+}
+
+/// Read the NUL-terminated UTF-8 string `value`; NULL reads as `None`
+///
+/// # Safety
+///
+/// `value` must be NULL or point to a NUL-terminated string.
+unsafe fn read_str<'a>(name: &str, value: *const c_char) -> Result<Option<&'a str>, Failure> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: guaranteed by the caller
+    let value = unsafe { CStr::from_ptr(value) };
+    value
+        .to_str()
+        .map(Some)
+        .map_err(|_| (T2T_ERR_ARGUMENT, format!("{} is not valid UTF-8", name)))
+}
+
+fn string_map(
+    name: &str,
+    value: &serde_json::Value,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("{} must be an object", name))?;
+    object
+        .iter()
+        .map(|(key, value)| match value.as_str() {
+            Some(value) => Ok((key.clone(), value.to_string())),
+            None => Err(format!("{}['{}'] must be a string", name, key)),
+        })
+        .collect()
+}
+
+/// Parse the `tex2typst` options of `json`, keyed by their Python names
+fn parse_options(json: &str) -> Result<Options, Failure> {
+    let invalid = |message: String| (T2T_ERR_OPTIONS, message);
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| invalid(format!("options_json is not valid JSON: {}", e)))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| invalid("options_json must be a JSON object".to_string()))?;
+
+    let mut options = Options::default();
+    for (name, value) in object {
+        let flag = || {
+            value
+                .as_bool()
+                .map(Some)
+                .ok_or_else(|| invalid(format!("{} must be a boolean", name)))
+        };
+        match name.as_str() {
+            "non_strict" => options.non_strict = flag()?,
+            "prefer_shorthands" => options.prefer_shorthands = flag()?,
+            "keep_spaces" => options.keep_spaces = flag()?,
+            "frac_to_slash" => options.frac_to_slash = flag()?,
+            "infty_to_oo" => options.infty_to_oo = flag()?,
+            "optimize" => options.optimize = flag()?,
+            "ignore_unknown_commands" => options.ignore_unknown_commands = flag()?,
+            "handle_physics" => options.handle_physics = flag()?,
+            "output_unicode" => options.output_unicode = flag()?,
+            "handle_text_mode" => {
+                let mode = value
+                    .as_str()
+                    .ok_or_else(|| invalid(format!("{} must be a string", name)))?;
+                options.handle_text_mode = Some(mode.to_string());
+            }
+            "custom_tex_macros" => {
+                options.custom_tex_macros = Some(string_map(name, value).map_err(invalid)?);
+            }
+            "symbol_map" => {
+                options.symbol_map = Some(string_map(name, value).map_err(invalid)?);
+            }
+            "environment_map" => {
+                options.environment_map = Some(string_map(name, value).map_err(invalid)?);
+            }
+            "replace_unknown_commands" => {
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| invalid(format!("{} must be an object", name)))?;
+                let replacements = object
+                    .iter()
+                    .map(|(key, value)| match value {
+                        serde_json::Value::Null => Ok((key.clone(), None)),
+                        serde_json::Value::String(value) => Ok((key.clone(), Some(value.clone()))),
+                        _ => Err(invalid(format!(
+                            "{}['{}'] must be a string or null",
+                            name, key
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?;
+                options.replace_unknown_commands = Some(replacements);
+            }
+            "js_options" => {
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| invalid(format!("{} must be an object", name)))?;
+                options.js_options = Some(object.clone().into_iter().collect());
+            }
+            _ => return Err(invalid(format!("Unknown option '{}'", name))),
+        }
+    }
+    Ok(options)
+}
+
+/// Run `tex` through this thread's converter, creating it on first use
+fn convert_tex(tex: &str, options: &Options) -> Result<String, Failure> {
+    CONVERTER.with(|cell| {
+        let mut converter = cell.borrow_mut();
+        // A converter that ran out of memory may be inconsistent; start over
+        if converter.as_ref().is_none_or(Converter::is_poisoned) {
+            *converter = Some(Converter::new().map_err(engine_failure)?);
+        }
+        let converter = converter.as_ref().expect("converter was just created");
+        converter.tex_to_typst(tex, options).map_err(engine_failure)
+    })
+}
+
+/// `value` as a C string; interior NULs cannot cross the C boundary, so it is cut
+/// off at the first one
+fn to_c_string(value: String) -> CString {
+    match CString::new(value) {
+        Ok(value) => value,
+        Err(e) => {
+            let end = e.nul_position();
+            let mut bytes = e.into_vec();
+            bytes.truncate(end);
+            CString::new(bytes).expect("NUL bytes were cut off")
+        }
+    }
+}
+
+/// Hand `value` to the caller as a string they free with [`t2t_free_string`]
+fn into_c_string(value: String) -> *mut c_char {
+    to_c_string(value).into_raw()
+}
+
+/// Read the arguments of a conversion export and convert, turning panics into
+/// `T2T_ERR_ENGINE`
+///
+/// # Safety
+///
+/// `tex` and `options_json` must each be NULL or point to a NUL-terminated string.
+unsafe fn convert_c_args(
+    tex: *const c_char,
+    options_json: *const c_char,
+) -> Result<String, Failure> {
+    let run = || -> Result<String, Failure> {
+        // SAFETY: both strings are NULL or NUL-terminated per the contract above
+        let tex = unsafe { read_str("tex", tex) }?
+            .ok_or_else(|| (T2T_ERR_ARGUMENT, "tex must not be NULL".to_string()))?;
+        let options = match unsafe { read_str("options_json", options_json) }? {
+            Some(json) => parse_options(json)?,
+            None => Options::default(),
+        };
+        convert_tex(tex, &options)
+    };
+    catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|_| {
+        // A panic may have left this thread's engine half-updated
+        let _ = CONVERTER.try_with(|cell| cell.try_borrow_mut().map(|mut c| c.take()));
+        Err((
+            T2T_ERR_ENGINE,
+            "Internal error: converter panicked".to_string(),
+        ))
+    })
+}
+
+/// Convert the LaTeX/TeX math `tex` to Typst.
+///
+/// `options_json` is NULL or a JSON object of `tex2typst` options with their
+/// Python names, e.g. `{"frac_to_slash": false}`. Returns `T2T_OK` and stores
+/// the result in `*out`, or returns one of the `T2T_ERR_*` codes and stores the
+/// message in `*err`. Whichever string is set is owned by the caller and must
+/// be released with `t2t_free_string`; the other is set to NULL.
+///
+/// # Safety
+///
+/// `tex` and `options_json` must each be NULL or point to a NUL-terminated
+/// string. `out` must be NULL or valid for a pointer write, and so must `err`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn t2t_convert_tex(
+    tex: *const c_char,
+    options_json: *const c_char,
+    out: *mut *mut c_char,
+    err: *mut *mut c_char,
+) -> c_int {
+    if !out.is_null() {
+        // SAFETY: `out` is valid for writes per the contract above
+        unsafe { *out = ptr::null_mut() };
+    }
+    if !err.is_null() {
+        // SAFETY: `err` is valid for writes per the contract above
+        unsafe { *err = ptr::null_mut() };
+    }
+
+    let result = if out.is_null() {
+        Err((T2T_ERR_ARGUMENT, "out must not be NULL".to_string()))
+    } else {
+        // SAFETY: both strings are NULL or NUL-terminated per the contract above
+        unsafe { convert_c_args(tex, options_json) }
+    };
+
+    match result {
+        Ok(typst) => {
+            // SAFETY: checked non-NULL above
+            unsafe { *out = into_c_string(typst) };
+            T2T_OK
+        }
+        Err((code, message)) => {
+            if !err.is_null() {
+                // SAFETY: `err` is valid for writes per the contract above
+                unsafe { *err = into_c_string(message) };
+            }
+            code
+        }
+    }
+}
+
+/// Convert `tex` like `t2t_convert_tex`, but into the caller's buffer `out_buf` of
+/// `out_len` bytes, for hosts that cannot free memory allocated by the library.
+///
+/// Returns the length of the result in bytes, without the terminating NUL, and
+/// copies as much of it as fits, always NUL-terminated when `out_len` is not 0. As
+/// with `snprintf`, a return value of `out_len` or more means the output was cut
+/// short; call again with a larger buffer, or with `out_buf` NULL and `out_len` 0
+/// to only measure it. On failure returns the negated `T2T_ERR_*` code, e.g.
+/// `-T2T_ERR_CONVERSION`; results longer than `INT_MAX` bytes give
+/// `-T2T_ERR_ARGUMENT`. Use `t2t_convert_tex` for the error message.
+///
+/// # Safety
+///
+/// `tex` and `options_json` must each be NULL or point to a NUL-terminated
+/// string. `out_buf` must be valid for writes of `out_len` bytes, or NULL with
+/// `out_len` 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn t2t_convert_tex_into(
+    tex: *const c_char,
+    options_json: *const c_char,
+    out_buf: *mut c_char,
+    out_len: usize,
+) -> c_int {
+    if out_buf.is_null() && out_len != 0 {
+        return -T2T_ERR_ARGUMENT;
+    }
+    // SAFETY: both strings are NULL or NUL-terminated per the contract above
+    let typst = match unsafe { convert_c_args(tex, options_json) } {
+        Ok(typst) => to_c_string(typst),
+        Err((code, _)) => return -code,
+    };
+    let bytes = typst.as_bytes();
+    let Ok(len) = c_int::try_from(bytes.len()) else {
+        return -T2T_ERR_ARGUMENT;
+    };
+    if out_len > 0 {
+        let copied = bytes.len().min(out_len - 1);
+        // SAFETY: `out_buf` holds `out_len` > `copied` bytes per the contract above,
+        // and cannot overlap the string just allocated
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr().cast::<c_char>(), out_buf, copied);
+            *out_buf.add(copied) = 0;
+        }
+    }
+    len
+}
+
+/// Release a string returned through `out` or `err`; NULL is ignored.
+///
+/// # Safety
+///
+/// `value` must be NULL or a string from `t2t_convert_tex` that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn t2t_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        // SAFETY: `value` came from `CString::into_raw` per the contract above
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
+
+/// The library version as a static string; do not free it
+#[unsafe(no_mangle)]
+pub extern "C" fn t2t_version() -> *const c_char {
+    VERSION.as_ptr()
+}
@@ -0,0 +1,1929 @@
+//! Pure-Rust converter built on the bundled JS library, usable without Python.
+//!
+//! ```no_run
+//! use _tex2typst_core::{Converter, Options};
+//!
+//! let converter = Converter::new()?;
+//! let options = Options {
+//!     frac_to_slash: Some(false),
+//!     ..Options::default()
+//! };
+//! assert_eq!(converter.tex_to_typst(r"\frac{1}{2}", &options)?, "frac(1, 2)");
+//! # Ok::<(), _tex2typst_core::ConvertError>(())
+//! ```
+
+use rquickjs::{
+    Array, CatchResultExt, CaughtError, Context, Ctx, Function, Object, Persistent, Runtime,
+};
+use std::borrow::Cow;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::rc::Rc;
+use std::sync::LazyLock;
+
+pub(crate) const JS_CODE: &str = include_str!("../js/tex2typst.bundle.js");
+
+/// Wraps the bundle's `tex2typst` to implement the `symbolMap` and
+/// `environmentMap` options
+const MAP_OPTIONS_JS: &str = include_str!("../js/map_options.js");
+
+/// Wraps `tex2typst` again to implement the `handlePhysics` option
+const PHYSICS_JS: &str = include_str!("../js/physics.js");
+
+/// Native stack QuickJS may use before raising "Maximum call stack size exceeded".
+///
+/// Well below the smallest stack a Python thread gets, so deeply nested input
+/// exhausts the engine's budget instead of overflowing the real stack.
+pub(crate) const JS_MAX_STACK_SIZE: usize = 1024 * 1024;
+
+/// Error returned by [`Converter`]
+#[derive(Debug)]
+pub enum ConvertError {
+    /// The JS bundle or an extension failed to load, or lacks a converter function
+    Engine(String),
+    /// The QuickJS runtime could not be created or handed a value
+    Runtime(String),
+    /// An option has a value the converter does not accept
+    InvalidOptions(String),
+    /// The JS engine ran out of memory; the converter is poisoned
+    OutOfMemory(String),
+    /// The JS converter rejected `input`
+    Conversion {
+        input: String,
+        exception: JsException,
+    },
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::Engine(message)
+            | ConvertError::Runtime(message)
+            | ConvertError::InvalidOptions(message)
+            | ConvertError::OutOfMemory(message) => f.write_str(message),
+            ConvertError::Conversion { exception, .. } => {
+                write!(f, "Conversion failed: {}", exception)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// A QuickJS exception split into its parts; displays as the message followed by
+/// the stack trace
+#[derive(Debug, Clone)]
+pub struct JsException {
+    pub message: String,
+    pub stack: Option<String>,
+    /// `(line, column)` of the innermost stack frame
+    pub position: Option<(u32, u32)>,
+}
+
+impl JsException {
+    /// `(line, column)` ending the first `at ...` frame of a QuickJS stack trace,
+    /// e.g. `at x (eval_script:5:1668)` or `at <anonymous>:1:42`
+    fn frame_position(stack: &str) -> Option<(u32, u32)> {
+        let frame = stack
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("at "))?;
+        let mut parts = frame.trim_end_matches(')').rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        Some((line, column))
+    }
+}
+
+impl std::fmt::Display for JsException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.stack {
+            Some(stack) => write!(f, "{}\nStack trace:\n{}", self.message, stack),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Split a QuickJS exception into its message, stack trace and source position
+pub(crate) fn format_js_exception(error: CaughtError) -> JsException {
+    let (message, stack) = match error {
+        CaughtError::Exception(exception) => (
+            exception
+                .message()
+                .unwrap_or_else(|| "Unknown error".to_string()),
+            exception.stack(),
+        ),
+        CaughtError::Error(err) => (err.to_string(), None),
+        CaughtError::Value(val) => (format!("JavaScript error: {:?}", val), None),
+    };
+    let position = stack.as_deref().and_then(JsException::frame_position);
+    JsException {
+        message,
+        stack,
+        position,
+    }
+}
+
+/// Whether `error` is QuickJS failing to allocate rather than the converter throwing.
+///
+/// The engine reports it as an `InternalError: out of memory`, or as a bare `null`
+/// when there was no memory left for the error object either.
+pub(crate) fn is_out_of_memory(error: &CaughtError) -> bool {
+    match error {
+        CaughtError::Error(rquickjs::Error::Allocation) => true,
+        CaughtError::Exception(exception) => {
+            exception.message().as_deref() == Some("out of memory")
+        }
+        CaughtError::Value(value) => value.is_null(),
+        CaughtError::Error(_) => false,
+    }
+}
+
+/// Message of a panic caught by [`Converter::guard`]
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Accepted values for the `handle_text_mode` option
+pub(crate) const HANDLE_TEXT_MODES: &[&str] = &["verbatim", "convert", "strip"];
+
+/// Options accepted by `tex2typst` as (Python name, JS name), in signature order
+pub(crate) const TEX2TYPST_OPTION_NAMES: &[(&str, &str)] = &[
+    ("non_strict", "nonStrict"),
+    ("prefer_shorthands", "preferShorthands"),
+    ("keep_spaces", "keepSpaces"),
+    ("frac_to_slash", "fracToSlash"),
+    ("infty_to_oo", "inftyToOo"),
+    ("optimize", "optimize"),
+    ("ignore_unknown_commands", "ignoreUnknownCommands"),
+    ("handle_physics", "handlePhysics"),
+    ("output_unicode", "outputUnicode"),
+    ("handle_text_mode", "handleTextMode"),
+    ("custom_tex_macros", "customTexMacros"),
+    ("replace_unknown_commands", "replaceUnknownCommands"),
+    ("symbol_map", "symbolMap"),
+    ("environment_map", "environmentMap"),
+];
+
+/// Options accepted by `typst2tex` as (Python name, JS name)
+pub(crate) const TYPST2TEX_OPTION_NAMES: &[(&str, &str)] = &[
+    ("block_math_mode", "blockMathMode"),
+    ("custom_typst_macros", "customTypstMacros"),
+];
+
+/// Reject `value` for `option` unless it is one of `choices`
+pub(crate) fn check_choice(
+    option: &str,
+    value: &str,
+    choices: &[&str],
+) -> Result<(), ConvertError> {
+    if choices.contains(&value) {
+        Ok(())
+    } else {
+        Err(ConvertError::InvalidOptions(format!(
+            "Invalid value {:?} for option '{}', expected one of: {}",
+            value,
+            option,
+            choices.join(", ")
+        )))
+    }
+}
+
+/// Conversion options; `None` leaves the bundled converter's default in place.
+///
+/// `block_math_mode` and `custom_typst_macros` only apply to
+/// [`Converter::typst_to_tex`], the TeX-only options only to
+/// [`Converter::tex_to_typst`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    pub non_strict: Option<bool>,
+    pub prefer_shorthands: Option<bool>,
+    pub keep_spaces: Option<bool>,
+    pub frac_to_slash: Option<bool>,
+    pub infty_to_oo: Option<bool>,
+    pub optimize: Option<bool>,
+    pub ignore_unknown_commands: Option<bool>,
+    /// Rewrite macros of the LaTeX `physics` package into plain TeX first
+    pub handle_physics: Option<bool>,
+    /// Emit Unicode characters such as `α` and `×` instead of Typst symbol names
+    pub output_unicode: Option<bool>,
+    /// `"verbatim"`, `"convert"` or `"strip"`
+    pub handle_text_mode: Option<String>,
+    pub custom_tex_macros: Option<HashMap<String, String>>,
+    /// Typst translations overriding the built-in ones of TeX symbols, keyed by
+    /// command name with or without backslash
+    pub symbol_map: Option<HashMap<String, String>>,
+    /// Typst functions standing in for TeX environments, keyed by environment name;
+    /// the cells of `\begin{name} a & b \\ c & d \end{name}` become `f(a, b; c, d)`
+    pub environment_map: Option<HashMap<String, String>>,
+    /// Typst fallbacks for unknown commands, keyed by name without backslash; a
+    /// `None` value drops the command
+    pub replace_unknown_commands: Option<HashMap<String, Option<String>>>,
+    pub block_math_mode: Option<bool>,
+    pub custom_typst_macros: Option<HashMap<String, String>>,
+    /// Further options handed to the JS converter under their JS names, e.g. the
+    /// integer options of a newer bundle; `null` values are skipped and the names
+    /// of the fields above are rejected
+    pub js_options: Option<JsOptions>,
+}
+
+/// Options for the JS converter under their JS names, set on its options object in
+/// insertion order
+pub type JsOptions = serde_json::Map<String, serde_json::Value>;
+
+/// `map` as a JS object, sorted by key so the output does not depend on `HashMap`
+/// iteration order
+fn string_map_value(map: &HashMap<String, String>) -> serde_json::Value {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort();
+    serde_json::Value::Object(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+            .collect(),
+    )
+}
+
+/// Add the raw `js_options` to `options_map`, rejecting the JS names in `names`
+pub(crate) fn merge_js_options(
+    options_map: &mut JsOptions,
+    js_options: &JsOptions,
+    names: &[(&str, &str)],
+) -> Result<(), ConvertError> {
+    for (key, value) in js_options {
+        if let Some((py_name, _)) = names.iter().find(|(_, js_name)| js_name == key) {
+            return Err(ConvertError::InvalidOptions(format!(
+                "js_options['{}'] duplicates the '{}' option",
+                key, py_name
+            )));
+        }
+        if !value.is_null() {
+            options_map.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(())
+}
+
+impl Options {
+    /// The JS options object of `tex2typst`, `None` if no option is set
+    pub(crate) fn tex2typst_js_options(&self) -> Result<Option<JsOptions>, ConvertError> {
+        let mut options_map = JsOptions::with_capacity(9);
+        for (name, value) in [
+            ("nonStrict", self.non_strict),
+            ("preferShorthands", self.prefer_shorthands),
+            ("keepSpaces", self.keep_spaces),
+            ("fracToSlash", self.frac_to_slash),
+            ("inftyToOo", self.infty_to_oo),
+            ("optimize", self.optimize),
+            ("ignoreUnknownCommands", self.ignore_unknown_commands),
+            ("handlePhysics", self.handle_physics),
+            ("outputUnicode", self.output_unicode),
+        ] {
+            if let Some(value) = value {
+                options_map.insert(name.to_string(), serde_json::Value::Bool(value));
+            }
+        }
+        if let Some(mode) = &self.handle_text_mode {
+            check_choice("handle_text_mode", mode, HANDLE_TEXT_MODES)?;
+            options_map.insert(
+                "handleTextMode".to_string(),
+                serde_json::Value::String(mode.clone()),
+            );
+        }
+        if let Some(macros) = &self.custom_tex_macros {
+            options_map.insert("customTexMacros".to_string(), string_map_value(macros));
+        }
+        // An empty dict would still switch the JS converter to its replacement path
+        if let Some(replacements) = self
+            .replace_unknown_commands
+            .as_ref()
+            .filter(|replacements| !replacements.is_empty())
+        {
+            let mut replacements: Vec<_> = replacements.iter().collect();
+            replacements.sort();
+            let replacements = replacements
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = value
+                        .clone()
+                        .map_or(serde_json::Value::Null, serde_json::Value::String);
+                    (key.clone(), value)
+                })
+                .collect();
+            options_map.insert(
+                "replaceUnknownCommands".to_string(),
+                serde_json::Value::Object(replacements),
+            );
+        }
+        if let Some(symbols) = self.symbol_map.as_ref().filter(|map| !map.is_empty()) {
+            options_map.insert("symbolMap".to_string(), string_map_value(symbols));
+        }
+        if let Some(environments) = self.environment_map.as_ref().filter(|map| !map.is_empty()) {
+            options_map.insert("environmentMap".to_string(), string_map_value(environments));
+        }
+        if let Some(js_options) = &self.js_options {
+            merge_js_options(&mut options_map, js_options, TEX2TYPST_OPTION_NAMES)?;
+        }
+        Ok(Some(options_map).filter(|map| !map.is_empty()))
+    }
+
+    /// The JS options object of `typst2tex`, `None` if no option is set
+    pub(crate) fn typst2tex_js_options(&self) -> Result<Option<JsOptions>, ConvertError> {
+        let mut options_map = JsOptions::new();
+        if let Some(value) = self.block_math_mode {
+            options_map.insert("blockMathMode".to_string(), serde_json::Value::Bool(value));
+        }
+        if let Some(macros) = &self.custom_typst_macros {
+            options_map.insert("customTypstMacros".to_string(), string_map_value(macros));
+        }
+        if let Some(js_options) = &self.js_options {
+            merge_js_options(&mut options_map, js_options, TYPST2TEX_OPTION_NAMES)?;
+        }
+        Ok(Some(options_map).filter(|map| !map.is_empty()))
+    }
+}
+
+/// Copy Typst math, letting `identifier` write each identifier and `other` rewrite
+/// the text between them; escapes and string literals are copied unchanged.
+///
+/// Dotted names such as `arrow.r` are passed as one identifier, with whether it is
+/// a field access or code (follows `.` or `#`) and whether it is called (followed
+/// by `(`). `other` sees the rest of the input at each remaining character and
+/// returns how many characters it wrote a replacement for, 0 to copy one as is.
+fn rewrite_typst_identifiers(
+    typst: &str,
+    mut identifier: impl FnMut(&mut String, &str, bool, bool),
+    mut other: impl FnMut(&mut String, &[char]) -> usize,
+) -> String {
+    let chars: Vec<char> = typst.chars().collect();
+    let mut out = String::with_capacity(typst.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' {
+            out.extend(&chars[i..(i + 2).min(chars.len())]);
+            i += 2;
+        } else if c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            out.extend(&chars[start..i]);
+        } else if c.is_alphabetic() {
+            let start = i;
+            loop {
+                while i < chars.len() && chars[i].is_alphanumeric() {
+                    i += 1;
+                }
+                // `.` continues the name only when a modifier follows
+                if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_alphabetic() {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let name: String = chars[start..i].iter().collect();
+            let access = start > 0 && matches!(chars[start - 1], '.' | '#');
+            let call = chars.get(i) == Some(&'(');
+            identifier(&mut out, &name, access, call);
+        } else {
+            match other(&mut out, &chars[i..]) {
+                0 => {
+                    out.push(c);
+                    i += 1;
+                }
+                consumed => i += consumed,
+            }
+        }
+    }
+    out
+}
+
+/// Distinct `\command` names in TeX source, in order of first use
+pub(crate) fn tex_command_names(tex: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut chars = tex.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some(&(i, next)) = chars.peek() {
+            if !next.is_ascii_alphabetic() {
+                break;
+            }
+            end = i + 1;
+            chars.next();
+        }
+        if end == start + 1 {
+            // Escaped character such as `\\` or `\{`
+            chars.next();
+            continue;
+        }
+        let name = &tex[start..end];
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Rewrite the `\text{...}` groups of `tex` for the `handleTextMode` option, which
+/// the bundle does not read: `"convert"` unwraps their contents into math and
+/// `"strip"` leaves an empty group. `"verbatim"` is what the bundle does anyway, and
+/// a `\text` without a closed group is left for the converter to report.
+fn apply_text_mode<'a>(tex: &'a str, options: Option<&JsOptions>) -> Cow<'a, str> {
+    let strip = match options
+        .and_then(|opts| opts.get("handleTextMode"))
+        .and_then(serde_json::Value::as_str)
+    {
+        Some("convert") => false,
+        Some("strip") => true,
+        _ => return Cow::Borrowed(tex),
+    };
+    rewrite_text_groups(tex, strip)
+}
+
+/// [`apply_text_mode`] for the `"strip"` mode, or `"convert"` without `strip`
+fn rewrite_text_groups(tex: &str, strip: bool) -> Cow<'_, str> {
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut search = 0;
+    while let Some(found) = tex[search..].find(r"\text") {
+        let start = search + found;
+        let after = start + r"\text".len();
+        search = after;
+        if tex[after..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // Another command such as `\textbf`
+            continue;
+        }
+        let open = after + (tex[after..].len() - tex[after..].trim_start().len());
+        if !tex[open..].starts_with('{') {
+            continue;
+        }
+        let mut depth = 0;
+        let mut close = None;
+        let mut chars = tex[open..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(open + i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(close) = close else {
+            break;
+        };
+        out.push_str(&tex[copied..start]);
+        out.push('{');
+        if !strip {
+            out.push_str(&rewrite_text_groups(&tex[open + 1..close], strip));
+        }
+        out.push('}');
+        copied = close + 1;
+        search = copied;
+    }
+    if copied == 0 {
+        return Cow::Borrowed(tex);
+    }
+    out.push_str(&tex[copied..]);
+    Cow::Owned(out)
+}
+
+/// Whether `options` define `command` (e.g. `\R`) through `customTexMacros` or map
+/// it through `symbolMap`, whose keys may leave out the backslash
+fn defines_command(options: &JsOptions, command: &str) -> bool {
+    let has = |option: &str, key: &str| {
+        options
+            .get(option)
+            .and_then(serde_json::Value::as_object)
+            .is_some_and(|map| map.contains_key(key))
+    };
+    has("customTexMacros", command) || has("symbolMap", command) || has("symbolMap", &command[1..])
+}
+
+/// Whether `options` turn on the `handlePhysics` wrapper
+fn handles_physics(options: &JsOptions) -> bool {
+    options
+        .get("handlePhysics")
+        .and_then(serde_json::Value::as_bool)
+        == Some(true)
+}
+
+/// Replace every use of the `\command`s in `commands` with `replacement`, matching
+/// whole command names only, so dropping `\a` leaves `\alpha` alone
+fn replace_tex_commands<'a>(tex: &'a str, commands: &[String], replacement: &str) -> Cow<'a, str> {
+    if commands.is_empty() {
+        return Cow::Borrowed(tex);
+    }
+    let mut out = String::with_capacity(tex.len());
+    let mut copied = 0;
+    let mut chars = tex.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some(&(i, next)) = chars.peek() {
+            if !next.is_ascii_alphabetic() {
+                break;
+            }
+            end = i + 1;
+            chars.next();
+        }
+        if end == start + 1 {
+            chars.next();
+        } else if commands.iter().any(|command| *command == tex[start..end]) {
+            out.push_str(&tex[copied..start]);
+            out.push_str(replacement);
+            copied = end;
+        }
+    }
+    if copied == 0 {
+        return Cow::Borrowed(tex);
+    }
+    out.push_str(&tex[copied..]);
+    Cow::Owned(out)
+}
+
+/// `options` with each of `commands` mapped to itself in `symbolMap`, so that the
+/// converter emits it as it is written
+fn pass_through_options(options: &JsOptions, commands: &[String]) -> JsOptions {
+    let mut options = options.clone();
+    let symbols = options
+        .entry("symbolMap".to_string())
+        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    if let Some(symbols) = symbols.as_object_mut() {
+        for command in commands {
+            symbols.insert(command[1..].to_string(), command.as_str().into());
+        }
+    }
+    options
+}
+
+/// Replace whole Typst identifiers named in the `customTypstMacros` option.
+///
+/// The JS `typst2tex` has no macro support, so substitution happens on the input.
+/// Dotted names such as `arrow.r` are matched as one identifier; string
+/// literals are left alone and replacements are not expanded again.
+pub(crate) fn expand_typst_macros<'a>(typst: &'a str, options: Option<&JsOptions>) -> Cow<'a, str> {
+    let Some(macros) = options
+        .and_then(|opts| opts.get("customTypstMacros"))
+        .and_then(|macros| macros.as_object())
+        .filter(|macros| !macros.is_empty())
+    else {
+        return Cow::Borrowed(typst);
+    };
+
+    Cow::Owned(rewrite_typst_identifiers(
+        typst,
+        |out, name, _, _| match macros.get(name).and_then(|value| value.as_str()) {
+            Some(replacement) => out.push_str(replacement),
+            None => out.push_str(name),
+        },
+        |_, _| 0,
+    ))
+}
+
+/// Typst symbol names and the characters `output_unicode` emits for them
+const TYPST_UNICODE_SYMBOLS: &[(&str, char)] = &[
+    // Greek
+    ("alpha", '\u{03B1}'),
+    ("beta", '\u{03B2}'),
+    ("gamma", '\u{03B3}'),
+    ("delta", '\u{03B4}'),
+    ("epsilon", '\u{03B5}'),
+    ("epsilon.alt", '\u{03F5}'),
+    ("zeta", '\u{03B6}'),
+    ("eta", '\u{03B7}'),
+    ("theta", '\u{03B8}'),
+    ("theta.alt", '\u{03D1}'),
+    ("iota", '\u{03B9}'),
+    ("kappa", '\u{03BA}'),
+    ("lambda", '\u{03BB}'),
+    ("mu", '\u{03BC}'),
+    ("nu", '\u{03BD}'),
+    ("xi", '\u{03BE}'),
+    ("pi", '\u{03C0}'),
+    ("pi.alt", '\u{03D6}'),
+    ("rho", '\u{03C1}'),
+    ("rho.alt", '\u{03F1}'),
+    ("sigma", '\u{03C3}'),
+    ("sigma.alt", '\u{03C2}'),
+    ("tau", '\u{03C4}'),
+    ("upsilon", '\u{03C5}'),
+    ("phi", '\u{03C6}'),
+    ("phi.alt", '\u{03D5}'),
+    ("chi", '\u{03C7}'),
+    ("psi", '\u{03C8}'),
+    ("omega", '\u{03C9}'),
+    ("Gamma", '\u{0393}'),
+    ("Delta", '\u{0394}'),
+    ("Theta", '\u{0398}'),
+    ("Lambda", '\u{039B}'),
+    ("Xi", '\u{039E}'),
+    ("Pi", '\u{03A0}'),
+    ("Sigma", '\u{03A3}'),
+    ("Upsilon", '\u{03A5}'),
+    ("Phi", '\u{03A6}'),
+    ("Psi", '\u{03A8}'),
+    ("Omega", '\u{03A9}'),
+    // Operators, relations and sets
+    ("times", '\u{00D7}'),
+    ("div", '\u{00F7}'),
+    ("dot.op", '\u{22C5}'),
+    ("plus.minus", '\u{00B1}'),
+    ("minus.plus", '\u{2213}'),
+    ("circle.small", '\u{2218}'),
+    ("bullet", '\u{2022}'),
+    ("star.op", '\u{22C6}'),
+    ("plus.o", '\u{2295}'),
+    ("times.circle", '\u{2297}'),
+    ("lt.eq", '\u{2264}'),
+    ("gt.eq", '\u{2265}'),
+    ("eq.not", '\u{2260}'),
+    ("lt.double", '\u{226A}'),
+    ("gt.double", '\u{226B}'),
+    ("approx", '\u{2248}'),
+    ("equiv", '\u{2261}'),
+    ("tilde.op", '\u{223C}'),
+    ("tilde.eq", '\u{2243}'),
+    ("tilde.equiv", '\u{2245}'),
+    ("prop", '\u{221D}'),
+    ("divides", '\u{2223}'),
+    ("parallel", '\u{2225}'),
+    ("perp", '\u{27C2}'),
+    ("in", '\u{2208}'),
+    ("in.not", '\u{2209}'),
+    ("in.rev", '\u{220B}'),
+    ("subset", '\u{2282}'),
+    ("subset.eq", '\u{2286}'),
+    ("supset", '\u{2283}'),
+    ("supset.eq", '\u{2287}'),
+    ("union", '\u{222A}'),
+    ("inter", '\u{2229}'),
+    ("without", '\u{2216}'),
+    ("nothing", '\u{2205}'),
+    ("diameter", '\u{2300}'),
+    ("forall", '\u{2200}'),
+    ("exists", '\u{2203}'),
+    ("not", '\u{00AC}'),
+    ("and", '\u{2227}'),
+    ("or", '\u{2228}'),
+    ("arrow.r", '\u{2192}'),
+    ("arrow.l", '\u{2190}'),
+    ("arrow.l.r", '\u{2194}'),
+    ("arrow.t", '\u{2191}'),
+    ("arrow.b", '\u{2193}'),
+    ("arrow.r.double", '\u{21D2}'),
+    ("arrow.l.double", '\u{21D0}'),
+    ("arrow.l.r.double", '\u{21D4}'),
+    ("arrow.r.bar", '\u{21A6}'),
+    ("arrow.r.double.long", '\u{27F9}'),
+    ("arrow.l.r.double.long", '\u{27FA}'),
+    ("sum", '\u{2211}'),
+    ("product", '\u{220F}'),
+    ("product.co", '\u{2210}'),
+    ("union.big", '\u{22C3}'),
+    ("inter.big", '\u{22C2}'),
+    ("integral", '\u{222B}'),
+    ("integral.double", '\u{222C}'),
+    ("integral.triple", '\u{222D}'),
+    ("integral.cont", '\u{222E}'),
+    ("diff", '\u{2202}'),
+    ("nabla", '\u{2207}'),
+    ("infinity", '\u{221E}'),
+    ("oo", '\u{221E}'),
+    ("dots.h", '\u{2026}'),
+    ("dots.c", '\u{22EF}'),
+    ("dots.v", '\u{22EE}'),
+    ("dots.down", '\u{22F1}'),
+    ("chevron.l", '\u{27E8}'),
+    ("chevron.r", '\u{27E9}'),
+    ("ell", '\u{2113}'),
+    ("planck", '\u{210F}'),
+    ("alef", '\u{2135}'),
+    ("angle", '\u{2220}'),
+    ("triangle.t", '\u{25B3}'),
+    ("square", '\u{25A1}'),
+    ("prime", '\u{2032}'),
+    ("dagger", '\u{2020}'),
+    ("degree", '\u{00B0}'),
+];
+
+/// Typst shorthands and their characters, longest first so `<=>` wins over `<=`
+const TYPST_UNICODE_SHORTHANDS: &[(&str, char)] = &[
+    ("<==>", '\u{27FA}'),
+    ("==>", '\u{27F9}'),
+    ("<=>", '\u{21D4}'),
+    ("|->", '\u{21A6}'),
+    ("<->", '\u{2194}'),
+    ("...", '\u{2026}'),
+    ("->", '\u{2192}'),
+    ("<-", '\u{2190}'),
+    ("=>", '\u{21D2}'),
+    ("<=", '\u{2264}'),
+    (">=", '\u{2265}'),
+    ("!=", '\u{2260}'),
+    ("<<", '\u{226A}'),
+    (">>", '\u{226B}'),
+];
+
+static TYPST_UNICODE_MAP: LazyLock<HashMap<&'static str, char>> =
+    LazyLock::new(|| TYPST_UNICODE_SYMBOLS.iter().copied().collect());
+
+/// Replace the Typst symbol names and shorthands in `TYPST_UNICODE_SYMBOLS` and
+/// `TYPST_UNICODE_SHORTHANDS` with their characters.
+///
+/// Names are matched whole, modifiers included, so `arrow.r.squiggly` stays as it
+/// is; string literals, field accesses and function calls such as `angle(x)` are
+/// left alone.
+pub(crate) fn unicode_symbols(typst: &str) -> String {
+    rewrite_typst_identifiers(
+        typst,
+        |out, name, access, call| match TYPST_UNICODE_MAP.get(name) {
+            Some(&symbol) if !access && !call => out.push(symbol),
+            _ => out.push_str(name),
+        },
+        |out, rest| {
+            for &(shorthand, symbol) in TYPST_UNICODE_SHORTHANDS {
+                let len = shorthand.len();
+                if rest.len() >= len && rest[..len].iter().copied().eq(shorthand.chars()) {
+                    out.push(symbol);
+                    return len;
+                }
+            }
+            0
+        },
+    )
+}
+
+/// Whether `options` ask for `outputUnicode`
+pub(crate) fn wants_unicode_output(options: Option<&JsOptions>) -> bool {
+    options
+        .and_then(|opts| opts.get("outputUnicode"))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// Build the JS options object passed to the converter functions
+pub(crate) fn build_js_options<'js>(
+    ctx: &Ctx<'js>,
+    opts: &JsOptions,
+) -> Result<Object<'js>, ConvertError> {
+    let runtime_error = |what: &'static str| {
+        move |e: rquickjs::Error| ConvertError::Runtime(format!("{}: {}", what, e))
+    };
+    let js_options =
+        Object::new(ctx.clone()).map_err(runtime_error("Failed to create JS object"))?;
+
+    // Set properties directly without JSON serialization
+    for (key, value) in opts.iter() {
+        match value {
+            serde_json::Value::Bool(b) => {
+                js_options
+                    .set(key.as_str(), *b)
+                    .map_err(runtime_error("Failed to set bool property"))?;
+            }
+            serde_json::Value::String(s) => {
+                js_options
+                    .set(key.as_str(), s.as_str())
+                    .map_err(runtime_error("Failed to set string property"))?;
+            }
+            serde_json::Value::Number(n) => {
+                js_options
+                    .set(key.as_str(), js_number(ctx, n))
+                    .map_err(runtime_error("Failed to set number property"))?;
+            }
+            serde_json::Value::Object(obj) => {
+                let nested_obj = Object::new(ctx.clone())
+                    .map_err(runtime_error("Failed to create nested object"))?;
+                for (k, v) in obj.iter() {
+                    match v {
+                        serde_json::Value::String(s) => {
+                            nested_obj
+                                .set(k.as_str(), s.as_str())
+                                .map_err(runtime_error("Failed to set nested property"))?;
+                        }
+                        serde_json::Value::Bool(b) => {
+                            nested_obj
+                                .set(k.as_str(), *b)
+                                .map_err(runtime_error("Failed to set nested property"))?;
+                        }
+                        serde_json::Value::Number(n) => {
+                            nested_obj
+                                .set(k.as_str(), js_number(ctx, n))
+                                .map_err(runtime_error("Failed to set nested property"))?;
+                        }
+                        // `None` values are forwarded as JS null
+                        serde_json::Value::Null => {
+                            nested_obj
+                                .set(k.as_str(), rquickjs::Value::new_null(ctx.clone()))
+                                .map_err(runtime_error("Failed to set nested property"))?;
+                        }
+                        _ => {
+                            let js_val = ctx.json_parse(v.to_string()).map_err(|e| {
+                                ConvertError::InvalidOptions(format!("Options parse failed: {}", e))
+                            })?;
+                            nested_obj
+                                .set(k.as_str(), js_val)
+                                .map_err(runtime_error("Failed to set nested property"))?;
+                        }
+                    }
+                }
+                js_options
+                    .set(key.as_str(), nested_obj)
+                    .map_err(runtime_error("Failed to set object property"))?;
+            }
+            serde_json::Value::Array(items) if items.iter().all(is_scalar) => {
+                let array =
+                    Array::new(ctx.clone()).map_err(runtime_error("Failed to create array"))?;
+                for (index, item) in items.iter().enumerate() {
+                    let item = match item {
+                        serde_json::Value::Bool(b) => rquickjs::Value::new_bool(ctx.clone(), *b),
+                        serde_json::Value::Number(n) => js_number(ctx, n),
+                        serde_json::Value::String(s) => rquickjs::String::from_str(ctx.clone(), s)
+                            .map_err(runtime_error("Failed to create string"))?
+                            .into_value(),
+                        _ => rquickjs::Value::new_null(ctx.clone()),
+                    };
+                    array
+                        .set(index, item)
+                        .map_err(runtime_error("Failed to set array item"))?;
+                }
+                js_options
+                    .set(key.as_str(), array)
+                    .map_err(runtime_error("Failed to set array property"))?;
+            }
+            _ => {
+                // Fallback to JSON for other types
+                let js_val = ctx.json_parse(value.to_string()).map_err(|e| {
+                    ConvertError::InvalidOptions(format!("Options parse failed: {}", e))
+                })?;
+                js_options
+                    .set(key.as_str(), js_val)
+                    .map_err(runtime_error("Failed to set property"))?;
+            }
+        }
+    }
+    Ok(js_options)
+}
+
+/// `n` as a JS number: an int when it fits in 32 bits (as JS integer options
+/// expect), a float otherwise. Other exact `i64`s become the float of the same
+/// integer value, which `Number.isInteger` still accepts
+fn js_number<'js>(ctx: &Ctx<'js>, n: &serde_json::Number) -> rquickjs::Value<'js> {
+    match n.as_i64() {
+        Some(i) => match i32::try_from(i) {
+            Ok(i) => rquickjs::Value::new_int(ctx.clone(), i),
+            Err(_) => rquickjs::Value::new_float(ctx.clone(), i as f64),
+        },
+        None => rquickjs::Value::new_float(ctx.clone(), n.as_f64().unwrap_or(f64::NAN)),
+    }
+}
+
+/// Whether `value` is set directly as an array item, without the JSON fallback
+fn is_scalar(value: &serde_json::Value) -> bool {
+    !matches!(
+        value,
+        serde_json::Value::Array(_) | serde_json::Value::Object(_)
+    )
+}
+
+/// A bundle function saved by `Converter::from_parts`, usable inside `ctx`
+pub(crate) fn restore_function<'js>(
+    ctx: &Ctx<'js>,
+    func: &Persistent<Function<'static>>,
+) -> Result<Function<'js>, ConvertError> {
+    func.clone()
+        .restore(ctx)
+        .map_err(|e| ConvertError::Engine(format!("Failed to restore JS function: {}", e)))
+}
+
+/// Call a converter function on `input`, with the options object if there is one
+pub(crate) fn call_converter<'js>(
+    ctx: &Ctx<'js>,
+    func: &Function<'js>,
+    input: &str,
+    js_options: Option<&Object<'js>>,
+) -> Result<String, CaughtError<'js>> {
+    match js_options {
+        Some(js_options) => func.call((input, js_options.clone())).catch(ctx),
+        None => func.call((input,)).catch(ctx),
+    }
+}
+
+/// A QuickJS engine running the converter bundle.
+///
+/// The engine is single-threaded: a `Converter` stays on the thread that created it.
+pub struct Converter {
+    // The saved functions must be released before the context and runtime,
+    // so they are declared (and dropped) first
+    /// The bundle's global `tex2typst` function
+    pub(crate) tex2typst_fn: Persistent<Function<'static>>,
+    /// The bundle's global `typst2tex` function
+    pub(crate) typst2tex_fn: Persistent<Function<'static>>,
+    /// Whether the bundle honours `outputUnicode` itself, probed on first use
+    native_unicode: OnceCell<bool>,
+    /// Answers of `is_unknown_command`, keyed by command (e.g. `\pdv`) and whether
+    /// the `handlePhysics` wrapper took part
+    unknown_commands: RefCell<HashMap<(String, bool), bool>>,
+    /// Bytes the engine held once the bundle and extensions were loaded
+    loaded_memory: usize,
+    /// Set once the engine ran out of memory or panicked and may be inconsistent;
+    /// shared with the converters `isolated` made, which use the same runtime
+    poisoned: Rc<Cell<bool>>,
+    pub(crate) rt: Runtime,
+    pub(crate) ctx: Context,
+}
+
+/// Characters of a failed bundle's stack trace quoted in its load error
+const LOAD_ERROR_STACK_CHARS: usize = 200;
+
+/// Crate version and the memory the OS has available, appended to errors creating
+/// an engine so deployment problems such as a container running out of memory
+/// show in the message itself
+fn creation_diagnostics() -> String {
+    let mut diagnostics = format!("tex2typst {}", env!("CARGO_PKG_VERSION"));
+    if let Some(available) = available_memory() {
+        diagnostics.push_str(&format!(
+            ", {} MiB memory available",
+            available / (1024 * 1024)
+        ));
+    }
+    diagnostics
+}
+
+/// Bytes of memory available to new allocations according to `/proc/meminfo`;
+/// `None` off Linux or where it cannot be read
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kib = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+/// `ConvertError::Runtime` for QuickJS failing to create `what`
+fn creation_error(what: &str, error: rquickjs::Error) -> ConvertError {
+    ConvertError::Runtime(format!(
+        "Failed to create the JS {}: {} ({})",
+        what,
+        error,
+        creation_diagnostics()
+    ))
+}
+
+/// `ConvertError::Engine` for the bundle or one of its wrappers throwing while
+/// evaluated, quoting the start of the stack trace
+fn load_error(error: CaughtError) -> ConvertError {
+    let exception = format_js_exception(error);
+    let mut message = format!("JS Load Error: {}", exception.message);
+    if let Some(stack) = exception.stack.as_deref().map(str::trim)
+        && !stack.is_empty()
+    {
+        let quoted: String = stack.chars().take(LOAD_ERROR_STACK_CHARS).collect();
+        let ellipsis = if quoted.len() < stack.len() {
+            "..."
+        } else {
+            ""
+        };
+        message.push_str(&format!("\nStack trace: {}{}", quoted, ellipsis));
+    }
+    message.push_str(&format!(" ({})", creation_diagnostics()));
+    ConvertError::Engine(message)
+}
+
+/// Evaluate `setup`, `source`, the option wrappers and `extensions` into `ctx` and
+/// save its `tex2typst` and `typst2tex` globals
+fn load_context(
+    ctx: &Context,
+    source: &str,
+    setup: impl FnOnce(&Ctx<'_>) -> Result<(), ConvertError>,
+    extensions: &[String],
+) -> Result<(Persistent<Function<'static>>, Persistent<Function<'static>>), ConvertError> {
+    ctx.with(|ctx| -> Result<_, ConvertError> {
+        setup(&ctx)?;
+        ctx.eval::<(), _>(source).catch(&ctx).map_err(load_error)?;
+        for wrapper in [MAP_OPTIONS_JS, PHYSICS_JS] {
+            ctx.eval::<(), _>(wrapper).catch(&ctx).map_err(load_error)?;
+        }
+        for (index, extension) in extensions.iter().enumerate() {
+            ctx.eval::<(), _>(extension.as_str())
+                .catch(&ctx)
+                .map_err(|e| {
+                    ConvertError::Engine(format!(
+                        "JS extension {} failed: {}",
+                        index,
+                        format_js_exception(e)
+                    ))
+                })?;
+        }
+        let globals = ctx.globals();
+        let resolve = |name: &str| -> Result<Persistent<Function<'static>>, ConvertError> {
+            let func: Function = globals.get(name).map_err(|_| {
+                ConvertError::Engine(format!(
+                    "Global function '{}' not found; the JS bundle appears corrupted",
+                    name
+                ))
+            })?;
+            Ok(Persistent::save(&ctx, func))
+        };
+        Ok((resolve("tex2typst")?, resolve("typst2tex")?))
+    })
+}
+
+impl Converter {
+    /// Create a converter running the embedded JS bundle
+    pub fn new() -> Result<Self, ConvertError> {
+        Self::from_source(JS_CODE)
+    }
+
+    /// Create a converter running the converter bundle `source`, which must define
+    /// global `tex2typst` and `typst2tex` functions
+    pub fn from_source(source: &str) -> Result<Self, ConvertError> {
+        Self::from_parts(source, |_| Ok(()), &[])
+    }
+
+    /// Create a converter running `setup`, then `source`, the wrappers implementing
+    /// `symbolMap`, `environmentMap` and `handlePhysics`, and then each of
+    /// `extensions`.
+    ///
+    /// The `tex2typst` and `typst2tex` globals are resolved once here, after the
+    /// extensions had a chance to replace them, so a bundle missing them fails now
+    /// rather than on the first call.
+    pub(crate) fn from_parts(
+        source: &str,
+        setup: impl FnOnce(&Ctx<'_>) -> Result<(), ConvertError>,
+        extensions: &[String],
+    ) -> Result<Self, ConvertError> {
+        let rt = Runtime::new().map_err(|e| creation_error("runtime", e))?;
+        rt.set_max_stack_size(JS_MAX_STACK_SIZE);
+        let ctx = Context::full(&rt).map_err(|e| creation_error("context", e))?;
+        let (tex2typst_fn, typst2tex_fn) = load_context(&ctx, source, setup, extensions)?;
+
+        let loaded_memory = usize::try_from(rt.memory_usage().malloc_size).unwrap_or(0);
+        Ok(Converter {
+            tex2typst_fn,
+            typst2tex_fn,
+            native_unicode: OnceCell::new(),
+            unknown_commands: RefCell::default(),
+            loaded_memory,
+            poisoned: Rc::new(Cell::new(false)),
+            rt,
+            ctx,
+        })
+    }
+
+    /// Create a converter running the converter bundle `source` in a fresh context on
+    /// this converter's runtime, for input that must not see what earlier conversions
+    /// did to the JS globals
+    pub fn isolated_from_source(&self, source: &str) -> Result<Self, ConvertError> {
+        self.isolated(source, |_| Ok(()), &[])
+    }
+
+    /// Create a converter running `setup`, `source`, the wrappers and `extensions`
+    /// like [`Converter::from_parts`], but in a fresh context on this converter's
+    /// runtime.
+    ///
+    /// Nothing a conversion does to the globals of one context is visible in the
+    /// other. The runtime, with its memory limit, stays shared, so running out of
+    /// memory poisons both converters.
+    pub(crate) fn isolated(
+        &self,
+        source: &str,
+        setup: impl FnOnce(&Ctx<'_>) -> Result<(), ConvertError>,
+        extensions: &[String],
+    ) -> Result<Self, ConvertError> {
+        let ctx = Context::full(&self.rt).map_err(|e| creation_error("context", e))?;
+        let (tex2typst_fn, typst2tex_fn) = load_context(&ctx, source, setup, extensions)?;
+        Ok(Converter {
+            tex2typst_fn,
+            typst2tex_fn,
+            native_unicode: OnceCell::new(),
+            unknown_commands: RefCell::default(),
+            loaded_memory: self.loaded_memory,
+            poisoned: self.poisoned.clone(),
+            rt: self.rt.clone(),
+            ctx,
+        })
+    }
+
+    /// Evaluate the converter bundle `source` again in this converter's context,
+    /// e.g. after the bundle file changed, and switch to the `tex2typst` and
+    /// `typst2tex` globals it defines
+    pub fn reload_from_source(&mut self, source: &str) -> Result<(), ConvertError> {
+        self.reload(source, &[])
+    }
+
+    /// Evaluate `source`, the wrappers and `extensions` again in this converter's
+    /// context, like [`Converter::from_parts`] without the setup.
+    ///
+    /// Globals of the old bundle that the new one does not overwrite stay defined.
+    /// If loading fails, the converter keeps calling the functions it had.
+    pub(crate) fn reload(
+        &mut self,
+        source: &str,
+        extensions: &[String],
+    ) -> Result<(), ConvertError> {
+        let (tex2typst_fn, typst2tex_fn) = load_context(&self.ctx, source, |_| Ok(()), extensions)?;
+        self.tex2typst_fn = tex2typst_fn;
+        self.typst2tex_fn = typst2tex_fn;
+        self.native_unicode = OnceCell::new();
+        self.unknown_commands.get_mut().clear();
+        Ok(())
+    }
+
+    /// Bytes allocated by the JS engine
+    pub fn memory_used(&self) -> i64 {
+        self.rt.memory_usage().memory_used_size
+    }
+
+    /// Allocation statistics of the JS engine
+    pub fn memory_usage(&self) -> rquickjs::qjs::JSMemoryUsage {
+        self.rt.memory_usage()
+    }
+
+    /// Collect the engine's unreachable objects now, such as reference cycles the
+    /// converter left behind
+    pub fn run_gc(&self) {
+        self.rt.run_gc();
+    }
+
+    /// Collect garbage automatically once the engine has allocated `threshold` bytes.
+    ///
+    /// After each automatic collection QuickJS moves the threshold to half again the
+    /// memory still in use; `usize::MAX` turns automatic collection off.
+    pub fn set_gc_threshold(&self, threshold: usize) {
+        self.rt.set_gc_threshold(threshold);
+    }
+
+    /// Cap the memory the engine may allocate beyond what loading the bundle took;
+    /// `None` lifts the cap
+    pub fn set_memory_limit(&self, headroom: Option<usize>) {
+        // QuickJS reads a limit of 0 as no limit
+        self.rt.set_memory_limit(
+            headroom.map_or(0, |headroom| self.loaded_memory.saturating_add(headroom)),
+        );
+    }
+
+    /// Whether the engine ran out of memory or panicked; a poisoned converter should
+    /// be dropped and created anew
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.get()
+    }
+
+    /// Mark the converter as no longer safe to use
+    pub(crate) fn poison(&self) {
+        self.poisoned.set(true);
+    }
+
+    /// The error for `error`, which ran the engine out of memory while converting
+    /// `input`, poisoning the converter
+    pub(crate) fn out_of_memory(&self, input: &str, error: CaughtError) -> ConvertError {
+        self.poison();
+        ConvertError::OutOfMemory(format!(
+            "JS engine ran out of memory converting {} bytes of input: {}",
+            input.len(),
+            format_js_exception(error).message
+        ))
+    }
+
+    /// Run `run`, turning a panic into [`ConvertError::Engine`] and poisoning the
+    /// converter, so a failure inside the engine never unwinds into the caller
+    pub(crate) fn guard<R, E: From<ConvertError>>(
+        &self,
+        run: impl FnOnce() -> Result<R, E>,
+    ) -> Result<R, E> {
+        catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|payload| {
+            self.poison();
+            Err(ConvertError::Engine(format!(
+                "Internal error in the JS engine: {}",
+                panic_message(payload.as_ref())
+            ))
+            .into())
+        })
+    }
+
+    /// Run `func` on `js_input`; a rejection is reported against `input`
+    pub(crate) fn call(
+        &self,
+        func: &Persistent<Function<'static>>,
+        js_input: &str,
+        input: &str,
+        options: Option<&JsOptions>,
+    ) -> Result<String, ConvertError> {
+        self.guard(|| {
+            self.ctx.with(|ctx| {
+                let func = restore_function(&ctx, func)?;
+                // Direct object construction (OPTIMIZATION: avoid full JSON serialization)
+                let js_options = options
+                    .map(|opts| build_js_options(&ctx, opts))
+                    .transpose()?;
+                call_converter(&ctx, &func, js_input, js_options.as_ref()).map_err(|e| {
+                    if is_out_of_memory(&e) {
+                        return self.out_of_memory(input, e);
+                    }
+                    ConvertError::Conversion {
+                        input: input.to_string(),
+                        exception: format_js_exception(e),
+                    }
+                })
+            })
+        })
+    }
+
+    /// Whether `tex2typst` output under `options` still needs `unicode_symbols`:
+    /// `outputUnicode` is set and the bundle does not already emit `α` for `\alpha`
+    pub(crate) fn needs_unicode_pass(&self, options: Option<&JsOptions>) -> bool {
+        wants_unicode_output(options)
+            && !*self.native_unicode.get_or_init(|| {
+                let probe = JsOptions::from_iter([(
+                    "outputUnicode".to_string(),
+                    serde_json::Value::Bool(true),
+                )]);
+                self.call(&self.tex2typst_fn, r"\alpha", r"\alpha", Some(&probe))
+                    .is_ok_and(|typst| typst == "\u{03B1}")
+            })
+    }
+
+    /// Whether the converter rejects `command` (e.g. `\pdv`) as an unknown token.
+    ///
+    /// Non-strict mode silently passes unknown commands through, so the bare command
+    /// is converted once in strict mode and the answer is cached.
+    pub fn is_unknown_command(&self, command: &str) -> Result<bool, ConvertError> {
+        self.guard(|| {
+            self.ctx
+                .with(|ctx| self.probe_unknown_command(&ctx, command, false))
+        })
+    }
+
+    /// [`Converter::is_unknown_command`] in an entered context. With `physics`, the
+    /// command is probed with three braced arguments through the `handlePhysics`
+    /// wrapper, so the `physics` macros it rewrites count as known.
+    fn probe_unknown_command(
+        &self,
+        ctx: &Ctx<'_>,
+        command: &str,
+        physics: bool,
+    ) -> Result<bool, ConvertError> {
+        let key = (command.to_string(), physics);
+        if let Some(&unknown) = self.unknown_commands.borrow().get(&key) {
+            return Ok(unknown);
+        }
+
+        let mut strict =
+            JsOptions::from_iter([("nonStrict".to_string(), serde_json::Value::Bool(false))]);
+        let probe = if physics {
+            strict.insert("handlePhysics".to_string(), serde_json::Value::Bool(true));
+            format!("{}{{x}}{{y}}{{z}}", command)
+        } else {
+            command.to_string()
+        };
+        let func = restore_function(ctx, &self.tex2typst_fn)?;
+        let strict = build_js_options(ctx, &strict)?;
+        let expected = format!("Unknown token: {}", command);
+        let unknown = match call_converter(ctx, &func, &probe, Some(&strict)) {
+            Ok(_) => false,
+            Err(e) if is_out_of_memory(&e) => return Err(self.out_of_memory(&probe, e)),
+            Err(e) => format_js_exception(e).message.lines().next() == Some(expected.as_str()),
+        };
+        self.unknown_commands.borrow_mut().insert(key, unknown);
+        Ok(unknown)
+    }
+
+    /// `options` with the `replaceUnknownCommands` entries giving Typst for an unknown
+    /// command added to `symbolMap`, which `js/map_options.js` substitutes; the bundle
+    /// itself reads neither `replaceUnknownCommands` nor `ignoreUnknownCommands`.
+    ///
+    /// Entries of `symbolMap` for the same command take precedence.
+    fn unknown_command_options<'a>(
+        &self,
+        ctx: &Ctx<'_>,
+        options: &'a JsOptions,
+    ) -> Result<Cow<'a, JsOptions>, ConvertError> {
+        let Some(replacements) = options
+            .get("replaceUnknownCommands")
+            .and_then(serde_json::Value::as_object)
+        else {
+            return Ok(Cow::Borrowed(options));
+        };
+        let physics = handles_physics(options);
+        let mut symbols = options
+            .get("symbolMap")
+            .and_then(serde_json::Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let mut added = false;
+        for (name, typst) in replacements {
+            // `None` and empty replacements drop the command instead
+            let Some(typst) = typst.as_str().filter(|typst| !typst.is_empty()) else {
+                continue;
+            };
+            let command = format!("\\{}", name.trim_start_matches('\\'));
+            if !defines_command(options, &command)
+                && self.probe_unknown_command(ctx, &command, physics)?
+            {
+                symbols.insert(command[1..].to_string(), typst.into());
+                added = true;
+            }
+        }
+        if !added {
+            return Ok(Cow::Borrowed(options));
+        }
+        let mut options = options.clone();
+        options.insert("symbolMap".to_string(), serde_json::Value::Object(symbols));
+        Ok(Cow::Owned(options))
+    }
+
+    /// `tex` with the unknown commands `replaceUnknownCommands` maps to `null` or an
+    /// empty string replaced by an empty group, which keeps their arguments apart
+    /// from the surrounding math, and with `ignoreUnknownCommands` the other unknown
+    /// commands of `tex`, which are to pass through as they are.
+    ///
+    /// Commands defined by `customTexMacros` or mapped by `symbolMap` are kept.
+    fn unknown_commands_in<'a>(
+        &self,
+        ctx: &Ctx<'_>,
+        tex: &'a str,
+        options: Option<&JsOptions>,
+    ) -> Result<(Cow<'a, str>, Vec<String>), ConvertError> {
+        let Some(opts) = options else {
+            return Ok((Cow::Borrowed(tex), Vec::new()));
+        };
+        let ignore = opts
+            .get("ignoreUnknownCommands")
+            .and_then(serde_json::Value::as_bool)
+            == Some(true);
+        let dropped_names: Vec<&str> = opts
+            .get("replaceUnknownCommands")
+            .and_then(serde_json::Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter(|(_, typst)| typst.as_str().is_none_or(str::is_empty))
+            .map(|(name, _)| name.trim_start_matches('\\'))
+            .collect();
+        if !ignore && dropped_names.is_empty() {
+            return Ok((Cow::Borrowed(tex), Vec::new()));
+        }
+
+        let physics = handles_physics(opts);
+        let mut dropped = Vec::new();
+        let mut passed = Vec::new();
+        for command in tex_command_names(tex) {
+            let drop = dropped_names.contains(&&command[1..]);
+            if (ignore || drop)
+                && !defines_command(opts, &command)
+                && self.probe_unknown_command(ctx, &command, physics)?
+            {
+                if drop {
+                    dropped.push(command);
+                } else {
+                    passed.push(command);
+                }
+            }
+        }
+        Ok((replace_tex_commands(tex, &dropped, "{}"), passed))
+    }
+
+    /// Convert LaTeX/TeX math to Typst
+    pub fn tex_to_typst(&self, tex: &str, options: &Options) -> Result<String, ConvertError> {
+        self.convert_tex(tex, options.tex2typst_js_options()?.as_ref())
+    }
+
+    /// Convert `tex` to Typst with the JS `options`, including the passes the bundle
+    /// leaves to Rust: unknown commands and `outputUnicode`
+    pub(crate) fn convert_tex(
+        &self,
+        tex: &str,
+        js_options: Option<&JsOptions>,
+    ) -> Result<String, ConvertError> {
+        let mut typst = String::new();
+        self.tex_to_typst_each(&[tex], js_options, |_, _, _, convert| {
+            typst = convert()?;
+            Ok::<_, ConvertError>(())
+        })?;
+        Ok(typst)
+    }
+
+    /// Convert Typst math to LaTeX
+    pub fn typst_to_tex(&self, typst: &str, options: &Options) -> Result<String, ConvertError> {
+        let js_options = options.typst2tex_js_options()?;
+        let expanded = expand_typst_macros(typst, js_options.as_ref());
+        self.call(&self.typst2tex_fn, &expanded, typst, js_options.as_ref())
+    }
+
+    /// Convert many LaTeX/TeX strings in one engine entry, reusing the options
+    /// object; each item gets its own result, except that running out of memory
+    /// fails the whole batch
+    pub fn tex_to_typst_batch<S: AsRef<str>>(
+        &self,
+        tex_list: &[S],
+        options: &Options,
+    ) -> Result<Vec<Result<String, ConvertError>>, ConvertError> {
+        let js_options = options.tex2typst_js_options()?;
+        let mut results = Vec::with_capacity(tex_list.len());
+        self.tex_to_typst_each(tex_list, js_options.as_ref(), |_, _, _, convert| {
+            match convert() {
+                // The engine is poisoned, so the rest of the batch cannot run
+                Err(e @ ConvertError::OutOfMemory(_)) => return Err(e),
+                result => results.push(result),
+            }
+            Ok(())
+        })?;
+        Ok(results)
+    }
+
+    /// Convert `tex_list` to Typst in a single context entry, sharing one options
+    /// object. `item` gets each item's index and input with a closure converting
+    /// it, and decides whether to convert, what to keep and when to stop the batch.
+    pub(crate) fn tex_to_typst_each<S: AsRef<str>, E: From<ConvertError>>(
+        &self,
+        tex_list: &[S],
+        js_options: Option<&JsOptions>,
+        mut item: impl FnMut(
+            &Ctx<'_>,
+            usize,
+            &str,
+            &mut dyn FnMut() -> Result<String, ConvertError>,
+        ) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let unicode_pass = self.needs_unicode_pass(js_options);
+        self.guard(|| {
+            self.ctx.with(|ctx| {
+                let func = restore_function(&ctx, &self.tex2typst_fn)?;
+                let js_options = js_options
+                    .map(|opts| self.unknown_command_options(&ctx, opts))
+                    .transpose()?;
+                let js_object = js_options
+                    .as_deref()
+                    .map(|opts| build_js_options(&ctx, opts))
+                    .transpose()?;
+                for (index, tex) in tex_list.iter().enumerate() {
+                    let tex = tex.as_ref();
+                    let mut convert = || {
+                        let text = apply_text_mode(tex, js_options.as_deref());
+                        let (input, passed) =
+                            self.unknown_commands_in(&ctx, &text, js_options.as_deref())?;
+                        let item_object;
+                        let js_object = match js_options.as_deref() {
+                            Some(opts) if !passed.is_empty() => {
+                                item_object =
+                                    build_js_options(&ctx, &pass_through_options(opts, &passed))?;
+                                Some(&item_object)
+                            }
+                            _ => js_object.as_ref(),
+                        };
+                        match call_converter(&ctx, &func, &input, js_object) {
+                            Ok(typst) if unicode_pass => Ok(unicode_symbols(&typst)),
+                            Ok(typst) => Ok(typst),
+                            Err(e) if is_out_of_memory(&e) => Err(self.out_of_memory(tex, e)),
+                            Err(e) => Err(ConvertError::Conversion {
+                                input: tex.to_string(),
+                                exception: format_js_exception(e),
+                            }),
+                        }
+                    };
+                    item(&ctx, index, tex, &mut convert)?;
+                }
+                Ok(())
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tex_to_typst() {
+        let converter = Converter::new().unwrap();
+        let options = Options::default();
+        assert_eq!(
+            converter.tex_to_typst(r"\frac{1}{2}", &options).unwrap(),
+            "1/2"
+        );
+        let options = Options {
+            frac_to_slash: Some(false),
+            infty_to_oo: Some(true),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\frac{1}{\infty}", &options)
+                .unwrap(),
+            "frac(1, oo)"
+        );
+        let options = Options {
+            symbol_map: Some(HashMap::from([("epsilon".to_string(), "E".to_string())])),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter.tex_to_typst(r"\epsilon^2", &options).unwrap(),
+            "E^2"
+        );
+        let options = Options {
+            environment_map: Some(HashMap::from([("v".to_string(), "vec".to_string())])),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\begin{v} a \\ b \end{v}", &options)
+                .unwrap(),
+            "vec(a; b)"
+        );
+        let options = Options {
+            output_unicode: Some(true),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\alpha \times \beta \leq \infty \text{alpha}", &options)
+                .unwrap(),
+            "\u{03B1} \u{00D7} \u{03B2} \u{2264} \u{221E} \"alpha\""
+        );
+    }
+
+    #[test]
+    fn unicode_output() {
+        assert_eq!(
+            unicode_symbols("sum_(i in NN) x_i -> oo, arrow.r.squiggly"),
+            "\u{2211}_(i \u{2208} NN) x_i \u{2192} \u{221E}, arrow.r.squiggly"
+        );
+        // Calls, field accesses and strings keep their names
+        assert_eq!(
+            unicode_symbols("angle(x) #alpha \"pi\" <=> pi.alt"),
+            "angle(x) #alpha \"pi\" \u{21D4} \u{03D6}"
+        );
+        // A bundle emitting Unicode itself is left to do so
+        let converter = Converter::from_source(
+            "var tex2typst = (tex, options) => options && options.outputUnicode ? '\\u03b1' : 'alpha';\
+             var typst2tex = tex2typst;",
+        )
+        .unwrap();
+        let options = Options {
+            output_unicode: Some(true),
+            ..Options::default()
+        };
+        assert!(!converter.needs_unicode_pass(options.tex2typst_js_options().unwrap().as_ref()));
+        assert_eq!(converter.tex_to_typst("x", &options).unwrap(), "\u{03B1}");
+    }
+
+    #[test]
+    fn text_mode() {
+        let converter = Converter::new().unwrap();
+        let mode = |mode: &str| Options {
+            handle_text_mode: Some(mode.to_string()),
+            ..Options::default()
+        };
+        let tex = r"x \text{ if } y";
+        assert_eq!(
+            converter.tex_to_typst(tex, &mode("verbatim")).unwrap(),
+            "x \" if \" y"
+        );
+        assert_eq!(
+            converter.tex_to_typst(tex, &mode("convert")).unwrap(),
+            "x i f y"
+        );
+        assert_eq!(converter.tex_to_typst(tex, &mode("strip")).unwrap(), "x y");
+        assert_eq!(
+            rewrite_text_groups(r"\text{a\}b} + \textbf{c} + \text{", true),
+            r"{} + \textbf{c} + \text{"
+        );
+    }
+
+    #[test]
+    fn unknown_commands() {
+        let converter = Converter::new().unwrap();
+        assert!(converter.is_unknown_command(r"\notacommand").unwrap());
+        assert!(!converter.is_unknown_command(r"\alpha").unwrap());
+
+        let options = Options {
+            ignore_unknown_commands: Some(true),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\alpha + \notacommand{x} y", &options)
+                .unwrap(),
+            r"alpha + \notacommand x y"
+        );
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\alpha + \notacommand{x} y", &Options::default())
+                .unwrap(),
+            "alpha + notacommand x y"
+        );
+        let options = Options {
+            replace_unknown_commands: Some(HashMap::from([
+                ("notacommand".to_string(), Some("beta".to_string())),
+                ("dropme".to_string(), None),
+                ("alpha".to_string(), Some("gamma".to_string())),
+            ])),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .tex_to_typst(r"\alpha + \notacommand + \dropme{x}", &options)
+                .unwrap(),
+            "alpha + beta + x"
+        );
+        assert_eq!(
+            tex_command_names(r"\a + \alpha \\ \{ \a"),
+            [r"\a", r"\alpha"]
+        );
+        assert_eq!(
+            replace_tex_commands(r"\a + \alpha \\a", &[r"\a".to_string()], "{}"),
+            r"{} + \alpha \\a"
+        );
+    }
+
+    #[test]
+    fn typst_to_tex() {
+        let converter = Converter::new().unwrap();
+        let options = Options {
+            custom_typst_macros: Some(HashMap::from([("RR".to_string(), "NN".to_string())])),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter
+                .typst_to_tex("alpha", &Options::default())
+                .unwrap(),
+            "\\alpha"
+        );
+        assert_eq!(
+            converter.typst_to_tex("RR", &options).unwrap(),
+            "\\mathbb{N}"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let converter = Converter::new().unwrap();
+        match converter.tex_to_typst(r"\frac{1}{", &Options::default()) {
+            Err(ConvertError::Conversion { input, exception }) => {
+                assert_eq!(input, r"\frac{1}{");
+                assert!(!exception.message.is_empty());
+            }
+            other => panic!("expected a conversion error, got {:?}", other),
+        }
+        let options = Options {
+            handle_text_mode: Some("bogus".to_string()),
+            ..Options::default()
+        };
+        assert!(matches!(
+            converter.tex_to_typst("x", &options),
+            Err(ConvertError::InvalidOptions(_))
+        ));
+        assert!(matches!(
+            Converter::from_source("var x = 1;"),
+            Err(ConvertError::Engine(_))
+        ));
+    }
+
+    #[test]
+    fn out_of_memory() {
+        let converter = Converter::new().unwrap();
+        converter.set_memory_limit(Some(256 * 1024));
+        let tex = "x^2 + ".repeat(50_000) + "y";
+        let err = converter
+            .tex_to_typst(&tex, &Options::default())
+            .unwrap_err();
+        assert!(matches!(err, ConvertError::OutOfMemory(_)), "{:?}", err);
+        assert!(converter.is_poisoned());
+
+        let converter = Converter::new().unwrap();
+        converter.set_memory_limit(Some(256 * 1024));
+        assert_eq!(
+            converter.tex_to_typst("x^2", &Options::default()).unwrap(),
+            "x^2"
+        );
+        assert!(!converter.is_poisoned());
+    }
+
+    #[test]
+    fn isolated() {
+        // Every call clobbers a global that the next call reads
+        let source = "var calls = 0; var tex2typst = (tex) => tex + (++calls); \
+                      var typst2tex = tex2typst;";
+        let converter = Converter::from_source(source).unwrap();
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "x1"
+        );
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "x2"
+        );
+
+        let isolated = converter.isolated_from_source(source).unwrap();
+        assert_eq!(
+            isolated.tex_to_typst("x", &Options::default()).unwrap(),
+            "x1"
+        );
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "x3"
+        );
+
+        // The runtime is shared, so running out of memory in one poisons both
+        isolated.poison();
+        assert!(converter.is_poisoned());
+    }
+
+    #[test]
+    fn reload() {
+        let mut converter =
+            Converter::from_source("var tex2typst = (tex) => 'v1'; var typst2tex = tex2typst;")
+                .unwrap();
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "v1"
+        );
+
+        converter
+            .reload_from_source("var tex2typst = (tex) => 'v2'; var typst2tex = tex2typst;")
+            .unwrap();
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "v2"
+        );
+
+        // A broken bundle leaves the loaded functions in place
+        assert!(converter.reload_from_source("function (").is_err());
+        assert_eq!(
+            converter.tex_to_typst("x", &Options::default()).unwrap(),
+            "v2"
+        );
+    }
+
+    #[test]
+    fn load_error() {
+        let source = "function broken() { throw new Error('bundle broke'); } broken();";
+        let Err(ConvertError::Engine(message)) = Converter::from_source(source) else {
+            panic!("a throwing bundle loaded");
+        };
+        assert!(message.starts_with("JS Load Error: bundle broke\nStack trace: "));
+        assert!(message.contains("at broken"));
+        assert!(message.contains(concat!("tex2typst ", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn batch() {
+        let converter = Converter::new().unwrap();
+        let results = converter
+            .tex_to_typst_batch(&[r"\alpha", r"\frac{1}{", "x^2"], &Options::default())
+            .unwrap();
+        assert_eq!(results[0].as_deref().unwrap(), "alpha");
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_deref().unwrap(), "x^2");
+    }
+
+    #[test]
+    fn js_options() {
+        // Echo the options object, showing how each value arrived in JS
+        let converter = Converter::from_source(
+            "var tex2typst = (tex, options) => JSON.stringify(options, \
+                 (key, value) => Number.isInteger(value) ? 'int:' + value : value);\
+             var typst2tex = tex2typst;",
+        )
+        .unwrap();
+        let options = Options {
+            frac_to_slash: Some(false),
+            js_options: Some(JsOptions::from_iter([
+                ("maxDepth".to_string(), serde_json::json!(8)),
+                ("dropped".to_string(), serde_json::Value::Null),
+            ])),
+            ..Options::default()
+        };
+        let echoed = converter.tex_to_typst("x", &options).unwrap();
+        let echoed: serde_json::Value = serde_json::from_str(&echoed).unwrap();
+        assert_eq!(
+            echoed,
+            serde_json::json!({"fracToSlash": false, "maxDepth": "int:8"})
+        );
+
+        let options = Options {
+            js_options: Some(JsOptions::from_iter([(
+                "blockMathMode".to_string(),
+                serde_json::json!(true),
+            )])),
+            ..Options::default()
+        };
+        assert_eq!(
+            converter.tex_to_typst("x", &options).unwrap(),
+            r#"{"blockMathMode":true}"#
+        );
+        assert!(matches!(
+            converter.typst_to_tex("x", &options),
+            Err(ConvertError::InvalidOptions(_))
+        ));
+    }
+
+    #[test]
+    fn options_order() {
+        // JSON.stringify lists properties in the order they were set
+        let converter = Converter::from_source(
+            "var tex2typst = (tex, options) => JSON.stringify(options);\
+             var typst2tex = tex2typst;",
+        )
+        .unwrap();
+        let options = Options {
+            frac_to_slash: Some(false),
+            non_strict: Some(true),
+            custom_tex_macros: Some(HashMap::from([
+                (r"\b".to_string(), "b".to_string()),
+                (r"\a".to_string(), "a".to_string()),
+            ])),
+            js_options: Some(JsOptions::from_iter([
+                ("zeta".to_string(), serde_json::json!(1)),
+                ("alpha".to_string(), serde_json::json!(2)),
+            ])),
+            ..Options::default()
+        };
+        let expected = r#"{"nonStrict":true,"fracToSlash":false,"customTexMacros":{"\\a":"a","\\b":"b"},"zeta":1,"alpha":2}"#;
+        for _ in 0..100 {
+            assert_eq!(converter.tex_to_typst("x", &options).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn js_option_types() {
+        // Echo `typeof`, integer-ness and value of every option as JS sees it
+        let converter = Converter::from_source(
+            r"
+            var describe = (v) =>
+                Array.isArray(v) ? v.map(describe) : [typeof v, Number.isInteger(v), v];
+            var tex2typst = (tex, options) =>
+                JSON.stringify(options[tex] === undefined ? null : describe(options[tex]));
+            var typst2tex = tex2typst;
+            ",
+        )
+        .unwrap();
+        let echo = |value: serde_json::Value| {
+            let options = Options {
+                js_options: Some(JsOptions::from_iter([("probe".to_string(), value)])),
+                ..Options::default()
+            };
+            let echoed = converter.tex_to_typst("probe", &options).unwrap();
+            serde_json::from_str::<serde_json::Value>(&echoed).unwrap()
+        };
+        assert_eq!(
+            echo(serde_json::json!(3)),
+            serde_json::json!(["number", true, 3])
+        );
+        assert_eq!(
+            echo(serde_json::json!(-7_000_000_000_i64)),
+            serde_json::json!(["number", true, -7_000_000_000_i64])
+        );
+        assert_eq!(
+            echo(serde_json::json!(2.5)),
+            serde_json::json!(["number", false, 2.5])
+        );
+        assert_eq!(
+            echo(serde_json::json!([1, 0.5, "a", true, null])),
+            serde_json::json!([
+                ["number", true, 1],
+                ["number", false, 0.5],
+                ["string", false, "a"],
+                ["boolean", false, true],
+                ["object", false, null]
+            ])
+        );
+        assert_eq!(
+            echo(serde_json::json!([[1], {"k": 2}])),
+            serde_json::json!([[["number", true, 1]], ["object", false, {"k": 2}]])
+        );
+    }
+
+    #[test]
+    fn frame_position() {
+        assert_eq!(
+            JsException::frame_position("Error: x\n    at f (eval_script:5:1668)\n"),
+            Some((5, 1668))
+        );
+        assert_eq!(JsException::frame_position("no frames"), None);
+    }
+}
@@ -0,0 +1,8096 @@
+use crate::core::{
+    ConvertError, HANDLE_TEXT_MODES, JS_CODE, JsException, JsOptions, TEX2TYPST_OPTION_NAMES,
+    TYPST2TEX_OPTION_NAMES, build_js_options, call_converter, expand_typst_macros,
+    format_js_exception, is_out_of_memory, merge_js_options, restore_function, tex_command_names,
+};
+use pyo3::IntoPyObjectExt;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyIterator, PyList, PyString, PyTuple, PyType};
+use rquickjs::function::Rest;
+use rquickjs::{CatchResultExt, Coerced, Ctx, Function, Object};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock, mpsc};
+use std::thread;
+use std::time::Instant;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod converter;
+mod disk_cache;
+mod documents;
+mod units;
+
+#[cfg(feature = "arrow")]
+use arrow::{ArrowStringResults, StringArrayBuffers, convert_arrow_stream, tex2typst_arrow};
+use converter::{Converter, WorkerConverter};
+use disk_cache::{
+    DISK_CACHE, DiskCache, disable_disk_cache, disk_cache_flush, disk_cache_get, disk_cache_insert,
+    disk_cache_key, disk_cache_stats, enable_disk_cache,
+};
+use documents::{
+    DocumentReport, Span, convert_latex_document, convert_markdown, convert_typst_document,
+};
+use units::{UNITS_STYLES, check_units_style, expand_chemistry, expand_siunitx, splice_siunitx};
+
+pyo3::create_exception!(
+    _tex2typst_core,
+    TexParseError,
+    pyo3::exceptions::PyValueError,
+    "Raised when TeX input uses commands the converter does not recognize."
+);
+
+pyo3::create_exception!(
+    _tex2typst_core,
+    EngineError,
+    pyo3::exceptions::PyRuntimeError,
+    "Raised when the JS engine cannot be set up from the bundled converter code."
+);
+
+/// Python exception for a conversion the JS engine rejected: `RecursionError` when it
+/// ran out of stack, `ValueError` otherwise
+fn js_failure(message: String) -> PyErr {
+    if message.contains("Maximum call stack size exceeded") || message.contains("stack overflow") {
+        PyErr::new::<pyo3::exceptions::PyRecursionError, _>(message)
+    } else {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(message)
+    }
+}
+
+/// Characters of an input quoted in an error message before it is cut off
+const ERROR_PREVIEW_CHARS: usize = 120;
+
+/// `input` cut to `ERROR_PREVIEW_CHARS` characters, ending in `...` if anything was cut
+fn input_preview(input: &str) -> Cow<'_, str> {
+    match input.char_indices().nth(ERROR_PREVIEW_CHARS) {
+        Some((end, _)) => Cow::Owned(format!("{}...", &input[..end])),
+        None => Cow::Borrowed(input),
+    }
+}
+
+/// Attach the full `input` to a conversion error as its `input` attribute, so messages
+/// need not embed it
+fn with_input(err: PyErr, input: &str) -> PyErr {
+    Python::attach(|py| {
+        // Built-in exception instances accept new attributes; nothing to do otherwise
+        let _ = err.value(py).setattr("input", input);
+    });
+    err
+}
+
+/// Error for a failed conversion of `input`
+fn conversion_error(input: &str, exception: JsException) -> PyErr {
+    let err = js_failure(format!("Conversion failed: {}", exception));
+    exception.annotate(with_input(err, input))
+}
+
+/// Error for the batch item at `index`, quoting only a preview of its input
+fn batch_item_error(index: usize, input: &str, exception: JsException) -> PyErr {
+    let err = js_failure(format!(
+        "Conversion failed for item {} ({} bytes, '{}'): {}",
+        index,
+        input.len(),
+        input_preview(input),
+        exception
+    ));
+    exception.annotate(with_input(err, input))
+}
+
+impl JsException {
+    /// Attach the parts to `err` as `js_message`, `js_stack` and `js_position`
+    fn annotate(&self, err: PyErr) -> PyErr {
+        Python::attach(|py| {
+            let value = err.value(py);
+            let _ = value.setattr("js_message", &self.message);
+            let _ = value.setattr("js_stack", &self.stack);
+            let _ = value.setattr("js_position", self.position);
+        });
+        err
+    }
+}
+
+impl From<ConvertError> for PyErr {
+    fn from(err: ConvertError) -> PyErr {
+        match err {
+            ConvertError::Engine(message) => EngineError::new_err(message),
+            ConvertError::Runtime(message) => {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(message)
+            }
+            ConvertError::InvalidOptions(message) => {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(message)
+            }
+            ConvertError::OutOfMemory(message) => {
+                PyErr::new::<pyo3::exceptions::PyMemoryError, _>(message)
+            }
+            ConvertError::Conversion { input, exception } => conversion_error(&input, exception),
+        }
+    }
+}
+
+/// Cache key of a conversion: the input and a hash of the direction and options
+type CacheKey = (String, u64);
+
+/// Least-recently-used cache of conversion results
+struct ConversionCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, (String, u64)>,
+    /// Last-use tick of every entry, oldest first
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl ConversionCache {
+    fn new(capacity: usize) -> Self {
+        ConversionCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<String> {
+        let Some((value, last_used)) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            STATS.cache_misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        self.hits += 1;
+        STATS.cache_hits.fetch_add(1, Ordering::Relaxed);
+        self.tick += 1;
+        let key = self.recency.remove(last_used).expect("entry is tracked");
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(value.clone())
+    }
+
+    /// Drop every entry, keeping the hit and miss counts
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn insert(&mut self, key: CacheKey, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity
+            && !self.entries.contains_key(&key)
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.tick, key);
+    }
+}
+
+/// Hash of the conversion direction and options, used as part of the cache key
+fn options_hash(direction: &str, options: Option<&JsOptions>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    direction.hash(&mut hasher);
+    if let Some(options) = options {
+        let mut entries: Vec<_> = options.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in entries {
+            name.hash(&mut hasher);
+            // Nested objects keep their insertion order, which reaches the JS converter
+            value.to_string().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Process-wide counters reported by `get_stats`, shared by all threads
+struct Stats {
+    tex2typst_conversions: AtomicU64,
+    typst2tex_conversions: AtomicU64,
+    calls: AtomicU64,
+    total_call_nanos: AtomicU64,
+    max_call_nanos: AtomicU64,
+    engines_initialized: AtomicU64,
+    js_init_nanos: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+static STATS: Stats = Stats {
+    tex2typst_conversions: AtomicU64::new(0),
+    typst2tex_conversions: AtomicU64::new(0),
+    calls: AtomicU64::new(0),
+    total_call_nanos: AtomicU64::new(0),
+    max_call_nanos: AtomicU64::new(0),
+    engines_initialized: AtomicU64::new(0),
+    js_init_nanos: AtomicU64::new(0),
+    cache_hits: AtomicU64::new(0),
+    cache_misses: AtomicU64::new(0),
+};
+
+impl Stats {
+    fn reset(&self) {
+        for counter in [
+            &self.tex2typst_conversions,
+            &self.typst2tex_conversions,
+            &self.calls,
+            &self.total_call_nanos,
+            &self.max_call_nanos,
+            &self.engines_initialized,
+            &self.js_init_nanos,
+            &self.cache_hits,
+            &self.cache_misses,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+fn elapsed_nanos(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Adds the wall time of one `ConverterInstance` call to `STATS` when dropped
+struct CallTimer(Instant);
+
+impl CallTimer {
+    fn start() -> Self {
+        CallTimer(Instant::now())
+    }
+}
+
+impl Drop for CallTimer {
+    fn drop(&mut self) {
+        let nanos = elapsed_nanos(self.0);
+        STATS.calls.fetch_add(1, Ordering::Relaxed);
+        STATS.total_call_nanos.fetch_add(nanos, Ordering::Relaxed);
+        STATS.max_call_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+}
+
+/// User JavaScript evaluated after the bundle in every new `ConverterInstance`
+struct JsExtensions {
+    /// In registration order
+    sources: Vec<String>,
+    /// Hex SHA-256 over all sources, part of disk cache keys; empty without extensions
+    digest: String,
+}
+
+static JS_EXTENSIONS: Mutex<JsExtensions> = Mutex::new(JsExtensions {
+    sources: Vec::new(),
+    digest: String::new(),
+});
+
+/// Bumped whenever `JS_EXTENSIONS` or `JS_BUNDLE` changes, so thread converters
+/// started before are replaced on their next use. Both only change while
+/// `JS_EXTENSIONS` is locked.
+static ENGINE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped in the child process after every `os.fork()`. Engines and worker
+/// threads from before belong to the parent: thread converters are replaced on
+/// their next use and `WorkerConverter`s refuse jobs.
+static FORK_EPOCH: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes each JS engine may allocate beyond what loading the bundle took, set by
+/// `set_js_memory_limit`; `None` for no limit
+static JS_MEMORY_LIMIT: Mutex<Option<usize>> = Mutex::new(None);
+
+/// QuickJS's own threshold for its first automatic garbage collection
+const DEFAULT_GC_THRESHOLD: usize = 256 * 1024;
+
+/// Bytes after which each JS engine collects garbage automatically, set by
+/// `set_js_gc_threshold`; `usize::MAX` when automatic collection is off
+static JS_GC_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_GC_THRESHOLD);
+
+/// Batch items a JS engine converts between garbage collections, set by
+/// `set_js_gc_interval`; 0 for none
+static JS_GC_INTERVAL: AtomicUsize = AtomicUsize::new(10_000);
+
+/// Converter bundle source run by new converters
+struct Bundle {
+    source: Cow<'static, str>,
+    /// Hex SHA-256 of `source`; part of every disk cache key, so a new bundle never
+    /// serves results of the old one
+    sha256: String,
+}
+
+impl Bundle {
+    fn new(source: Cow<'static, str>) -> Self {
+        let sha256 = to_hex(&sha256(source.as_bytes()));
+        Bundle { source, sha256 }
+    }
+}
+
+static EMBEDDED_BUNDLE: LazyLock<Arc<Bundle>> =
+    LazyLock::new(|| Arc::new(Bundle::new(Cow::Borrowed(JS_CODE))));
+
+/// The bundle installed by `load_bundle`, `None` for the embedded one.
+///
+/// Only read when a converter is created, so an engine mid-call never sees a
+/// half-swapped bundle.
+static JS_BUNDLE: RwLock<Option<Arc<Bundle>>> = RwLock::new(None);
+
+/// The bundle new converters run
+fn active_bundle() -> Arc<Bundle> {
+    JS_BUNDLE
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| EMBEDDED_BUNDLE.clone())
+}
+
+impl JsExtensions {
+    fn set(&mut self, sources: Vec<String>) {
+        self.digest = extensions_digest(&sources);
+        self.sources = sources;
+        ENGINE_GENERATION.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// `JsExtensions::digest` of `sources`
+fn extensions_digest(sources: &[String]) -> String {
+    if sources.is_empty() {
+        String::new()
+    } else {
+        to_hex(&sha256(sources.join("\0").as_bytes()))
+    }
+}
+
+/// Internal converter instance
+/// The JavaScript code is loaded once per thread via lazy singleton pattern
+struct ConverterInstance {
+    engine: crate::core::Converter,
+    /// Results of `tex2typst_cached` and `typst2tex_cached`
+    cache: RefCell<ConversionCache>,
+    /// Inputs sent to the JS converter, counting every item of a batch
+    conversions: Cell<u64>,
+    /// `ENGINE_GENERATION` when the bundle and extensions evaluated here were read
+    engine_generation: u64,
+    /// `FORK_EPOCH` when the engine was created
+    fork_epoch: u64,
+    /// `Bundle::sha256` of the bundle the engine runs, part of its disk cache keys
+    bundle_sha256: String,
+    /// `JsExtensions::digest` of the extensions the engine runs, part of its disk
+    /// cache keys
+    extensions_digest: String,
+    /// `JS_MEMORY_LIMIT` last applied to the engine
+    memory_limit: Cell<Option<usize>>,
+    /// `JS_GC_THRESHOLD` last applied to the engine
+    gc_threshold: Cell<usize>,
+    /// Batch items converted since the last collection counted by `JS_GC_INTERVAL`
+    items_since_gc: Cell<usize>,
+}
+
+impl ConverterInstance {
+    fn new() -> PyResult<Self> {
+        Self::with_cache_size(0)
+    }
+
+    /// Create a converter whose `*_cached` methods keep up to `cache_size` results
+    fn with_cache_size(cache_size: usize) -> PyResult<Self> {
+        Self::from_source(None, cache_size)
+    }
+
+    /// Create a converter running the converter bundle `source` (the active bundle
+    /// if `None`), followed by the registered JS extensions
+    fn from_source(source: Option<&str>, cache_size: usize) -> PyResult<Self> {
+        let (bundle, extensions, generation) = {
+            let extensions = JS_EXTENSIONS.lock().unwrap();
+            let generation = ENGINE_GENERATION.load(Ordering::Acquire);
+            (active_bundle(), extensions.sources.clone(), generation)
+        };
+        let bundle = match source {
+            Some(source) => Arc::new(Bundle::new(Cow::Owned(source.to_string()))),
+            None => bundle,
+        };
+        Self::from_parts(&bundle, &extensions, generation, cache_size)
+    }
+
+    /// Create a converter running `bundle` and then each of `extensions`, with
+    /// `console` output recorded for `get_pending_warnings`
+    fn from_parts(
+        bundle: &Bundle,
+        extensions: &[String],
+        engine_generation: u64,
+        cache_size: usize,
+    ) -> PyResult<Self> {
+        let started = Instant::now();
+        let engine =
+            crate::core::Converter::from_parts(&bundle.source, install_console, extensions)?;
+        let memory_limit = *JS_MEMORY_LIMIT.lock().unwrap();
+        engine.set_memory_limit(memory_limit);
+        let gc_threshold = JS_GC_THRESHOLD.load(Ordering::Relaxed);
+        engine.set_gc_threshold(gc_threshold);
+        STATS.engines_initialized.fetch_add(1, Ordering::Relaxed);
+        STATS
+            .js_init_nanos
+            .fetch_add(elapsed_nanos(started), Ordering::Relaxed);
+
+        Ok(ConverterInstance {
+            engine,
+            cache: RefCell::new(ConversionCache::new(cache_size)),
+            conversions: Cell::new(0),
+            engine_generation,
+            fork_epoch: FORK_EPOCH.load(Ordering::Acquire),
+            bundle_sha256: bundle.sha256.clone(),
+            extensions_digest: extensions_digest(extensions),
+            memory_limit: Cell::new(memory_limit),
+            gc_threshold: Cell::new(gc_threshold),
+            items_since_gc: Cell::new(0),
+        })
+    }
+
+    /// Apply `JS_MEMORY_LIMIT` and `JS_GC_THRESHOLD` if they changed since the
+    /// engine last saw them
+    fn sync_engine_limits(&self) {
+        let memory_limit = *JS_MEMORY_LIMIT.lock().unwrap();
+        if self.memory_limit.replace(memory_limit) != memory_limit {
+            self.engine.set_memory_limit(memory_limit);
+        }
+        let gc_threshold = JS_GC_THRESHOLD.load(Ordering::Relaxed);
+        if self.gc_threshold.replace(gc_threshold) != gc_threshold {
+            self.engine.set_gc_threshold(gc_threshold);
+        }
+    }
+
+    /// Collect the engine's garbage once every `JS_GC_INTERVAL` batch items, so
+    /// cycles left by long batches are reclaimed before the batch ends
+    fn collect_every_interval(&self, ctx: &Ctx<'_>) {
+        let interval = JS_GC_INTERVAL.load(Ordering::Relaxed);
+        if interval == 0 {
+            return;
+        }
+        let items = self.items_since_gc.get() + 1;
+        if items >= interval {
+            ctx.run_gc();
+            self.items_since_gc.set(0);
+        } else {
+            self.items_since_gc.set(items);
+        }
+    }
+
+    /// A converter on a fresh JS context of this converter's runtime, running the
+    /// current bundle and extensions, so nothing earlier conversions did to the JS
+    /// globals can affect it, nor anything it does outlive it. Its caches start empty.
+    fn isolated(&self) -> PyResult<Self> {
+        let (bundle, extensions, digest) = {
+            let extensions = JS_EXTENSIONS.lock().unwrap();
+            let digest = extensions.digest.clone();
+            (active_bundle(), extensions.sources.clone(), digest)
+        };
+        let engine = self
+            .engine
+            .isolated(&bundle.source, install_console, &extensions)?;
+        Ok(ConverterInstance {
+            engine,
+            cache: RefCell::new(ConversionCache::new(0)),
+            conversions: Cell::new(0),
+            engine_generation: self.engine_generation,
+            fork_epoch: self.fork_epoch,
+            bundle_sha256: bundle.sha256.clone(),
+            extensions_digest: digest,
+            memory_limit: Cell::new(self.memory_limit.get()),
+            gc_threshold: Cell::new(self.gc_threshold.get()),
+            items_since_gc: Cell::new(0),
+        })
+    }
+
+    /// `tex2typst_batch` on fresh JS contexts as `isolation` asks
+    fn tex2typst_batch_isolated<S: AsRef<str>>(
+        &self,
+        tex_list: &[S],
+        options: Option<&JsOptions>,
+        keep_original_on_error: bool,
+        positions: Option<&[usize]>,
+        isolation: Isolation,
+    ) -> PyResult<(Vec<String>, Vec<usize>)> {
+        match isolation {
+            Isolation::Shared => {
+                self.tex2typst_batch(tex_list, options, keep_original_on_error, positions)
+            }
+            Isolation::PerBatch => self.isolated()?.tex2typst_batch(
+                tex_list,
+                options,
+                keep_original_on_error,
+                positions,
+            ),
+            Isolation::PerItem => {
+                let mut results = Vec::with_capacity(tex_list.len());
+                let mut failed_indices = Vec::new();
+                for (index, tex) in tex_list.iter().enumerate() {
+                    let position = positions.map_or(index, |positions| positions[index]);
+                    let (converted, failed) = self.isolated()?.tex2typst_batch(
+                        std::slice::from_ref(tex),
+                        options,
+                        keep_original_on_error,
+                        Some(&[position]),
+                    )?;
+                    results.extend(converted);
+                    if !failed.is_empty() {
+                        failed_indices.push(index);
+                    }
+                }
+                Ok((results, failed_indices))
+            }
+        }
+    }
+
+    /// `typst2tex_batch` on fresh JS contexts as `isolation` asks
+    fn typst2tex_batch_isolated(
+        &self,
+        typst_list: &[String],
+        options: Option<&JsOptions>,
+        positions: Option<&[usize]>,
+        isolation: Isolation,
+    ) -> PyResult<Vec<String>> {
+        match isolation {
+            Isolation::Shared => self.typst2tex_batch(typst_list, options, positions),
+            Isolation::PerBatch => self
+                .isolated()?
+                .typst2tex_batch(typst_list, options, positions),
+            Isolation::PerItem => typst_list
+                .iter()
+                .enumerate()
+                .map(|(index, typst)| {
+                    let position = positions.map_or(index, |positions| positions[index]);
+                    let mut converted = self.isolated()?.typst2tex_batch(
+                        std::slice::from_ref(typst),
+                        options,
+                        Some(&[position]),
+                    )?;
+                    Ok(converted.remove(0))
+                })
+                .collect(),
+        }
+    }
+
+    /// Count `count` inputs sent to the JS converter here and in `direction` of `STATS`
+    fn count_conversions(&self, direction: &AtomicU64, count: usize) {
+        self.conversions.set(self.conversions.get() + count as u64);
+        direction.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Version of the upstream tex2typst library in the bundle, or "unknown" for
+    /// bundles built without the `tex2typstVersion` global
+    fn js_bundle_version(&self) -> String {
+        self.engine.ctx.with(|ctx| {
+            ctx.globals()
+                .get::<_, Option<String>>("tex2typstVersion")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "unknown".to_string())
+        })
+    }
+
+    /// Whether the bundle (or a JS extension) defines a global function `name`
+    fn has_global_function(&self, name: &str) -> bool {
+        self.engine
+            .ctx
+            .with(|ctx| ctx.globals().get::<_, Function>(name).is_ok())
+    }
+
+    /// Sorted names of the enumerable global properties holding functions
+    fn global_function_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.engine.ctx.with(|ctx| {
+            ctx.globals()
+                .props::<String, rquickjs::Value>()
+                .filter_map(Result::ok)
+                .filter(|(_, value)| value.is_function())
+                .map(|(name, _)| name)
+                .collect()
+        });
+        names.sort_unstable();
+        names
+    }
+
+    /// Look up `input` in the result cache, running `convert` on a miss.
+    ///
+    /// Failed conversions are not cached.
+    fn cached(
+        &self,
+        direction: &str,
+        input: &str,
+        options: Option<&JsOptions>,
+        convert: impl FnOnce() -> PyResult<String>,
+    ) -> PyResult<String> {
+        let key = (input.to_string(), options_hash(direction, options));
+        if let Some(result) = self.cache.borrow_mut().get(&key) {
+            return Ok(result);
+        }
+        let result = convert()?;
+        self.cache.borrow_mut().insert(key, result.clone());
+        Ok(result)
+    }
+
+    /// `tex2typst` backed by the LRU result cache
+    fn tex2typst_cached(&self, tex: &str, options: Option<&JsOptions>) -> PyResult<String> {
+        self.cached("tex2typst", tex, options, || self.tex2typst(tex, options))
+    }
+
+    /// `typst2tex` backed by the LRU result cache
+    fn typst2tex_cached(&self, typst: &str, options: Option<&JsOptions>) -> PyResult<String> {
+        self.cached("typst2tex", typst, options, || {
+            self.typst2tex(typst, options)
+        })
+    }
+
+    /// Whether the converter rejects `command` (e.g. `\pdv`) as an unknown token
+    fn is_unknown_command(&self, command: &str) -> PyResult<bool> {
+        Ok(self.engine.is_unknown_command(command)?)
+    }
+
+    /// Commands used in `tex` that the converter does not recognize, in order of first use
+    fn unknown_commands(&self, tex: &str, macros: &HashSet<String>) -> PyResult<Vec<String>> {
+        let mut unknown = Vec::new();
+        for command in tex_command_names(tex) {
+            if !macros.contains(&command) && self.is_unknown_command(&command)? {
+                unknown.push(command);
+            }
+        }
+        Ok(unknown)
+    }
+
+    /// Disk cache key of converting `input` with this converter's engine
+    fn disk_cache_key(
+        &self,
+        direction: &str,
+        input: &str,
+        options: Option<&JsOptions>,
+    ) -> Option<[u8; 32]> {
+        disk_cache_key(
+            direction,
+            input,
+            options,
+            &self.bundle_sha256,
+            &self.extensions_digest,
+        )
+    }
+
+    fn tex2typst(&self, tex: &str, options: Option<&JsOptions>) -> PyResult<String> {
+        let _timer = CallTimer::start();
+        self.count_conversions(&STATS.tex2typst_conversions, 1);
+        let key = self.disk_cache_key("tex2typst", tex, options);
+        if let Some(hit) = key.as_ref().and_then(disk_cache_get) {
+            return Ok(hit);
+        }
+        let result = self.engine.convert_tex(tex, options)?;
+        if let Some(key) = key {
+            disk_cache_insert(key, &result);
+            disk_cache_flush();
+        }
+        Ok(result)
+    }
+
+    /// Batch process multiple tex strings - reduces Rust<->JS boundary crossings
+    ///
+    /// With `keep_original_on_error`, a failed item is replaced by its input and its
+    /// index is recorded instead of aborting the whole batch. Errors name the item by
+    /// its entry in `positions`, the items' indices in the caller's list, if given.
+    fn tex2typst_batch<S: AsRef<str>>(
+        &self,
+        tex_list: &[S],
+        options: Option<&JsOptions>,
+        keep_original_on_error: bool,
+        positions: Option<&[usize]>,
+    ) -> PyResult<(Vec<String>, Vec<usize>)> {
+        let _timer = CallTimer::start();
+        self.count_conversions(&STATS.tex2typst_conversions, tex_list.len());
+        let mut results = Vec::with_capacity(tex_list.len());
+        let mut failed_indices = Vec::new();
+        self.engine
+            .tex_to_typst_each(tex_list, options, |ctx, index, tex, convert| {
+                let key = self.disk_cache_key("tex2typst", tex, options);
+                if let Some(hit) = key.as_ref().and_then(disk_cache_get) {
+                    results.push(hit);
+                    return Ok(());
+                }
+                self.collect_every_interval(ctx);
+                match convert() {
+                    Ok(typst) => {
+                        if let Some(key) = key {
+                            disk_cache_insert(key, &typst);
+                        }
+                        results.push(typst);
+                    }
+                    // The engine is poisoned, so not even a fallback can continue
+                    Err(e @ ConvertError::OutOfMemory(_)) => return Err(e.into()),
+                    // Keep the document valid: fall back to the untouched input
+                    Err(_) if keep_original_on_error => {
+                        results.push(tex.to_string());
+                        failed_indices.push(index);
+                    }
+                    Err(ConvertError::Conversion { exception, .. }) => {
+                        let position = positions.map_or(index, |positions| positions[index]);
+                        return Err(batch_item_error(position, tex, exception));
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+                PyResult::Ok(())
+            })?;
+        disk_cache_flush();
+        Ok((results, failed_indices))
+    }
+
+    fn typst2tex(&self, input: &str, options: Option<&JsOptions>) -> PyResult<String> {
+        let typst = input;
+        let _timer = CallTimer::start();
+        self.count_conversions(&STATS.typst2tex_conversions, 1);
+        let key = self.disk_cache_key("typst2tex", typst, options);
+        if let Some(hit) = key.as_ref().and_then(disk_cache_get) {
+            return Ok(hit);
+        }
+        let expanded = expand_typst_macros(typst, options);
+        let typst = expanded.as_ref();
+        let result = self
+            .engine
+            .call(&self.engine.typst2tex_fn, typst, input, options)?;
+        if let Some(key) = key {
+            disk_cache_insert(key, &result);
+            disk_cache_flush();
+        }
+        Ok(result)
+    }
+
+    /// Batch process multiple typst strings - reduces Rust<->JS boundary crossings
+    ///
+    /// Errors name the item by its entry in `positions`, if given.
+    fn typst2tex_batch(
+        &self,
+        typst_list: &[String],
+        options: Option<&JsOptions>,
+        positions: Option<&[usize]>,
+    ) -> PyResult<Vec<String>> {
+        let _timer = CallTimer::start();
+        self.count_conversions(&STATS.typst2tex_conversions, typst_list.len());
+        self.engine.guard(|| {
+            self.engine.ctx.with(|ctx| {
+                let func = restore_function(&ctx, &self.engine.typst2tex_fn)?;
+
+                let mut results = Vec::with_capacity(typst_list.len());
+
+                // Pre-create options object once if needed (shared across all conversions)
+                let js_options_obj = options
+                    .map(|opts| build_js_options(&ctx, opts))
+                    .transpose()?;
+
+                // Process all items in a single context entry
+                for (index, typst) in typst_list.iter().enumerate() {
+                    let key = self.disk_cache_key("typst2tex", typst, options);
+                    if let Some(hit) = key.as_ref().and_then(disk_cache_get) {
+                        results.push(hit);
+                        continue;
+                    }
+                    self.collect_every_interval(&ctx);
+                    let expanded = expand_typst_macros(typst, options);
+                    let result = call_converter(&ctx, &func, &expanded, js_options_obj.as_ref())
+                        .map_err(|e| {
+                            if is_out_of_memory(&e) {
+                                return self.engine.out_of_memory(typst, e).into();
+                            }
+                            let position = positions.map_or(index, |positions| positions[index]);
+                            batch_item_error(position, typst, format_js_exception(e))
+                        })?;
+                    if let Some(key) = key {
+                        disk_cache_insert(key, &result);
+                    }
+                    results.push(result);
+                }
+
+                disk_cache_flush();
+                Ok(results)
+            })
+        })
+    }
+
+    /// Annotated Typst from the bundle's `explainConversion` global, or `None` for
+    /// bundles that do not define it
+    fn explain_conversion(
+        &self,
+        tex: &str,
+        options: Option<&JsOptions>,
+    ) -> PyResult<Option<String>> {
+        let _timer = CallTimer::start();
+        self.engine.guard(|| {
+            self.engine.ctx.with(|ctx| {
+                let Ok(func) = ctx.globals().get::<_, Function>("explainConversion") else {
+                    return Ok(None);
+                };
+                self.count_conversions(&STATS.tex2typst_conversions, 1);
+                let js_options = options
+                    .map(|opts| build_js_options(&ctx, opts))
+                    .transpose()?;
+                func.call((tex, js_options))
+                    .catch(&ctx)
+                    .map(Some)
+                    .map_err(|e| {
+                        if is_out_of_memory(&e) {
+                            return self.engine.out_of_memory(tex, e).into();
+                        }
+                        js_failure(format!("Conversion failed: {}", format_js_exception(e)))
+                    })
+            })
+        })
+    }
+
+    /// Run one of the bundle's parsers and serialize the resulting AST to JSON
+    fn parse_to_json(
+        &self,
+        func_name: &str,
+        input: &str,
+        custom_tex_macros: Option<&HashMap<String, String>>,
+    ) -> PyResult<String> {
+        self.engine.guard(|| {
+            self.engine.ctx.with(|ctx| {
+                let globals = ctx.globals();
+                let func: Function = globals.get(func_name).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyAttributeError, _>(format!(
+                        "Global function '{}' not found.",
+                        func_name
+                    ))
+                })?;
+
+                let ast: rquickjs::Value = if let Some(macros) = custom_tex_macros {
+                    let js_macros = Object::new(ctx.clone()).map_err(|e| {
+                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                            "Failed to create JS object: {}",
+                            e
+                        ))
+                    })?;
+                    for (k, v) in macros.iter() {
+                        js_macros.set(k.as_str(), v.as_str()).map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                                "Failed to set nested property: {}",
+                                e
+                            ))
+                        })?;
+                    }
+                    func.call((input, js_macros)).catch(&ctx)
+                } else {
+                    func.call((input,)).catch(&ctx)
+                }
+                .map_err(|e| {
+                    if is_out_of_memory(&e) {
+                        return self.engine.out_of_memory(input, e).into();
+                    }
+                    js_failure(format!("Parse failed: {}", format_js_exception(e)))
+                })?;
+
+                // Serialize on the JS side; cyclic structures surface as a JS TypeError
+                let json = ctx.json_stringify(ast).catch(&ctx).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "AST serialization failed: {}",
+                        format_js_exception(e)
+                    ))
+                })?;
+
+                match json {
+                    Some(s) => s.to_string().map_err(|e| {
+                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                            "Failed to read serialized AST: {}",
+                            e
+                        ))
+                    }),
+                    None => Ok("null".to_string()),
+                }
+            })
+        })
+    }
+}
+
+/// Messages of the JS engines' `console` calls kept until `clear_warnings`; older
+/// ones are dropped beyond this
+const MAX_PENDING_WARNINGS: usize = 1000;
+
+thread_local! {
+    /// `console` output of the JS engines on this thread, oldest first
+    static PENDING_WARNINGS: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Define a global `console` whose methods record their message in
+/// `PENDING_WARNINGS` instead of dropping it
+fn install_console(ctx: &Ctx<'_>) -> Result<(), ConvertError> {
+    let console_error =
+        |e: rquickjs::Error| ConvertError::Engine(format!("Failed to install console: {}", e));
+    let console = Object::new(ctx.clone()).map_err(console_error)?;
+    for method in ["log", "info", "debug", "warn", "error"] {
+        let record = Function::new(ctx.clone(), |args: Rest<Coerced<String>>| {
+            let message: Vec<String> = args.0.into_iter().map(|arg| arg.0).collect();
+            PENDING_WARNINGS.with(|warnings| {
+                let mut warnings = warnings.borrow_mut();
+                if warnings.len() == MAX_PENDING_WARNINGS {
+                    warnings.pop_front();
+                }
+                warnings.push_back(message.join(" "));
+            });
+        })
+        .map_err(console_error)?;
+        console.set(method, record).map_err(console_error)?;
+    }
+    ctx.globals().set("console", console).map_err(console_error)
+}
+
+// Thread-local lazy singleton for module-level functions.
+//
+// Every OS thread has its own slot, so two threads making their first call at once
+// each create their own converter: neither can see the other's `None`, and this does
+// not depend on the GIL, which the calls may have released. A process-wide
+// `OnceLock` is not possible, as rquickjs runtimes are not `Send` without its
+// `parallel` feature, and would make all threads queue for one engine. What does
+// need care is re-entrancy on one thread: the slot is replaced through `borrow_mut`,
+// so Python code that may convert (the init hook) runs only after that borrow ends.
+thread_local! {
+    static THREAD_CONVERTER: RefCell<Option<ConverterInstance>> = const { RefCell::new(None) };
+    /// Called whenever `get_thread_converter` creates this thread's converter
+    static CONVERTER_INIT_HOOK: RefCell<Option<Py<PyAny>>> = const { RefCell::new(None) };
+}
+
+/// Make sure this thread's converter runs the current bundle and extensions;
+/// returns whether it had to be created
+fn get_thread_converter() -> PyResult<bool> {
+    let generation = ENGINE_GENERATION.load(Ordering::Acquire);
+    let fork_epoch = FORK_EPOCH.load(Ordering::Acquire);
+    let created = THREAD_CONVERTER.with(|converter| -> PyResult<bool> {
+        if let Some(current) = converter.borrow().as_ref() {
+            // Also replace a converter started before the bundle or extensions
+            // changed, inherited from the parent process, or left broken by running
+            // out of memory
+            if (current.engine_generation, current.fork_epoch) == (generation, fork_epoch)
+                && !current.engine.is_poisoned()
+            {
+                current.sync_engine_limits();
+                return Ok(false);
+            }
+        }
+        *converter.borrow_mut() = Some(ConverterInstance::new()?);
+        Ok(true)
+    })?;
+    // Run outside the borrow, so the hook can convert on the new converter itself
+    if created && CONVERTER_INIT_HOOK.with(|hook| hook.borrow().is_some()) {
+        Python::attach(|py| {
+            let hook = CONVERTER_INIT_HOOK
+                .with(|hook| hook.borrow().as_ref().map(|hook| hook.clone_ref(py)));
+            hook.map_or(Ok(()), |hook| hook.call0(py).map(drop))
+        })?;
+    }
+    Ok(created)
+}
+
+/// Run `convert` on this thread's converter, or on a fresh JS context of it if
+/// `isolated`, which is dropped afterwards
+fn with_thread_converter<R>(
+    isolated: bool,
+    convert: impl FnOnce(&ConverterInstance) -> PyResult<R>,
+) -> PyResult<R> {
+    get_thread_converter()?;
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        if isolated {
+            convert(&converter.isolated()?)
+        } else {
+            convert(converter)
+        }
+    })
+}
+
+/// Names of user-defined macros, from the call or else from the process-wide defaults
+fn defined_macro_names(custom_tex_macros: Option<&Bound<PyDict>>) -> PyResult<HashSet<String>> {
+    if let Some(macros) = custom_tex_macros {
+        return Ok(pydict_to_string_map(macros)?.into_keys().collect());
+    }
+    let defaults = DEFAULT_OPTIONS.lock().unwrap();
+    Ok(defaults
+        .get("custom_tex_macros")
+        .and_then(|macros| macros.as_object())
+        .map(|macros| macros.keys().cloned().collect())
+        .unwrap_or_default())
+}
+
+/// Convert Python dict to HashMap for custom_tex_macros
+fn pydict_to_string_map(py_dict: &Bound<PyDict>) -> PyResult<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    for (key, value) in py_dict.iter() {
+        let key_str: String = key.extract()?;
+        let value_str: String = value.extract()?;
+        map.insert(key_str, value_str);
+    }
+    Ok(map)
+}
+
+/// Convert Python dict to HashMap, keeping `None` values
+fn pydict_to_optional_string_map(
+    py_dict: &Bound<PyDict>,
+) -> PyResult<HashMap<String, Option<String>>> {
+    let mut map = HashMap::new();
+    for (key, value) in py_dict.iter() {
+        let key_str: String = key.extract()?;
+        let value_str: Option<String> = value.extract()?;
+        map.insert(key_str, value_str);
+    }
+    Ok(map)
+}
+
+/// Accepted values for the `wrap` option
+const WRAP_MODES: &[&str] = &["inline", "block", "none", "auto"];
+
+/// Validate `wrap`; `"auto"` re-wraps in the style of the delimiters stripped from the input
+fn check_wrap(wrap: Option<&str>, auto_strip_delimiters: bool) -> PyResult<()> {
+    let Some(wrap) = wrap else {
+        return Ok(());
+    };
+    check_choice("wrap", wrap, WRAP_MODES)?;
+    if wrap == "auto" && !auto_strip_delimiters {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "wrap='auto' requires auto_strip_delimiters=True",
+        ));
+    }
+    Ok(())
+}
+
+/// Accepted string values for the `isolated` option of the batch functions
+const ISOLATION_MODES: &[&str] = &["per_item", "per_batch"];
+
+/// Which conversions of a call get a fresh JS context instead of the thread's own
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Isolation {
+    Shared,
+    PerBatch,
+    PerItem,
+}
+
+impl Isolation {
+    /// `isolated` of a single conversion
+    fn single(isolated: bool) -> Self {
+        if isolated {
+            Isolation::PerItem
+        } else {
+            Isolation::Shared
+        }
+    }
+
+    /// `isolated` of a batch: a bool, where `True` isolates every item, or one of
+    /// `ISOLATION_MODES`
+    fn batch(isolated: Option<&Bound<PyAny>>) -> PyResult<Self> {
+        let Some(isolated) = isolated else {
+            return Ok(Isolation::Shared);
+        };
+        if let Ok(flag) = isolated.cast::<PyBool>() {
+            return Ok(Isolation::single(flag.is_true()));
+        }
+        let mode: String = isolated.extract().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "isolated must be a bool, 'per_item' or 'per_batch'",
+            )
+        })?;
+        check_choice("isolated", &mode, ISOLATION_MODES)?;
+        Ok(if mode == "per_batch" {
+            Isolation::PerBatch
+        } else {
+            Isolation::PerItem
+        })
+    }
+}
+
+/// The `wrap` mode for one output: `"auto"` follows the stripped delimiters, if any
+fn resolve_wrap(wrap: Option<&str>, display: Option<bool>) -> &str {
+    match (wrap, display) {
+        (Some("auto"), Some(true)) => "block",
+        (Some("auto"), Some(false)) => "inline",
+        (Some("auto"), None) | (None, _) => "none",
+        (Some(wrap), _) => wrap,
+    }
+}
+
+/// Opening and closing math delimiters and whether they denote display math,
+/// longest first so `$$` is not mistaken for `$`
+const MATH_DELIMITERS: &[(&str, &str, bool)] = &[
+    ("$$", "$$", true),
+    ("\\[", "\\]", true),
+    ("\\(", "\\)", false),
+    ("$", "$", false),
+];
+
+/// Positions of unescaped math delimiters (`$`, `$$`, `\(`, `\)`, `\[`, `\]`) in `tex`
+fn math_delimiter_tokens(tex: &str) -> Vec<(usize, &str)> {
+    let bytes = tex.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if i + 1 < bytes.len() => {
+                if matches!(bytes[i + 1], b'(' | b')' | b'[' | b']') {
+                    tokens.push((i, &tex[i..i + 2]));
+                }
+                // Skip the escaped character, including `\$` and `\\`
+                i += 2;
+                continue;
+            }
+            b'$' => {
+                let len = if bytes.get(i + 1) == Some(&b'$') {
+                    2
+                } else {
+                    1
+                };
+                tokens.push((i, &tex[i..i + len]));
+                i += len;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Remove one matching pair of math delimiters enclosing the trimmed input.
+///
+/// Returns the trimmed inner math and whether it was display math, or the input unchanged
+/// with `None` when it is not enclosed in delimiters. Delimiters at only one end and
+/// nested or repeated delimiters are reported rather than being guessed at.
+fn strip_math_delimiters(tex: &str) -> Result<(&str, Option<bool>), String> {
+    let trimmed = tex.trim();
+    let tokens = math_delimiter_tokens(trimmed);
+    let opening = tokens.first().filter(|(pos, _)| *pos == 0);
+    // A lone token such as `$` cannot both open and close the input
+    let closing = tokens
+        .last()
+        .filter(|(pos, token)| pos + token.len() == trimmed.len())
+        .filter(|_| opening.is_none() || tokens.len() > 1);
+
+    let (open, close) = match (opening, closing) {
+        (None, None) => return Ok((tex, None)),
+        (Some((_, open)), None) => {
+            return Err(format!(
+                "Unbalanced math delimiter: '{}' at the start of the input is never closed",
+                open
+            ));
+        }
+        (None, Some((_, close))) => {
+            return Err(format!(
+                "Unbalanced math delimiter: '{}' at the end of the input was never opened",
+                close
+            ));
+        }
+        (Some((_, open)), Some((_, close))) => (*open, *close),
+    };
+
+    let Some(&(_, expected, display)) = MATH_DELIMITERS.iter().find(|(o, _, _)| *o == open) else {
+        return Err(format!(
+            "Unbalanced math delimiter: input starts with closing '{}'",
+            open
+        ));
+    };
+    if close != expected {
+        return Err(format!(
+            "Unbalanced math delimiters: '{}' at the start is closed by '{}'",
+            open, close
+        ));
+    }
+    if tokens.len() > 2 {
+        return Err(format!(
+            "Nested or repeated math delimiters: found '{}' inside '{}...{}'",
+            tokens[1].1, open, close
+        ));
+    }
+    Ok((
+        trimmed[open.len()..trimmed.len() - close.len()].trim(),
+        Some(display),
+    ))
+}
+
+/// Surround Typst math with `$` delimiters according to a `wrap` mode.
+///
+/// Typst treats math as a block only when whitespace follows the opening `$` and
+/// precedes the closing one, so `"block"` pads the content with single spaces.
+fn wrap_typst_math(typst: String, wrap: &str) -> String {
+    match wrap {
+        "inline" => format!("${}$", typst),
+        "block" => format!("$ {} $", typst),
+        _ => typst,
+    }
+}
+
+/// Accepted values for the `delimiters` option of `typst2tex`
+const TEX_DELIMITERS: &[&str] = &["dollars", "latex", "none"];
+
+/// Surround LaTeX math with the delimiters `delimiters` names, if any.
+///
+/// Display math gets `\[...\]` or `$$...$$`, inline math `\(...\)` or `$...$`; the
+/// content is not padded. `trailing_newline` ends delimited display math with `\n`.
+fn wrap_tex_math(
+    tex: String,
+    delimiters: Option<&str>,
+    display: bool,
+    trailing_newline: bool,
+) -> String {
+    let (open, close) = match (delimiters, display) {
+        (Some("latex"), true) => ("\\[", "\\]"),
+        (Some("latex"), false) => ("\\(", "\\)"),
+        (Some("dollars"), true) => ("$$", "$$"),
+        (Some("dollars"), false) => ("$", "$"),
+        _ => return tex,
+    };
+    let newline = if display && trailing_newline {
+        "\n"
+    } else {
+        ""
+    };
+    format!("{}{}{}{}", open, tex, close, newline)
+}
+
+/// Whether `typst2tex` options select block math mode, which the JS converter
+/// defaults to
+fn is_block_math_mode(options: Option<&JsOptions>) -> bool {
+    options
+        .and_then(|options| options.get("blockMathMode"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(true)
+}
+
+/// Typst math shorthands read as one token, longest first
+const TYPST_SHORTHANDS: &[&str] = &[
+    "<==>", "==>", "<=>", "|->", "<->", "...", "->", "<-", "=>", "<=", ">=", "!=", "<<", ">>", ":=",
+];
+
+/// Symbol names of binary operators and relations, after which `+`/`-` is a sign
+const TYPST_OPERATOR_NAMES: &[&str] = &[
+    "times",
+    "div",
+    "dot.op",
+    "plus.minus",
+    "minus.plus",
+    "approx",
+    "equiv",
+    "prop",
+    "in",
+    "in.not",
+    "subset",
+    "subset.eq",
+    "supset",
+    "supset.eq",
+    "union",
+    "inter",
+    "without",
+    "and",
+    "or",
+    "not",
+    "eq",
+    "eq.not",
+    "lt.eq",
+    "gt.eq",
+    "arrow.r",
+    "arrow.l",
+    "arrow.l.r",
+    "arrow.r.double",
+    "arrow.l.double",
+    "arrow.l.r.double",
+];
+
+/// Operators `format_typst_math` surrounds with single spaces
+const TYPST_SPACED_OPERATORS: &[&str] = &[
+    "=", "+", "-", "<", ">", "<==>", "==>", "<=>", "|->", "<->", "->", "<-", "=>", "<=", ">=",
+    "!=", "<<", ">>", ":=",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum TypstTokenKind {
+    /// Identifier, number, string literal or escape
+    Operand,
+    Open,
+    Close,
+    Punct,
+}
+
+/// A token of Typst math and whether whitespace preceded it
+struct TypstToken<'a> {
+    text: &'a str,
+    /// Byte offset of `text` in the tokenized string
+    start: usize,
+    kind: TypstTokenKind,
+    space_before: bool,
+}
+
+/// Split Typst math into tokens; whitespace only sets `space_before`
+fn tokenize_typst_math(typst: &str) -> Vec<TypstToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut space_before = false;
+    let mut chars = typst.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            space_before = true;
+            continue;
+        }
+        let rest = &typst[start..];
+        let mut end = start + c.len_utf8();
+        let kind = if c == '"' {
+            let mut escaped = false;
+            for (i, c) in chars.by_ref() {
+                end = i + c.len_utf8();
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    break;
+                }
+            }
+            TypstTokenKind::Operand
+        } else if c == '\\' {
+            // `\` before whitespace is a line break, otherwise it escapes one character
+            if let Some(&(i, next)) = chars.peek().filter(|(_, next)| !next.is_whitespace()) {
+                end = i + next.len_utf8();
+                chars.next();
+            }
+            TypstTokenKind::Operand
+        } else if c.is_alphanumeric() {
+            let numeric = c.is_ascii_digit();
+            while let Some(&(i, next)) = chars.peek() {
+                // `.` continues a number before a digit and a name before a modifier
+                let continues = if next == '.' {
+                    typst[i + 1..].chars().next().is_some_and(|after| {
+                        if numeric {
+                            after.is_ascii_digit()
+                        } else {
+                            after.is_alphabetic()
+                        }
+                    })
+                } else if numeric {
+                    next.is_ascii_digit()
+                } else {
+                    next.is_alphanumeric()
+                };
+                if !continues {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+            TypstTokenKind::Operand
+        } else if let Some(shorthand) = TYPST_SHORTHANDS.iter().find(|s| rest.starts_with(**s)) {
+            end = start + shorthand.len();
+            while chars.peek().is_some_and(|&(i, _)| i < end) {
+                chars.next();
+            }
+            TypstTokenKind::Punct
+        } else {
+            match c {
+                '(' | '[' | '{' => TypstTokenKind::Open,
+                ')' | ']' | '}' => TypstTokenKind::Close,
+                _ => TypstTokenKind::Punct,
+            }
+        };
+        tokens.push(TypstToken {
+            text: &typst[start..end],
+            start,
+            kind,
+            space_before,
+        });
+        space_before = false;
+    }
+    tokens
+}
+
+/// Drop `lr(...)` around a single `(...)`, `[...]` or `{...}` group, which Typst
+/// already scales to its content; returns whether any was dropped
+fn unwrap_redundant_lr(tokens: &mut Vec<TypstToken<'_>>) -> bool {
+    // Index of the closing token matching each opening one
+    let mut closing = vec![None; tokens.len()];
+    let mut stack = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TypstTokenKind::Open => stack.push(index),
+            TypstTokenKind::Close => {
+                if let Some(open) = stack.pop() {
+                    closing[open] = Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    let pair =
+        |open: &str, close: &str| matches!((open, close), ("(", ")") | ("[", "]") | ("{", "}"));
+    let mut dropped = vec![false; tokens.len()];
+    for index in 0..tokens.len().saturating_sub(3) {
+        if tokens[index].text != "lr"
+            || tokens[index + 1].text != "("
+            || tokens[index + 1].space_before
+        {
+            continue;
+        }
+        let (Some(call_end), Some(group_end)) = (closing[index + 1], closing[index + 2]) else {
+            continue;
+        };
+        if group_end + 1 == call_end && pair(tokens[index + 2].text, tokens[group_end].text) {
+            dropped[index] = true;
+            dropped[index + 1] = true;
+            dropped[call_end] = true;
+        }
+    }
+    if !dropped.contains(&true) {
+        return false;
+    }
+    let mut index = 0;
+    let mut space_before = false;
+    tokens.retain_mut(|token| {
+        let keep = !dropped[index];
+        index += 1;
+        // A dropped `lr` hands its leading space to what replaces it
+        if keep {
+            token.space_before |= std::mem::take(&mut space_before);
+        } else {
+            space_before |= token.space_before;
+        }
+        keep
+    });
+    true
+}
+
+/// Remove grouping parentheses around a single name, number or string where Typst
+/// drops them anyway: after `^` and `_`, and around an operand of `/`.
+///
+/// `x^(2)` becomes `x^2` and `(a)/(b)` `a/b`, but function calls such as `f(a)/b`
+/// and groups the next token would merge with, as in `x^(2)y`, are kept. Everything
+/// else, spacing included, is left as it is.
+fn collapse_redundant_parens(typst: &str) -> String {
+    let tokens = tokenize_typst_math(typst);
+    let mut removed = Vec::new();
+    for index in 0..tokens.len().saturating_sub(2) {
+        let (open, inner, close) = (&tokens[index], &tokens[index + 1], &tokens[index + 2]);
+        if open.text != "("
+            || !matches!(inner.kind, TypstTokenKind::Operand)
+            || close.text != ")"
+            || inner.text.starts_with('\\')
+        {
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+        let next = tokens.get(index + 3);
+        let after =
+            |texts: &[&str]| previous.is_some_and(|previous| texts.contains(&previous.text));
+        // Whether the group is an attachment or an operand of a fraction
+        let fraction = after(&["/"]) || next.is_some_and(|next| next.text == "/");
+        if !after(&["^", "_"]) && !fraction {
+            continue;
+        }
+        // An operand right before `(` calls it; one right after `)` would merge with it
+        let call = !open.space_before
+            && previous.is_some_and(|previous| {
+                matches!(
+                    previous.kind,
+                    TypstTokenKind::Operand | TypstTokenKind::Close
+                )
+            });
+        let merges = next.is_some_and(|next| {
+            !next.space_before
+                && matches!(next.kind, TypstTokenKind::Operand | TypstTokenKind::Open)
+        });
+        if call || merges {
+            continue;
+        }
+        removed.push(open.start..open.start + 1);
+        removed.push(close.start..close.start + 1);
+    }
+    if removed.is_empty() {
+        return typst.to_string();
+    }
+    let mut out = String::with_capacity(typst.len());
+    let mut copied = 0;
+    for range in removed {
+        out.push_str(&typst[copied..range.start]);
+        copied = range.end;
+    }
+    out.push_str(&typst[copied..]);
+    out
+}
+
+/// Canonical spacing of Typst math, so equivalent output of different converter
+/// versions compares equal.
+///
+/// Whitespace outside string literals collapses to single spaces; the operators in
+/// `TYPST_SPACED_OPERATORS` get one space on each side (a sign only before it),
+/// `,` and `;` one after; brackets, `^`, `_`, `/` and primes none. Spaces that
+/// separate names or a function from its arguments are kept, and redundant `lr(...)`
+/// wrappers are removed. Applying it twice gives the same result as once.
+fn canonical_typst_math(typst: &str) -> String {
+    let mut tokens = tokenize_typst_math(typst);
+    // Unwrapping one `lr` can make the one around it redundant
+    while unwrap_redundant_lr(&mut tokens) {}
+
+    let tight = |text: &str| matches!(text, "^" | "_" | "/");
+    let mut out = String::with_capacity(typst.len());
+    // Whether a `+`/`-` is binary: it follows something it can subtract from
+    let mut last_operand = false;
+    let mut signs = vec![false; tokens.len()];
+    for (index, token) in tokens.iter().enumerate() {
+        signs[index] = matches!(token.text, "+" | "-") && !last_operand;
+        last_operand = (matches!(token.kind, TypstTokenKind::Operand)
+            && !TYPST_OPERATOR_NAMES.contains(&token.text))
+            || matches!(token.kind, TypstTokenKind::Close)
+            || matches!(token.text, "'" | "!");
+    }
+    let spaced =
+        |index: usize| TYPST_SPACED_OPERATORS.contains(&tokens[index].text) && !signs[index];
+    for (index, token) in tokens.iter().enumerate() {
+        if index > 0 {
+            let previous = &tokens[index - 1];
+            let space = if matches!(token.kind, TypstTokenKind::Close)
+                || matches!(token.text, "," | ";" | "'")
+                || matches!(previous.kind, TypstTokenKind::Open)
+                || tight(token.text)
+                || tight(previous.text)
+                || signs[index - 1]
+            {
+                false
+            } else if (spaced(index) && previous.text != "&")
+                || (spaced(index - 1) && token.text != "&")
+            {
+                true
+            } else {
+                matches!(previous.text, "," | ";") || token.space_before
+            };
+            if space {
+                out.push(' ');
+            }
+        }
+        out.push_str(token.text);
+    }
+    out
+}
+
+/// Ensure a string-valued option is one of its accepted choices
+fn check_choice(option: &str, value: &str, choices: &[&str]) -> PyResult<()> {
+    Ok(crate::core::check_choice(option, value, choices)?)
+}
+
+/// Process-wide default options keyed by Python name, merged beneath per-call kwargs
+static DEFAULT_OPTIONS: LazyLock<Mutex<JsOptions>> = LazyLock::new(|| Mutex::new(JsOptions::new()));
+
+/// Fill in process-wide defaults for options that were not passed explicitly.
+///
+/// The defaults come first, in `names` order; explicit options replace them in place
+/// and follow in their own order.
+fn apply_default_options(options_map: &mut JsOptions, names: &[(&str, &str)]) {
+    let defaults = DEFAULT_OPTIONS.lock().unwrap();
+    if defaults.is_empty() {
+        return;
+    }
+    let mut merged = JsOptions::new();
+    for (py_name, js_name) in names {
+        if let Some(value) = defaults.get(*py_name) {
+            merged.insert(js_name.to_string(), value.clone());
+        }
+    }
+    merged.extend(std::mem::take(options_map));
+    *options_map = merged;
+}
+
+/// Maximum JSON nesting depth accepted when converting a parsed AST to Python
+const MAX_AST_DEPTH: usize = 100;
+
+/// Maximum nesting of TeX groups, `\left...\right` and environments `parse_tex`
+/// hands to the JS parser.
+///
+/// The parser recurses on every level and, for some constructs, exhausts
+/// `JS_MAX_STACK_SIZE` a little beyond this, before `MAX_AST_DEPTH` is checked;
+/// nested `\frac`s already exceed `MAX_AST_DEPTH` at this depth.
+const MAX_PARSE_NESTING_DEPTH: usize = MAX_AST_DEPTH / 2;
+
+/// Compute the maximum nesting depth of a JSON document without parsing it
+fn json_depth(json: &str) -> usize {
+    let mut depth = 0usize;
+    let mut max_depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for b in json.bytes() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max_depth
+}
+
+/// Convert a parsed JSON value into native Python objects
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<Py<PyAny>> {
+    match value {
+        serde_json::Value::Null => Ok(py.None()),
+        serde_json::Value::Bool(b) => b.into_py_any(py),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py_any(py)
+            } else if let Some(u) = n.as_u64() {
+                u.into_py_any(py)
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_py_any(py)
+            }
+        }
+        serde_json::Value::String(s) => s.into_py_any(py),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            Ok(list.into_any().unbind())
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (k, v) in map {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            Ok(dict.into_any().unbind())
+        }
+    }
+}
+
+/// Convert the JSON produced by `parse_to_json` into Python dicts/lists
+fn ast_json_to_py(py: Python<'_>, json: &str) -> PyResult<Py<PyAny>> {
+    let depth = json_depth(json);
+    if depth > MAX_AST_DEPTH {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "AST nesting depth {} exceeds the maximum of {}",
+            depth, MAX_AST_DEPTH
+        )));
+    }
+
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to deserialize AST: {}", e))
+    })?;
+    json_to_py(py, &value)
+}
+
+/// Typst functions provided by third-party packages rather than the standard library
+const TYPST_PACKAGE_FUNCTIONS: &[(&str, &str)] = &[
+    ("dv", "physica"),
+    ("pdv", "physica"),
+    ("bra", "physica"),
+    ("ket", "physica"),
+    ("braket", "physica"),
+    ("ketbra", "physica"),
+    ("expval", "physica"),
+    ("mel", "physica"),
+    ("grad", "physica"),
+    ("curl", "physica"),
+    ("laplacian", "physica"),
+    ("tensor", "physica"),
+    ("ce", "typsium"),
+    ("qty", "unify"),
+    ("unit", "unify"),
+    ("num", "unify"),
+    ("numrange", "unify"),
+    ("qtyrange", "unify"),
+];
+
+/// Result of a conversion together with metadata about the emitted Typst
+#[pyclass(frozen, get_all)]
+struct ConversionInfo {
+    /// Converted Typst string
+    typst: String,
+    /// Distinct symbol and function names in the output, in order of first use
+    symbols: Vec<String>,
+    /// Typst packages that must be imported for the output to compile
+    required_packages: Vec<String>,
+    /// `(tex_name, typst_label)` of each `\label` removed with `labels=True`
+    labels: Vec<(String, String)>,
+    /// Typst markup of the `\tag{...}` removed with `tags=True`, e.g. `(3a)`
+    tag: Option<String>,
+}
+
+#[pymethods]
+impl ConversionInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "ConversionInfo(typst={:?}, symbols={:?}, required_packages={:?}, labels={:?}, tag={})",
+            self.typst,
+            self.symbols,
+            self.required_packages,
+            self.labels,
+            match &self.tag {
+                Some(tag) => format!("{:?}", tag),
+                None => "None".to_string(),
+            }
+        )
+    }
+}
+
+/// Outcome of a batch conversion with `return_summary=True`
+///
+/// The results stay Rust strings until `results` is read.
+#[pyclass(frozen)]
+struct BatchResult {
+    results: Vec<Option<String>>,
+    errors: Vec<(usize, String)>,
+    elapsed: f64,
+    engine_inits: u64,
+}
+
+#[pymethods]
+impl BatchResult {
+    /// Converted items in input order; failed items hold their input with
+    /// `keep_original_on_error=True`, skipped empty items `empty_replacement`
+    #[getter]
+    fn results(&self) -> Vec<Option<String>> {
+        self.results.clone()
+    }
+
+    /// `(index, message)` of every item that failed to convert
+    #[getter]
+    fn errors(&self) -> Vec<(usize, String)> {
+        self.errors.clone()
+    }
+
+    /// Wall time of the whole call in seconds
+    #[getter]
+    fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Items handled per second of `elapsed`
+    #[getter]
+    fn items_per_second(&self) -> f64 {
+        if self.elapsed > 0.0 {
+            self.results.len() as f64 / self.elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// JS engines started during the call, on any thread
+    #[getter]
+    fn engine_inits(&self) -> u64 {
+        self.engine_inits
+    }
+
+    fn __len__(&self) -> usize {
+        self.results.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BatchResult(items={}, errors={}, elapsed={:.6}, engine_inits={})",
+            self.results.len(),
+            self.errors.len(),
+            self.elapsed,
+            self.engine_inits
+        )
+    }
+}
+
+impl BatchResult {
+    /// Summary of a batch started at `started`, when `STATS` had counted
+    /// `inits_before` engines
+    fn new(
+        results: Vec<Option<String>>,
+        errors: Vec<(usize, String)>,
+        started: Instant,
+        inits_before: u64,
+    ) -> Self {
+        BatchResult {
+            results,
+            errors,
+            elapsed: started.elapsed().as_secs_f64(),
+            engine_inits: STATS
+                .engines_initialized
+                .load(Ordering::Relaxed)
+                .saturating_sub(inits_before),
+        }
+    }
+}
+
+/// Message of a failed conversion: the JS error message when there is one
+fn error_message(py: Python<'_>, err: &PyErr) -> String {
+    let value = err.value(py);
+    value
+        .getattr("js_message")
+        .and_then(|message| message.extract::<String>())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+impl ConversionInfo {
+    /// Scan Typst output for symbol names and the packages they require
+    fn from_typst(typst: String) -> Self {
+        let mut symbols: Vec<String> = Vec::new();
+        let mut required_packages: Vec<String> = Vec::new();
+        let chars: Vec<char> = typst.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '"' {
+                // Skip string literals, honoring escapes
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            } else if c.is_ascii_alphabetic() {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric()
+                        || (chars[i] == '.'
+                            && i + 1 < chars.len()
+                            && chars[i + 1].is_ascii_alphabetic()))
+                {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                // Single letters are plain variables, not symbols
+                if name.len() < 2 {
+                    continue;
+                }
+                if let Some((_, package)) = TYPST_PACKAGE_FUNCTIONS.iter().find(|(f, _)| *f == name)
+                    && !required_packages.iter().any(|p| p == package)
+                {
+                    required_packages.push(package.to_string());
+                }
+                if !symbols.contains(&name) {
+                    symbols.push(name);
+                }
+            } else {
+                i += 1;
+            }
+        }
+
+        ConversionInfo {
+            typst,
+            symbols,
+            required_packages,
+            labels: Vec::new(),
+            tag: None,
+        }
+    }
+}
+
+/// Convert LaTeX/TeX math to Typst format.
+///
+/// Uses a thread-local lazy singleton - the converter is initialized only on the
+/// first call within each thread, avoiding import-time overhead.
+///
+/// Args:
+///     tex: LaTeX/TeX math string to convert
+///     non_strict: Allow non-strict parsing (default: None)
+///     prefer_shorthands: Prefer shorthand notation (default: None)
+///     keep_spaces: Preserve spaces in output (default: None)
+///     frac_to_slash: Convert fractions to slash notation (default: None)
+///     infty_to_oo: Convert infinity symbol to oo (default: None)
+///     optimize: Optimize output (default: None)
+///     ignore_unknown_commands: Pass commands the converter does not recognize through
+///         as `\cmd` (default: None)
+///     handle_physics: Rewrite macros of the `physics` package (`\dv`, `\pdv`, `\qty`,
+///         `\abs`, `\norm`, `\ket`, ...) into plain TeX first (default: None)
+///     output_unicode: Emit Unicode characters (`α`, `×`, ...) instead of Typst symbol
+///         names (default: None)
+///     handle_text_mode: How to treat `\text{...}` contents: "verbatim", "convert" or "strip" (default: None)
+///     custom_tex_macros: Custom TeX macro definitions (default: None)
+///     replace_unknown_commands: Typst fallbacks for unknown commands, `None` drops them (default: None)
+///     symbol_map: Typst translations overriding those of built-in TeX symbols, keyed by
+///         command name (default: None)
+///     environment_map: Typst functions standing in for TeX environments, keyed by
+///         environment name (default: None)
+///     trim_input: Strip leading/trailing whitespace before conversion (default: False)
+///     report_unknown: Also return the commands the converter did not recognize (default: False)
+///     fail_on_unknown: Raise `TexParseError` listing unrecognized commands (default: False)
+///     preamble: LaTeX preamble whose macro definitions are applied beneath `custom_tex_macros`;
+///         macros with arguments are expanded before conversion (default: None)
+///     max_input_length: Raise `ValueError` for input longer than this many bytes (default: None)
+///     wrap: Surround the output with `$...$` ("inline"), `$ ... $` ("block") or nothing
+///         ("none"); "auto" follows the delimiters removed by `auto_strip_delimiters`
+///         (default: "none")
+///     auto_strip_delimiters: Remove one pair of `$...$`, `$$...$$`, `\(...\)` or `\[...\]`
+///         around the input; unbalanced or nested delimiters raise `ValueError` (default: False)
+///     chem: Translate mhchem `\ce{...}` formulas (subscripts, charges, arrows and
+///         coefficients); unsupported constructs raise `ValueError` (default: False)
+///     handle_mhchem: Same as `chem` (default: False)
+///     siunitx: Convert `\SI`/`\qty`, `\si`/`\unit` and `\num`; unknown unit macros
+///         are reported in a `UserWarning` (default: False)
+///     handle_siunitx: Same as `siunitx` (default: False)
+///     units_style: "plain" math, or "unify"/"metro" package calls for siunitx output
+///         (default: "plain")
+///     labels: Remove `\label{...}` and attach the (sanitized) label to block-wrapped
+///         output as `<name>` (default: False)
+///     tags: Remove `\tag{...}` and `\tag*{...}`; block-wrapped output becomes a
+///         `math.equation` numbered with the tag (default: False)
+///     normalize_unicode: NFC-normalize the input and replace Unicode math characters
+///         such as `−`, `′`, `α` and `×` (and non-breaking spaces) with TeX (default: False)
+///     unicode_report: Also return the `(character, tex)` substitutions applied by
+///         `normalize_unicode` (default: False)
+///     strip_comments: Remove `%` comments (not `\%`) before conversion; a comment also
+///         swallows its line break, as in TeX (default: False)
+///     collapse_redundant_braces: Drop grouping parentheses Typst ignores, as in
+///         `x^(2)` and `(a)/(b)` (default: False)
+///     normalize_output: Canonicalize the spacing of the Typst output with
+///         `format_typst_math` (default: False)
+///     timing: Also return the wall time of the call in seconds (default: False)
+///     keep_original: "comment" to also return a Typst comment quoting the input, to
+///         put before block-wrapped output and after any other (default: None)
+///     max_nesting_depth: Raise `TexParseError` for input whose groups, `\left`/`\right`
+///         pairs and environments nest deeper than this; None disables the check
+///         (default: 512)
+///     js_options: Further options passed to the JS converter under their JS names,
+///         e.g. `{"maxExpansionDepth": 8}`; ints are passed as JS numbers (default: None)
+///     isolated: Convert on a fresh JS context, reloading the bundle and extensions, that
+///         is dropped after the call, so the input cannot see or change JS globals
+///         other conversions use (default: False)
+///
+/// With `tags=True`, other wraps than "block" drop the removed tag with a `UserWarning`.
+///
+/// Returns:
+///     Converted Typst string, or a tuple with `unknown_commands` (`report_unknown`),
+///     the substitutions (`unicode_report`) and the comment (`keep_original`) appended
+///     in that order; with `timing`, a `(result, elapsed_seconds)` tuple
+#[pyfunction]
+#[pyo3(signature = (tex, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, trim_input=false, report_unknown=false, fail_on_unknown=false, preamble=None, max_input_length=None, wrap=None, auto_strip_delimiters=false, chem=false, handle_mhchem=false, siunitx=false, handle_siunitx=false, units_style=None, labels=false, tags=false, normalize_unicode=false, unicode_report=false, strip_comments=false, collapse_redundant_braces=false, normalize_output=false, timing=false, keep_original=None, max_nesting_depth=Some(DEFAULT_MAX_NESTING_DEPTH), js_options=None, isolated=false))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst<'py>(
+    py: Python<'py>,
+    tex: String,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<'py, PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    trim_input: bool,
+    report_unknown: bool,
+    fail_on_unknown: bool,
+    preamble: Option<&str>,
+    max_input_length: Option<usize>,
+    wrap: Option<&str>,
+    auto_strip_delimiters: bool,
+    chem: bool,
+    handle_mhchem: bool,
+    siunitx: bool,
+    handle_siunitx: bool,
+    units_style: Option<&str>,
+    labels: bool,
+    tags: bool,
+    normalize_unicode: bool,
+    unicode_report: bool,
+    strip_comments: bool,
+    collapse_redundant_braces: bool,
+    normalize_output: bool,
+    timing: bool,
+    keep_original: Option<&str>,
+    max_nesting_depth: Option<usize>,
+    js_options: Option<&Bound<PyDict>>,
+    isolated: bool,
+) -> PyResult<Py<PyAny>> {
+    let siunitx = siunitx || handle_siunitx;
+    let chem = chem || handle_mhchem;
+    let started = Instant::now();
+    check_input_length(&tex, max_input_length)?;
+    check_nesting_depth(&tex, max_nesting_depth)?;
+    check_wrap(wrap, auto_strip_delimiters)?;
+    check_units_style(units_style, siunitx)?;
+    check_unicode_report(unicode_report, normalize_unicode)?;
+    if let Some(mode) = keep_original {
+        check_choice("keep_original", mode, KEEP_ORIGINAL_MODES)?;
+    }
+    let original = keep_original.map(|_| tex.clone());
+    let tex = if strip_comments {
+        strip_tex_comments(&tex)
+    } else {
+        tex
+    };
+    let tex = if trim_input { tex.trim() } else { &tex };
+    let (normalized, substitutions) = if normalize_unicode {
+        normalize_unicode_math(py, tex)?
+    } else {
+        (String::new(), Vec::new())
+    };
+    let tex = if normalize_unicode { &normalized } else { tex };
+    let (tex, display) = if auto_strip_delimiters {
+        strip_math_delimiters(tex).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?
+    } else {
+        (tex, None)
+    };
+    let preamble = preamble
+        .map(|preamble| apply_preamble(py, preamble, custom_tex_macros))
+        .transpose()?;
+    let custom_tex_macros = preamble
+        .as_ref()
+        .map(|(_, merged)| merged)
+        .or(custom_tex_macros);
+    let expanded;
+    let tex = match &preamble {
+        Some((argument_macros, _)) => {
+            expanded = expand_argument_macros(tex, argument_macros)?;
+            &expanded
+        }
+        None => tex,
+    };
+    let (stripped, extracted_labels) = if labels {
+        extract_labels(tex, &mut LabelRegistry::default())
+    } else {
+        (String::new(), Vec::new())
+    };
+    let tex = if labels { &stripped } else { tex };
+    let (untagged, tag) = if tags {
+        extract_tag(tex).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?
+    } else {
+        (String::new(), None)
+    };
+    let tex = if tags { &untagged } else { tex };
+    let chemistry;
+    let tex = if chem {
+        chemistry =
+            expand_chemistry(tex).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        &chemistry
+    } else {
+        tex
+    };
+    let siunitx = siunitx
+        .then(|| expand_siunitx(tex, units_style.unwrap_or("plain")))
+        .transpose()
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    let tex = match &siunitx {
+        Some(expansion) => {
+            if !expansion.warnings.is_empty() {
+                warn_user(py, &format!("siunitx: {}", expansion.warnings.join("; ")))?;
+            }
+            &expansion.tex
+        }
+        None => tex,
+    };
+
+    let mut unknown = Vec::new();
+    if report_unknown || fail_on_unknown {
+        let macros = defined_macro_names(custom_tex_macros)?;
+        unknown = with_thread_converter(isolated, |converter| {
+            converter.unknown_commands(tex, &macros)
+        })?;
+    }
+    if fail_on_unknown && !unknown.is_empty() {
+        return Err(TexParseError::new_err(format!(
+            "Unknown commands: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        js_options,
+    )?;
+    let typst = convert_tex(tex, opts.as_ref(), Isolation::single(isolated))?;
+    let typst = match &siunitx {
+        Some(expansion) => splice_siunitx(&typst, &expansion.splices),
+        None => typst,
+    };
+    let typst = if collapse_redundant_braces {
+        collapse_redundant_parens(&typst)
+    } else {
+        typst
+    };
+    let typst = if normalize_output {
+        canonical_typst_math(&typst)
+    } else {
+        typst
+    };
+    let tag = tag.as_ref().map(tag_to_typst).transpose()?;
+    let wrap = resolve_wrap(wrap, display);
+    let typst = attach_equation_metadata(py, typst, wrap, &extracted_labels, tag.as_deref())?;
+    let mut result = vec![typst.into_py_any(py)?];
+    if report_unknown {
+        result.push(unknown.into_py_any(py)?);
+    }
+    if unicode_report {
+        result.push(substitutions.into_py_any(py)?);
+    }
+    if let Some(original) = original {
+        result.push(original_comment(&original, wrap).into_py_any(py)?);
+    }
+    let result = if result.len() == 1 {
+        result.remove(0)
+    } else {
+        PyTuple::new(py, result)?.into_any().unbind()
+    };
+    if timing {
+        (result, started.elapsed().as_secs_f64()).into_py_any(py)
+    } else {
+        Ok(result)
+    }
+}
+
+/// The keyword options shared by the `tex2typst` entry points, as one
+/// [`crate::core::Options`] each entry point builds once
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_options(
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+) -> PyResult<crate::core::Options> {
+    if let Some(mode) = &handle_text_mode {
+        check_choice("handle_text_mode", mode, HANDLE_TEXT_MODES)?;
+    }
+    Ok(crate::core::Options {
+        non_strict,
+        prefer_shorthands,
+        keep_spaces,
+        frac_to_slash,
+        infty_to_oo,
+        optimize,
+        ignore_unknown_commands,
+        handle_physics,
+        output_unicode,
+        handle_text_mode,
+        custom_tex_macros: custom_tex_macros.map(pydict_to_string_map).transpose()?,
+        replace_unknown_commands: replace_unknown_commands
+            .map(pydict_to_optional_string_map)
+            .transpose()?,
+        symbol_map: symbol_map.map(pydict_to_string_map).transpose()?,
+        environment_map: environment_map.map(pydict_to_string_map).transpose()?,
+        ..Default::default()
+    })
+}
+
+/// Build the JS options map of `options` with the process-wide defaults applied
+/// and the call's `js_options` merged in
+fn build_tex2typst_options(
+    options: &crate::core::Options,
+    js_options: Option<&Bound<PyDict>>,
+) -> PyResult<Option<JsOptions>> {
+    let mut options_map = options.tex2typst_js_options()?.unwrap_or_default();
+    apply_default_options(&mut options_map, TEX2TYPST_OPTION_NAMES);
+    let opts = Some(options_map).filter(|map| !map.is_empty());
+    with_js_options(opts, js_options, TEX2TYPST_OPTION_NAMES)
+}
+
+/// Shared implementation of `tex2typst`
+fn convert_tex(tex: &str, opts: Option<&JsOptions>, isolation: Isolation) -> PyResult<String> {
+    with_thread_converter(isolation != Isolation::Shared, |converter| {
+        converter.tex2typst(tex, opts)
+    })
+}
+
+/// Convert Typst math to LaTeX/TeX format.
+///
+/// Uses a thread-local lazy singleton - the converter is initialized only on the
+/// first call within each thread, avoiding import-time overhead.
+///
+/// Args:
+///     typst: Typst math string to convert
+///     block_math_mode: Use block math mode (default: None)
+///     custom_typst_macros: Typst identifiers replaced before conversion (default: None)
+///     trim_input: Strip leading/trailing whitespace before conversion (default: False)
+///     max_input_length: Raise `ValueError` for input longer than this many bytes (default: None)
+///     delimiters: Surround the output with `\(...\)`/`\[...\]` ("latex"), `$...$`/`$$...$$`
+///         ("dollars") or nothing ("none"), the display form in block math mode (default: None)
+///     trailing_newline: End delimited display math with a line break (default: False)
+///     js_options: Further options passed to the JS converter under their JS names (default: None)
+///     isolated: Convert on a fresh JS context that is dropped after the call (default: False)
+///
+/// Returns:
+///     Converted LaTeX/TeX string
+#[pyfunction]
+#[pyo3(signature = (typst, *, block_math_mode=None, custom_typst_macros=None, trim_input=false, max_input_length=None, delimiters=None, trailing_newline=false, js_options=None, isolated=false))]
+#[allow(clippy::too_many_arguments)]
+fn typst2tex(
+    typst: String,
+    block_math_mode: Option<bool>,
+    custom_typst_macros: Option<&Bound<PyDict>>,
+    trim_input: bool,
+    max_input_length: Option<usize>,
+    delimiters: Option<&str>,
+    trailing_newline: bool,
+    js_options: Option<&Bound<PyDict>>,
+    isolated: bool,
+) -> PyResult<String> {
+    check_input_length(&typst, max_input_length)?;
+    if let Some(delimiters) = delimiters {
+        check_choice("delimiters", delimiters, TEX_DELIMITERS)?;
+    }
+    let opts = build_typst2tex_options(block_math_mode, custom_typst_macros)?;
+    let opts = with_js_options(opts, js_options, TYPST2TEX_OPTION_NAMES)?;
+
+    let tex = with_thread_converter(isolated, |converter| {
+        converter.typst2tex(
+            if trim_input { typst.trim() } else { &typst },
+            opts.as_ref(),
+        )
+    })?;
+    Ok(wrap_tex_math(
+        tex,
+        delimiters,
+        is_block_math_mode(opts.as_ref()),
+        trailing_newline,
+    ))
+}
+
+/// Build the JS options map shared by `typst2tex` and `typst2tex_batch`
+fn build_typst2tex_options(
+    block_math_mode: Option<bool>,
+    custom_typst_macros: Option<&Bound<PyDict>>,
+) -> PyResult<Option<JsOptions>> {
+    let options = crate::core::Options {
+        block_math_mode,
+        custom_typst_macros: custom_typst_macros.map(pydict_to_string_map).transpose()?,
+        ..Default::default()
+    };
+    let mut options_map = options.typst2tex_js_options()?.unwrap_or_default();
+    apply_default_options(&mut options_map, TYPST2TEX_OPTION_NAMES);
+
+    Ok(if options_map.is_empty() {
+        None
+    } else {
+        Some(options_map)
+    })
+}
+
+/// Call a batch `pre_process`/`post_process` hook on one item and return its replacement.
+///
+/// Exceptions raised by the hook, or a non-`str` return value, abort the batch with a
+/// `ValueError` naming the item; the original exception is chained as its cause.
+fn call_item_hook(hook: &Bound<PyAny>, name: &str, index: usize, item: &str) -> PyResult<String> {
+    let py = hook.py();
+    hook.call1((item,))
+        .and_then(|value| value.extract::<String>())
+        .map_err(|e| {
+            let err = PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "item {}: {} raised {}",
+                index, name, e
+            ));
+            err.set_cause(py, Some(e));
+            err
+        })
+}
+
+/// Replace every item with the result of `hook`, if one was given
+fn apply_item_hook(hook: Option<&Bound<PyAny>>, name: &str, items: &mut [String]) -> PyResult<()> {
+    if let Some(hook) = hook {
+        for (index, item) in items.iter_mut().enumerate() {
+            *item = call_item_hook(hook, name, index, item)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reject input longer than `max_input_length` bytes before it reaches the JS engine
+fn check_input_length(input: &str, max_input_length: Option<usize>) -> PyResult<()> {
+    match max_input_length {
+        Some(max) if input.len() > max => {
+            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Input is {} bytes long, exceeding max_input_length={}",
+                input.len(),
+                max
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Batch variant of `check_input_length` that lists every item over the limit
+fn check_batch_input_length(items: &[String], max_input_length: Option<usize>) -> PyResult<()> {
+    let Some(max) = max_input_length else {
+        return Ok(());
+    };
+    let violators: Vec<String> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.len() > max)
+        .map(|(index, item)| format!("item {} ({} bytes)", index, item.len()))
+        .collect();
+    if violators.is_empty() {
+        return Ok(());
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "Inputs exceed max_input_length={}: {}",
+        max,
+        violators.join(", ")
+    )))
+}
+
+/// Default `max_nesting_depth` of the `tex2typst` entry points
+const DEFAULT_MAX_NESTING_DEPTH: usize = 512;
+
+/// Deepest nesting of `{...}` groups, `\left...\right` and `\begin...\end` in TeX source
+fn tex_nesting_depth(tex: &str) -> usize {
+    let mut cursor = TexCursor::new(tex);
+    let (mut depth, mut max_depth) = (0usize, 0usize);
+    while let Some(c) = cursor.peek() {
+        match c {
+            '\\' => {
+                match cursor.read_control_sequence() {
+                    Some("\\left") => depth += 1,
+                    Some("\\right") => depth = depth.saturating_sub(1),
+                    Some("\\begin") => {
+                        // The environment name is not a group of its own
+                        cursor.read_group();
+                        depth += 1;
+                    }
+                    Some("\\end") => {
+                        cursor.read_group();
+                        depth = depth.saturating_sub(1);
+                    }
+                    _ => {}
+                }
+                max_depth = max_depth.max(depth);
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        max_depth = max_depth.max(depth);
+        cursor.bump();
+    }
+    max_depth
+}
+
+/// Reject TeX nested deeper than `max_nesting_depth` before it reaches the JS engine,
+/// whose recursive parser would exhaust its stack
+fn check_nesting_depth(tex: &str, max_nesting_depth: Option<usize>) -> PyResult<()> {
+    match max_nesting_depth {
+        Some(max) if tex.len() > max => {
+            let depth = tex_nesting_depth(tex);
+            if depth > max {
+                return Err(TexParseError::new_err(format!(
+                    "Input nests {} levels deep, exceeding max_nesting_depth={}",
+                    depth, max
+                )));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Batch variant of `check_nesting_depth` that lists every item over the limit
+fn check_batch_nesting_depth(items: &[String], max_nesting_depth: Option<usize>) -> PyResult<()> {
+    let Some(max) = max_nesting_depth else {
+        return Ok(());
+    };
+    let violators: Vec<String> = items
+        .iter()
+        .enumerate()
+        // Nesting a level takes at least one byte
+        .filter(|(_, item)| item.len() > max)
+        .map(|(index, item)| (index, tex_nesting_depth(item)))
+        .filter(|&(_, depth)| depth > max)
+        .map(|(index, depth)| format!("item {} ({} levels)", index, depth))
+        .collect();
+    if violators.is_empty() {
+        return Ok(());
+    }
+    Err(TexParseError::new_err(format!(
+        "Inputs exceed max_nesting_depth={}: {}",
+        max,
+        violators.join(", ")
+    )))
+}
+
+/// Split `max_input_length` off `**options`, which otherwise only hold JS options
+fn take_max_input_length<'py>(
+    options: Option<&Bound<'py, PyDict>>,
+) -> PyResult<(Option<usize>, Option<Bound<'py, PyDict>>)> {
+    let Some(options) = options else {
+        return Ok((None, None));
+    };
+    let options = options.copy()?;
+    let max_input_length = match options.get_item("max_input_length")? {
+        Some(value) => {
+            options.del_item("max_input_length")?;
+            value.extract::<Option<usize>>()?
+        }
+        None => None,
+    };
+    Ok((max_input_length, Some(options)))
+}
+
+/// Strip leading/trailing whitespace without reallocating, used by `trim_input`
+fn trim_in_place(s: &mut String) {
+    s.truncate(s.trim_end().len());
+    let leading = s.len() - s.trim_start().len();
+    s.drain(..leading);
+}
+
+/// Positions of the non-empty items of a batch, used to implement `skip_empty`.
+///
+/// Only the non-empty items are sent to the JS engine; `restore` puts the
+/// `empty_replacement` back at the skipped positions so the output length
+/// always matches the input length.
+struct EmptySkip {
+    kept: Vec<usize>,
+    len: usize,
+}
+
+impl EmptySkip {
+    fn split(items: Vec<String>) -> (Vec<String>, EmptySkip) {
+        let len = items.len();
+        let mut kept = Vec::with_capacity(len);
+        let mut non_empty = Vec::with_capacity(len);
+        for (index, item) in items.into_iter().enumerate() {
+            if !item.is_empty() {
+                kept.push(index);
+                non_empty.push(item);
+            }
+        }
+        (non_empty, EmptySkip { kept, len })
+    }
+
+    /// Pass every item through, for callers that only sometimes skip
+    fn keep_all(items: Vec<String>) -> (Vec<String>, EmptySkip) {
+        let len = items.len();
+        (
+            items,
+            EmptySkip {
+                kept: (0..len).collect(),
+                len,
+            },
+        )
+    }
+
+    /// Map an index into the filtered batch back to the original batch
+    fn original_index(&self, index: usize) -> usize {
+        self.kept[index]
+    }
+
+    fn restore<T: Clone>(&self, results: Vec<T>, replacement: Option<T>) -> Vec<Option<T>> {
+        let mut restored = vec![replacement; self.len];
+        for (index, result) in self.kept.iter().zip(results) {
+            restored[*index] = Some(result);
+        }
+        restored
+    }
+
+    /// `restore` for values that cannot be cloned; `replacement` makes each stand-in
+    fn restore_with<T>(&self, results: Vec<T>, mut replacement: impl FnMut() -> T) -> Vec<T> {
+        let mut results = results.into_iter();
+        let mut kept = self.kept.iter().peekable();
+        (0..self.len)
+            .map(|index| match kept.next_if(|kept| **kept == index) {
+                Some(_) => results.next().expect("one result per kept item"),
+                None => replacement(),
+            })
+            .collect()
+    }
+}
+
+/// Identical items of a batch collapsed to one, for `dedupe=True`.
+///
+/// Only the unique items are sent to the JS engine; `restore` fans their results
+/// back out to every position that held the same input.
+struct Dedupe {
+    /// Per item, the index of its unique copy
+    slots: Vec<usize>,
+    /// Per unique item, the index of its first occurrence
+    first: Vec<usize>,
+}
+
+impl Dedupe {
+    fn split(items: Vec<String>) -> (Vec<String>, Dedupe) {
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut slots = Vec::with_capacity(items.len());
+        let mut first = Vec::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let next = indices.len();
+            let slot = *indices.entry(item).or_insert_with(|| {
+                first.push(index);
+                next
+            });
+            slots.push(slot);
+        }
+        let mut unique = vec![String::new(); indices.len()];
+        for (item, slot) in indices {
+            unique[slot] = item;
+        }
+        (unique, Dedupe { slots, first })
+    }
+
+    /// `positions` of the first occurrence of every unique item
+    fn first_positions(&self, positions: &[usize]) -> Vec<usize> {
+        self.first.iter().map(|&index| positions[index]).collect()
+    }
+
+    fn restore<T: Clone>(&self, results: Vec<T>) -> Vec<T> {
+        self.slots
+            .iter()
+            .map(|&slot| results[slot].clone())
+            .collect()
+    }
+
+    /// Every item whose unique copy is listed in `unique_indices`, in order
+    fn restore_indices(&self, unique_indices: &[usize]) -> Vec<usize> {
+        let listed: HashSet<usize> = unique_indices.iter().copied().collect();
+        (0..self.slots.len())
+            .filter(|index| listed.contains(&self.slots[*index]))
+            .collect()
+    }
+}
+
+/// Batch convert multiple LaTeX/TeX strings to Typst format (internal batch API).
+///
+/// This function is used internally by the Python wrapper to optimize list processing.
+/// It processes all conversions in a single Rust/JS context entry, reducing overhead.
+///
+/// With `keep_original_on_error=True`, items that fail to convert are returned
+/// unchanged instead of raising. With `return_failed_indices=True`, a tuple
+/// `(results, failed_indices)` is returned instead of the plain list.
+/// With `return_summary=True`, a `BatchResult` holding the results, the
+/// `(index, message)` of failed items, timing and engine starts is returned instead.
+/// With `return_exceptions=True`, each item that fails to convert is replaced by its
+/// exception instance in the returned list.
+/// With `skip_empty=True`, empty strings are not sent to the converter and
+/// `empty_replacement` (default `None`) is returned in their place.
+/// With `trim_input=True`, every item is stripped of surrounding whitespace first.
+/// With `report_unknown=True`, a dict mapping item index to the unrecognized
+/// commands of that item is appended to the returned tuple; `fail_on_unknown=True`
+/// raises `TexParseError` instead. `preamble` is parsed once and applied to every item.
+/// With `max_input_length`, every item is checked and all items over the limit are reported.
+/// `wrap` applies to converted items only, not to originals kept by `keep_original_on_error`.
+/// Delimiter errors from `auto_strip_delimiters` and `\ce` errors from `chem` name the offending item.
+/// With `labels=True`, labels are made unique across the whole batch. With `tags=True`,
+/// `\tag{...}` errors name the offending item.
+/// `pre_process` is called on every input before any other step and `post_process` on
+/// every converted item before wrapping; originals kept on error are not passed to it.
+/// With `collapse_redundant_braces=True`, ignored grouping parentheses are dropped from
+/// converted items, and with `normalize_output=True` they are canonicalized by
+/// `format_typst_math`; both run before `post_process`.
+/// Items nested deeper than `max_nesting_depth` are all reported in one `TexParseError`.
+/// `progress_callback` is called with `(completed, total)` after every
+/// `callback_every_n` converted items and after the last one.
+/// A nonzero `chunk_size` converts the list in sub-batches of that many items, and
+/// `progress_bar`, a tqdm-compatible object, has `update(n)` called after each one.
+/// With `dedupe=True`, identical inputs are converted once and the result is copied
+/// to all their positions; `progress_callback` and `progress_bar` then count unique inputs, and an
+/// error names the first item holding the failing input.
+/// With `atomic=True`, a failed item always raises and discards the whole batch,
+/// overriding `keep_original_on_error`.
+/// `js_options` are passed to the JS converter as they are, under their JS names.
+/// `isolated="per_batch"` converts the list on one fresh JS context, `"per_item"` (or
+/// `True`) every item on its own; the unknown-command scan shares a single one.
+#[pyfunction]
+#[pyo3(signature = (tex_list, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, keep_original_on_error=false, return_failed_indices=false, return_summary=false, return_exceptions=false, skip_empty=false, empty_replacement=None, trim_input=false, report_unknown=false, fail_on_unknown=false, preamble=None, max_input_length=None, wrap=None, auto_strip_delimiters=false, chem=false, handle_mhchem=false, siunitx=false, handle_siunitx=false, units_style=None, labels=false, tags=false, normalize_unicode=false, unicode_report=false, strip_comments=false, collapse_redundant_braces=false, normalize_output=false, pre_process=None, post_process=None, progress_callback=None, callback_every_n=100, progress_bar=None, chunk_size=0, dedupe=false, atomic=false, max_nesting_depth=Some(DEFAULT_MAX_NESTING_DEPTH), js_options=None, isolated=None))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_batch<'py>(
+    py: Python<'py>,
+    mut tex_list: Vec<String>,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<'py, PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    keep_original_on_error: bool,
+    return_failed_indices: bool,
+    return_summary: bool,
+    return_exceptions: bool,
+    skip_empty: bool,
+    empty_replacement: Option<String>,
+    trim_input: bool,
+    report_unknown: bool,
+    fail_on_unknown: bool,
+    preamble: Option<&str>,
+    max_input_length: Option<usize>,
+    wrap: Option<&str>,
+    auto_strip_delimiters: bool,
+    chem: bool,
+    handle_mhchem: bool,
+    siunitx: bool,
+    handle_siunitx: bool,
+    units_style: Option<&str>,
+    labels: bool,
+    tags: bool,
+    normalize_unicode: bool,
+    unicode_report: bool,
+    strip_comments: bool,
+    collapse_redundant_braces: bool,
+    normalize_output: bool,
+    pre_process: Option<&Bound<PyAny>>,
+    post_process: Option<&Bound<PyAny>>,
+    progress_callback: Option<&Bound<PyAny>>,
+    callback_every_n: usize,
+    progress_bar: Option<&Bound<PyAny>>,
+    chunk_size: usize,
+    dedupe: bool,
+    atomic: bool,
+    max_nesting_depth: Option<usize>,
+    js_options: Option<&Bound<PyDict>>,
+    isolated: Option<&Bound<PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let siunitx = siunitx || handle_siunitx;
+    let chem = chem || handle_mhchem;
+    let started = Instant::now();
+    let inits_before = STATS.engines_initialized.load(Ordering::Relaxed);
+    let isolation = Isolation::batch(isolated)?;
+    if callback_every_n == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "callback_every_n must be at least 1",
+        ));
+    }
+    if return_summary && (return_failed_indices || report_unknown || unicode_report) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "return_summary cannot be combined with return_failed_indices, report_unknown or unicode_report",
+        ));
+    }
+    if return_exceptions
+        && (keep_original_on_error || return_failed_indices || return_summary || atomic)
+    {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "return_exceptions cannot be combined with keep_original_on_error, return_failed_indices, return_summary or atomic",
+        ));
+    }
+    check_batch_input_length(&tex_list, max_input_length)?;
+    check_batch_nesting_depth(&tex_list, max_nesting_depth)?;
+    check_wrap(wrap, auto_strip_delimiters)?;
+    check_units_style(units_style, siunitx)?;
+    check_unicode_report(unicode_report, normalize_unicode)?;
+    apply_item_hook(pre_process, "pre_process", &mut tex_list)?;
+    if strip_comments {
+        for tex in tex_list.iter_mut() {
+            *tex = strip_tex_comments(tex);
+        }
+    }
+    if trim_input {
+        tex_list.iter_mut().for_each(trim_in_place);
+    }
+    let mut substitutions = Vec::new();
+    if normalize_unicode {
+        for (index, tex) in tex_list.iter_mut().enumerate() {
+            let (normalized, applied) = normalize_unicode_math(py, tex)?;
+            *tex = normalized;
+            if !applied.is_empty() {
+                substitutions.push((index, applied));
+            }
+        }
+    }
+    let mut display_modes = vec![None; tex_list.len()];
+    if auto_strip_delimiters {
+        for (index, tex) in tex_list.iter_mut().enumerate() {
+            let (math, display) = strip_math_delimiters(tex).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("item {}: {}", index, e))
+            })?;
+            if display.is_some() {
+                *tex = math.to_string();
+            }
+            display_modes[index] = display;
+        }
+    }
+    let preamble = preamble
+        .map(|preamble| apply_preamble(py, preamble, custom_tex_macros))
+        .transpose()?;
+    let custom_tex_macros = preamble
+        .as_ref()
+        .map(|(_, merged)| merged)
+        .or(custom_tex_macros);
+    if let Some((argument_macros, _)) = &preamble {
+        for tex in tex_list.iter_mut() {
+            *tex = expand_argument_macros(tex, argument_macros)?;
+        }
+    }
+    let mut item_labels = vec![Vec::new(); tex_list.len()];
+    if labels {
+        // One registry for the whole batch, so labels stay unique across items
+        let mut registry = LabelRegistry::default();
+        for (tex, labels) in tex_list.iter_mut().zip(item_labels.iter_mut()) {
+            (*tex, *labels) = extract_labels(tex, &mut registry);
+        }
+    }
+    let mut item_tags = vec![None; tex_list.len()];
+    if tags {
+        for (index, (tex, item_tag)) in tex_list.iter_mut().zip(item_tags.iter_mut()).enumerate() {
+            let item_error = |e: String| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("item {}: {}", index, e))
+            };
+            let (untagged, tag) = extract_tag(tex).map_err(item_error)?;
+            *tex = untagged;
+            *item_tag = tag
+                .as_ref()
+                .map(tag_to_typst)
+                .transpose()
+                .map_err(|e| item_error(e.value(py).to_string()))?;
+        }
+    }
+    if chem {
+        for (index, tex) in tex_list.iter_mut().enumerate() {
+            *tex = expand_chemistry(tex).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("item {}: {}", index, e))
+            })?;
+        }
+    }
+    // Per item: the input before placeholders were substituted, and the splices
+    let mut siunitx_items = Vec::new();
+    if siunitx {
+        let mut warnings = Vec::new();
+        for (index, tex) in tex_list.iter_mut().enumerate() {
+            let expansion = expand_siunitx(tex, units_style.unwrap_or("plain")).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("item {}: {}", index, e))
+            })?;
+            warnings.extend(
+                expansion
+                    .warnings
+                    .into_iter()
+                    .map(|warning| format!("item {}: {}", index, warning)),
+            );
+            let original = std::mem::replace(tex, expansion.tex);
+            siunitx_items.push((original, expansion.splices));
+        }
+        if !warnings.is_empty() {
+            warn_user(py, &format!("siunitx: {}", warnings.join("; ")))?;
+        }
+    }
+
+    let mut unknown = Vec::new();
+    if report_unknown || fail_on_unknown {
+        let macros = defined_macro_names(custom_tex_macros)?;
+        // Only bare command names reach the engine here, so one fresh context serves
+        // the whole scan even with `isolated="per_item"`
+        with_thread_converter(isolation != Isolation::Shared, |converter| {
+            for (index, tex) in tex_list.iter().enumerate() {
+                let commands = converter.unknown_commands(tex, &macros)?;
+                if !commands.is_empty() {
+                    unknown.push((index, commands));
+                }
+            }
+            Ok(())
+        })?;
+    }
+    if fail_on_unknown && !unknown.is_empty() {
+        let items: Vec<String> = unknown
+            .iter()
+            .map(|(index, commands)| format!("item {}: {}", index, commands.join(", ")))
+            .collect();
+        return Err(TexParseError::new_err(format!(
+            "Unknown commands in {}",
+            items.join("; ")
+        )));
+    }
+
+    let (tex_list, skip) = if skip_empty {
+        EmptySkip::split(tex_list)
+    } else {
+        EmptySkip::keep_all(tex_list)
+    };
+    let (tex_list, dedupe) = if dedupe {
+        let (unique, dedupe) = Dedupe::split(tex_list);
+        (unique, Some(dedupe))
+    } else {
+        (tex_list, None)
+    };
+    let positions = match &dedupe {
+        Some(dedupe) => Cow::Owned(dedupe.first_positions(&skip.kept)),
+        None => Cow::Borrowed(skip.kept.as_slice()),
+    };
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        js_options,
+    )?;
+    // Failed items are converted again for their exceptions with these
+    let summary_opts = (return_summary || return_exceptions).then_some(&opts);
+    let (results, failed_indices) = convert_tex_batch(
+        tex_list,
+        opts.as_ref(),
+        // All results stay staged until the last item converted, so raising on the
+        // first failure discards them
+        (keep_original_on_error || return_exceptions) && !atomic,
+        Some(&positions),
+        chunk_size,
+        progress_callback.map(|callback| (callback, callback_every_n)),
+        progress_bar,
+        isolation,
+    )?;
+    let (results, failed_indices) = match dedupe {
+        Some(dedupe) => (
+            dedupe.restore(results),
+            dedupe.restore_indices(&failed_indices),
+        ),
+        None => (results, failed_indices),
+    };
+    // A failed item still holds the input it was sent as; convert it again for
+    // its exception, like `tex2typst_iter` does
+    let mut failures: HashMap<usize, PyErr> = match summary_opts
+        .filter(|_| !failed_indices.is_empty())
+    {
+        // Under isolation every failed item is converted again on a fresh context
+        Some(opts) => failed_indices
+            .iter()
+            .map(|&index| {
+                let converted =
+                    with_thread_converter(isolation != Isolation::Shared, |converter| {
+                        converter.tex2typst(&results[index], opts.as_ref())
+                    });
+                let err = match converted {
+                    Err(err) => err,
+                    Ok(_) => PyErr::new::<pyo3::exceptions::PyValueError, _>("Conversion failed"),
+                };
+                (index, err)
+            })
+            .collect(),
+        None => HashMap::new(),
+    };
+    let results: Vec<String> = if siunitx {
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(index, typst)| {
+                let (original, splices) = &siunitx_items[skip.original_index(index)];
+                if failed_indices.contains(&index) {
+                    original.clone()
+                } else {
+                    splice_siunitx(&typst, splices)
+                }
+            })
+            .collect()
+    } else {
+        results
+    };
+    let results: Vec<String> = if collapse_redundant_braces || normalize_output {
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(index, typst)| {
+                if failed_indices.contains(&index) {
+                    return typst;
+                }
+                let typst = if collapse_redundant_braces {
+                    collapse_redundant_parens(&typst)
+                } else {
+                    typst
+                };
+                if normalize_output {
+                    canonical_typst_math(&typst)
+                } else {
+                    typst
+                }
+            })
+            .collect()
+    } else {
+        results
+    };
+    let results: Vec<String> = match post_process {
+        Some(hook) => results
+            .into_iter()
+            .enumerate()
+            .map(|(index, typst)| {
+                if failed_indices.contains(&index) {
+                    Ok(typst)
+                } else {
+                    call_item_hook(hook, "post_process", skip.original_index(index), &typst)
+                }
+            })
+            .collect::<PyResult<_>>()?,
+        None => results,
+    };
+    let wrapped = wrap.is_some_and(|wrap| wrap != "none");
+    let results: Vec<String> = if wrapped || item_tags.iter().any(Option::is_some) {
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(index, typst)| {
+                // Items that fell back to their TeX input are not Typst math
+                if failed_indices.contains(&index) {
+                    Ok(typst)
+                } else {
+                    let original_index = skip.original_index(index);
+                    let wrap = resolve_wrap(wrap, display_modes[original_index]);
+                    attach_equation_metadata(
+                        py,
+                        typst,
+                        wrap,
+                        &item_labels[original_index],
+                        item_tags[original_index].as_deref(),
+                    )
+                }
+            })
+            .collect::<PyResult<_>>()?
+    } else {
+        results
+    };
+
+    if return_summary {
+        let mut errors: Vec<(usize, String)> = failures
+            .iter()
+            .map(|(&index, err)| (skip.original_index(index), error_message(py, err)))
+            .collect();
+        errors.sort_by_key(|(index, _)| *index);
+        let results = if skip_empty {
+            skip.restore(results, empty_replacement)
+        } else {
+            results.into_iter().map(Some).collect()
+        };
+        return BatchResult::new(results, errors, started, inits_before).into_py_any(py);
+    }
+    let mut output = vec![if return_exceptions {
+        let items: Vec<PyResult<Py<PyAny>>> = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, typst)| match failures.remove(&index) {
+                Some(err) => Ok(err.into_value(py).into_any()),
+                None => typst.into_py_any(py),
+            })
+            .collect();
+        let items = skip.restore_with(items, || empty_replacement.clone().into_py_any(py));
+        items
+            .into_iter()
+            .collect::<PyResult<Vec<_>>>()?
+            .into_py_any(py)?
+    } else if skip_empty {
+        skip.restore(results, empty_replacement).into_py_any(py)?
+    } else {
+        results.into_py_any(py)?
+    }];
+    if return_failed_indices {
+        let failed_indices: Vec<usize> = failed_indices
+            .into_iter()
+            .map(|index| skip.original_index(index))
+            .collect();
+        output.push(failed_indices.into_py_any(py)?);
+    }
+    if report_unknown {
+        let unknown_by_index = PyDict::new(py);
+        for (index, commands) in unknown {
+            unknown_by_index.set_item(index, commands)?;
+        }
+        output.push(unknown_by_index.into_any().unbind());
+    }
+    if unicode_report {
+        let substitutions_by_index = PyDict::new(py);
+        for (index, applied) in substitutions {
+            substitutions_by_index.set_item(index, applied)?;
+        }
+        output.push(substitutions_by_index.into_any().unbind());
+    }
+
+    if output.len() == 1 {
+        Ok(output.pop().unwrap())
+    } else {
+        PyTuple::new(py, output)?.into_py_any(py)
+    }
+}
+
+/// Shared implementation of `tex2typst_batch`, returning the converted strings
+/// together with the indices of items that fell back to their original input.
+///
+/// A nonzero `chunk_size` converts the list in chunks of that many items, each in
+/// its own JS context entry. With `progress`, chunks also end after every that many
+/// items, where the callback is called with `(completed, total)`. The converter is
+/// not borrowed while the callback runs, so it may convert on its own.
+/// With `Isolation::PerBatch`, all chunks share one fresh JS context.
+#[allow(clippy::too_many_arguments)]
+fn convert_tex_batch(
+    tex_list: Vec<String>,
+    opts: Option<&JsOptions>,
+    keep_original_on_error: bool,
+    positions: Option<&[usize]>,
+    chunk_size: usize,
+    progress: Option<(&Bound<PyAny>, usize)>,
+    progress_bar: Option<&Bound<PyAny>>,
+    isolation: Isolation,
+) -> PyResult<(Vec<String>, Vec<usize>)> {
+    get_thread_converter()?;
+
+    let per_batch = match isolation {
+        Isolation::PerBatch => Some(with_thread_converter(false, ConverterInstance::isolated)?),
+        _ => None,
+    };
+    let convert = |tex_list: &[String], positions: Option<&[usize]>| match &per_batch {
+        Some(converter) => {
+            converter.tex2typst_batch(tex_list, opts, keep_original_on_error, positions)
+        }
+        None => THREAD_CONVERTER.with(|converter| {
+            converter
+                .borrow()
+                .as_ref()
+                .unwrap()
+                .tex2typst_batch_isolated(
+                    tex_list,
+                    opts,
+                    keep_original_on_error,
+                    positions,
+                    isolation,
+                )
+        }),
+    };
+
+    if chunk_size == 0 && progress.is_none() && progress_bar.is_none() {
+        return convert(&tex_list, positions);
+    }
+
+    let total = tex_list.len();
+    let indices: Vec<usize>;
+    let positions = match positions {
+        Some(positions) => positions,
+        None => {
+            indices = (0..total).collect();
+            &indices
+        }
+    };
+    let mut results = Vec::with_capacity(total);
+    let mut failed_indices = Vec::new();
+    // The end of the chunk starting at `start`, given boundaries every `step` items
+    let next_boundary = |start: usize, step: usize| (start / step + 1) * step;
+    let mut start = 0;
+    while start < total {
+        let mut end = total;
+        if chunk_size > 0 {
+            end = end.min(next_boundary(start, chunk_size));
+        }
+        if let Some((_, every_n)) = progress {
+            end = end.min(next_boundary(start, every_n));
+        }
+        let (converted, failed) = convert(&tex_list[start..end], Some(&positions[start..end]))?;
+        results.extend(converted);
+        failed_indices.extend(failed.into_iter().map(|index| start + index));
+        if let Some((callback, every_n)) = progress
+            && (end.is_multiple_of(every_n) || end == total)
+        {
+            callback.call1((end, total))?;
+        }
+        if let Some(bar) = progress_bar {
+            bar.call_method1("update", (end - start,))?;
+        }
+        start = end;
+    }
+    Ok((results, failed_indices))
+}
+
+/// Batch convert multiple Typst strings to LaTeX/TeX format (internal batch API).
+///
+/// This function is used internally by the Python wrapper to optimize list processing.
+/// It processes all conversions in a single Rust/JS context entry, reducing overhead.
+/// With `skip_empty=True`, empty strings are not sent to the converter and
+/// `empty_replacement` (default `None`) is returned in their place.
+/// With `trim_input=True`, every item is stripped of surrounding whitespace first.
+/// With `max_input_length`, all items longer than that many bytes are reported at once.
+/// `pre_process` and `post_process` are called on every input and converted output.
+/// `delimiters` and `trailing_newline` apply to converted items, after `post_process`.
+/// With `dedupe=True`, identical inputs are converted once.
+/// With `return_summary=True`, a `BatchResult` is returned instead of the list.
+/// `js_options` are passed to the JS converter as they are, under their JS names.
+/// `isolated` takes "per_batch" or "per_item" (or `True`) as for `tex2typst_batch`.
+#[pyfunction]
+#[pyo3(signature = (typst_list, *, block_math_mode=None, custom_typst_macros=None, skip_empty=false, empty_replacement=None, trim_input=false, max_input_length=None, delimiters=None, trailing_newline=false, pre_process=None, post_process=None, dedupe=false, return_summary=false, js_options=None, isolated=None))]
+#[allow(clippy::too_many_arguments)]
+fn typst2tex_batch(
+    py: Python<'_>,
+    mut typst_list: Vec<String>,
+    block_math_mode: Option<bool>,
+    custom_typst_macros: Option<&Bound<PyDict>>,
+    skip_empty: bool,
+    empty_replacement: Option<String>,
+    trim_input: bool,
+    max_input_length: Option<usize>,
+    delimiters: Option<&str>,
+    trailing_newline: bool,
+    pre_process: Option<&Bound<PyAny>>,
+    post_process: Option<&Bound<PyAny>>,
+    dedupe: bool,
+    return_summary: bool,
+    js_options: Option<&Bound<PyDict>>,
+    isolated: Option<&Bound<PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let started = Instant::now();
+    let inits_before = STATS.engines_initialized.load(Ordering::Relaxed);
+    let isolation = Isolation::batch(isolated)?;
+    check_batch_input_length(&typst_list, max_input_length)?;
+    if let Some(delimiters) = delimiters {
+        check_choice("delimiters", delimiters, TEX_DELIMITERS)?;
+    }
+    let opts = build_typst2tex_options(block_math_mode, custom_typst_macros)?;
+    let opts = with_js_options(opts, js_options, TYPST2TEX_OPTION_NAMES)?;
+    apply_item_hook(pre_process, "pre_process", &mut typst_list)?;
+    if trim_input {
+        typst_list.iter_mut().for_each(trim_in_place);
+    }
+    let (typst_list, skip) = if skip_empty {
+        EmptySkip::split(typst_list)
+    } else {
+        EmptySkip::keep_all(typst_list)
+    };
+    let mut results = if dedupe {
+        let (unique, dedupe) = Dedupe::split(typst_list);
+        let positions = dedupe.first_positions(&skip.kept);
+        dedupe.restore(convert_typst_batch(
+            unique,
+            opts.as_ref(),
+            Some(&positions),
+            isolation,
+        )?)
+    } else {
+        convert_typst_batch(typst_list, opts.as_ref(), Some(&skip.kept), isolation)?
+    };
+    if let Some(hook) = post_process {
+        for (index, tex) in results.iter_mut().enumerate() {
+            *tex = call_item_hook(hook, "post_process", skip.original_index(index), tex)?;
+        }
+    }
+    if delimiters.is_some() {
+        let display = is_block_math_mode(opts.as_ref());
+        for tex in results.iter_mut() {
+            *tex = wrap_tex_math(std::mem::take(tex), delimiters, display, trailing_newline);
+        }
+    }
+    if return_summary {
+        // Any failure raised above; there is no per-item error policy here
+        let results = if skip_empty {
+            skip.restore(results, empty_replacement)
+        } else {
+            results.into_iter().map(Some).collect()
+        };
+        return BatchResult::new(results, Vec::new(), started, inits_before).into_py_any(py);
+    }
+    if skip_empty {
+        skip.restore(results, empty_replacement).into_py_any(py)
+    } else {
+        results.into_py_any(py)
+    }
+}
+
+/// Shared implementation of `typst2tex_batch`
+fn convert_typst_batch(
+    typst_list: Vec<String>,
+    opts: Option<&JsOptions>,
+    positions: Option<&[usize]>,
+    isolation: Isolation,
+) -> PyResult<Vec<String>> {
+    get_thread_converter()?;
+
+    THREAD_CONVERTER.with(|converter| {
+        converter
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .typst2tex_batch_isolated(&typst_list, opts, positions, isolation)
+    })
+}
+
+/// Parse LaTeX/TeX math and return the upstream AST as Python dicts/lists.
+///
+/// The node schema is passed through as-is from the tex2typst JS library and
+/// is not considered stable across bundle upgrades.
+///
+/// Args:
+///     tex: LaTeX/TeX math string to parse
+///     custom_tex_macros: Custom TeX macro definitions (default: None)
+///
+/// Returns:
+///     Root node of the parsed tree
+#[pyfunction]
+#[pyo3(signature = (tex, *, custom_tex_macros=None))]
+fn parse_tex(
+    py: Python<'_>,
+    tex: String,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    get_thread_converter()?;
+
+    let macros = custom_tex_macros.map(pydict_to_string_map).transpose()?;
+    let depth = tex_nesting_depth(&tex);
+    if depth > MAX_PARSE_NESTING_DEPTH {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "TeX nesting depth {} exceeds the maximum of {}",
+            depth, MAX_PARSE_NESTING_DEPTH
+        )));
+    }
+
+    let json = THREAD_CONVERTER.with(|converter| {
+        converter
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .parse_to_json("parseTex", &tex, macros.as_ref())
+    })?;
+
+    ast_json_to_py(py, &json)
+}
+
+/// Parse Typst math and return the upstream AST as Python dicts/lists.
+///
+/// The node schema is passed through as-is from the tex2typst JS library and
+/// is not considered stable across bundle upgrades.
+///
+/// Args:
+///     typst: Typst math string to parse
+///
+/// Returns:
+///     Root node of the parsed tree
+#[pyfunction]
+fn parse_typst(py: Python<'_>, typst: String) -> PyResult<Py<PyAny>> {
+    get_thread_converter()?;
+
+    let json = THREAD_CONVERTER.with(|converter| {
+        converter
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .parse_to_json("parseTypst", &typst, None)
+    })?;
+
+    ast_json_to_py(py, &json)
+}
+
+/// Global JS function returning the Typst tree `tex2typst` serializes
+const TEX2TYPST_AST_FUNCTION: &str = "tex2typstAST";
+/// Global JS function returning the TeX tree `typst2tex` serializes
+const TYPST2TEX_AST_FUNCTION: &str = "typst2texAST";
+
+/// Run the converted-tree function `func_name` on `input` and return the tree.
+///
+/// The stock bundle does not define these functions, so `NotImplementedError` is
+/// raised unless a rebuilt bundle or a JS extension provides it.
+fn converted_ast(py: Python<'_>, func_name: &str, input: &str) -> PyResult<Py<PyAny>> {
+    get_thread_converter()?;
+
+    let json = THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        if !converter.has_global_function(func_name) {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                format!(
+                    "The JS bundle does not define a global '{}' function; rebuild it with one \
+                     or add it with register_js_extension",
+                    func_name
+                ),
+            ));
+        }
+        converter.parse_to_json(func_name, input, None)
+    })?;
+
+    ast_json_to_py(py, &json)
+}
+
+/// Convert LaTeX/TeX math and return the resulting Typst tree as Python dicts/lists.
+///
+/// Requires a bundle defining a global `tex2typstAST(tex)` function, which the
+/// bundled build does not; raises `NotImplementedError` otherwise.
+///
+/// Args:
+///     tex: LaTeX/TeX math string to convert
+///
+/// Returns:
+///     Root node of the converted tree
+#[pyfunction]
+fn tex2typst_ast(py: Python<'_>, tex: &str) -> PyResult<Py<PyAny>> {
+    converted_ast(py, TEX2TYPST_AST_FUNCTION, tex)
+}
+
+/// Convert Typst math and return the resulting TeX tree as Python dicts/lists.
+///
+/// Requires a bundle defining a global `typst2texAST(typst)` function, which the
+/// bundled build does not; raises `NotImplementedError` otherwise.
+///
+/// Args:
+///     typst: Typst math string to convert
+///
+/// Returns:
+///     Root node of the converted tree
+#[pyfunction]
+fn typst2tex_ast(py: Python<'_>, typst: &str) -> PyResult<Py<PyAny>> {
+    converted_ast(py, TYPST2TEX_AST_FUNCTION, typst)
+}
+
+/// Convert a pandas Series of LaTeX/TeX strings to a Series of Typst strings.
+///
+/// The values are extracted with `Series.to_list()` and converted in a single
+/// batch; the returned Series keeps the index and name of the input.
+/// With `skip_empty=True`, empty strings become `empty_replacement` (default `None`).
+/// With `dedupe=True`, every distinct value is converted once.
+#[cfg(feature = "pandas")]
+#[pyfunction]
+#[pyo3(signature = (s, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, skip_empty=false, empty_replacement=None, max_input_length=None, dedupe=false))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_series<'py>(
+    py: Python<'py>,
+    s: &Bound<'py, PyAny>,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    skip_empty: bool,
+    empty_replacement: Option<String>,
+    max_input_length: Option<usize>,
+    dedupe: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tex_list: Vec<String> = s.call_method0("to_list")?.extract()?;
+    check_batch_input_length(&tex_list, max_input_length)?;
+    let (tex_list, skip) = if skip_empty {
+        EmptySkip::split(tex_list)
+    } else {
+        EmptySkip::keep_all(tex_list)
+    };
+    let (tex_list, dedupe) = if dedupe {
+        let (unique, dedupe) = Dedupe::split(tex_list);
+        (unique, Some(dedupe))
+    } else {
+        (tex_list, None)
+    };
+    let positions = match &dedupe {
+        Some(dedupe) => Cow::Owned(dedupe.first_positions(&skip.kept)),
+        None => Cow::Borrowed(skip.kept.as_slice()),
+    };
+
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    let (results, _) = convert_tex_batch(
+        tex_list,
+        opts.as_ref(),
+        false,
+        Some(&positions),
+        0,
+        None,
+        None,
+        Isolation::Shared,
+    )?;
+    let results = match dedupe {
+        Some(dedupe) => dedupe.restore(results),
+        None => results,
+    };
+    let results = skip.restore(results, empty_replacement);
+
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("index", s.getattr("index")?)?;
+    kwargs.set_item("name", s.getattr("name")?)?;
+    py.import("pandas")?
+        .getattr("Series")?
+        .call((results,), Some(&kwargs))
+}
+
+/// Convert the non-null values of a column in one batch on the thread converter,
+/// keeping nulls as `None`. `first` is the column position of `values[0]`, used in
+/// error messages.
+fn convert_optional_tex(
+    py: Python<'_>,
+    values: &[Option<&str>],
+    first: usize,
+    options: Option<&JsOptions>,
+    keep_original_on_error: bool,
+) -> PyResult<Vec<Option<String>>> {
+    let (indices, tex_list): (Vec<usize>, Vec<&str>) = values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| value.map(|value| (index, value)))
+        .unzip();
+    let positions: Vec<usize> = indices.iter().map(|index| first + index).collect();
+    let (results, _) = py.detach(|| {
+        THREAD_CONVERTER.with(|converter| {
+            converter.borrow().as_ref().unwrap().tex2typst_batch(
+                &tex_list,
+                options,
+                keep_original_on_error,
+                Some(&positions),
+            )
+        })
+    })?;
+
+    let mut output = vec![None; values.len()];
+    for (index, typst) in indices.into_iter().zip(results) {
+        output[index] = Some(typst);
+    }
+    Ok(output)
+}
+
+/// Convert a dataframe column of LaTeX/TeX strings to Typst.
+///
+/// Accepts a pandas or polars Series, or any iterable of strings. Missing values
+/// (`None`, NaN, `pd.NA`) stay `None`. When built with the `arrow` feature, a column
+/// exporting `__arrow_c_stream__` is read in place and the result is a pyarrow
+/// array; otherwise the result is a list.
+#[pyfunction]
+#[pyo3(signature = (obj, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, keep_original_on_error=false))]
+#[allow(clippy::too_many_arguments)]
+fn convert_series<'py>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    keep_original_on_error: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    get_thread_converter()?;
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+
+    #[cfg(feature = "arrow")]
+    if let Some(values) = convert_arrow_stream(py, obj, opts.as_ref(), keep_original_on_error)? {
+        let results = ArrowStringResults {
+            buffers: Mutex::new(Some(StringArrayBuffers::new(&values))),
+        };
+        drop(values);
+        return py.import("pyarrow")?.call_method1("array", (results,));
+    }
+
+    let items = if obj.hasattr("to_list")? {
+        obj.call_method0("to_list")?
+    } else {
+        obj.clone()
+    };
+    let mut values: Vec<Option<String>> = Vec::new();
+    for (position, item) in items.try_iter()?.enumerate() {
+        let item = item?;
+        if item.is_none()
+            || item
+                .cast::<pyo3::types::PyFloat>()
+                .is_ok_and(|f| f.value().is_nan())
+            || item.get_type().name()? == "NAType"
+        {
+            values.push(None);
+        } else if let Ok(s) = item.cast::<pyo3::types::PyString>() {
+            values.push(Some(s.to_str()?.to_owned()));
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "convert_series expects str values, got {} at position {}",
+                item.get_type().name()?,
+                position
+            )));
+        }
+    }
+    let values: Vec<Option<&str>> = values.iter().map(Option::as_deref).collect();
+    let results = convert_optional_tex(py, &values, 0, opts.as_ref(), keep_original_on_error)?;
+    Ok(PyList::new(py, results)?.into_any())
+}
+
+/// Convert LaTeX/TeX math to Typst format and report metadata about the output.
+///
+/// Accepts the same options as `tex2typst`. With `labels=True`, `\label{...}` is removed
+/// before conversion and reported in `ConversionInfo.labels`; with `tags=True`, a
+/// `\tag{...}` is removed and reported in `ConversionInfo.tag`.
+///
+/// Returns:
+///     ConversionInfo with the Typst string, symbols used and required packages
+#[pyfunction]
+#[pyo3(signature = (tex, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, max_input_length=None, labels=false, tags=false))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_with_info(
+    tex: String,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    max_input_length: Option<usize>,
+    labels: bool,
+    tags: bool,
+) -> PyResult<ConversionInfo> {
+    check_input_length(&tex, max_input_length)?;
+    let (tex, labels) = if labels {
+        extract_labels(&tex, &mut LabelRegistry::default())
+    } else {
+        (tex, Vec::new())
+    };
+    let (tex, tag) = if tags {
+        extract_tag(&tex).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?
+    } else {
+        (tex, None)
+    };
+    let tag = tag.as_ref().map(tag_to_typst).transpose()?;
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    let typst = convert_tex(&tex, opts.as_ref(), Isolation::Shared)?;
+    Ok(ConversionInfo {
+        labels,
+        tag,
+        ..ConversionInfo::from_typst(typst)
+    })
+}
+
+/// Batch variant of `tex2typst_with_info` using a single Rust/JS context entry.
+///
+/// With `skip_empty=True`, empty strings yield `None`, or the info for
+/// `empty_replacement` when one is given.
+#[pyfunction]
+#[pyo3(signature = (tex_list, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, skip_empty=false, empty_replacement=None, max_input_length=None, labels=false, tags=false))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_batch_with_info(
+    py: Python<'_>,
+    tex_list: Vec<String>,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    skip_empty: bool,
+    empty_replacement: Option<String>,
+    max_input_length: Option<usize>,
+    labels: bool,
+    tags: bool,
+) -> PyResult<Vec<Option<ConversionInfo>>> {
+    check_batch_input_length(&tex_list, max_input_length)?;
+    let mut registry = LabelRegistry::default();
+    let (tex_list, mut item_labels): (Vec<String>, Vec<_>) = if labels {
+        tex_list
+            .iter()
+            .map(|tex| extract_labels(tex, &mut registry))
+            .unzip()
+    } else {
+        let count = tex_list.len();
+        (tex_list, vec![Vec::new(); count])
+    };
+    let mut item_tags = vec![None; tex_list.len()];
+    let tex_list = if tags {
+        tex_list
+            .into_iter()
+            .zip(item_tags.iter_mut())
+            .enumerate()
+            .map(|(index, (tex, item_tag))| {
+                let item_error = |e: String| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "item {}: {}",
+                        index, e
+                    ))
+                };
+                let (untagged, tag) = extract_tag(&tex).map_err(item_error)?;
+                *item_tag = tag
+                    .as_ref()
+                    .map(tag_to_typst)
+                    .transpose()
+                    .map_err(|e| item_error(e.value(py).to_string()))?;
+                Ok(untagged)
+            })
+            .collect::<PyResult<Vec<_>>>()?
+    } else {
+        tex_list
+    };
+    let (tex_list, skip) = if skip_empty {
+        EmptySkip::split(tex_list)
+    } else {
+        EmptySkip::keep_all(tex_list)
+    };
+
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    let (results, _) = convert_tex_batch(
+        tex_list,
+        opts.as_ref(),
+        false,
+        Some(&skip.kept),
+        0,
+        None,
+        None,
+        Isolation::Shared,
+    )?;
+    Ok(skip
+        .restore(results, empty_replacement)
+        .into_iter()
+        .zip(item_labels.iter_mut().zip(item_tags))
+        .map(|(typst, (labels, tag))| {
+            typst.map(|typst| ConversionInfo {
+                labels: std::mem::take(labels),
+                tag,
+                ..ConversionInfo::from_typst(typst)
+            })
+        })
+        .collect())
+}
+
+/// Environments `tex2typst_aligned` unwraps when they enclose the whole input
+const ALIGNED_ENVIRONMENTS: &[&str] = &[
+    "align",
+    "align*",
+    "aligned",
+    "split",
+    "eqnarray",
+    "eqnarray*",
+];
+
+/// Strip `\begin{env} ... \end{env}` around the input for an alignment environment.
+fn unwrap_aligned_environment(tex: &str) -> &str {
+    for env in ALIGNED_ENVIRONMENTS {
+        let begin = format!("\\begin{{{}}}", env);
+        let end = format!("\\end{{{}}}", env);
+        if let Some(body) = tex
+            .strip_prefix(&begin)
+            .and_then(|rest| rest.strip_suffix(&end))
+        {
+            return body;
+        }
+    }
+    tex
+}
+
+/// Split an aligned equation into rows of cells.
+///
+/// Rows end at `\\` (its `*` and `[<skip>]` are dropped) and cells at an unescaped `&`,
+/// but only outside braces and nested environments, so `cases`, matrices and `\text{..}`
+/// keep theirs. A trailing `\\` does not start an empty row.
+fn split_aligned_rows(tex: &str) -> Vec<Vec<&str>> {
+    let body = unwrap_aligned_environment(tex.trim());
+    let mut cursor = TexCursor::new(body);
+    let mut rows = Vec::new();
+    let mut cells = Vec::new();
+    let mut cell_start = 0;
+    let (mut brace_depth, mut env_depth) = (0usize, 0usize);
+    while let Some(c) = cursor.peek() {
+        let top_level = brace_depth == 0 && env_depth == 0;
+        match c {
+            '\\' if top_level && cursor.src[cursor.pos..].starts_with("\\\\") => {
+                cells.push(&body[cell_start..cursor.pos]);
+                rows.push(std::mem::take(&mut cells));
+                cursor.pos += 2;
+                if cursor.peek() == Some('*') {
+                    cursor.bump();
+                }
+                cursor.read_delimited('[', ']');
+                cell_start = cursor.pos;
+                continue;
+            }
+            '\\' => {
+                match cursor.read_control_sequence() {
+                    Some("\\begin") => env_depth += 1,
+                    Some("\\end") => env_depth = env_depth.saturating_sub(1),
+                    _ => {}
+                }
+                continue;
+            }
+            '{' => brace_depth += 1,
+            '}' => brace_depth = brace_depth.saturating_sub(1),
+            '&' if top_level => {
+                cells.push(&body[cell_start..cursor.pos]);
+                cell_start = cursor.pos + 1;
+            }
+            _ => {}
+        }
+        cursor.bump();
+    }
+    cells.push(&body[cell_start..]);
+    if cells.len() > 1 || !cells[0].trim().is_empty() || rows.is_empty() {
+        rows.push(cells);
+    }
+    rows
+}
+
+/// Convert an aligned multi-line equation such as `a &= b \\ &= c`.
+///
+/// Rows are split on `\\` and cells on `&`; each non-empty cell goes through the converter
+/// on its own and the result is joined back into `&`-aligned Typst math with one row per
+/// line. An enclosing `align`, `align*`, `aligned`, `split` or `eqnarray` environment is
+/// unwrapped first. Accepts the conversion options of `tex2typst`.
+///
+/// Returns:
+///     Typst math without `$` delimiters, ready for a block equation
+#[pyfunction]
+#[pyo3(signature = (tex, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, max_input_length=None))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_aligned(
+    tex: &str,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    max_input_length: Option<usize>,
+) -> PyResult<String> {
+    check_input_length(tex, max_input_length)?;
+    let rows = split_aligned_rows(tex);
+    // Convert every cell in a single context entry
+    let cells: Vec<String> = rows
+        .iter()
+        .flatten()
+        .map(|cell| cell.trim())
+        .filter(|cell| !cell.is_empty())
+        .map(str::to_string)
+        .collect();
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    let (converted, _) = convert_tex_batch(
+        cells,
+        opts.as_ref(),
+        false,
+        None,
+        0,
+        None,
+        None,
+        Isolation::Shared,
+    )?;
+    let mut converted = converted.into_iter();
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|cells| {
+            let mut line = String::new();
+            for (index, cell) in cells.iter().enumerate() {
+                if index > 0 {
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push('&');
+                }
+                if !cell.trim().is_empty() {
+                    let typst = converted.next().unwrap_or_default();
+                    // `&= b` reads like the TeX source; other cells get a space after `&`
+                    if line.ends_with('&')
+                        && !typst.starts_with(['=', '<', '>', ':', '~', '!', '-', '+'])
+                    {
+                        line.push(' ');
+                    }
+                    line.push_str(&typst);
+                }
+            }
+            line
+        })
+        .collect();
+    Ok(lines.join(" \\\n"))
+}
+
+/// Option asking the JS converter to annotate its output with `/* ... */` comments
+const EXPLAIN_OPTION: &str = "explainConversion";
+
+/// `text` made safe to embed in a Typst block comment, which nests
+fn typst_comment_text(text: &str) -> String {
+    text.replace("*/", "* /").replace("/*", "/ *")
+}
+
+/// Convert LaTeX/TeX math to Typst annotated with `/* ... */` comments, for tutorials
+/// and diff viewers.
+///
+/// Uses the bundle's `explainConversion` function if it has one. Otherwise `tex2typst`
+/// also runs with the `explainConversion: true` option; if that changes nothing the
+/// bundle ignores the option, and the plain Typst is prefixed with a
+/// `/* input: ... */` comment holding the original TeX. Accepts the conversion options
+/// of `tex2typst`.
+///
+/// Returns:
+///     Typst math with comments
+#[pyfunction]
+#[pyo3(signature = (tex, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, max_input_length=None))]
+#[allow(clippy::too_many_arguments)]
+fn explain_conversion(
+    tex: &str,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    max_input_length: Option<usize>,
+) -> PyResult<String> {
+    check_input_length(tex, max_input_length)?;
+    let mut opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?
+    .unwrap_or_default();
+    get_thread_converter()?;
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        let options = (!opts.is_empty()).then(|| opts.clone());
+        if let Some(explained) = converter.explain_conversion(tex, options.as_ref())? {
+            return Ok(explained);
+        }
+        let typst = converter.tex2typst(tex, options.as_ref())?;
+        opts.insert(EXPLAIN_OPTION.to_string(), serde_json::Value::Bool(true));
+        let explained = converter.tex2typst(tex, Some(&opts))?;
+        if explained != typst {
+            Ok(explained)
+        } else {
+            Ok(format!(
+                "/* input: {} */\n{}",
+                typst_comment_text(tex),
+                typst
+            ))
+        }
+    })
+}
+
+/// TeX in the form `roundtrip_check` compares: whitespace is dropped except where it
+/// ends a control word before a letter, and `^{x}`/`_{x}` lose the braces around a
+/// single character
+fn normalize_roundtrip_tex(tex: &str) -> String {
+    let mut normalized = String::with_capacity(tex.len());
+    let mut cursor = TexCursor::new(tex);
+    let mut after_control_word = false;
+    while let Some(c) = cursor.peek() {
+        if c == '\\' {
+            let command = cursor.read_control_sequence().unwrap_or_default();
+            normalized.push_str(command);
+            after_control_word =
+                command.len() > 1 && command[1..].starts_with(|c: char| c.is_ascii_alphabetic());
+            continue;
+        }
+        if c.is_whitespace() {
+            cursor.skip_whitespace();
+            if after_control_word && cursor.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                normalized.push(' ');
+            }
+            continue;
+        }
+        after_control_word = false;
+        cursor.bump();
+        normalized.push(c);
+        if matches!(c, '^' | '_') {
+            let rest = &tex[cursor.pos..];
+            let mut chars = rest.chars();
+            if let (Some('{'), Some(inner), Some('}')) = (chars.next(), chars.next(), chars.next())
+                && !matches!(inner, '\\' | '{' | '}')
+                && !inner.is_whitespace()
+            {
+                normalized.push(inner);
+                cursor.pos += 2 + inner.len_utf8();
+            }
+        }
+    }
+    normalized
+}
+
+/// `ndiff`-style comparison of two lines, with a `?` line marking where they diverge
+fn line_diff(expected: &str, actual: &str) -> String {
+    if expected == actual {
+        return String::new();
+    }
+    let common = expected
+        .chars()
+        .zip(actual.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    format!("- {}\n+ {}\n? {}^", expected, actual, " ".repeat(common))
+}
+
+/// Outcome of converting TeX to Typst and back
+struct Roundtrip {
+    typst: Option<String>,
+    tex: Option<String>,
+    error: Option<String>,
+    equivalent: bool,
+    diff: String,
+}
+
+fn run_roundtrip(
+    py: Python<'_>,
+    tex: &str,
+    max_input_length: Option<usize>,
+) -> PyResult<Roundtrip> {
+    check_input_length(tex, max_input_length)?;
+    get_thread_converter()?;
+    let (typst, back) = THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        let typst = converter.tex2typst(tex, None);
+        let back = typst
+            .as_ref()
+            .ok()
+            .map(|typst| converter.typst2tex(typst, None));
+        (typst, back)
+    });
+    let (typst, error) = match typst {
+        Ok(typst) => (Some(typst), None),
+        Err(e) => (None, Some(format!("tex2typst: {}", e.value(py)))),
+    };
+    let (back, error) = match back {
+        Some(Ok(back)) => (Some(back), error),
+        Some(Err(e)) => (None, Some(format!("typst2tex: {}", e.value(py)))),
+        None => (None, error),
+    };
+    let (equivalent, diff) = match &back {
+        Some(back) => {
+            let diff = line_diff(
+                &normalize_roundtrip_tex(tex),
+                &normalize_roundtrip_tex(back),
+            );
+            (diff.is_empty(), diff)
+        }
+        None => (false, String::new()),
+    };
+    Ok(Roundtrip {
+        typst,
+        tex: back,
+        error,
+        equivalent,
+        diff,
+    })
+}
+
+/// Whether TeX survives a TeX -> Typst -> TeX round trip unchanged.
+///
+/// The input and the final TeX are compared after dropping insignificant whitespace and
+/// the braces of single-character scripts (`x^{2}` equals `x^2`). A conversion error
+/// in either direction counts as a failed round trip.
+///
+/// Raises:
+///     ValueError: If the input is longer than `max_input_length` bytes
+#[pyfunction]
+#[pyo3(signature = (tex, *, max_input_length=None))]
+fn roundtrip_check(py: Python<'_>, tex: &str, max_input_length: Option<usize>) -> PyResult<bool> {
+    Ok(run_roundtrip(py, tex, max_input_length)?.equivalent)
+}
+
+/// Details of a TeX -> Typst -> TeX round trip, for reports of `roundtrip_check` failures.
+///
+/// Returns:
+///     dict with "input", "intermediate" (Typst), "output" (final TeX), "equivalent",
+///     "diff" (empty when equivalent) and "error" (the failed step's message, or None);
+///     "intermediate" and "output" are None when their step did not run or failed
+#[pyfunction]
+#[pyo3(signature = (tex, *, max_input_length=None))]
+fn roundtrip_diff(
+    py: Python<'_>,
+    tex: &str,
+    max_input_length: Option<usize>,
+) -> PyResult<Py<PyAny>> {
+    let roundtrip = run_roundtrip(py, tex, max_input_length)?;
+    let report = PyDict::new(py);
+    report.set_item("input", tex)?;
+    report.set_item("intermediate", roundtrip.typst)?;
+    report.set_item("output", roundtrip.tex)?;
+    report.set_item("equivalent", roundtrip.equivalent)?;
+    report.set_item("diff", roundtrip.diff)?;
+    report.set_item("error", roundtrip.error)?;
+    Ok(report.into_any().unbind())
+}
+
+/// A macro harvested from a preamble by `parse_preamble`
+struct PreambleMacro {
+    body: String,
+    num_args: usize,
+}
+
+/// Macro definitions and warnings collected from a LaTeX preamble
+struct Preamble {
+    /// Definitions in source order; later ones override earlier ones on lookup
+    macros: Vec<(String, PreambleMacro)>,
+    warnings: Vec<String>,
+}
+
+/// Declaration commands that define macros the converter cannot expand
+const UNSUPPORTED_DECLARATIONS: &[&str] = &[
+    "\\NewDocumentCommand",
+    "\\RenewDocumentCommand",
+    "\\ProvideDocumentCommand",
+    "\\DeclareDocumentCommand",
+];
+
+/// Upper bound on macro expansions per input, guarding against recursive definitions
+const MAX_MACRO_EXPANSIONS: usize = 10_000;
+
+/// Minimal reader over TeX source for declarations and macro arguments
+struct TexCursor<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> TexCursor<'a> {
+    fn new(src: &'a str) -> Self {
+        TexCursor { src, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Read `\letters` or a `\` followed by a single character
+    fn read_control_sequence(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        if self.peek() != Some('\\') {
+            return None;
+        }
+        self.bump();
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.bump();
+                }
+            }
+            Some(_) => {
+                self.bump();
+            }
+            None => {}
+        }
+        Some(&self.src[start..self.pos])
+    }
+
+    /// Read a balanced `open ... close` group and return its contents
+    fn read_delimited(&mut self, open: char, close: char) -> Option<&'a str> {
+        if self.peek() != Some(open) {
+            return None;
+        }
+        self.bump();
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' => {
+                    // Escaped delimiters like `\{` do not count
+                    self.read_control_sequence();
+                    continue;
+                }
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                c if c == close && depth == 0 => {
+                    let content = &self.src[start..self.pos];
+                    self.bump();
+                    return Some(content);
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+        None
+    }
+
+    fn read_group(&mut self) -> Option<&'a str> {
+        self.read_delimited('{', '}')
+    }
+
+    /// Read one macro argument: a brace group, a control sequence or a single character
+    fn read_argument(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '{' => self.read_group(),
+            '\\' => self.read_control_sequence(),
+            _ => {
+                let start = self.pos;
+                self.bump();
+                Some(&self.src[start..self.pos])
+            }
+        }
+    }
+
+    /// Read the macro name of a declaration, written as `\name` or `{\name}`
+    fn read_macro_name(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        if self.peek() == Some('{') {
+            let name = self.read_group()?.trim();
+            let valid = name.len() > 1
+                && name.starts_with('\\')
+                && name[1..].chars().all(|c| c.is_ascii_alphabetic());
+            return valid.then_some(name);
+        }
+        self.read_control_sequence()
+            .filter(|name| name.len() > 1 && name[1..].chars().all(|c| c.is_ascii_alphabetic()))
+    }
+}
+
+/// Remove `%` comments, keeping escaped `\%`.
+///
+/// As in TeX, a comment also swallows the line break ending it (`\n` or `\r\n`) and the
+/// indentation of the next line, so a trailing `%` joins two lines.
+fn strip_tex_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut escaped = false;
+    while let Some(c) = chars.next() {
+        if c == '%' && !escaped {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    break;
+                }
+            }
+            while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+            continue;
+        }
+        escaped = c == '\\' && !escaped;
+        stripped.push(c);
+    }
+    stripped
+}
+
+/// Harvest `\newcommand`, `\renewcommand`, `\providecommand` and `\def` declarations
+fn parse_preamble_source(text: &str) -> Preamble {
+    let source = strip_tex_comments(text);
+    let mut cursor = TexCursor::new(&source);
+    let mut preamble = Preamble {
+        macros: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            cursor.bump();
+            continue;
+        }
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        match command {
+            "\\newcommand" | "\\renewcommand" | "\\providecommand" => {
+                if cursor.peek() == Some('*') {
+                    cursor.bump();
+                }
+                let Some(name) = cursor.read_macro_name() else {
+                    preamble
+                        .warnings
+                        .push(format!("{}: missing macro name", command));
+                    continue;
+                };
+                cursor.skip_whitespace();
+                let mut num_args = 0;
+                if let Some(count) = cursor.read_delimited('[', ']') {
+                    match count.trim().parse::<usize>() {
+                        Ok(n) if n <= 9 => num_args = n,
+                        _ => {
+                            preamble.warnings.push(format!(
+                                "{}{{{}}}: invalid argument count '{}'",
+                                command, name, count
+                            ));
+                            cursor.skip_whitespace();
+                            cursor.read_group();
+                            continue;
+                        }
+                    }
+                }
+                cursor.skip_whitespace();
+                if cursor.read_delimited('[', ']').is_some() {
+                    preamble.warnings.push(format!(
+                        "{}{{{}}}: optional arguments with defaults are not supported",
+                        command, name
+                    ));
+                    cursor.skip_whitespace();
+                    cursor.read_group();
+                    continue;
+                }
+                cursor.skip_whitespace();
+                match cursor.read_group() {
+                    Some(body) => preamble.macros.push((
+                        name.to_string(),
+                        PreambleMacro {
+                            body: body.to_string(),
+                            num_args,
+                        },
+                    )),
+                    None => preamble
+                        .warnings
+                        .push(format!("{}{{{}}}: missing definition body", command, name)),
+                }
+            }
+            "\\def" => {
+                let Some(name) = cursor.read_macro_name() else {
+                    preamble
+                        .warnings
+                        .push("\\def: missing macro name".to_string());
+                    continue;
+                };
+                let params_start = cursor.pos;
+                while cursor.peek().is_some_and(|c| c != '{') {
+                    cursor.bump();
+                }
+                let params: String = source[params_start..cursor.pos]
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                // Only undelimited parameters `#1#2...#n` map onto plain arguments
+                let num_args = params.len() / 2;
+                let expected: String = (1..=num_args).map(|i| format!("#{}", i)).collect();
+                let undelimited = num_args <= 9 && params == expected;
+                let body = cursor.read_group();
+                match body {
+                    Some(body) if undelimited => preamble.macros.push((
+                        name.to_string(),
+                        PreambleMacro {
+                            body: body.to_string(),
+                            num_args,
+                        },
+                    )),
+                    Some(_) => preamble.warnings.push(format!(
+                        "\\def{}: delimited parameters '{}' are not supported",
+                        name, params
+                    )),
+                    None => preamble
+                        .warnings
+                        .push(format!("\\def{}: missing definition body", name)),
+                }
+            }
+            command if UNSUPPORTED_DECLARATIONS.contains(&command) => {
+                let name = cursor.read_macro_name().unwrap_or("?");
+                preamble.warnings.push(format!(
+                    "{}{{{}}}: declaration is not supported",
+                    command, name
+                ));
+            }
+            _ => {}
+        }
+    }
+    preamble
+}
+
+/// Substitute `#1`..`#9` in a macro body; `##` stands for a literal `#`
+fn substitute_macro_args(body: &str, args: &[&str]) -> String {
+    let mut result = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '#' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('#') => {
+                chars.next();
+                result.push('#');
+            }
+            Some(d)
+                if d.is_ascii_digit() && d != '0' && (d as usize - '0' as usize) <= args.len() =>
+            {
+                chars.next();
+                result.push_str(args[d as usize - '1' as usize]);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Expand uses of argument-taking macros, which the JS converter cannot substitute itself
+fn expand_argument_macros(tex: &str, macros: &HashMap<String, PreambleMacro>) -> PyResult<String> {
+    let mut current = tex.to_string();
+    let mut expansions = 0;
+    loop {
+        let mut expanded = String::with_capacity(current.len());
+        let mut changed = false;
+        let mut cursor = TexCursor::new(&current);
+        while let Some(c) = cursor.peek() {
+            if c != '\\' {
+                expanded.push(c);
+                cursor.bump();
+                continue;
+            }
+            let start = cursor.pos;
+            let command = cursor.read_control_sequence().unwrap_or_default();
+            let Some(definition) = macros.get(command) else {
+                expanded.push_str(command);
+                continue;
+            };
+
+            let args: Option<Vec<&str>> = (0..definition.num_args)
+                .map(|_| cursor.read_argument())
+                .collect();
+            let Some(args) = args else {
+                // Too few arguments left: keep the text as written
+                expanded.push_str(&current[start..]);
+                break;
+            };
+            expansions += 1;
+            if expansions > MAX_MACRO_EXPANSIONS {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Macro expansion limit exceeded while expanding {}; is it recursive?",
+                    command
+                )));
+            }
+            expanded.push_str(&substitute_macro_args(&definition.body, &args));
+            changed = true;
+        }
+        if !changed {
+            return Ok(current);
+        }
+        current = expanded;
+    }
+}
+
+/// Emit a `UserWarning` attributed to the caller
+fn warn_user(py: Python<'_>, message: &str) -> PyResult<()> {
+    let message =
+        std::ffi::CString::new(message.replace('\0', "")).expect("NUL bytes were removed");
+    PyErr::warn(
+        py,
+        &py.get_type::<pyo3::exceptions::PyUserWarning>(),
+        &message,
+        1,
+    )
+}
+
+/// Parse `preamble`, warn about unsupported declarations and split its macros into
+/// argument-taking ones (expanded in Rust) and `custom_tex_macros` merged beneath
+/// the explicit ones (or the process-wide default when none are given)
+fn apply_preamble<'py>(
+    py: Python<'py>,
+    preamble: &str,
+    custom_tex_macros: Option<&Bound<'py, PyDict>>,
+) -> PyResult<(HashMap<String, PreambleMacro>, Bound<'py, PyDict>)> {
+    let parsed = parse_preamble_source(preamble);
+    if !parsed.warnings.is_empty() {
+        warn_user(
+            py,
+            &format!(
+                "Ignored preamble declarations: {}",
+                parsed.warnings.join("; ")
+            ),
+        )?;
+    }
+
+    // Later definitions replace earlier ones, as `\renewcommand` would
+    let definitions: HashMap<String, PreambleMacro> = parsed.macros.into_iter().collect();
+    let (mut argument_macros, plain_macros): (HashMap<_, _>, HashMap<_, _>) = definitions
+        .into_iter()
+        .partition(|(_, definition)| definition.num_args > 0);
+    let merged = PyDict::new(py);
+    for (name, definition) in plain_macros {
+        // The JS converter substitutes bodies verbatim, so expand argument macros here
+        merged.set_item(
+            name,
+            expand_argument_macros(&definition.body, &argument_macros)?,
+        )?;
+    }
+
+    if let Some(explicit) = custom_tex_macros {
+        merged.update(explicit.as_mapping())?;
+    } else if let Some(serde_json::Value::Object(defaults)) =
+        DEFAULT_OPTIONS.lock().unwrap().get("custom_tex_macros")
+    {
+        for (name, body) in defaults {
+            merged.set_item(name, json_to_py(py, body)?)?;
+        }
+    }
+    // Explicit zero-argument macros shadow preamble definitions with arguments
+    for name in merged.keys() {
+        argument_macros.remove(&name.extract::<String>()?);
+    }
+    Ok((argument_macros, merged))
+}
+
+/// Parse `\newcommand`, `\renewcommand`, `\providecommand` and `\def` declarations.
+///
+/// Returns:
+///     dict with "macros" (name -> body, usable as `custom_tex_macros` for macros
+///     without arguments), "num_args" (name -> argument count) and "warnings"
+///     (declarations that were recognized but not supported)
+#[pyfunction]
+fn parse_preamble(py: Python<'_>, text: &str) -> PyResult<Py<PyAny>> {
+    let parsed = parse_preamble_source(text);
+    let macros = PyDict::new(py);
+    let num_args = PyDict::new(py);
+    for (name, definition) in parsed.macros {
+        macros.set_item(&name, definition.body)?;
+        num_args.set_item(name, definition.num_args)?;
+    }
+
+    let result = PyDict::new(py);
+    result.set_item("macros", macros)?;
+    result.set_item("num_args", num_args)?;
+    result.set_item("warnings", parsed.warnings)?;
+    Ok(result.into_any().unbind())
+}
+
+/// File formats accepted by `load_macros_from_file` and `save_macros_to_file`
+#[derive(Clone, Copy, PartialEq)]
+enum MacroFileFormat {
+    Json,
+    Toml,
+}
+
+impl MacroFileFormat {
+    /// Pick the format from the file extension
+    fn from_path(path: &std::path::Path) -> PyResult<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("json") => Ok(MacroFileFormat::Json),
+            Some("toml") => Ok(MacroFileFormat::Toml),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unsupported macro file '{}', expected a .json or .toml extension",
+                path.display()
+            ))),
+        }
+    }
+}
+
+fn non_string_macro(path: &std::path::Path, name: &str, kind: &str) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+        "Macro '{}' in '{}' must map to a string, got {}",
+        name,
+        path.display(),
+        kind
+    ))
+}
+
+/// Read a JSON or TOML file mapping macro names to their TeX expansions.
+///
+/// The result can be passed as `custom_tex_macros`. TOML is read with the standard
+/// library `tomllib` (Python 3.11+) or the `tomli` package.
+///
+/// Raises:
+///     FileNotFoundError: If the file does not exist
+///     ValueError: If the extension is not .json/.toml or the file does not parse
+///         as a table of macros
+///     KeyError: If a macro maps to something other than a string
+#[pyfunction]
+fn load_macros_from_file(py: Python<'_>, path: std::path::PathBuf) -> PyResult<Py<PyAny>> {
+    let format = MacroFileFormat::from_path(&path)?;
+    let text = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
+            format!("Macro file not found: '{}'", path.display()),
+        ),
+        _ => e.into(),
+    })?;
+    let invalid = |e: String| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid macro file '{}': {}",
+            path.display(),
+            e
+        ))
+    };
+
+    let macros = PyDict::new(py);
+    match format {
+        MacroFileFormat::Json => {
+            let value: serde_json::Value =
+                serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+            let serde_json::Value::Object(entries) = value else {
+                return Err(invalid(
+                    "expected an object of macro definitions".to_string(),
+                ));
+            };
+            for (name, body) in entries {
+                let serde_json::Value::String(body) = body else {
+                    let kind = match body {
+                        serde_json::Value::Null => "null",
+                        serde_json::Value::Bool(_) => "bool",
+                        serde_json::Value::Number(_) => "number",
+                        serde_json::Value::Array(_) => "array",
+                        _ => "object",
+                    };
+                    return Err(non_string_macro(&path, &name, kind));
+                };
+                macros.set_item(name, body)?;
+            }
+        }
+        MacroFileFormat::Toml => {
+            let tomllib = py
+                .import("tomllib")
+                .or_else(|_| py.import("tomli"))
+                .map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyModuleNotFoundError, _>(
+                        "Reading TOML macro files needs Python 3.11+ or the 'tomli' package",
+                    )
+                })?;
+            let table = tomllib
+                .call_method1("loads", (text,))
+                .map_err(|e| invalid(e.value(py).to_string()))?;
+            for (name, body) in table.cast::<PyDict>()?.iter() {
+                let name: String = name.extract()?;
+                let Ok(body) = body.extract::<String>() else {
+                    let kind = body.get_type().name()?.to_string();
+                    return Err(non_string_macro(&path, &name, &kind));
+                };
+                macros.set_item(name, body)?;
+            }
+        }
+    }
+    Ok(macros.into_any().unbind())
+}
+
+/// Write macros to a JSON or TOML file readable by `load_macros_from_file`.
+///
+/// The format follows the file extension; entries are written in dict order.
+///
+/// Raises:
+///     ValueError: If the extension is not .json/.toml
+///     KeyError: If a macro maps to something other than a string
+#[pyfunction]
+fn save_macros_to_file(macros: &Bound<PyDict>, path: std::path::PathBuf) -> PyResult<()> {
+    let format = MacroFileFormat::from_path(&path)?;
+    let mut entries = Vec::with_capacity(macros.len());
+    for (name, body) in macros.iter() {
+        let name: String = name.extract()?;
+        let Ok(body) = body.extract::<String>() else {
+            let kind = body.get_type().name()?.to_string();
+            return Err(non_string_macro(&path, &name, &kind));
+        };
+        // JSON string escapes are also valid TOML basic-string escapes; TOML
+        // additionally requires DEL to be escaped
+        let quote = |s: String| {
+            serde_json::Value::String(s)
+                .to_string()
+                .replace('\u{7f}', "\\u007F")
+        };
+        entries.push((quote(name), quote(body)));
+    }
+
+    let text = match format {
+        MacroFileFormat::Json if entries.is_empty() => "{}\n".to_string(),
+        MacroFileFormat::Json => {
+            let lines: Vec<String> = entries
+                .iter()
+                .map(|(name, body)| format!("  {}: {}", name, body))
+                .collect();
+            format!("{{\n{}\n}}\n", lines.join(",\n"))
+        }
+        MacroFileFormat::Toml => entries
+            .iter()
+            .map(|(name, body)| format!("{} = {}\n", name, body))
+            .collect(),
+    };
+    std::fs::write(&path, text)?;
+    Ok(())
+}
+
+/// Commands in prose that `convert_refs` rewrites to `@label` references
+const REF_COMMANDS: &[&str] = &["\\ref", "\\eqref", "\\autoref"];
+
+/// Sanitize a TeX label name into a Typst label: characters other than letters, digits,
+/// `_`, `-`, `:` and `.` become `-`
+fn typst_label_name(name: &str) -> String {
+    let mut label: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_-:.".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    // A trailing `.` or `:` would end an `@reference` early
+    while label.ends_with(['.', ':']) {
+        label.pop();
+    }
+    if label.is_empty() {
+        label.push_str("label");
+    }
+    label
+}
+
+/// Assigns unique Typst labels across one call, numbering duplicates `-2`, `-3`, ...
+#[derive(Default)]
+struct LabelRegistry {
+    used: HashSet<String>,
+}
+
+impl LabelRegistry {
+    fn assign(&mut self, name: &str) -> String {
+        let base = typst_label_name(name);
+        let mut label = base.clone();
+        let mut n = 1;
+        while !self.used.insert(label.clone()) {
+            n += 1;
+            label = format!("{}-{}", base, n);
+        }
+        label
+    }
+}
+
+/// Remove `\label{...}` from math, returning the stripped TeX and the
+/// `(original, typst)` label pairs in order
+fn extract_labels(tex: &str, registry: &mut LabelRegistry) -> (String, Vec<(String, String)>) {
+    let mut stripped = String::with_capacity(tex.len());
+    let mut labels = Vec::new();
+    let mut cursor = TexCursor::new(tex);
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            stripped.push(c);
+            cursor.bump();
+            continue;
+        }
+        let start = cursor.pos;
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        if command != "\\label" {
+            stripped.push_str(command);
+            continue;
+        }
+        cursor.skip_whitespace();
+        match cursor.read_group() {
+            Some(name) => {
+                let label = registry.assign(name);
+                labels.push((name.to_string(), label));
+            }
+            // Not a well-formed `\label{...}`: leave it to the converter
+            None => stripped.push_str(&tex[start..cursor.pos]),
+        }
+    }
+    (stripped, labels)
+}
+
+/// Attach the first label and the tag to block-wrapped Typst; Typst equations take a
+/// single label.
+///
+/// A tag becomes the numbering of the equation, and the equation counter is stepped
+/// back so that the tagged equation does not shift the numbers after it.
+fn attach_equation_metadata(
+    py: Python<'_>,
+    typst: String,
+    wrap: &str,
+    labels: &[(String, String)],
+    tag: Option<&str>,
+) -> PyResult<String> {
+    let Some(tag) = tag else {
+        return attach_label(py, wrap_typst_math(typst, wrap), wrap, labels);
+    };
+    if wrap != "block" {
+        warn_user(
+            py,
+            &format!("\\tag is only kept with wrap='block'; dropped {}", tag),
+        )?;
+        return attach_label(py, wrap_typst_math(typst, wrap), wrap, labels);
+    }
+    let equation = format!(
+        "#math.equation(block: true, numbering: _ => [{}], $ {} $)",
+        tag, typst
+    );
+    Ok(format!(
+        "{}#counter(math.equation).update(n => n - 1)",
+        attach_label(py, equation, wrap, labels)?
+    ))
+}
+
+/// Attach the first label to block-wrapped Typst; Typst equations take a single label
+fn attach_label(
+    py: Python<'_>,
+    typst: String,
+    wrap: &str,
+    labels: &[(String, String)],
+) -> PyResult<String> {
+    let Some((_, first)) = labels.first().filter(|_| wrap == "block") else {
+        return Ok(typst);
+    };
+    if labels.len() > 1 {
+        let dropped: Vec<String> = labels[1..]
+            .iter()
+            .map(|(_, label)| format!("<{}>", label))
+            .collect();
+        warn_user(
+            py,
+            &format!(
+                "A Typst equation takes a single label; attached <{}>, dropped {}",
+                first,
+                dropped.join(", ")
+            ),
+        )?;
+    }
+    Ok(format!("{} <{}>", typst, first))
+}
+
+/// Rewrite `\ref{...}`, `\eqref{...}` and `\autoref{...}` in prose to Typst `@label`
+/// references.
+///
+/// Args:
+///     text: LaTeX prose
+///     labels: Mapping of TeX label names to Typst labels, such as
+///         `dict(info.labels)` from `tex2typst_with_info(..., labels=True)`;
+///         names not in it are sanitized the same way (default: None)
+///
+/// Returns:
+///     The text with references rewritten
+#[pyfunction]
+#[pyo3(signature = (text, labels=None))]
+fn convert_refs(text: &str, labels: Option<HashMap<String, String>>) -> String {
+    let labels = labels.unwrap_or_default();
+    let mut converted = String::with_capacity(text.len());
+    let mut cursor = TexCursor::new(text);
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            converted.push(c);
+            cursor.bump();
+            continue;
+        }
+        let start = cursor.pos;
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        if !REF_COMMANDS.contains(&command) {
+            converted.push_str(command);
+            continue;
+        }
+        match cursor.read_group() {
+            Some(name) => {
+                converted.push('@');
+                match labels.get(name.trim()) {
+                    Some(label) => converted.push_str(label),
+                    None => converted.push_str(&typst_label_name(name)),
+                }
+            }
+            None => converted.push_str(&text[start..cursor.pos]),
+        }
+    }
+    converted
+}
+
+/// Accepted values for the `keep_original` option
+const KEEP_ORIGINAL_MODES: &[&str] = &["comment"];
+
+/// Typst comment quoting the TeX `original` of a formula converted with `wrap`.
+///
+/// Comments cannot go inside `$...$`, so the caller puts it next to the math: a
+/// `/* tex: ... */` comment before block-wrapped output, with the `/*` and `*/` of
+/// the TeX spaced apart since Typst block comments nest, and a `// tex: ...` comment
+/// after any other output, on one line with its line breaks written as `\n`. Both
+/// are valid in markup and in code.
+fn original_comment(original: &str, wrap: &str) -> String {
+    let original = original.trim();
+    if wrap == "block" {
+        let quoted = original.replace("/*", "/ *").replace("*/", "* /");
+        format!("/* tex: {} */", quoted)
+    } else {
+        let quoted = original.replace("\r\n", "\\n").replace(['\n', '\r'], "\\n");
+        format!("// tex: {}", quoted)
+    }
+}
+
+/// Remove the comments added for `keep_original="comment"` from Typst text.
+///
+/// A `// tex: ...` comment goes with the spaces before it, up to the end of its
+/// line; a `/* tex: ... */` comment with the line break after it. Other comments
+/// are kept.
+///
+/// Args:
+///     text: Typst text holding converted math and its original-TeX comments
+///
+/// Returns:
+///     The text without those comments
+#[pyfunction]
+fn strip_original_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let line = rest.find("// tex: ");
+        let block = rest.find("/* tex: ");
+        let (start, is_block) = match (line, block) {
+            (Some(line), Some(block)) if block < line => (block, true),
+            (Some(line), _) => (line, false),
+            (None, Some(block)) => (block, true),
+            (None, None) => break,
+        };
+        if is_block {
+            let Some(end) = rest[start..].find("*/") else {
+                break;
+            };
+            stripped.push_str(&rest[..start]);
+            rest = &rest[start + end + "*/".len()..];
+            rest = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+                .unwrap_or(rest);
+        } else {
+            stripped.push_str(rest[..start].trim_end_matches([' ', '\t']));
+            rest = &rest[start..];
+            rest = &rest[rest.find(['\r', '\n']).unwrap_or(rest.len())..];
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// A `\tag{...}` or `\tag*{...}` removed from a formula
+struct TexTag {
+    body: String,
+    starred: bool,
+}
+
+/// Remove `\tag{...}` and `\tag*{...}` from math; more than one tag is an error
+fn extract_tag(tex: &str) -> Result<(String, Option<TexTag>), String> {
+    if !tex.contains("\\tag") {
+        return Ok((tex.to_string(), None));
+    }
+    let mut stripped = String::with_capacity(tex.len());
+    let mut tags: Vec<TexTag> = Vec::new();
+    let mut cursor = TexCursor::new(tex);
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            stripped.push(c);
+            cursor.bump();
+            continue;
+        }
+        let start = cursor.pos;
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        if command != "\\tag" {
+            stripped.push_str(command);
+            continue;
+        }
+        let starred = cursor.peek() == Some('*');
+        if starred {
+            cursor.bump();
+        }
+        cursor.skip_whitespace();
+        match cursor.read_group() {
+            Some(body) => {
+                let unescaped = body.replace("\\\\", "").replace("\\$", "");
+                if unescaped.matches('$').count() % 2 == 1 {
+                    return Err(format!("Unbalanced '$' in \\tag{{{}}}", body));
+                }
+                tags.push(TexTag {
+                    body: body.to_string(),
+                    starred,
+                })
+            }
+            // Not a well-formed `\tag{...}`: leave it to the converter
+            None => stripped.push_str(&tex[start..cursor.pos]),
+        }
+    }
+    if tags.len() > 1 {
+        let found: Vec<String> = tags
+            .iter()
+            .map(|tag| {
+                format!(
+                    "\\tag{}{{{}}}",
+                    if tag.starred { "*" } else { "" },
+                    tag.body
+                )
+            })
+            .collect();
+        return Err(format!(
+            "A formula takes a single \\tag, found {}",
+            found.join(", ")
+        ));
+    }
+    Ok((stripped, tags.pop()))
+}
+
+/// Render a tag as Typst markup: text is escaped, `$...$` is converted as math and the
+/// result is parenthesized unless the tag is starred
+fn tag_to_typst(tag: &TexTag) -> PyResult<String> {
+    let mut markup = String::new();
+    let mut in_math = false;
+    let mut math = String::new();
+    let mut chars = tag.body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            if in_math {
+                get_thread_converter()?;
+                let typst = THREAD_CONVERTER.with(|converter| {
+                    converter.borrow().as_ref().unwrap().tex2typst(&math, None)
+                })?;
+                markup.push_str(&format!("${}$", typst));
+                math.clear();
+            }
+            in_math = !in_math;
+        } else if in_math {
+            math.push(c);
+        } else if c == '\\' {
+            // `\%`, `\&`, ... are the plain character in text; other commands stay literal
+            match chars.peek() {
+                Some(&next) if !next.is_alphabetic() => {
+                    chars.next();
+                    markup.push_str(&typst_markup_escape(next));
+                }
+                _ => markup.push_str("\\\\"),
+            }
+        } else {
+            markup.push_str(&typst_markup_escape(c));
+        }
+    }
+    Ok(if tag.starred {
+        markup
+    } else {
+        format!("({})", markup)
+    })
+}
+
+/// Escape a character that has a meaning in Typst markup
+fn typst_markup_escape(c: char) -> String {
+    if "\\#$*_[]<>@`/".contains(c) {
+        format!("\\{}", c)
+    } else {
+        c.to_string()
+    }
+}
+
+/// Unicode characters found in copy-pasted math and the TeX that replaces them
+/// with `normalize_unicode=True`
+const UNICODE_TEX_MAP: &[(char, &str)] = &[
+    // Spaces and invisible characters
+    ('\u{00A0}', " "),
+    ('\u{2002}', " "),
+    ('\u{2003}', " "),
+    ('\u{2009}', " "),
+    ('\u{200A}', " "),
+    ('\u{202F}', " "),
+    ('\u{200B}', ""),
+    ('\u{2060}', ""),
+    ('\u{FEFF}', ""),
+    // Dashes, primes and scripts
+    ('\u{2212}', "-"),
+    ('\u{2013}', "-"),
+    ('\u{2032}', "'"),
+    ('\u{2033}', "''"),
+    ('\u{2034}', "'''"),
+    ('\u{00B9}', "^{1}"),
+    ('\u{00B2}', "^{2}"),
+    ('\u{00B3}', "^{3}"),
+    ('\u{00B0}', "^{\\circ}"),
+    // Operators and relations
+    ('\u{00D7}', "\\times"),
+    ('\u{00F7}', "\\div"),
+    ('\u{22C5}', "\\cdot"),
+    ('\u{00B7}', "\\cdot"),
+    ('\u{2219}', "\\cdot"),
+    ('\u{00B1}', "\\pm"),
+    ('\u{2213}', "\\mp"),
+    ('\u{2218}', "\\circ"),
+    ('\u{2297}', "\\otimes"),
+    ('\u{2295}', "\\oplus"),
+    ('\u{2264}', "\\leq"),
+    ('\u{2265}', "\\geq"),
+    ('\u{2260}', "\\neq"),
+    ('\u{2248}', "\\approx"),
+    ('\u{2261}', "\\equiv"),
+    ('\u{223C}', "\\sim"),
+    ('\u{2245}', "\\cong"),
+    ('\u{221D}', "\\propto"),
+    ('\u{226A}', "\\ll"),
+    ('\u{226B}', "\\gg"),
+    ('\u{2208}', "\\in"),
+    ('\u{2209}', "\\notin"),
+    ('\u{220B}', "\\ni"),
+    ('\u{2282}', "\\subset"),
+    ('\u{2286}', "\\subseteq"),
+    ('\u{2283}', "\\supset"),
+    ('\u{2287}', "\\supseteq"),
+    ('\u{222A}', "\\cup"),
+    ('\u{2229}', "\\cap"),
+    ('\u{2205}', "\\emptyset"),
+    ('\u{2200}', "\\forall"),
+    ('\u{2203}', "\\exists"),
+    ('\u{00AC}', "\\neg"),
+    ('\u{2227}', "\\land"),
+    ('\u{2228}', "\\lor"),
+    // Arrows
+    ('\u{2192}', "\\to"),
+    ('\u{2190}', "\\leftarrow"),
+    ('\u{2194}', "\\leftrightarrow"),
+    ('\u{21D2}', "\\Rightarrow"),
+    ('\u{21D0}', "\\Leftarrow"),
+    ('\u{21D4}', "\\Leftrightarrow"),
+    ('\u{21A6}', "\\mapsto"),
+    // Big operators and calculus
+    ('\u{2211}', "\\sum"),
+    ('\u{220F}', "\\prod"),
+    ('\u{222B}', "\\int"),
+    ('\u{222C}', "\\iint"),
+    ('\u{222E}', "\\oint"),
+    ('\u{221A}', "\\sqrt"),
+    ('\u{2202}', "\\partial"),
+    ('\u{2207}', "\\nabla"),
+    ('\u{221E}', "\\infty"),
+    // Dots and delimiters
+    ('\u{2026}', "\\ldots"),
+    ('\u{22EF}', "\\cdots"),
+    ('\u{27E8}', "\\langle"),
+    ('\u{27E9}', "\\rangle"),
+    ('\u{2016}', "\\|"),
+    // Letterlike symbols
+    ('\u{211D}', "\\mathbb{R}"),
+    ('\u{2115}', "\\mathbb{N}"),
+    ('\u{2124}', "\\mathbb{Z}"),
+    ('\u{211A}', "\\mathbb{Q}"),
+    ('\u{2102}', "\\mathbb{C}"),
+    ('\u{210F}', "\\hbar"),
+    ('\u{2113}', "\\ell"),
+    // Greek
+    ('\u{03B1}', "\\alpha"),
+    ('\u{03B2}', "\\beta"),
+    ('\u{03B3}', "\\gamma"),
+    ('\u{03B4}', "\\delta"),
+    ('\u{03B5}', "\\varepsilon"),
+    ('\u{03F5}', "\\epsilon"),
+    ('\u{03B6}', "\\zeta"),
+    ('\u{03B7}', "\\eta"),
+    ('\u{03B8}', "\\theta"),
+    ('\u{03D1}', "\\vartheta"),
+    ('\u{03B9}', "\\iota"),
+    ('\u{03BA}', "\\kappa"),
+    ('\u{03BB}', "\\lambda"),
+    ('\u{03BC}', "\\mu"),
+    ('\u{00B5}', "\\mu"),
+    ('\u{03BD}', "\\nu"),
+    ('\u{03BE}', "\\xi"),
+    ('\u{03C0}', "\\pi"),
+    ('\u{03C1}', "\\rho"),
+    ('\u{03C3}', "\\sigma"),
+    ('\u{03C2}', "\\varsigma"),
+    ('\u{03C4}', "\\tau"),
+    ('\u{03C5}', "\\upsilon"),
+    ('\u{03C6}', "\\varphi"),
+    ('\u{03D5}', "\\phi"),
+    ('\u{03C7}', "\\chi"),
+    ('\u{03C8}', "\\psi"),
+    ('\u{03C9}', "\\omega"),
+    ('\u{0393}', "\\Gamma"),
+    ('\u{0394}', "\\Delta"),
+    ('\u{2206}', "\\Delta"),
+    ('\u{0398}', "\\Theta"),
+    ('\u{039B}', "\\Lambda"),
+    ('\u{039E}', "\\Xi"),
+    ('\u{03A0}', "\\Pi"),
+    ('\u{03A3}', "\\Sigma"),
+    ('\u{03A5}', "\\Upsilon"),
+    ('\u{03A6}', "\\Phi"),
+    ('\u{03A8}', "\\Psi"),
+    ('\u{03A9}', "\\Omega"),
+    ('\u{2126}', "\\Omega"),
+];
+
+/// Validate `unicode_report`, which reports what `normalize_unicode` replaced
+fn check_unicode_report(unicode_report: bool, normalize_unicode: bool) -> PyResult<()> {
+    if unicode_report && !normalize_unicode {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "unicode_report requires normalize_unicode=True",
+        ));
+    }
+    Ok(())
+}
+
+/// NFC-normalize `tex` and replace the characters in `UNICODE_TEX_MAP`, returning the
+/// distinct `(character, tex)` substitutions in order of first use
+fn normalize_unicode_math(py: Python<'_>, tex: &str) -> PyResult<(String, Vec<(String, String)>)> {
+    if tex.is_ascii() {
+        return Ok((tex.to_string(), Vec::new()));
+    }
+    // No normalization tables in the crate; Python ships them
+    let composed: String = py
+        .import("unicodedata")?
+        .call_method1("normalize", ("NFC", tex))?
+        .extract()?;
+
+    let mut normalized = String::with_capacity(composed.len());
+    let mut substitutions: Vec<(String, String)> = Vec::new();
+    let mut chars = composed.chars().peekable();
+    while let Some(c) = chars.next() {
+        let Some((_, replacement)) = UNICODE_TEX_MAP.iter().find(|(from, _)| *from == c) else {
+            normalized.push(c);
+            continue;
+        };
+        normalized.push_str(replacement);
+        // `\alpha` directly followed by a letter would read as one control word
+        let control_word = replacement.starts_with('\\')
+            && replacement.ends_with(|c: char| c.is_ascii_alphabetic());
+        if control_word && chars.peek().is_some_and(|next| next.is_ascii_alphabetic()) {
+            normalized.push(' ');
+        }
+        let substitution = (c.to_string(), replacement.to_string());
+        if !substitutions.contains(&substitution) {
+            substitutions.push(substitution);
+        }
+    }
+    Ok((normalized, substitutions))
+}
+
+/// Math syntax for rendering a number in `num_to_typst` and `num_to_tex`
+struct NumberSyntax {
+    infinity: &'static str,
+    nan: &'static str,
+    times: &'static str,
+    /// Power of ten from the exponent; TeX braces every exponent, Typst only negative ones
+    power: fn(i32) -> String,
+}
+
+const TYPST_NUMBER_SYNTAX: NumberSyntax = NumberSyntax {
+    infinity: "oo",
+    nan: "\"NaN\"",
+    times: "times",
+    power: |exponent| {
+        if exponent < 0 {
+            format!("10^({})", exponent)
+        } else {
+            format!("10^{}", exponent)
+        }
+    },
+};
+
+const TEX_NUMBER_SYNTAX: NumberSyntax = NumberSyntax {
+    infinity: "\\infty",
+    nan: "\\mathrm{NaN}",
+    times: "\\times",
+    power: |exponent| format!("10^{{{}}}", exponent),
+};
+
+/// Format a float as math, switching to `m times 10^e` where Python's `repr` would
+/// use `e`-notation (exponents below -4 or from 16 up).
+///
+/// Digits are the shortest that round-trip, so `0.1` stays `0.1`; integral values drop
+/// the `.0` and a mantissa of exactly 1 is left out (`10^(-10)`).
+fn format_number(n: f64, syntax: &NumberSyntax) -> String {
+    if n.is_nan() {
+        return syntax.nan.to_string();
+    }
+    let sign = if n.is_sign_negative() && n != 0.0 {
+        "-"
+    } else {
+        ""
+    };
+    let n = n.abs();
+    if n.is_infinite() {
+        return format!("{}{}", sign, syntax.infinity);
+    }
+    // `{:e}` gives the shortest round-trip digits, e.g. `1.5e-10`
+    let scientific = format!("{:e}", n);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    if n == 0.0 || (-4..16).contains(&exponent) {
+        return format!("{}{}", sign, n);
+    }
+    let power = (syntax.power)(exponent);
+    if mantissa == "1" {
+        format!("{}{}", sign, power)
+    } else {
+        format!("{}{} {} {}", sign, mantissa, syntax.times, power)
+    }
+}
+
+/// Format a number as Typst math, e.g. `1.5e-10` as `1.5 times 10^(-10)`.
+///
+/// Infinities become `oo` and NaN the string `"NaN"`.
+#[pyfunction]
+fn num_to_typst(n: f64) -> String {
+    format_number(n, &TYPST_NUMBER_SYNTAX)
+}
+
+/// Format a number as TeX math, e.g. `1.5e-10` as `1.5 \times 10^{-10}`.
+///
+/// Infinities become `\infty` and NaN `\mathrm{NaN}`.
+#[pyfunction]
+fn num_to_tex(n: f64) -> String {
+    format_number(n, &TEX_NUMBER_SYNTAX)
+}
+
+/// Typst symbol and function names that TeX would spell with a backslash
+const TYPST_WORDS: &[&str] = &[
+    "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta", "iota", "kappa",
+    "lambda", "mu", "nu", "xi", "omicron", "pi", "rho", "sigma", "tau", "upsilon", "phi", "chi",
+    "psi", "omega", "Gamma", "Delta", "Theta", "Lambda", "Xi", "Pi", "Sigma", "Phi", "Psi",
+    "Omega", "infinity", "oo", "dot", "dots", "arrow", "times", "plus", "minus", "approx", "in",
+    "subset", "union", "sect", "forall", "exists", "partial", "nabla", "sum", "product",
+    "integral", "lim", "sin", "cos", "tan", "log", "ln", "exp", "det", "max", "min", "dif", "RR",
+    "NN", "ZZ", "QQ", "CC", "quad", "emptyset", "sym",
+];
+
+/// Typst shorthands for operators that TeX writes as commands (`\to`, `\leq`, ...)
+const TYPST_OPERATORS: &[&str] = &["<->", "|->", "->", "=>", "<-", "<=", ">=", "!=", "<<", ">>"];
+
+/// Accepted `convert_auto` targets
+const FORMAT_TARGETS: &[&str] = &["typst", "tex"];
+
+/// Accumulate heuristic evidence for the input being TeX and for it being Typst
+fn format_scores(s: &str) -> (u32, u32) {
+    let chars: Vec<char> = s.chars().collect();
+    let (mut tex, mut typst) = (0, 0);
+    let mut i = 0;
+    while i < chars.len() {
+        let next = chars.get(i + 1).copied();
+        match chars[i] {
+            '\\' => {
+                // `\cmd` is TeX; escaped characters like `\#` are valid in both
+                if next.is_some_and(|c| c.is_ascii_alphabetic()) {
+                    tex += 3;
+                    i += 1;
+                    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                        i += 1;
+                    }
+                } else {
+                    i += 2;
+                }
+                continue;
+            }
+            '^' | '_' if next == Some('{') => {
+                tex += 2;
+                i += 2;
+                continue;
+            }
+            '^' | '_' if next == Some('(') => {
+                typst += 2;
+                i += 2;
+                continue;
+            }
+            '{' => tex += 1,
+            '#' => typst += 3,
+            '"' => {
+                // String literals are Typst text; their contents are not inspected
+                typst += 2;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let next = chars.get(i).copied();
+                if TYPST_WORDS.contains(&word.as_str()) || (word.len() >= 2 && next == Some('(')) {
+                    typst += 2;
+                }
+                // Modifiers such as `arrow.r` or `dots.h`
+                if word.len() >= 2
+                    && next == Some('.')
+                    && chars.get(i + 1).is_some_and(|c| c.is_ascii_alphabetic())
+                {
+                    typst += 2;
+                }
+                continue;
+            }
+            _ => {
+                let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+                if let Some(op) = TYPST_OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                    typst += 2;
+                    i += op.len();
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    (tex, typst)
+}
+
+/// Classify input as "tex", "typst" or "ambiguous".
+///
+/// One format wins only when its evidence is at least twice the other's.
+fn classify_format(s: &str) -> &'static str {
+    let (tex, typst) = format_scores(s);
+    if tex > 0 && tex >= 2 * typst {
+        "tex"
+    } else if typst > 0 && typst >= 2 * tex {
+        "typst"
+    } else {
+        "ambiguous"
+    }
+}
+
+/// Guess whether a math string is written in TeX or Typst.
+///
+/// The heuristics look for backslash commands, `^{...}` groups and braces on the
+/// TeX side, and for function calls like `frac(`, bare symbol names, `->` style
+/// shorthands, string literals and `#` on the Typst side.
+///
+/// Returns:
+///     "tex", "typst", or "ambiguous" when the evidence is missing or mixed
+#[pyfunction]
+fn detect_format(s: &str) -> &'static str {
+    classify_format(s)
+}
+
+/// Canonicalize the spacing of Typst math without running the converter.
+///
+/// Equivalent output of different tex2typst versions formats the same: whitespace
+/// outside strings collapses, `=`, `+`, `->` and other relations get single
+/// spaces, `,` and `;` a space after, brackets, `^`, `_` and `/` none, and
+/// redundant `lr(...)` wrappers are dropped. Formatting is idempotent.
+#[pyfunction]
+fn format_typst_math(typst: &str) -> String {
+    canonical_typst_math(typst)
+}
+
+/// Whether two tokens of canonical Typst math mean the same; numbers compare by
+/// value with `numbers_by_value`
+fn same_typst_token(a: &TypstToken<'_>, b: &TypstToken<'_>, numbers_by_value: bool) -> bool {
+    if a.kind != b.kind || a.space_before != b.space_before {
+        return false;
+    }
+    if a.text == b.text {
+        return true;
+    }
+    let numeric = |text: &str| text.starts_with(|c: char| c.is_ascii_digit());
+    numbers_by_value
+        && numeric(a.text)
+        && numeric(b.text)
+        && a.text.parse::<f64>().ok() == b.text.parse::<f64>().ok()
+}
+
+/// Token-level diff of canonical Typst in `git diff --word-diff` style, `[-...-]`
+/// marking tokens only in `a` and `{+...+}` tokens only in `b`; `None` if the
+/// token streams match
+fn typst_token_diff(a: &str, b: &str, numbers_by_value: bool) -> Option<String> {
+    let (a, b) = (canonical_typst_math(a), canonical_typst_math(b));
+    let (a, b) = (tokenize_typst_math(&a), tokenize_typst_math(&b));
+    let same = |i: usize, j: usize| same_typst_token(&a[i], &b[j], numbers_by_value);
+    if a.len() == b.len() && (0..a.len()).all(|i| same(i, i)) {
+        return None;
+    }
+    // Longest common subsequence lengths of the suffixes `a[i..]` and `b[j..]`
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if same(i, j) {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut diff = String::new();
+    let mut push = |marker: Option<char>, token: &TypstToken<'_>, open: &mut Option<char>| {
+        if *open != marker {
+            match open.take() {
+                Some('-') => diff.push_str("-]"),
+                Some(_) => diff.push_str("+}"),
+                None => {}
+            }
+            if token.space_before {
+                diff.push(' ');
+            }
+            match marker {
+                Some('-') => diff.push_str("[-"),
+                Some(_) => diff.push_str("{+"),
+                None => {}
+            }
+            *open = marker;
+        } else if token.space_before {
+            diff.push(' ');
+        }
+        diff.push_str(token.text);
+    };
+    let mut open = None;
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && same(i, j) {
+            push(None, &a[i], &mut open);
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && common[i + 1][j] >= common[i][j + 1]) {
+            push(Some('-'), &a[i], &mut open);
+            i += 1;
+        } else {
+            push(Some('+'), &b[j], &mut open);
+            j += 1;
+        }
+    }
+    match open {
+        Some('-') => diff.push_str("-]"),
+        Some(_) => diff.push_str("+}"),
+        None => {}
+    }
+    Some(diff)
+}
+
+/// Whether two Typst math strings mean the same.
+///
+/// Both are canonicalized like `format_typst_math` and compared token by token, so
+/// spacing and redundant `lr(...)` do not matter. String literals compare exactly,
+/// numbers by value (`0.50` equals `0.5`) unless `numbers_by_value=False`.
+#[pyfunction]
+#[pyo3(signature = (a, b, *, numbers_by_value=true))]
+fn typst_equivalent(a: &str, b: &str, numbers_by_value: bool) -> bool {
+    typst_token_diff(a, b, numbers_by_value).is_none()
+}
+
+/// `typst_equivalent` for each pair of `list_a` and `list_b`.
+///
+/// Raises:
+///     ValueError: If the lists differ in length
+#[pyfunction]
+#[pyo3(signature = (list_a, list_b, *, numbers_by_value=true))]
+fn typst_equivalent_batch(
+    list_a: Vec<String>,
+    list_b: Vec<String>,
+    numbers_by_value: bool,
+) -> PyResult<Vec<bool>> {
+    if list_a.len() != list_b.len() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "list_a has {} items but list_b has {}",
+            list_a.len(),
+            list_b.len()
+        )));
+    }
+    Ok(list_a
+        .iter()
+        .zip(&list_b)
+        .map(|(a, b)| typst_token_diff(a, b, numbers_by_value).is_none())
+        .collect())
+}
+
+/// Token-level diff of two Typst math strings, `None` if `typst_equivalent`.
+///
+/// Tokens of the canonical forms only in `a` are marked `[-...-]`, those only in
+/// `b` `{+...+}`, as in `git diff --word-diff`.
+#[pyfunction]
+#[pyo3(signature = (a, b, *, numbers_by_value=true))]
+fn diff_typst(a: &str, b: &str, numbers_by_value: bool) -> Option<String> {
+    typst_token_diff(a, b, numbers_by_value)
+}
+
+/// Commands whose braced argument is read as text, where spacing is significant
+const TEXT_ARGUMENT_COMMANDS: &[&str] = &[
+    "\\text",
+    "\\textrm",
+    "\\textbf",
+    "\\textit",
+    "\\textsf",
+    "\\texttt",
+    "\\textup",
+    "\\textnormal",
+    "\\emph",
+    "\\mbox",
+    "\\hbox",
+    "\\operatorname",
+    "\\tag",
+    "\\label",
+];
+
+/// Characters the converter reads as one number when nothing separates them
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || c == '.'
+}
+
+/// Number of parameters a macro body uses: its highest `#1`..`#9`
+fn macro_arg_count(body: &str) -> usize {
+    let mut count = 0;
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c == '#' {
+            match chars.next() {
+                Some(d @ '1'..='9') => count = count.max(d as usize - '0' as usize),
+                _ => continue,
+            }
+        }
+    }
+    count
+}
+
+/// TeX math respelled so that spellings the converter treats alike compare equal.
+///
+/// Whitespace is dropped except where it ends a control word before a letter,
+/// separates digits the converter would otherwise join into one number, or comes
+/// between `\\` and `[`. Arguments of `TEXT_ARGUMENT_COMMANDS` are kept as they
+/// are, `^{x}`/`_{x}` lose the braces around a single character unless a number
+/// follows, and braces around the whole input are removed.
+fn canonical_tex(tex: &str) -> String {
+    let mut normalized = String::with_capacity(tex.len());
+    let mut cursor = TexCursor::new(tex);
+    let mut after_control_word = false;
+    let mut after_line_break = false;
+    while let Some(c) = cursor.peek() {
+        if c == '\\' {
+            let command = cursor.read_control_sequence().unwrap_or_default();
+            normalized.push_str(command);
+            after_control_word =
+                command.len() > 1 && command[1..].starts_with(|c: char| c.is_ascii_alphabetic());
+            after_line_break = command == "\\\\";
+            if TEXT_ARGUMENT_COMMANDS.contains(&command) {
+                let mut argument = TexCursor {
+                    src: tex,
+                    pos: cursor.pos,
+                };
+                argument.skip_whitespace();
+                let starred = argument.peek() == Some('*');
+                if starred {
+                    argument.bump();
+                    argument.skip_whitespace();
+                }
+                let start = argument.pos;
+                if argument.read_group().is_some() {
+                    if starred {
+                        normalized.push('*');
+                    }
+                    normalized.push_str(&tex[start..argument.pos]);
+                    cursor.pos = argument.pos;
+                    after_control_word = false;
+                }
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            cursor.skip_whitespace();
+            let keep = cursor.peek().is_some_and(|next| {
+                (after_control_word && next.is_ascii_alphabetic())
+                    || (after_line_break && next == '[')
+                    || (normalized.ends_with(is_number_char) && is_number_char(next))
+            });
+            if keep {
+                normalized.push(' ');
+            }
+            continue;
+        }
+        after_control_word = false;
+        after_line_break = false;
+        cursor.bump();
+        normalized.push(c);
+        if matches!(c, '^' | '_') {
+            let mut argument = TexCursor {
+                src: tex,
+                pos: cursor.pos,
+            };
+            argument.skip_whitespace();
+            let Some(group) = argument.read_group() else {
+                continue;
+            };
+            let mut chars = group.trim().chars();
+            let (Some(inner), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            let end = argument.pos;
+            argument.skip_whitespace();
+            if !"\\{}%#&^_~$'".contains(inner) && !argument.peek().is_some_and(is_number_char) {
+                normalized.push(inner);
+                cursor.pos = end;
+            }
+        }
+    }
+    // Braces around everything only group what is already one expression
+    while normalized.starts_with('{') {
+        let mut group = TexCursor::new(&normalized);
+        let Some(inner) = group.read_group() else {
+            break;
+        };
+        if group.pos != normalized.len() {
+            break;
+        }
+        normalized = inner.to_string();
+    }
+    normalized
+}
+
+/// Canonical form of TeX math, for hashing and deduplicating inputs.
+///
+/// Comments are stripped, insignificant whitespace and redundant braces removed and,
+/// with `expand_macros` (keyed like `custom_tex_macros`, using `#1`..`#9` for
+/// arguments), macros expanded. Spellings such as `\frac {1} {2}`, `\frac{1}{2}` and
+/// `{\frac{1}{2}}` normalize the same, and the result converts like the input with
+/// `strip_comments=True`.
+#[pyfunction]
+#[pyo3(signature = (tex, *, expand_macros=None))]
+fn normalize_tex(tex: &str, expand_macros: Option<HashMap<String, String>>) -> PyResult<String> {
+    let mut tex = strip_tex_comments(tex);
+    if let Some(macros) = expand_macros {
+        let macros = macros
+            .into_iter()
+            .map(|(name, body)| {
+                let num_args = macro_arg_count(&body);
+                (name, PreambleMacro { body, num_args })
+            })
+            .collect();
+        tex = expand_argument_macros(&tex, &macros)?;
+    }
+    Ok(canonical_tex(&tex))
+}
+
+/// Convert to `target` ("typst" or "tex"), detecting the input format first.
+///
+/// Input that already matches the target is returned unchanged. Ambiguous input
+/// is converted as if it were in the other format, unless `strict_detection=True`,
+/// in which case a `ValueError` is raised. Remaining kwargs are the options of
+/// `tex2typst` or `typst2tex`, depending on the target.
+#[pyfunction]
+#[pyo3(signature = (s, target="typst", *, strict_detection=false, **options))]
+fn convert_auto(
+    s: String,
+    target: &str,
+    strict_detection: bool,
+    options: Option<&Bound<PyDict>>,
+) -> PyResult<String> {
+    check_choice("target", target, FORMAT_TARGETS)?;
+    let (max_input_length, options) = take_max_input_length(options)?;
+    let to_typst = target == "typst";
+    let names = if to_typst {
+        TEX2TYPST_OPTION_NAMES
+    } else {
+        TYPST2TEX_OPTION_NAMES
+    };
+    let opts = kwargs_to_js_options("convert_auto", options.as_ref(), names)?;
+    check_input_length(&s, max_input_length)?;
+
+    let detected = classify_format(&s);
+    if detected == target {
+        return Ok(s);
+    }
+    if detected == "ambiguous" && strict_detection {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Could not determine whether input is TeX or Typst: '{}'",
+            input_preview(&s)
+        )));
+    }
+
+    get_thread_converter()?;
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        if to_typst {
+            converter.tex2typst(&s, opts.as_ref())
+        } else {
+            converter.typst2tex(&s, opts.as_ref())
+        }
+    })
+}
+
+/// Maximum number of error messages kept in a `convert_jsonl` summary
+const MAX_JSONL_ERRORS: usize = 10;
+
+/// Accepted values for the `errors` policy of `convert_jsonl`
+const JSONL_ERROR_POLICIES: &[&str] = &["skip", "null", "raise"];
+
+/// Why a single JSONL record could not be converted
+enum JsonlFailure {
+    Record(String),
+    Conversion(PyErr),
+}
+
+/// Reason a `convert_jsonl` run stopped early
+enum JsonlAbort {
+    Io(std::io::Error),
+    Failure(usize, JsonlFailure),
+}
+
+/// Write `value` into the record under `out_field`, leaving other fields untouched
+fn splice_jsonl_field(
+    line: &str,
+    mut record: serde_json::Map<String, serde_json::Value>,
+    out_field: &str,
+    value: serde_json::Value,
+) -> String {
+    if record.contains_key(out_field) {
+        // Overwriting an existing key requires re-serializing the record
+        record.insert(out_field.to_string(), value);
+        return serde_json::Value::Object(record).to_string();
+    }
+
+    // Append the new field to the original text so other fields keep their formatting
+    let body = line.trim_end();
+    let body = &body[..body.len() - 1];
+    let separator = if record.is_empty() { "" } else { "," };
+    format!(
+        "{}{}{}:{}}}",
+        body,
+        separator,
+        serde_json::Value::String(out_field.to_string()),
+        value
+    )
+}
+
+/// Stream a JSON Lines file, converting one field of every record from TeX to Typst.
+///
+/// The GIL is released while the file is processed; only one line is held in
+/// memory at a time.
+///
+/// Args:
+///     input_path: Path of the JSON Lines file to read
+///     output_path: Path of the JSON Lines file to write
+///     tex_field: Name of the field holding the TeX input (default: "tex")
+///     out_field: Name of the field to write the Typst output to (default: "typst")
+///     errors: Failure policy: "skip" drops the record, "null" writes it with a
+///         null output field, "raise" stops with ValueError (default: "skip")
+///     max_input_length: Records whose TeX is longer than this many bytes fail (default: None)
+///     Remaining keyword arguments are the `tex2typst` conversion options.
+///
+/// Returns:
+///     Summary dict with "converted" and "failed" counts and the first error messages
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, *, tex_field="tex".to_string(), out_field="typst".to_string(), errors="skip".to_string(), non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, max_input_length=None))]
+#[allow(clippy::too_many_arguments)]
+fn convert_jsonl(
+    py: Python<'_>,
+    input_path: std::path::PathBuf,
+    output_path: std::path::PathBuf,
+    tex_field: String,
+    out_field: String,
+    errors: String,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    max_input_length: Option<usize>,
+) -> PyResult<Py<PyAny>> {
+    use std::io::{BufRead, Write};
+
+    check_choice("errors", &errors, JSONL_ERROR_POLICIES)?;
+    get_thread_converter()?;
+
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+
+    let reader = std::io::BufReader::new(std::fs::File::open(&input_path)?);
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&output_path)?);
+
+    let outcome = py.detach(|| {
+        let mut converted = 0usize;
+        let mut failures: Vec<(usize, JsonlFailure)> = Vec::new();
+        let mut failed = 0usize;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_no = index + 1;
+            let line = line.map_err(JsonlAbort::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(serde_json::Value::Object(record)) => Some(record),
+                Ok(_) => None,
+                Err(e) => {
+                    let failure = JsonlFailure::Record(format!("invalid JSON: {}", e));
+                    if errors == "raise" {
+                        return Err(JsonlAbort::Failure(line_no, failure));
+                    }
+                    failed += 1;
+                    if failures.len() < MAX_JSONL_ERRORS {
+                        failures.push((line_no, failure));
+                    }
+                    if errors == "null" {
+                        writeln!(writer, "{}", line).map_err(JsonlAbort::Io)?;
+                    }
+                    continue;
+                }
+            };
+
+            let result = match record.as_ref().map(|r| r.get(&tex_field)) {
+                None => Err(JsonlFailure::Record(
+                    "record is not a JSON object".to_string(),
+                )),
+                Some(Some(serde_json::Value::String(tex)))
+                    if max_input_length.is_some_and(|max| tex.len() > max) =>
+                {
+                    Err(JsonlFailure::Record(format!(
+                        "field '{}' is {} bytes long, exceeding max_input_length={}",
+                        tex_field,
+                        tex.len(),
+                        max_input_length.unwrap()
+                    )))
+                }
+                Some(Some(serde_json::Value::String(tex))) => THREAD_CONVERTER
+                    .with(|converter| {
+                        converter
+                            .borrow()
+                            .as_ref()
+                            .unwrap()
+                            .tex2typst(tex, opts.as_ref())
+                    })
+                    .map_err(JsonlFailure::Conversion),
+                Some(Some(_)) => Err(JsonlFailure::Record(format!(
+                    "field '{}' is not a string",
+                    tex_field
+                ))),
+                Some(None) => Err(JsonlFailure::Record(format!(
+                    "missing field '{}'",
+                    tex_field
+                ))),
+            };
+
+            match result {
+                Ok(typst) => {
+                    converted += 1;
+                    let out = splice_jsonl_field(
+                        &line,
+                        record.unwrap(),
+                        &out_field,
+                        serde_json::Value::String(typst),
+                    );
+                    writeln!(writer, "{}", out).map_err(JsonlAbort::Io)?;
+                }
+                Err(failure) => {
+                    if errors == "raise" {
+                        return Err(JsonlAbort::Failure(line_no, failure));
+                    }
+                    failed += 1;
+                    if failures.len() < MAX_JSONL_ERRORS {
+                        failures.push((line_no, failure));
+                    }
+                    if errors == "null" {
+                        let out = match record {
+                            Some(record) => splice_jsonl_field(
+                                &line,
+                                record,
+                                &out_field,
+                                serde_json::Value::Null,
+                            ),
+                            None => line,
+                        };
+                        writeln!(writer, "{}", out).map_err(JsonlAbort::Io)?;
+                    }
+                }
+            }
+        }
+
+        writer.flush().map_err(JsonlAbort::Io)?;
+        Ok((converted, failed, failures))
+    });
+
+    let format_failure = |line_no: usize, failure: JsonlFailure| match failure {
+        JsonlFailure::Record(msg) => format!("line {}: {}", line_no, msg),
+        JsonlFailure::Conversion(err) => format!("line {}: {}", line_no, err.value(py)),
+    };
+
+    let (converted, failed, failures) = match outcome {
+        Ok(summary) => summary,
+        Err(JsonlAbort::Io(e)) => return Err(e.into()),
+        Err(JsonlAbort::Failure(line_no, failure)) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                format_failure(line_no, failure),
+            ));
+        }
+    };
+
+    let summary = PyDict::new(py);
+    summary.set_item("converted", converted)?;
+    summary.set_item("failed", failed)?;
+    summary.set_item(
+        "errors",
+        failures
+            .into_iter()
+            .map(|(line_no, failure)| format_failure(line_no, failure))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(summary.into_any().unbind())
+}
+
+/// Accepted values for the `errors` policy of `tex2typst_file`
+const TEXT_FILE_ERROR_POLICIES: &[&str] = &["keep", "empty", "raise"];
+
+/// Convert a text file with one TeX formula per line into a file of Typst lines.
+///
+/// Non-empty lines are converted in a single batch; blank lines stay blank so line
+/// numbers match between the files. The output file is only created once every line
+/// has been converted (or has failed under the `errors` policy).
+///
+/// Args:
+///     input_path: Path of the text file to read
+///     output_path: Path of the text file to write
+///     errors: Failure policy: "keep" writes the TeX line unchanged, "empty" writes a
+///         blank line, "raise" stops with ValueError (default: "keep")
+///     max_input_length: Lines longer than this many bytes fail (default: None)
+///     Remaining keyword arguments are the `tex2typst` conversion options.
+///
+/// Returns:
+///     Number of lines converted successfully
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, *, errors="keep".to_string(), non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, max_input_length=None))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_file(
+    py: Python<'_>,
+    input_path: std::path::PathBuf,
+    output_path: std::path::PathBuf,
+    errors: String,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    max_input_length: Option<usize>,
+) -> PyResult<usize> {
+    use std::io::{BufRead, Write};
+
+    check_choice("errors", &errors, TEXT_FILE_ERROR_POLICIES)?;
+    get_thread_converter()?;
+
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+
+    let reader = std::io::BufReader::new(std::fs::File::open(&input_path)?);
+    let lines = py.detach(|| reader.lines().collect::<std::io::Result<Vec<String>>>())?;
+
+    // (line index, TeX) of every line handed to the converter
+    let (indices, tex_list): (Vec<usize>, Vec<String>) = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter(|(_, line)| max_input_length.is_none_or(|max| line.len() <= max))
+        .map(|(index, line)| (index, line.clone()))
+        .unzip();
+
+    let (results, failed_indices) = py.detach(|| {
+        THREAD_CONVERTER.with(|converter| {
+            converter.borrow().as_ref().unwrap().tex2typst_batch(
+                &tex_list,
+                opts.as_ref(),
+                true,
+                None,
+            )
+        })
+    })?;
+
+    let mut output: Vec<Option<String>> = vec![None; lines.len()];
+    for (index, typst) in indices.iter().zip(results) {
+        output[*index] = Some(typst);
+    }
+    let mut failed: Vec<usize> = failed_indices.iter().map(|&i| indices[i]).collect();
+    for (index, line) in lines.iter().enumerate() {
+        if !line.trim().is_empty() && output[index].is_none() {
+            failed.push(index);
+        }
+    }
+    failed.sort_unstable();
+    for &index in &failed {
+        output[index] = None;
+    }
+
+    if let Some(&index) = failed.first().filter(|_| errors == "raise") {
+        let line = &lines[index];
+        let message = match max_input_length {
+            Some(max) if line.len() > max => format!(
+                "{} bytes long, exceeding max_input_length={}",
+                line.len(),
+                max
+            ),
+            _ => THREAD_CONVERTER
+                .with(|converter| {
+                    converter
+                        .borrow()
+                        .as_ref()
+                        .unwrap()
+                        .tex2typst(line, opts.as_ref())
+                })
+                .err()
+                .map(|e| e.value(py).to_string())
+                .unwrap_or_default(),
+        };
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "line {}: {}",
+            index + 1,
+            message
+        )));
+    }
+
+    let converted = lines.iter().filter(|line| !line.trim().is_empty()).count() - failed.len();
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&output_path)?);
+    py.detach(|| {
+        for (line, typst) in lines.iter().zip(&output) {
+            match typst {
+                Some(typst) => writeln!(writer, "{}", typst)?,
+                None if errors == "keep" && !line.trim().is_empty() => {
+                    writeln!(writer, "{}", line)?
+                }
+                None => writeln!(writer)?,
+            }
+        }
+        writer.flush()
+    })?;
+    Ok(converted)
+}
+
+/// Accepted values for the `errors` policy of `tex2typst_iter`
+const TEX_ITER_ERROR_POLICIES: &[&str] = &["raise", "keep", "null"];
+
+/// Iterator returned by `tex2typst_iter`, converting its source a chunk at a time
+#[pyclass(module = "tex2typst")]
+struct TexIterator {
+    /// `None` once the source is exhausted or has raised
+    source: Option<Py<PyIterator>>,
+    options: Option<JsOptions>,
+    chunk_size: usize,
+    errors: String,
+    /// Converted items of the current chunk not yet yielded
+    pending: VecDeque<Option<String>>,
+    /// Raised once `pending` is drained, ending the iteration
+    error: Option<PyErr>,
+    /// Number of items pulled from the source so far
+    pulled: usize,
+}
+
+impl TexIterator {
+    /// Pull the next chunk from the source and convert it into `pending`.
+    ///
+    /// Items pulled before the source raised or yielded a non-string are still
+    /// converted; the error is kept for after them. Under the "raise" policy the
+    /// chunk is cut at the first failed item, whose error replaces any later one.
+    fn fill(&mut self, py: Python<'_>) -> PyResult<()> {
+        let Some(source) = self.source.as_ref() else {
+            return Ok(());
+        };
+        let mut source = source.bind(py).clone();
+        let start = self.pulled;
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        while chunk.len() < self.chunk_size {
+            let item = match source.next() {
+                Some(Ok(item)) => item,
+                Some(Err(e)) => {
+                    self.error = Some(e);
+                    break;
+                }
+                None => break,
+            };
+            match item.extract::<String>() {
+                Ok(tex) => chunk.push(tex),
+                Err(_) => {
+                    self.error = Some(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "tex2typst_iter items must be str, got {} at item {}",
+                        item.get_type().name()?,
+                        start + chunk.len()
+                    )));
+                    break;
+                }
+            }
+        }
+        self.pulled += chunk.len();
+        if chunk.len() < self.chunk_size {
+            self.source = None;
+        }
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        get_thread_converter()?;
+        let options = self.options.as_ref();
+        let (results, failed_indices) = py.detach(|| {
+            THREAD_CONVERTER.with(|converter| {
+                converter
+                    .borrow()
+                    .as_ref()
+                    .unwrap()
+                    .tex2typst_batch(&chunk, options, true, None)
+            })
+        })?;
+        let mut results: Vec<Option<String>> = results.into_iter().map(Some).collect();
+        match self.errors.as_str() {
+            "raise" => {
+                if let Some(&index) = failed_indices.first() {
+                    // Convert the failed item again for its error, numbered in the whole stream
+                    let error = THREAD_CONVERTER
+                        .with(|converter| {
+                            converter.borrow().as_ref().unwrap().tex2typst_batch(
+                                &chunk[index..=index],
+                                options,
+                                false,
+                                Some(&[start + index]),
+                            )
+                        })
+                        .err()
+                        .unwrap_or_else(|| {
+                            js_failure(format!("Conversion failed for item {}", start + index))
+                        });
+                    results.truncate(index);
+                    self.source = None;
+                    self.error = Some(error);
+                }
+            }
+            "null" => {
+                for index in failed_indices {
+                    results[index] = None;
+                }
+            }
+            _ => {}
+        }
+        self.pending.extend(results);
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl TexIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Option<String>>> {
+        if self.pending.is_empty() && self.error.is_none() {
+            self.fill(py)?;
+        }
+        if let Some(item) = self.pending.pop_front() {
+            return Ok(Some(item));
+        }
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Lazily convert an iterable of TeX strings to Typst.
+///
+/// Up to `chunk_size` items are pulled from the iterable at a time and converted in
+/// one batch with the GIL released, so memory use stays proportional to `chunk_size`
+/// however long the input is.
+///
+/// Args:
+///     iterable: Iterable of TeX strings, e.g. a generator
+///     chunk_size: Number of items pulled and converted at a time (default: 1024)
+///     errors: Failure policy: "raise" yields the items before the failed one and
+///         then raises ValueError, "keep" yields the TeX unchanged, "null" yields
+///         None (default: "raise")
+///     Remaining keyword arguments are the `tex2typst` conversion options.
+///
+/// Returns:
+///     Iterator over the converted strings, in input order
+#[pyfunction]
+#[pyo3(signature = (iterable, *, chunk_size=1024, errors="raise".to_string(), non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None))]
+#[allow(clippy::too_many_arguments)]
+fn tex2typst_iter(
+    iterable: &Bound<PyAny>,
+    chunk_size: usize,
+    errors: String,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+) -> PyResult<TexIterator> {
+    if chunk_size == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "chunk_size must be at least 1",
+        ));
+    }
+    check_choice("errors", &errors, TEX_ITER_ERROR_POLICIES)?;
+    let options = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    Ok(TexIterator {
+        source: Some(iterable.try_iter()?.unbind()),
+        options,
+        chunk_size,
+        errors,
+        pending: VecDeque::new(),
+        error: None,
+        pulled: 0,
+    })
+}
+
+/// Validate a Python option value and convert it to its JSON representation
+fn option_value_to_json(name: &str, value: &Bound<PyAny>) -> PyResult<serde_json::Value> {
+    match name {
+        "custom_tex_macros" | "custom_typst_macros" | "symbol_map" | "environment_map" => {
+            // Sorted like the maps of `crate::core::Options`
+            let macros = pydict_to_string_map(value.cast::<PyDict>()?)?;
+            serde_json::to_value(macros.into_iter().collect::<BTreeMap<_, _>>())
+        }
+        "replace_unknown_commands" => {
+            let replacements = pydict_to_optional_string_map(value.cast::<PyDict>()?)?;
+            serde_json::to_value(replacements.into_iter().collect::<BTreeMap<_, _>>())
+        }
+        "handle_text_mode" => {
+            let mode: String = value.extract()?;
+            check_choice(name, &mode, HANDLE_TEXT_MODES)?;
+            Ok(serde_json::Value::String(mode))
+        }
+        _ => {
+            let flag: bool = value.extract().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "Option '{}' expects a bool, got {}",
+                    name,
+                    value
+                        .get_type()
+                        .name()
+                        .map(|n| n.to_string())
+                        .unwrap_or_default()
+                ))
+            })?;
+            Ok(serde_json::Value::Bool(flag))
+        }
+    }
+    .map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Failed to serialize option '{}': {}",
+            name, e
+        ))
+    })
+}
+
+/// Convert a `js_options` value for `key` to JSON; ints stay integers
+fn js_option_value_to_json(key: &str, value: &Bound<PyAny>) -> PyResult<serde_json::Value> {
+    if value.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    // bool before int: Python bools are ints
+    if let Ok(flag) = value.cast::<PyBool>() {
+        return Ok(serde_json::Value::Bool(flag.is_true()));
+    }
+    if value.is_instance_of::<PyInt>() {
+        if let Ok(n) = value.extract::<i64>() {
+            return Ok(n.into());
+        }
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "js_options['{}'] is out of range for a JS number",
+            key
+        )));
+    }
+    if let Ok(n) = value.cast::<PyFloat>() {
+        return serde_json::Number::from_f64(n.value())
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "js_options['{}'] must be a finite number",
+                    key
+                ))
+            });
+    }
+    if let Ok(text) = value.cast::<PyString>() {
+        return Ok(serde_json::Value::String(text.to_str()?.to_string()));
+    }
+    if let Ok(dict) = value.cast::<PyDict>() {
+        let mut object = serde_json::Map::with_capacity(dict.len());
+        for (name, item) in dict.iter() {
+            let name: String = name.extract()?;
+            object.insert(
+                name.clone(),
+                js_option_value_to_json(&format!("{}']['{}", key, name), &item)?,
+            );
+        }
+        return Ok(serde_json::Value::Object(object));
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        let items = value
+            .try_iter()?
+            .enumerate()
+            .map(|(index, item)| {
+                js_option_value_to_json(&format!("{}'][{}]['", key, index), &item?)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(serde_json::Value::Array(items));
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "js_options['{}'] must be None, bool, int, float, str, list or dict, got {}",
+        key,
+        value
+            .get_type()
+            .name()
+            .map(|n| n.to_string())
+            .unwrap_or_default()
+    )))
+}
+
+/// Merge the `js_options` dict of a call into `opts`; `names` are the typed
+/// options, which `js_options` may not set under their JS names
+fn with_js_options(
+    opts: Option<JsOptions>,
+    js_options: Option<&Bound<PyDict>>,
+    names: &[(&str, &str)],
+) -> PyResult<Option<JsOptions>> {
+    let Some(js_options) = js_options else {
+        return Ok(opts);
+    };
+    let mut extra = JsOptions::with_capacity(js_options.len());
+    for (key, value) in js_options.iter() {
+        let key: String = key.extract()?;
+        let value = js_option_value_to_json(&key, &value)?;
+        extra.insert(key, value);
+    }
+    let mut options_map = opts.unwrap_or_default();
+    merge_js_options(&mut options_map, &extra, names)?;
+    Ok(Some(options_map).filter(|map| !map.is_empty()))
+}
+
+/// Convert `**options` kwargs into a JS options map, validating names against `names`.
+///
+/// Options are set in `names` order, after the process-wide defaults, so the map
+/// does not depend on the order the kwargs were given in.
+fn kwargs_to_js_options(
+    method: &str,
+    kwargs: Option<&Bound<PyDict>>,
+    names: &[(&str, &str)],
+) -> PyResult<Option<JsOptions>> {
+    let mut options_map = JsOptions::new();
+    if let Some(kwargs) = kwargs {
+        for key in kwargs.keys() {
+            let name: String = key.extract()?;
+            if !names.iter().any(|(py_name, _)| *py_name == name) {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "{}() got an unexpected keyword argument '{}'",
+                    method, name
+                )));
+            }
+        }
+        for (name, js_name) in names {
+            let Some(value) = kwargs.get_item(name)?.filter(|value| !value.is_none()) else {
+                continue;
+            };
+            let json_value = option_value_to_json(name, &value)?;
+            if *name == "replace_unknown_commands"
+                && json_value.as_object().is_some_and(|m| m.is_empty())
+            {
+                continue;
+            }
+            options_map.insert(js_name.to_string(), json_value);
+        }
+    }
+
+    apply_default_options(&mut options_map, names);
+
+    Ok(if options_map.is_empty() {
+        None
+    } else {
+        Some(options_map)
+    })
+}
+
+/// Set process-wide default options for all conversion functions.
+///
+/// Defaults are merged beneath explicit per-call kwargs: passing `None` for an
+/// option uses the default, while an explicit value (including `False`) wins.
+/// Passing `None` here removes the default for that option.
+#[pyfunction]
+#[pyo3(signature = (**kwargs))]
+fn set_default_options(kwargs: Option<&Bound<PyDict>>) -> PyResult<()> {
+    let Some(kwargs) = kwargs else {
+        return Ok(());
+    };
+
+    // Validate everything before touching the shared map
+    let mut updates: Vec<(String, Option<serde_json::Value>)> = Vec::with_capacity(kwargs.len());
+    for (key, value) in kwargs.iter() {
+        let name: String = key.extract()?;
+        let known = TEX2TYPST_OPTION_NAMES
+            .iter()
+            .chain(TYPST2TEX_OPTION_NAMES)
+            .any(|(py_name, _)| *py_name == name);
+        if !known {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "set_default_options() got an unexpected keyword argument '{}'",
+                name
+            )));
+        }
+
+        if value.is_none() {
+            updates.push((name, None));
+            continue;
+        }
+
+        let json_value = option_value_to_json(&name, &value)?;
+        updates.push((name, Some(json_value)));
+    }
+
+    let mut defaults = DEFAULT_OPTIONS.lock().unwrap();
+    for (name, value) in updates {
+        match value {
+            Some(v) => defaults.insert(name, v),
+            None => defaults.shift_remove(&name),
+        };
+    }
+    Ok(())
+}
+
+/// Return a copy of the process-wide default options.
+#[pyfunction]
+fn get_default_options(py: Python<'_>) -> PyResult<Py<PyAny>> {
+    let defaults = DEFAULT_OPTIONS.lock().unwrap();
+    let dict = PyDict::new(py);
+    for (name, value) in defaults.iter() {
+        dict.set_item(name, json_to_py(py, value)?)?;
+    }
+    Ok(dict.into_any().unbind())
+}
+
+/// Remove all process-wide default options.
+#[pyfunction]
+fn reset_default_options() {
+    DEFAULT_OPTIONS.lock().unwrap().clear();
+}
+
+/// Type of a keyword option that is handled in Rust rather than by the JS converter
+enum CallOptionKind {
+    Bool,
+    Str,
+    Int,
+    Choice(&'static [&'static str]),
+    /// A bool or one of the listed strings
+    BoolOrChoice(&'static [&'static str]),
+    Callable,
+}
+
+/// Keyword options of `tex2typst`/`typst2tex` besides the JS converter options
+const CALL_OPTION_KINDS: &[(&str, CallOptionKind)] = &[
+    ("trim_input", CallOptionKind::Bool),
+    ("report_unknown", CallOptionKind::Bool),
+    ("fail_on_unknown", CallOptionKind::Bool),
+    ("preamble", CallOptionKind::Str),
+    ("max_input_length", CallOptionKind::Int),
+    ("wrap", CallOptionKind::Choice(WRAP_MODES)),
+    ("auto_strip_delimiters", CallOptionKind::Bool),
+    ("chem", CallOptionKind::Bool),
+    ("handle_mhchem", CallOptionKind::Bool),
+    ("siunitx", CallOptionKind::Bool),
+    ("handle_siunitx", CallOptionKind::Bool),
+    ("units_style", CallOptionKind::Choice(UNITS_STYLES)),
+    ("labels", CallOptionKind::Bool),
+    ("tags", CallOptionKind::Bool),
+    ("normalize_unicode", CallOptionKind::Bool),
+    ("unicode_report", CallOptionKind::Bool),
+    ("strip_comments", CallOptionKind::Bool),
+    ("collapse_redundant_braces", CallOptionKind::Bool),
+    ("normalize_output", CallOptionKind::Bool),
+    ("timing", CallOptionKind::Bool),
+    ("keep_original", CallOptionKind::Choice(KEEP_ORIGINAL_MODES)),
+    ("delimiters", CallOptionKind::Choice(TEX_DELIMITERS)),
+    ("trailing_newline", CallOptionKind::Bool),
+    ("max_nesting_depth", CallOptionKind::Int),
+    ("keep_original_on_error", CallOptionKind::Bool),
+    ("return_failed_indices", CallOptionKind::Bool),
+    ("return_summary", CallOptionKind::Bool),
+    ("skip_empty", CallOptionKind::Bool),
+    ("empty_replacement", CallOptionKind::Str),
+    ("pre_process", CallOptionKind::Callable),
+    ("post_process", CallOptionKind::Callable),
+    ("progress_callback", CallOptionKind::Callable),
+    ("callback_every_n", CallOptionKind::Int),
+    ("chunk_size", CallOptionKind::Int),
+    ("dedupe", CallOptionKind::Bool),
+    ("atomic", CallOptionKind::Bool),
+    ("isolated", CallOptionKind::BoolOrChoice(ISOLATION_MODES)),
+];
+
+/// Default value of an option listed by `get_supported_options`
+enum OptionDefault {
+    None,
+    Bool(bool),
+    Int(i64),
+    Str(&'static str),
+}
+
+/// An option listed by `get_supported_options`
+struct OptionInfo {
+    name: &'static str,
+    type_name: &'static str,
+    default: OptionDefault,
+    choices: Option<&'static [&'static str]>,
+    functions: &'static [&'static str],
+    description: &'static str,
+}
+
+const BOTH: &[&str] = &["tex2typst", "typst2tex"];
+const TEX_ONLY: &[&str] = &["tex2typst"];
+const TYPST_ONLY: &[&str] = &["typst2tex"];
+
+/// Every keyword option of `tex2typst` and `typst2tex`; defaults are those of the
+/// bundled JS converter where it supplies them
+const SUPPORTED_OPTIONS: &[OptionInfo] = &[
+    OptionInfo {
+        name: "non_strict",
+        type_name: "bool",
+        default: OptionDefault::Bool(true),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Allow non-strict parsing",
+    },
+    OptionInfo {
+        name: "prefer_shorthands",
+        type_name: "bool",
+        default: OptionDefault::Bool(true),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Prefer shorthand notation such as `->` over `arrow.r`",
+    },
+    OptionInfo {
+        name: "keep_spaces",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Preserve spaces in output",
+    },
+    OptionInfo {
+        name: "frac_to_slash",
+        type_name: "bool",
+        default: OptionDefault::Bool(true),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Convert fractions to slash notation",
+    },
+    OptionInfo {
+        name: "infty_to_oo",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Convert the infinity symbol to oo",
+    },
+    OptionInfo {
+        name: "optimize",
+        type_name: "bool",
+        default: OptionDefault::Bool(true),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Optimize output",
+    },
+    OptionInfo {
+        name: "ignore_unknown_commands",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Pass unrecognized commands through as `\\cmd`",
+    },
+    OptionInfo {
+        name: "handle_physics",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Rewrite physics package macros into plain TeX",
+    },
+    OptionInfo {
+        name: "output_unicode",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Emit Unicode characters instead of Typst symbol names",
+    },
+    OptionInfo {
+        name: "handle_text_mode",
+        type_name: "str",
+        default: OptionDefault::Str("verbatim"),
+        choices: Some(HANDLE_TEXT_MODES),
+        functions: TEX_ONLY,
+        description: "How to treat \\text{...} contents",
+    },
+    OptionInfo {
+        name: "custom_tex_macros",
+        type_name: "dict[str, str]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Custom TeX macro definitions",
+    },
+    OptionInfo {
+        name: "symbol_map",
+        type_name: "dict[str, str]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Typst translations overriding those of built-in TeX symbols",
+    },
+    OptionInfo {
+        name: "environment_map",
+        type_name: "dict[str, str]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Typst functions standing in for TeX environments",
+    },
+    OptionInfo {
+        name: "replace_unknown_commands",
+        type_name: "dict[str, str | None]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Typst fallbacks for unknown commands, keyed by name without backslash",
+    },
+    OptionInfo {
+        name: "block_math_mode",
+        type_name: "bool",
+        default: OptionDefault::Bool(true),
+        choices: None,
+        functions: TYPST_ONLY,
+        description: "Use block math mode",
+    },
+    OptionInfo {
+        name: "custom_typst_macros",
+        type_name: "dict[str, str]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TYPST_ONLY,
+        description: "Typst identifiers replaced by Typst code before conversion",
+    },
+    OptionInfo {
+        name: "js_options",
+        type_name: "dict[str, Any]",
+        default: OptionDefault::None,
+        choices: None,
+        functions: BOTH,
+        description: "Further JS converter options under their JS names, passed as they are",
+    },
+    OptionInfo {
+        name: "trim_input",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: BOTH,
+        description: "Strip leading/trailing whitespace from each input",
+    },
+    OptionInfo {
+        name: "max_input_length",
+        type_name: "int",
+        default: OptionDefault::None,
+        choices: None,
+        functions: BOTH,
+        description: "Raise ValueError for inputs longer than this many bytes",
+    },
+    OptionInfo {
+        name: "report_unknown",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Also return the commands the converter did not recognize",
+    },
+    OptionInfo {
+        name: "fail_on_unknown",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Raise TexParseError listing unrecognized commands",
+    },
+    OptionInfo {
+        name: "preamble",
+        type_name: "str",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "LaTeX preamble whose macro definitions are applied",
+    },
+    OptionInfo {
+        name: "wrap",
+        type_name: "str",
+        default: OptionDefault::Str("none"),
+        choices: Some(WRAP_MODES),
+        functions: TEX_ONLY,
+        description: "Surround the output with Typst math delimiters",
+    },
+    OptionInfo {
+        name: "auto_strip_delimiters",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Remove one pair of math delimiters around the input",
+    },
+    OptionInfo {
+        name: "chem",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Translate mhchem \\ce{...} formulas",
+    },
+    OptionInfo {
+        name: "handle_mhchem",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Same as `chem`",
+    },
+    OptionInfo {
+        name: "siunitx",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Convert siunitx \\SI, \\si and \\num commands",
+    },
+    OptionInfo {
+        name: "handle_siunitx",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Same as `siunitx`",
+    },
+    OptionInfo {
+        name: "units_style",
+        type_name: "str",
+        default: OptionDefault::Str("plain"),
+        choices: Some(UNITS_STYLES),
+        functions: TEX_ONLY,
+        description: "Typst output for siunitx commands",
+    },
+    OptionInfo {
+        name: "labels",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Attach \\label{...} to block-wrapped output",
+    },
+    OptionInfo {
+        name: "tags",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Number block-wrapped output with its \\tag{...}",
+    },
+    OptionInfo {
+        name: "normalize_unicode",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Replace Unicode math characters with TeX before conversion",
+    },
+    OptionInfo {
+        name: "unicode_report",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Also return the substitutions made by normalize_unicode",
+    },
+    OptionInfo {
+        name: "strip_comments",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Remove % comments before conversion",
+    },
+    OptionInfo {
+        name: "collapse_redundant_braces",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Drop grouping parentheses Typst ignores, as in x^(2)",
+    },
+    OptionInfo {
+        name: "normalize_output",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Canonicalize the spacing of the Typst output",
+    },
+    OptionInfo {
+        name: "timing",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Also return the elapsed seconds (string input only)",
+    },
+    OptionInfo {
+        name: "keep_original",
+        type_name: "str",
+        default: OptionDefault::None,
+        choices: Some(KEEP_ORIGINAL_MODES),
+        functions: TEX_ONLY,
+        description: "Also return a Typst comment quoting the input (string input only)",
+    },
+    OptionInfo {
+        name: "delimiters",
+        type_name: "str",
+        default: OptionDefault::None,
+        choices: Some(TEX_DELIMITERS),
+        functions: TYPST_ONLY,
+        description: "Surround the output with \\(...\\)/\\[...\\] (latex), $...$/$$...$$ (dollars) or nothing",
+    },
+    OptionInfo {
+        name: "trailing_newline",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TYPST_ONLY,
+        description: "End delimited display math with a line break",
+    },
+    OptionInfo {
+        name: "max_nesting_depth",
+        type_name: "int",
+        default: OptionDefault::Int(DEFAULT_MAX_NESTING_DEPTH as i64),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Raise TexParseError for input nested deeper than this",
+    },
+    OptionInfo {
+        name: "keep_original_on_error",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Return the original TeX for items that fail (list input only)",
+    },
+    OptionInfo {
+        name: "return_failed_indices",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Also return the indices of failed items (list input only)",
+    },
+    OptionInfo {
+        name: "return_summary",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: BOTH,
+        description: "Return a BatchResult with failures and timing (list input only)",
+    },
+    OptionInfo {
+        name: "skip_empty",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: BOTH,
+        description: "Do not convert empty strings (list input only)",
+    },
+    OptionInfo {
+        name: "empty_replacement",
+        type_name: "str",
+        default: OptionDefault::None,
+        choices: None,
+        functions: BOTH,
+        description: "Value returned for skipped empty strings",
+    },
+    OptionInfo {
+        name: "pre_process",
+        type_name: "callable",
+        default: OptionDefault::None,
+        choices: None,
+        functions: BOTH,
+        description: "Called with every input string before conversion (list input only)",
+    },
+    OptionInfo {
+        name: "post_process",
+        type_name: "callable",
+        default: OptionDefault::None,
+        choices: None,
+        functions: BOTH,
+        description: "Called with every converted string (list input only)",
+    },
+    OptionInfo {
+        name: "progress_callback",
+        type_name: "callable",
+        default: OptionDefault::None,
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Called as (completed, total) during the batch (list input only)",
+    },
+    OptionInfo {
+        name: "callback_every_n",
+        type_name: "int",
+        default: OptionDefault::Int(100),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "How many items to convert between progress callbacks",
+    },
+    OptionInfo {
+        name: "chunk_size",
+        type_name: "int",
+        default: OptionDefault::Int(0),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Convert the list in sub-batches of this many items; 0 for one batch",
+    },
+    OptionInfo {
+        name: "dedupe",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: BOTH,
+        description: "Convert each distinct input once (list input only)",
+    },
+    OptionInfo {
+        name: "atomic",
+        type_name: "bool",
+        default: OptionDefault::Bool(false),
+        choices: None,
+        functions: TEX_ONLY,
+        description: "Either every item converts or the call raises (list input only)",
+    },
+    OptionInfo {
+        name: "isolated",
+        type_name: "bool | str",
+        default: OptionDefault::Bool(false),
+        choices: Some(ISOLATION_MODES),
+        functions: BOTH,
+        description: "Convert on fresh JS contexts dropped after the call; lists take per_item or per_batch",
+    },
+];
+
+/// List every keyword option of `tex2typst` and `typst2tex`.
+///
+/// Returns a dict keyed by option name; each entry holds the option's `type`,
+/// `default`, `description` and the `functions` accepting it, plus `choices` for
+/// options limited to a set of strings. No JavaScript runs.
+#[pyfunction]
+fn get_supported_options(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let options = PyDict::new(py);
+    for option in SUPPORTED_OPTIONS {
+        let entry = PyDict::new(py);
+        entry.set_item("type", option.type_name)?;
+        match option.default {
+            OptionDefault::None => entry.set_item("default", py.None())?,
+            OptionDefault::Bool(value) => entry.set_item("default", value)?,
+            OptionDefault::Int(value) => entry.set_item("default", value)?,
+            OptionDefault::Str(value) => entry.set_item("default", value)?,
+        }
+        if let Some(choices) = option.choices {
+            entry.set_item("choices", choices.to_vec())?;
+        }
+        entry.set_item("functions", option.functions.to_vec())?;
+        entry.set_item("description", option.description)?;
+        options.set_item(option.name, entry)?;
+    }
+    Ok(options)
+}
+
+/// Options whose dict values are merged key by key instead of replaced
+const MERGED_DICT_OPTIONS: &[&str] = &[
+    "custom_tex_macros",
+    "custom_typst_macros",
+    "replace_unknown_commands",
+];
+
+/// Check one `merge_options` entry against the option schema
+fn check_option_value(name: &str, value: &Bound<PyAny>) -> PyResult<()> {
+    let type_error = |expected: &str| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Option '{}' expects {}, got {}",
+            name,
+            expected,
+            value
+                .get_type()
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_default()
+        ))
+    };
+    let is_js_option = TEX2TYPST_OPTION_NAMES
+        .iter()
+        .chain(TYPST2TEX_OPTION_NAMES)
+        .any(|(py_name, _)| *py_name == name);
+    if is_js_option {
+        if MERGED_DICT_OPTIONS.contains(&name) {
+            let entries = value.cast::<PyDict>().map_err(|_| type_error("a dict"))?;
+            for (key, entry) in entries.iter() {
+                let allowed = entry.is_instance_of::<pyo3::types::PyString>()
+                    || (name == "replace_unknown_commands" && entry.is_none());
+                if !allowed {
+                    return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "Option '{}' expects str values, got {} for {}",
+                        name,
+                        entry
+                            .get_type()
+                            .name()
+                            .map(|n| n.to_string())
+                            .unwrap_or_default(),
+                        key.repr()?
+                    )));
+                }
+            }
+        }
+        return option_value_to_json(name, value).map(|_| ());
+    }
+    let Some((_, kind)) = CALL_OPTION_KINDS.iter().find(|(n, _)| *n == name) else {
+        let normalized = |s: &str| s.replace('_', "").to_ascii_lowercase();
+        let suggestion = TEX2TYPST_OPTION_NAMES
+            .iter()
+            .chain(TYPST2TEX_OPTION_NAMES)
+            .map(|(py_name, _)| *py_name)
+            .chain(CALL_OPTION_KINDS.iter().map(|(n, _)| *n))
+            .find(|known| normalized(known) == normalized(name));
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            match suggestion {
+                Some(known) => format!("Unknown option '{}', did you mean '{}'?", name, known),
+                None => format!("Unknown option '{}'", name),
+            },
+        ));
+    };
+    match kind {
+        CallOptionKind::Bool if !value.is_instance_of::<pyo3::types::PyBool>() => {
+            Err(type_error("a bool"))
+        }
+        CallOptionKind::Str if !value.is_instance_of::<pyo3::types::PyString>() => {
+            Err(type_error("a str"))
+        }
+        CallOptionKind::Int
+            if value.extract::<usize>().is_err()
+                || value.is_instance_of::<pyo3::types::PyBool>() =>
+        {
+            Err(type_error("a non-negative int"))
+        }
+        CallOptionKind::Choice(choices) => {
+            let choice: String = value.extract().map_err(|_| type_error("a str"))?;
+            check_choice(name, &choice, choices)
+        }
+        CallOptionKind::BoolOrChoice(_) if value.is_instance_of::<pyo3::types::PyBool>() => Ok(()),
+        CallOptionKind::BoolOrChoice(choices) => {
+            let choice: String = value.extract().map_err(|_| type_error("a bool or str"))?;
+            check_choice(name, &choice, choices)
+        }
+        CallOptionKind::Callable if !value.is_callable() => Err(type_error("a callable")),
+        _ => Ok(()),
+    }
+}
+
+/// Merge option dicts left to right, later dicts winning, after validating every entry.
+///
+/// Option names are checked against the keyword options of `tex2typst` and `typst2tex`
+/// and values against their types, so a typo such as `"nonstrict"` raises right away.
+/// `custom_tex_macros`, `custom_typst_macros` and `replace_unknown_commands` are merged
+/// key by key; a `None` value removes the option from the result.
+///
+/// Raises:
+///     TypeError: For unknown option names, wrongly typed values or non-dict arguments
+///     ValueError: For values outside an option's choices, such as `wrap="display"`
+#[pyfunction]
+#[pyo3(signature = (*option_dicts))]
+fn merge_options<'py>(
+    py: Python<'py>,
+    option_dicts: &Bound<'py, PyTuple>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let merged = PyDict::new(py);
+    for (position, options) in option_dicts.iter().enumerate() {
+        let options = options.cast::<PyDict>().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "merge_options() argument {} must be a dict, got {}",
+                position + 1,
+                options
+                    .get_type()
+                    .name()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default()
+            ))
+        })?;
+        for (key, value) in options.iter() {
+            let name: String = key.extract().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyTypeError, _>("Option names must be strings")
+            })?;
+            if value.is_none() {
+                if merged.contains(&name)? {
+                    merged.del_item(&name)?;
+                }
+                continue;
+            }
+            check_option_value(&name, &value)?;
+            if MERGED_DICT_OPTIONS.contains(&name.as_str()) {
+                let combined = match merged.get_item(&name)? {
+                    Some(previous) => previous.cast::<PyDict>()?.copy()?,
+                    None => PyDict::new(py),
+                };
+                combined.update(value.cast::<PyDict>()?.as_mapping())?;
+                merged.set_item(name, combined)?;
+            } else {
+                merged.set_item(name, value)?;
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Version of the upstream tex2typst JS library embedded in this build.
+///
+/// Returns:
+///     Version string such as "0.5.6", or "unknown" if the bundle does not record it
+#[pyfunction]
+fn get_js_bundle_version() -> PyResult<String> {
+    get_thread_converter()?;
+    THREAD_CONVERTER.with(|converter| Ok(converter.borrow().as_ref().unwrap().js_bundle_version()))
+}
+
+/// Names of the global functions defined in this thread's JS engine, sorted.
+///
+/// Lists what the bundle and the registered JS extensions export, such as
+/// `tex2typst` and `typst2tex`; built-ins like `parseInt` are not enumerable and
+/// are left out.
+#[pyfunction]
+fn list_global_functions() -> PyResult<Vec<String>> {
+    get_thread_converter()?;
+    THREAD_CONVERTER
+        .with(|converter| Ok(converter.borrow().as_ref().unwrap().global_function_names()))
+}
+
+/// Options `Converter` handles itself instead of forwarding to the JS converter
+const CONVERTER_OPTION_NAMES: &[&str] = &["trim_input", "max_input_length"];
+
+/// The `tex2typstVersion` literal in a bundle source, read without starting an engine
+fn bundled_js_version(source: &str) -> Option<&str> {
+    const MARKER: &str = "tex2typstVersion=\"";
+    let start = source.find(MARKER)? + MARKER.len();
+    let len = source[start..].find('"')?;
+    Some(&source[start..start + len])
+}
+
+/// Result of `supported_commands`, with the `ENGINE_GENERATION` it was collected for
+static SUPPORTED_COMMANDS: Mutex<Option<(u64, Arc<Vec<String>>)>> = Mutex::new(None);
+
+/// Sorted `\name` commands the current bundle and extensions recognize.
+///
+/// The bundle keeps its symbol tables private, so every run of ASCII letters in the
+/// bundle and extension sources is a candidate, kept if `is_unknown_command` accepts
+/// it. Collected once per bundle and extension change for the whole process.
+fn supported_commands() -> PyResult<Arc<Vec<String>>> {
+    get_thread_converter()?;
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        let mut cached = SUPPORTED_COMMANDS.lock().unwrap();
+        if let Some((generation, commands)) = cached.as_ref()
+            && *generation == converter.engine_generation
+        {
+            return Ok(commands.clone());
+        }
+        let bundle = active_bundle();
+        let extensions = JS_EXTENSIONS.lock().unwrap().sources.clone();
+        let mut candidates = BTreeSet::new();
+        for source in std::iter::once(&*bundle.source).chain(extensions.iter().map(String::as_str))
+        {
+            candidates.extend(
+                source
+                    .split(|c: char| !c.is_ascii_alphabetic())
+                    .filter(|name| !name.is_empty()),
+            );
+        }
+        let mut commands = Vec::new();
+        for name in candidates {
+            let command = format!("\\{}", name);
+            if !converter.is_unknown_command(&command)? {
+                commands.push(command);
+            }
+        }
+        let commands = Arc::new(commands);
+        *cached = Some((converter.engine_generation, commands.clone()));
+        Ok(commands)
+    })
+}
+
+/// Sorted `\name` commands (e.g. `\frac`) the converter recognizes, to screen
+/// documents before a long job. Includes commands of registered JS extensions.
+#[pyfunction]
+fn list_supported_commands() -> PyResult<Vec<String>> {
+    Ok(supported_commands()?.to_vec())
+}
+
+/// Commands in `tex` the converter does not recognize, in order of first use.
+///
+/// Only `\` followed by letters counts; macros of `custom_tex_macros` (or the
+/// process-wide default) are skipped. Commands missing from `list_supported_commands`
+/// are converted on their own first, so the result matches `report_unknown`.
+#[pyfunction]
+#[pyo3(signature = (tex, *, custom_tex_macros=None))]
+fn find_unsupported_commands(
+    tex: &str,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+) -> PyResult<Vec<String>> {
+    let macros = defined_macro_names(custom_tex_macros)?;
+    let supported = supported_commands()?;
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().unwrap();
+        let mut unsupported = Vec::new();
+        for command in tex_command_names(tex) {
+            if macros.contains(&command) || supported.binary_search(&command).is_ok() {
+                continue;
+            }
+            if converter.is_unknown_command(&command)? {
+                unsupported.push(command);
+            }
+        }
+        Ok(unsupported)
+    })
+}
+
+/// Versions of the extension's components and the state of this thread's converter.
+///
+/// Does not start the thread's JS engine if it is not running yet.
+///
+/// Returns:
+///     dict with "rust_crate_version", "js_bundle_version", "quickjs_version",
+///     "rquickjs_version", "thread_converter_initialized" and "thread_conversion_count"
+#[pyfunction]
+fn get_converter_info(py: Python<'_>) -> PyResult<Py<PyAny>> {
+    let (initialized, js_version, conversions) =
+        THREAD_CONVERTER.with(|converter| match converter.borrow().as_ref() {
+            Some(converter) => (
+                true,
+                converter.js_bundle_version(),
+                converter.conversions.get(),
+            ),
+            None => (
+                false,
+                bundled_js_version(&active_bundle().source)
+                    .unwrap_or("unknown")
+                    .to_string(),
+                0,
+            ),
+        });
+
+    let info = PyDict::new(py);
+    info.set_item("rust_crate_version", env!("CARGO_PKG_VERSION"))?;
+    info.set_item("js_bundle_version", js_version)?;
+    info.set_item("quickjs_version", quickjs_version())?;
+    info.set_item("rquickjs_version", env!("RQUICKJS_VERSION"))?;
+    info.set_item("thread_converter_initialized", initialized)?;
+    info.set_item("thread_conversion_count", conversions)?;
+    Ok(info.into_any().unbind())
+}
+
+/// Version of the QuickJS engine linked into this build
+fn quickjs_version() -> String {
+    // SAFETY: `JS_GetVersion` returns a pointer to a static NUL-terminated string
+    unsafe { std::ffi::CStr::from_ptr(rquickjs::qjs::JS_GetVersion()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Conversions `check_installation` runs: direction, input and expected output
+const INSTALLATION_CHECKS: &[(&str, &str, &str)] = &[
+    ("tex2typst", r"\frac{1}{2}", "frac(1, 2)"),
+    ("tex2typst", r"\alpha", "alpha"),
+    ("typst2tex", "frac(1, 2)", r"\frac{1}{2}"),
+    ("typst2tex", "alpha", r"\alpha"),
+];
+
+/// Result of one `check_installation` check: its name and the error if it failed
+type InstallationCheck = (String, Option<String>);
+
+/// Run the `check_installation` checks that need this thread's converter
+fn installation_checks() -> Vec<InstallationCheck> {
+    let started = get_thread_converter().map_err(|e| Python::attach(|py| error_message(py, &e)));
+    let mut checks = vec![("converter_init".to_string(), started.clone().err())];
+    // Explicit options, so `set_default_options` cannot change the results
+    let options = crate::core::Options {
+        frac_to_slash: Some(false),
+        ..Default::default()
+    };
+
+    THREAD_CONVERTER.with(|converter| {
+        let converter = converter.borrow();
+        let converter = converter.as_ref().filter(|_| started.is_ok());
+        let not_started = || Some("converter did not start".to_string());
+        for name in ["tex2typst", "typst2tex"] {
+            let error = match converter {
+                Some(converter) => (!converter.has_global_function(name))
+                    .then(|| format!("the bundle does not define a global function {}", name)),
+                None => not_started(),
+            };
+            checks.push((format!("global_function:{}", name), error));
+        }
+        for (direction, input, expected) in INSTALLATION_CHECKS {
+            let error = match converter {
+                Some(converter) => {
+                    let result = if *direction == "tex2typst" {
+                        converter.engine.tex_to_typst(input, &options)
+                    } else {
+                        converter.engine.typst_to_tex(input, &options)
+                    };
+                    match result {
+                        Ok(output) => (output != *expected)
+                            .then(|| format!("expected {:?}, got {:?}", expected, output)),
+                        Err(e) => Some(e.to_string()),
+                    }
+                }
+                None => not_started(),
+            };
+            checks.push((format!("{}:{}", direction, input), error));
+        }
+    });
+    checks
+}
+
+/// Self-test of this installation, for bug reports: imports the extension module,
+/// starts this thread's converter, checks the bundle defines the converter functions
+/// and runs a few known conversions in both directions.
+///
+/// Failures are collected rather than raised, unless `raise_on_failure` is set.
+///
+/// Returns:
+///     dict with "ok" (whether every check passed), "module_path" (the extension
+///     module file), "versions" ("rust_crate_version", "js_bundle_version",
+///     "quickjs_version" and "rquickjs_version") and "checks", a list of dicts
+///     with "name", "ok" and "error" (None if the check passed)
+///
+/// Raises:
+///     EngineError: With `raise_on_failure`, if any check failed
+#[pyfunction]
+#[pyo3(pass_module, signature = (*, raise_on_failure=false))]
+fn check_installation(module: &Bound<'_, PyModule>, raise_on_failure: bool) -> PyResult<Py<PyAny>> {
+    let py = module.py();
+    let mut checks = vec![("import".to_string(), None)];
+    checks.extend(py.detach(installation_checks));
+
+    let js_version = THREAD_CONVERTER.with(|converter| match converter.borrow().as_ref() {
+        Some(converter) => converter.js_bundle_version(),
+        None => bundled_js_version(&active_bundle().source)
+            .unwrap_or("unknown")
+            .to_string(),
+    });
+    let failures: Vec<String> = checks
+        .iter()
+        .filter_map(|(name, error)| error.as_ref().map(|error| format!("{}: {}", name, error)))
+        .collect();
+    if raise_on_failure && !failures.is_empty() {
+        return Err(EngineError::new_err(format!(
+            "Installation check failed: {}",
+            failures.join("; ")
+        )));
+    }
+
+    let versions = PyDict::new(py);
+    versions.set_item("rust_crate_version", env!("CARGO_PKG_VERSION"))?;
+    versions.set_item("js_bundle_version", js_version)?;
+    versions.set_item("quickjs_version", quickjs_version())?;
+    versions.set_item("rquickjs_version", env!("RQUICKJS_VERSION"))?;
+    let results = PyList::empty(py);
+    for (name, error) in checks {
+        let check = PyDict::new(py);
+        check.set_item("name", name)?;
+        check.set_item("ok", error.is_none())?;
+        check.set_item("error", error)?;
+        results.append(check)?;
+    }
+
+    let report = PyDict::new(py);
+    report.set_item("ok", failures.is_empty())?;
+    report.set_item("module_path", module.filename()?)?;
+    report.set_item("versions", versions)?;
+    report.set_item("checks", results)?;
+    Ok(report.into_any().unbind())
+}
+
+/// Cumulative conversion statistics of all threads since import or `reset_stats()`.
+///
+/// A call is one conversion request handled by a JS engine: a single string or a
+/// whole batch. Engines are counted when created, whether thread-local, owned by
+/// a `Converter` or by a `WorkerConverter`.
+///
+/// Returns:
+///     dict with "tex2typst_conversions" and "typst2tex_conversions" (inputs,
+///     counting every batch item), "calls", "total_time_seconds",
+///     "max_call_time_seconds", "engines_initialized", "js_init_time_seconds",
+///     "cache_hits" and "cache_misses" (of all `Converter` result caches) and
+///     "js_memory_used_bytes" (this thread's engine, None if it is not running)
+#[pyfunction]
+fn get_stats(py: Python<'_>) -> PyResult<Py<PyAny>> {
+    let seconds = |counter: &AtomicU64| counter.load(Ordering::Relaxed) as f64 / 1e9;
+    let memory_used = THREAD_CONVERTER.with(|converter| {
+        converter
+            .borrow()
+            .as_ref()
+            .map(|converter| converter.engine.memory_used())
+    });
+
+    let stats = PyDict::new(py);
+    stats.set_item(
+        "tex2typst_conversions",
+        STATS.tex2typst_conversions.load(Ordering::Relaxed),
+    )?;
+    stats.set_item(
+        "typst2tex_conversions",
+        STATS.typst2tex_conversions.load(Ordering::Relaxed),
+    )?;
+    stats.set_item("calls", STATS.calls.load(Ordering::Relaxed))?;
+    stats.set_item("total_time_seconds", seconds(&STATS.total_call_nanos))?;
+    stats.set_item("max_call_time_seconds", seconds(&STATS.max_call_nanos))?;
+    stats.set_item(
+        "engines_initialized",
+        STATS.engines_initialized.load(Ordering::Relaxed),
+    )?;
+    stats.set_item("js_init_time_seconds", seconds(&STATS.js_init_nanos))?;
+    stats.set_item("cache_hits", STATS.cache_hits.load(Ordering::Relaxed))?;
+    stats.set_item("cache_misses", STATS.cache_misses.load(Ordering::Relaxed))?;
+    stats.set_item("js_memory_used_bytes", memory_used)?;
+    Ok(stats.into_any().unbind())
+}
+
+/// Messages the JS engines on this thread passed to `console.log`, `console.warn` and
+/// the other `console` methods since the last `clear_warnings()`, oldest first.
+///
+/// Only the most recent 1000 are kept.
+#[pyfunction]
+fn get_pending_warnings() -> Vec<String> {
+    PENDING_WARNINGS.with(|warnings| warnings.borrow().iter().cloned().collect())
+}
+
+/// Forget the messages reported by `get_pending_warnings`
+#[pyfunction]
+fn clear_warnings() {
+    PENDING_WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+}
+
+/// Cap the memory every JS engine may allocate beyond what loading the bundle took.
+///
+/// Applies to running engines on their next call. A conversion exceeding it raises
+/// `MemoryError`, and the engine is replaced on the thread's next conversion.
+///
+/// Args:
+///     limit_bytes: Bytes of headroom, or None to lift the limit
+#[pyfunction]
+#[pyo3(signature = (limit_bytes))]
+fn set_js_memory_limit(limit_bytes: Option<usize>) {
+    *JS_MEMORY_LIMIT.lock().unwrap() = limit_bytes;
+}
+
+/// The limit set by `set_js_memory_limit`, None if there is none
+#[pyfunction]
+fn get_js_memory_limit() -> Option<usize> {
+    *JS_MEMORY_LIMIT.lock().unwrap()
+}
+
+/// Collect garbage automatically once a JS engine has allocated this many bytes.
+///
+/// After each automatic collection QuickJS moves the threshold to half again the
+/// memory still in use. Applies to running engines on their next call.
+///
+/// Args:
+///     threshold_bytes: Bytes before the first automatic collection, or None to
+///         turn automatic collection off
+#[pyfunction]
+#[pyo3(signature = (threshold_bytes))]
+fn set_js_gc_threshold(threshold_bytes: Option<usize>) {
+    JS_GC_THRESHOLD.store(threshold_bytes.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// The threshold set by `set_js_gc_threshold`, None if automatic collection is off
+#[pyfunction]
+fn get_js_gc_threshold() -> Option<usize> {
+    Some(JS_GC_THRESHOLD.load(Ordering::Relaxed)).filter(|&threshold| threshold != usize::MAX)
+}
+
+/// Collect garbage every `every_n_items` items a batch converts, on top of the
+/// engine's automatic collection.
+///
+/// Args:
+///     every_n_items: Items between collections, or 0 to never collect in batches
+#[pyfunction]
+#[pyo3(signature = (every_n_items))]
+fn set_js_gc_interval(every_n_items: usize) {
+    JS_GC_INTERVAL.store(every_n_items, Ordering::Relaxed);
+}
+
+/// The interval set by `set_js_gc_interval`
+#[pyfunction]
+fn get_js_gc_interval() -> usize {
+    JS_GC_INTERVAL.load(Ordering::Relaxed)
+}
+
+/// Collect the garbage of this thread's JS engine now.
+///
+/// Does nothing if the thread has not converted anything yet.
+#[pyfunction]
+fn run_gc() {
+    THREAD_CONVERTER.with(|converter| {
+        if let Some(converter) = converter.borrow().as_ref() {
+            converter.engine.run_gc();
+        }
+    });
+}
+
+/// Allocation statistics of this thread's JS engine, None if the thread has not
+/// converted anything yet.
+///
+/// Returns:
+///     Dict with `malloc_size` (bytes allocated), `memory_used_size` (bytes in
+///     use including allocator overhead), `malloc_count`, `obj_count` and
+///     `str_count`
+#[pyfunction]
+fn memory_usage(py: Python<'_>) -> PyResult<Option<Bound<'_, PyDict>>> {
+    let Some(usage) = THREAD_CONVERTER.with(|converter| {
+        converter
+            .borrow()
+            .as_ref()
+            .map(|converter| converter.engine.memory_usage())
+    }) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("malloc_size", usage.malloc_size)?;
+    dict.set_item("memory_used_size", usage.memory_used_size)?;
+    dict.set_item("malloc_count", usage.malloc_count)?;
+    dict.set_item("obj_count", usage.obj_count)?;
+    dict.set_item("str_count", usage.str_count)?;
+    Ok(Some(dict))
+}
+
+/// Zero the counters reported by `get_stats`.
+#[pyfunction]
+fn reset_stats() {
+    STATS.reset();
+}
+
+/// Registered with `os.register_at_fork` to run in the child after a fork
+#[pyfunction]
+fn after_fork_in_child() {
+    FORK_EPOCH.fetch_add(1, Ordering::AcqRel);
+    let mut cache = DISK_CACHE.lock().unwrap();
+    *cache = cache.take().and_then(DiskCache::into_child);
+}
+
+/// Start this thread's JS engine now rather than on its first conversion.
+///
+/// Returns:
+///     Seconds spent starting it, 0.0 if it was already running
+#[pyfunction]
+fn warmup(py: Python<'_>) -> PyResult<f64> {
+    py.detach(|| {
+        let started = Instant::now();
+        let created = get_thread_converter()?;
+        Ok(if created {
+            started.elapsed().as_secs_f64()
+        } else {
+            0.0
+        })
+    })
+}
+
+/// Call `callback` with no arguments each time this thread's module-level converter
+/// is created: on the first conversion, and when it restarts after a bundle or
+/// extension change or a fork. A converter already running is not affected.
+///
+/// The hook is per thread and replaces any earlier one; its exceptions propagate
+/// from the call that created the converter.
+///
+/// Raises:
+///     TypeError: If `callback` is not callable
+#[pyfunction]
+fn set_converter_init_hook(callback: Bound<'_, PyAny>) -> PyResult<()> {
+    if !callback.is_callable() {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "callback must be callable",
+        ));
+    }
+    CONVERTER_INIT_HOOK.with(|hook| *hook.borrow_mut() = Some(callback.unbind()));
+    Ok(())
+}
+
+/// Remove this thread's hook set by `set_converter_init_hook`
+#[pyfunction]
+fn clear_converter_init_hook() {
+    CONVERTER_INIT_HOOK.with(|hook| hook.borrow_mut().take());
+}
+
+/// Evaluate the JavaScript `source` after the bundle in every converter, e.g. to
+/// replace `tex2typst` or extend a symbol table.
+///
+/// The extension is tried in a new converter first, which becomes this thread's
+/// converter; if it raises, the error carries the JS stack trace and nothing is
+/// registered. Module-level converters of other threads are restarted with all
+/// extensions on their next call. `Converter` and `WorkerConverter` instances keep
+/// the engine they were started with.
+///
+/// Raises:
+///     EngineError: If the bundle and the extensions registered so far, followed
+///         by `source`, fail to evaluate or no longer define the converter functions
+#[pyfunction]
+fn register_js_extension(source: &str) -> PyResult<()> {
+    let mut extensions = JS_EXTENSIONS.lock().unwrap();
+    let mut sources = extensions.sources.clone();
+    sources.push(source.to_string());
+    // The generation `set` publishes below; changes only happen under the lock
+    let generation = ENGINE_GENERATION.load(Ordering::Acquire) + 1;
+    let converter = ConverterInstance::from_parts(&active_bundle(), &sources, generation, 0)?;
+    extensions.set(sources);
+    drop(extensions);
+    THREAD_CONVERTER.with(|slot| *slot.borrow_mut() = Some(converter));
+    Ok(())
+}
+
+/// Remove all JS extensions; module-level converters restart without them on their
+/// next call
+#[pyfunction]
+fn clear_js_extensions() {
+    JS_EXTENSIONS.lock().unwrap().set(Vec::new());
+}
+
+/// Convert `tex` with a fresh converter running the bundle `source` instead of the
+/// built-in one, so tests can check how broken bundles fail.
+#[cfg(feature = "test-bundle")]
+#[pyfunction]
+fn _tex2typst_with_bundle(source: &str, tex: &str) -> PyResult<String> {
+    ConverterInstance::from_source(Some(source), 0)?.tex2typst(tex, None)
+}
+
+/// Make `bundle` (`None` for the embedded one) the bundle of all converters started
+/// from now on.
+///
+/// It is first started with the registered extensions in a converter that becomes
+/// this thread's, so a broken bundle raises before anything changes.
+fn install_bundle(bundle: Option<Bundle>) -> PyResult<()> {
+    let extensions = JS_EXTENSIONS.lock().unwrap();
+    let installed = bundle.as_ref().unwrap_or(&EMBEDDED_BUNDLE);
+    let generation = ENGINE_GENERATION.load(Ordering::Acquire) + 1;
+    let converter = ConverterInstance::from_parts(installed, &extensions.sources, generation, 0)?;
+    *JS_BUNDLE.write().unwrap() = bundle.map(Arc::new);
+    ENGINE_GENERATION.fetch_add(1, Ordering::AcqRel);
+    drop(extensions);
+    THREAD_CONVERTER.with(|slot| *slot.borrow_mut() = Some(converter));
+    Ok(())
+}
+
+/// Replace the embedded tex2typst.js bundle for all converters started from now on.
+///
+/// Module-level converters of other threads restart with it on their next call;
+/// `Converter` and `WorkerConverter` instances keep the engine they were started
+/// with. Registered JS extensions run after the new bundle.
+///
+/// Args:
+///     path_or_source: Path of the bundle file, or its source if `is_path` is false
+///     is_path: Whether `path_or_source` is a path (default: True)
+///
+/// Raises:
+///     FileNotFoundError: If the bundle file does not exist
+///     EngineError: If the bundle fails to evaluate or does not define the global
+///         `tex2typst` and `typst2tex` functions; the active bundle is kept then
+#[pyfunction]
+#[pyo3(signature = (path_or_source, *, is_path=true))]
+fn load_bundle(path_or_source: &Bound<PyAny>, is_path: bool) -> PyResult<()> {
+    let source = if is_path {
+        read_bundle_file(&path_or_source.extract::<std::path::PathBuf>()?)?
+    } else {
+        path_or_source.extract()?
+    };
+    install_bundle(Some(Bundle::new(Cow::Owned(source))))
+}
+
+/// Source of the bundle file at `path`, raising `FileNotFoundError` if it is missing
+fn read_bundle_file(path: &std::path::Path) -> PyResult<String> {
+    std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
+            format!("Bundle file not found: '{}'", path.display()),
+        ),
+        _ => e.into(),
+    })
+}
+
+/// Go back to the embedded tex2typst.js bundle, undoing `load_bundle`
+#[pyfunction]
+fn reset_bundle() -> PyResult<()> {
+    install_bundle(None)
+}
+
+/// Hex SHA-256 of the bundle converters are started with, embedded or loaded
+#[pyfunction]
+fn bundle_sha256() -> String {
+    active_bundle().sha256.clone()
+}
+
+/// SHA-256 digest of `data`
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[pymodule]
+#[pyo3(name = "_tex2typst_core")]
+fn tex2typst_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Not available on Windows, which cannot fork
+    let os = m.py().import("os")?;
+    if os.hasattr("register_at_fork")? {
+        let kwargs = PyDict::new(m.py());
+        kwargs.set_item("after_in_child", wrap_pyfunction!(after_fork_in_child, m)?)?;
+        os.call_method("register_at_fork", (), Some(&kwargs))?;
+    }
+    m.add_function(wrap_pyfunction!(tex2typst, m)?)?;
+    m.add_function(wrap_pyfunction!(typst2tex, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(typst2tex_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_tex, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_typst, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_ast, m)?)?;
+    m.add_function(wrap_pyfunction!(typst2tex_ast, m)?)?;
+    #[cfg(feature = "pandas")]
+    m.add_function(wrap_pyfunction!(tex2typst_series, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_series, m)?)?;
+    #[cfg(feature = "arrow")]
+    m.add_function(wrap_pyfunction!(tex2typst_arrow, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_with_info, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_batch_with_info, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_aligned, m)?)?;
+    m.add_function(wrap_pyfunction!(explain_conversion, m)?)?;
+    m.add_function(wrap_pyfunction!(roundtrip_check, m)?)?;
+    m.add_function(wrap_pyfunction!(roundtrip_diff, m)?)?;
+    m.add_class::<ConversionInfo>()?;
+    m.add_class::<BatchResult>()?;
+    m.add_class::<Span>()?;
+    m.add_class::<DocumentReport>()?;
+    m.add("TexParseError", m.py().get_type::<TexParseError>())?;
+    m.add("EngineError", m.py().get_type::<EngineError>())?;
+    m.add_class::<Converter>()?;
+    m.add_function(wrap_pyfunction!(get_js_bundle_version, m)?)?;
+    m.add_function(wrap_pyfunction!(list_global_functions, m)?)?;
+    m.add_function(wrap_pyfunction!(get_converter_info, m)?)?;
+    m.add_function(wrap_pyfunction!(check_installation, m)?)?;
+    m.add_function(wrap_pyfunction!(get_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(set_js_memory_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(get_js_memory_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(set_js_gc_threshold, m)?)?;
+    m.add_function(wrap_pyfunction!(get_js_gc_threshold, m)?)?;
+    m.add_function(wrap_pyfunction!(set_js_gc_interval, m)?)?;
+    m.add_function(wrap_pyfunction!(get_js_gc_interval, m)?)?;
+    m.add_function(wrap_pyfunction!(run_gc, m)?)?;
+    m.add_function(wrap_pyfunction!(memory_usage, m)?)?;
+    m.add_function(wrap_pyfunction!(warmup, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(set_converter_init_hook, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_converter_init_hook, m)?)?;
+    m.add_function(wrap_pyfunction!(list_supported_commands, m)?)?;
+    m.add_function(wrap_pyfunction!(find_unsupported_commands, m)?)?;
+    m.add_function(wrap_pyfunction!(register_js_extension, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_js_extensions, m)?)?;
+    m.add_function(wrap_pyfunction!(get_pending_warnings, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_warnings, m)?)?;
+    m.add_function(wrap_pyfunction!(load_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(bundle_sha256, m)?)?;
+    #[cfg(feature = "test-bundle")]
+    m.add_function(wrap_pyfunction!(_tex2typst_with_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(enable_disk_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(disable_disk_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(disk_cache_stats, m)?)?;
+    m.add_class::<WorkerConverter>()?;
+    m.add_function(wrap_pyfunction!(parse_preamble, m)?)?;
+    m.add_function(wrap_pyfunction!(load_macros_from_file, m)?)?;
+    m.add_function(wrap_pyfunction!(save_macros_to_file, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_refs, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_original_comments, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_typst_document, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_latex_document, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_markdown, m)?)?;
+    m.add_function(wrap_pyfunction!(num_to_typst, m)?)?;
+    m.add_function(wrap_pyfunction!(format_typst_math, m)?)?;
+    m.add_function(wrap_pyfunction!(typst_equivalent, m)?)?;
+    m.add_function(wrap_pyfunction!(typst_equivalent_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_typst, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_tex, m)?)?;
+    m.add_function(wrap_pyfunction!(num_to_tex, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_format, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_auto, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_jsonl, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_file, m)?)?;
+    m.add_function(wrap_pyfunction!(tex2typst_iter, m)?)?;
+    m.add_function(wrap_pyfunction!(set_default_options, m)?)?;
+    m.add_function(wrap_pyfunction!(get_default_options, m)?)?;
+    m.add_function(wrap_pyfunction!(get_supported_options, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_default_options, m)?)?;
+    m.add_function(wrap_pyfunction!(merge_options, m)?)?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
@@ -0,0 +1,548 @@
+//! Arrow C data interface: reading string columns in place and exporting the
+//! results as an Arrow array (`arrow` feature).
+
+use super::*;
+
+/// `ArrowSchema` of the Arrow C data interface
+#[repr(C)]
+struct ArrowSchema {
+    format: *const std::ffi::c_char,
+    name: *const std::ffi::c_char,
+    metadata: *const std::ffi::c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut std::ffi::c_void,
+}
+
+/// `ArrowArray` of the Arrow C data interface
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const std::ffi::c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut std::ffi::c_void,
+}
+
+/// A C data interface struct moved into a capsule; only its producer touches the
+/// pointers inside
+struct Exported<T>(T);
+
+// SAFETY: the buffers behind the pointers are owned by the struct's `private_data`
+// and released exactly once, from whichever thread drops the capsule
+unsafe impl<T> Send for Exported<T> {}
+
+/// Borrow the values of an Arrow string array from its C data interface structs.
+///
+/// Null slots are `None`. Nothing is copied: the slices point into the array's
+/// data buffer, which lives as long as the capsules holding `schema` and `array`.
+/// `first` is the index of the array's first value in the caller's input, used in
+/// error messages.
+///
+/// # Safety
+///
+/// `schema` and `array` must be valid, unreleased C data interface structs.
+unsafe fn arrow_string_values<'a>(
+    schema: &ArrowSchema,
+    array: &ArrowArray,
+    first: usize,
+) -> PyResult<Vec<Option<&'a str>>> {
+    // SAFETY: `format` is a NUL-terminated string by the C data interface
+    let format = unsafe { std::ffi::CStr::from_ptr(schema.format) }.to_bytes();
+    // string and large_string have validity, offsets and data buffers; string_view has
+    // validity, views, any number of data buffers and their sizes
+    let expected_buffers = match format {
+        b"u" | b"U" => Some(3),
+        b"vu" => None,
+        other => {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "Expected an Arrow string, large_string or string_view array, got Arrow format {:?}",
+                String::from_utf8_lossy(other)
+            )));
+        }
+    };
+    if expected_buffers.is_some_and(|n| array.n_buffers != n) || array.n_buffers < 3 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Arrow string array has {} buffers, expected {}",
+            array.n_buffers,
+            expected_buffers.unwrap_or(3)
+        )));
+    }
+    let length = array.length as usize;
+    let offset = array.offset as usize;
+    // SAFETY: `n_buffers` entries, checked above
+    let buffers = unsafe { std::slice::from_raw_parts(array.buffers, array.n_buffers as usize) };
+    let validity = buffers[0].cast::<u8>();
+    // SAFETY (all reads below): the buffers hold what the format prescribes for
+    // `offset + length` slots
+    let value_bytes = |index: usize| -> &'a [u8] {
+        let (data, start, len) = unsafe {
+            match format {
+                b"u" => {
+                    let offsets = buffers[1].cast::<i32>();
+                    let start = *offsets.add(index) as usize;
+                    (buffers[2], start, *offsets.add(index + 1) as usize - start)
+                }
+                b"U" => {
+                    let offsets = buffers[1].cast::<i64>();
+                    let start = *offsets.add(index) as usize;
+                    (buffers[2], start, *offsets.add(index + 1) as usize - start)
+                }
+                _ => {
+                    // 16-byte views: length, then the data inline or its location
+                    let view = buffers[1].cast::<u8>().add(index * 16);
+                    let len = view.cast::<i32>().read_unaligned() as usize;
+                    if len <= 12 {
+                        (view.add(4).cast(), 0, len)
+                    } else {
+                        let buffer = view.add(8).cast::<i32>().read_unaligned() as usize;
+                        let start = view.add(12).cast::<i32>().read_unaligned() as usize;
+                        (buffers[2 + buffer], start, len)
+                    }
+                }
+            }
+        };
+        if len == 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(data.cast::<u8>().add(start), len) }
+        }
+    };
+
+    let mut values = Vec::with_capacity(length);
+    for index in offset..offset + length {
+        // SAFETY: the validity bitmap, if present, holds a bit per slot
+        let valid =
+            validity.is_null() || unsafe { *validity.add(index / 8) } & (1 << (index % 8)) != 0;
+        if !valid {
+            values.push(None);
+            continue;
+        }
+        let value = std::str::from_utf8(value_bytes(index)).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "item {} is not valid UTF-8: {}",
+                first + index - offset,
+                e
+            ))
+        })?;
+        values.push(Some(value));
+    }
+    Ok(values)
+}
+
+/// Arrow buffers of a string array; large_string offsets are only used past 2 GiB of data
+pub(super) struct StringArrayBuffers {
+    length: usize,
+    null_count: usize,
+    validity: Vec<u8>,
+    offsets: Vec<i32>,
+    large_offsets: Vec<i64>,
+    data: Vec<u8>,
+}
+
+impl StringArrayBuffers {
+    pub(super) fn new(values: &[Option<String>]) -> Self {
+        let mut validity = vec![0u8; values.len().div_ceil(8)];
+        let mut large_offsets = Vec::with_capacity(values.len() + 1);
+        let mut data = Vec::with_capacity(values.iter().flatten().map(String::len).sum());
+        large_offsets.push(0);
+        let mut null_count = 0;
+        for (index, value) in values.iter().enumerate() {
+            match value {
+                Some(value) => {
+                    validity[index / 8] |= 1 << (index % 8);
+                    data.extend_from_slice(value.as_bytes());
+                }
+                None => null_count += 1,
+            }
+            large_offsets.push(data.len() as i64);
+        }
+        let offsets = if data.len() <= i32::MAX as usize {
+            std::mem::take(&mut large_offsets)
+                .into_iter()
+                .map(|offset| offset as i32)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        StringArrayBuffers {
+            length: values.len(),
+            null_count,
+            validity,
+            offsets,
+            large_offsets,
+            data,
+        }
+    }
+}
+
+/// What the `private_data` of an exported `ArrowArray` owns
+struct ExportedStringArray {
+    /// Only held so the memory `pointers` point into stays allocated
+    _buffers: StringArrayBuffers,
+    pointers: [*const std::ffi::c_void; 3],
+}
+
+unsafe extern "C" fn release_arrow_schema(schema: *mut ArrowSchema) {
+    // SAFETY: called once by the consumer, with the schema built by `__arrow_c_array__`
+    unsafe {
+        drop(Box::from_raw(
+            (*schema).private_data.cast::<std::ffi::CString>(),
+        ));
+        (*schema).release = None;
+    }
+}
+
+unsafe extern "C" fn release_arrow_array(array: *mut ArrowArray) {
+    // SAFETY: called once by the consumer, with the array built by `__arrow_c_array__`
+    unsafe {
+        drop(Box::from_raw(
+            (*array).private_data.cast::<ExportedStringArray>(),
+        ));
+        (*array).release = None;
+    }
+}
+
+/// Capsule holding `value`, released by `release` unless a consumer moved it out
+fn arrow_capsule<'py, T: 'static>(
+    py: Python<'py>,
+    value: T,
+    name: &std::ffi::CStr,
+    release: fn(&mut T),
+) -> PyResult<Bound<'py, pyo3::types::PyCapsule>> {
+    pyo3::types::PyCapsule::new_with_destructor(
+        py,
+        Exported(value),
+        Some(name.to_owned()),
+        move |mut value, _| release(&mut value.0),
+    )
+}
+
+/// Converted strings handed to pyarrow through the Arrow PyCapsule interface
+#[pyclass(frozen)]
+pub(super) struct ArrowStringResults {
+    /// `None` once exported
+    pub(super) buffers: Mutex<Option<StringArrayBuffers>>,
+}
+
+#[pymethods]
+impl ArrowStringResults {
+    /// Export the strings as a string array, or a large_string array past 2 GiB
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        // Only one layout is offered; consumers cast if they asked for another
+        let _ = requested_schema;
+        let buffers = self.buffers.lock().unwrap().take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Arrow results were already exported")
+        })?;
+        let large = buffers.offsets.is_empty();
+        let (length, null_count) = (buffers.length, buffers.null_count);
+        let offsets: *const std::ffi::c_void = if large {
+            buffers.large_offsets.as_ptr().cast()
+        } else {
+            buffers.offsets.as_ptr().cast()
+        };
+        let pointers = [
+            buffers.validity.as_ptr().cast(),
+            offsets,
+            buffers.data.as_ptr().cast(),
+        ];
+        let exported = Box::into_raw(Box::new(ExportedStringArray {
+            _buffers: buffers,
+            pointers,
+        }));
+        let format = Box::new(if large { c"U" } else { c"u" }.to_owned());
+        let schema = ArrowSchema {
+            format: format.as_ptr(),
+            name: c"".as_ptr(),
+            metadata: std::ptr::null(),
+            // ARROW_FLAG_NULLABLE
+            flags: 2,
+            n_children: 0,
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: Some(release_arrow_schema),
+            private_data: Box::into_raw(format).cast(),
+        };
+        let array = ArrowArray {
+            length: length as i64,
+            null_count: null_count as i64,
+            offset: 0,
+            n_buffers: 3,
+            n_children: 0,
+            // SAFETY: `exported` stays allocated until `release_arrow_array`
+            buffers: unsafe { (*exported).pointers.as_mut_ptr() },
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: Some(release_arrow_array),
+            private_data: exported.cast(),
+        };
+        let schema = arrow_capsule(py, schema, c"arrow_schema", |schema| {
+            if let Some(release) = schema.release {
+                // SAFETY: not moved out by a consumer, so still ours to release
+                unsafe { release(schema) }
+            }
+        })?;
+        let array = arrow_capsule(py, array, c"arrow_array", |array| {
+            if let Some(release) = array.release {
+                // SAFETY: not moved out by a consumer, so still ours to release
+                unsafe { release(array) }
+            }
+        })?;
+        Ok((schema.into_any(), array.into_any()))
+    }
+}
+
+/// Convert one Arrow string array, returning the results with nulls kept as `None`
+fn convert_arrow_array(
+    py: Python<'_>,
+    array: &Bound<PyAny>,
+    first: usize,
+    options: Option<&JsOptions>,
+    keep_original_on_error: bool,
+) -> PyResult<Vec<Option<String>>> {
+    let (schema_capsule, array_capsule): (
+        Bound<pyo3::types::PyCapsule>,
+        Bound<pyo3::types::PyCapsule>,
+    ) = array.call_method0("__arrow_c_array__")?.extract()?;
+    let schema = schema_capsule.pointer_checked(Some(c"arrow_schema"))?;
+    let c_array = array_capsule.pointer_checked(Some(c"arrow_array"))?;
+    // SAFETY: the capsules hold unreleased structs, and stay alive until we return
+    let values = unsafe {
+        arrow_string_values(
+            schema.cast::<ArrowSchema>().as_ref(),
+            c_array.cast::<ArrowArray>().as_ref(),
+            first,
+        )?
+    };
+
+    convert_optional_tex(py, &values, first, options, keep_original_on_error)
+}
+
+/// Convert a pyarrow StringArray or ChunkedArray of LaTeX/TeX strings to Typst.
+///
+/// The values are read in place through the Arrow C data interface and converted
+/// with the GIL released. Nulls stay null. The result is a pyarrow array of the
+/// same length, or a ChunkedArray with the same chunks for ChunkedArray input.
+/// With `keep_original_on_error=True`, failed items keep their TeX.
+#[pyfunction]
+#[pyo3(signature = (array, *, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, keep_original_on_error=false))]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn tex2typst_arrow<'py>(
+    py: Python<'py>,
+    array: &Bound<'py, PyAny>,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    keep_original_on_error: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    get_thread_converter()?;
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        None,
+    )?;
+    let pyarrow = py.import("pyarrow")?;
+    let to_pyarrow = |values: Vec<Option<String>>| {
+        let buffers = StringArrayBuffers::new(&values);
+        drop(values);
+        let results = ArrowStringResults {
+            buffers: Mutex::new(Some(buffers)),
+        };
+        pyarrow.call_method1("array", (results,))
+    };
+
+    if array.hasattr("__arrow_c_array__")? {
+        let values = convert_arrow_array(py, array, 0, opts.as_ref(), keep_original_on_error)?;
+        return to_pyarrow(values);
+    }
+    if !array.hasattr("chunks")? {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "tex2typst_arrow expects a pyarrow Array or ChunkedArray, got {}",
+            array.get_type().name()?
+        )));
+    }
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    for chunk in array.getattr("chunks")?.try_iter()? {
+        let values =
+            convert_arrow_array(py, &chunk?, first, opts.as_ref(), keep_original_on_error)?;
+        first += values.len();
+        chunks.push(to_pyarrow(values)?);
+    }
+    let value_type = match chunks.first() {
+        Some(chunk) => chunk.getattr("type")?,
+        None => pyarrow.call_method0("string")?,
+    };
+    pyarrow.call_method1("chunked_array", (chunks, value_type))
+}
+
+/// `ArrowArrayStream` of the Arrow C stream interface
+#[repr(C)]
+struct ArrowArrayStream {
+    get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> i32>,
+    get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> i32>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const std::ffi::c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut std::ffi::c_void,
+}
+
+/// A C data interface struct received from a producer, released when dropped
+struct Imported<T: ArrowRelease>(T);
+
+trait ArrowRelease {
+    fn release_callback(&mut self) -> Option<unsafe extern "C" fn(*mut Self)>;
+}
+
+impl ArrowRelease for ArrowSchema {
+    fn release_callback(&mut self) -> Option<unsafe extern "C" fn(*mut Self)> {
+        self.release
+    }
+}
+
+impl ArrowRelease for ArrowArray {
+    fn release_callback(&mut self) -> Option<unsafe extern "C" fn(*mut Self)> {
+        self.release
+    }
+}
+
+impl ArrowRelease for ArrowArrayStream {
+    fn release_callback(&mut self) -> Option<unsafe extern "C" fn(*mut Self)> {
+        self.release
+    }
+}
+
+impl<T: ArrowRelease> Imported<T> {
+    fn empty() -> Self {
+        // SAFETY: the structs are pointers, integers and `Option`s of function
+        // pointers, all valid as zero; a null `release` marks them as empty
+        Imported(unsafe { std::mem::zeroed() })
+    }
+}
+
+impl<T: ArrowRelease> Drop for Imported<T> {
+    fn drop(&mut self) {
+        if let Some(release) = self.0.release_callback() {
+            // SAFETY: filled in by the producer and not released yet
+            unsafe { release(&mut self.0) }
+        }
+    }
+}
+
+/// Read a column through its `__arrow_c_stream__` export and convert its values.
+///
+/// Returns `None` when `obj` has no such export, cannot export itself (e.g. mixed
+/// object values) or is not of a string type, so that the caller can fall back to
+/// reading the values one by one and name the offending one.
+pub(super) fn convert_arrow_stream(
+    py: Python<'_>,
+    obj: &Bound<PyAny>,
+    options: Option<&JsOptions>,
+    keep_original_on_error: bool,
+) -> PyResult<Option<Vec<Option<String>>>> {
+    if !obj.hasattr("__arrow_c_stream__")? {
+        return Ok(None);
+    }
+    let Ok(capsule) = obj.call_method0("__arrow_c_stream__") else {
+        return Ok(None);
+    };
+    let capsule = capsule.cast_into::<pyo3::types::PyCapsule>()?;
+    let pointer = capsule
+        .pointer_checked(Some(c"arrow_array_stream"))?
+        .cast::<ArrowArrayStream>()
+        .as_ptr();
+    // SAFETY: the capsule holds an unreleased stream; moving it out and clearing
+    // `release` in the capsule leaves the capsule's destructor nothing to do
+    let mut stream = Imported(unsafe { pointer.read() });
+    unsafe { (*pointer).release = None };
+    let stream_error = |stream: &mut ArrowArrayStream, code: i32| {
+        let message = stream
+            .get_last_error
+            // SAFETY: the stream is unreleased; the message lives until its next call
+            .map(|get_last_error| unsafe { get_last_error(stream) })
+            .filter(|message| !message.is_null())
+            .map(|message| unsafe { std::ffi::CStr::from_ptr(message) }.to_string_lossy())
+            .unwrap_or_default();
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Arrow stream failed with error code {}: {}",
+            code, message
+        ))
+    };
+    let (Some(get_schema), Some(get_next)) = (stream.0.get_schema, stream.0.get_next) else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Arrow stream has no get_schema or get_next callback",
+        ));
+    };
+
+    let mut schema = Imported::<ArrowSchema>::empty();
+    // SAFETY (all callbacks): the stream is unreleased and the out-structs empty
+    let code = unsafe { get_schema(&mut stream.0, &mut schema.0) };
+    if code != 0 {
+        return Err(stream_error(&mut stream.0, code));
+    }
+    let format = unsafe { std::ffi::CStr::from_ptr(schema.0.format) }.to_bytes();
+    if !matches!(format, b"u" | b"U" | b"vu") {
+        return Ok(None);
+    }
+
+    let mut output = Vec::new();
+    loop {
+        let mut array = Imported::<ArrowArray>::empty();
+        let code = unsafe { get_next(&mut stream.0, &mut array.0) };
+        if code != 0 {
+            return Err(stream_error(&mut stream.0, code));
+        }
+        if array.0.release.is_none() {
+            break;
+        }
+        let first = output.len();
+        // SAFETY: the array is unreleased until `array` drops, after its values are
+        // converted
+        let values = unsafe { arrow_string_values(&schema.0, &array.0, first)? };
+        output.extend(convert_optional_tex(
+            py,
+            &values,
+            first,
+            options,
+            keep_original_on_error,
+        )?);
+    }
+    Ok(Some(output))
+}
@@ -0,0 +1,690 @@
+//! The `Converter` and `WorkerConverter` classes.
+
+use super::*;
+
+/// A job sent to the `WorkerConverter` background thread
+enum WorkerJob {
+    Convert {
+        tex: String,
+        options: Option<JsOptions>,
+        reply: mpsc::Sender<PyResult<String>>,
+    },
+    ConvertMany {
+        tex_list: Vec<String>,
+        options: Option<JsOptions>,
+        reply: mpsc::Sender<PyResult<Vec<String>>>,
+    },
+}
+
+/// Event loop of the `WorkerConverter` thread: owns the JS engine until the channel closes
+fn run_worker(jobs: mpsc::Receiver<WorkerJob>, ready: mpsc::Sender<PyResult<()>>) {
+    let mut converter = match ConverterInstance::new() {
+        Ok(converter) => converter,
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    for job in jobs {
+        if converter.engine.is_poisoned() {
+            converter = match ConverterInstance::new() {
+                Ok(converter) => converter,
+                // Without an engine the remaining jobs fail when the channel closes
+                Err(_) => return,
+            };
+        }
+        converter.sync_engine_limits();
+        // A dropped reply receiver only means the caller went away; keep serving
+        match job {
+            WorkerJob::Convert {
+                tex,
+                options,
+                reply,
+            } => {
+                let _ = reply.send(converter.tex2typst(&tex, options.as_ref()));
+            }
+            WorkerJob::ConvertMany {
+                tex_list,
+                options,
+                reply,
+            } => {
+                let result = converter
+                    .tex2typst_batch(&tex_list, options.as_ref(), false, None)
+                    .map(|(results, _)| results);
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+/// A converter backed by one dedicated OS thread that owns the JS engine.
+///
+/// Unlike the thread-local converter used by the module-level functions, a single
+/// warm engine is shared by any number of Python threads: calls are queued to the
+/// worker and wait for the result with the GIL released.
+#[pyclass(frozen)]
+pub(super) struct WorkerConverter {
+    sender: Mutex<Option<mpsc::Sender<WorkerJob>>>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+    /// `FORK_EPOCH` when the worker thread was spawned
+    fork_epoch: u64,
+}
+
+impl WorkerConverter {
+    /// Enqueue a job, failing if the converter has been closed
+    fn submit(&self, job: WorkerJob) -> PyResult<()> {
+        // The worker thread does not exist in a forked child; a job would wait forever
+        if self.fork_epoch != FORK_EPOCH.load(Ordering::Acquire) {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "WorkerConverter was created before os.fork(); its thread only runs in the parent",
+            ));
+        }
+        let sender = self.sender.lock().unwrap().clone().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("WorkerConverter is closed")
+        })?;
+        sender.send(job).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("WorkerConverter thread has exited")
+        })
+    }
+}
+
+/// Wait for a worker reply without holding the GIL
+fn wait_for_reply<T: Send>(py: Python<'_>, reply: mpsc::Receiver<PyResult<T>>) -> PyResult<T> {
+    py.detach(move || reply.recv()).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("WorkerConverter thread has exited")
+    })?
+}
+
+#[pymethods]
+impl WorkerConverter {
+    /// Spawn the worker thread and wait until its JS engine is initialized.
+    #[new]
+    fn new(py: Python<'_>) -> PyResult<Self> {
+        let (sender, jobs) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("tex2typst-worker".to_string())
+            .spawn(move || run_worker(jobs, ready_sender))
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to spawn worker thread: {}",
+                    e
+                ))
+            })?;
+        wait_for_reply(py, ready)?;
+
+        Ok(WorkerConverter {
+            sender: Mutex::new(Some(sender)),
+            handle: Mutex::new(Some(handle)),
+            fork_epoch: FORK_EPOCH.load(Ordering::Acquire),
+        })
+    }
+
+    /// Convert a LaTeX/TeX string to Typst on the worker thread.
+    ///
+    /// Accepts the same keyword options as `tex2typst`, plus `max_input_length`.
+    #[pyo3(signature = (tex, **options))]
+    fn convert(
+        &self,
+        py: Python<'_>,
+        tex: String,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<String> {
+        let (max_input_length, options) = take_max_input_length(options)?;
+        let options = kwargs_to_js_options("convert", options.as_ref(), TEX2TYPST_OPTION_NAMES)?;
+        check_input_length(&tex, max_input_length)?;
+        let (reply, result) = mpsc::channel();
+        self.submit(WorkerJob::Convert {
+            tex,
+            options,
+            reply,
+        })?;
+        wait_for_reply(py, result)
+    }
+
+    /// Convert a list of LaTeX/TeX strings as a single job on the worker thread.
+    ///
+    /// Accepts the same keyword options as `tex2typst`, plus `max_input_length`.
+    #[pyo3(signature = (tex_list, **options))]
+    fn convert_many(
+        &self,
+        py: Python<'_>,
+        tex_list: Vec<String>,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<Vec<String>> {
+        let (max_input_length, options) = take_max_input_length(options)?;
+        let options =
+            kwargs_to_js_options("convert_many", options.as_ref(), TEX2TYPST_OPTION_NAMES)?;
+        check_batch_input_length(&tex_list, max_input_length)?;
+        let (reply, result) = mpsc::channel();
+        self.submit(WorkerJob::ConvertMany {
+            tex_list,
+            options,
+            reply,
+        })?;
+        wait_for_reply(py, result)
+    }
+
+    /// Stop accepting jobs and join the worker thread once queued jobs finish.
+    ///
+    /// Calling `close` more than once is a no-op.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        // Dropping the last sender ends the worker's job loop
+        self.sender.lock().unwrap().take();
+        let handle = self.handle.lock().unwrap().take();
+        if let Some(handle) = handle {
+            py.detach(|| handle.join()).map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("WorkerConverter thread panicked")
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Whether `close` has been called
+    #[getter]
+    fn closed(&self) -> bool {
+        self.sender.lock().unwrap().is_none()
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: Option<&Bound<PyAny>>,
+        _exc_value: Option<&Bound<PyAny>>,
+        _traceback: Option<&Bound<PyAny>>,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+}
+
+/// Default number of results kept by a `Converter`
+const DEFAULT_CONVERTER_CACHE_SIZE: usize = 128;
+
+/// A value that may only be used on the thread that created it.
+///
+/// The wrapper itself can cross threads, so a `Converter` can be pickled or garbage
+/// collected elsewhere (`multiprocessing` pickles tasks on a handler thread), while the
+/// JS engine inside is only ever touched by its own thread.
+struct ThreadBound<T> {
+    owner: std::thread::ThreadId,
+    value: std::mem::ManuallyDrop<T>,
+}
+
+// SAFETY: `value` is only reachable through `get` and `get_mut`, which check that the
+// caller is the owner thread, and `drop` leaks it on any other thread.
+unsafe impl<T> Send for ThreadBound<T> {}
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+impl<T> ThreadBound<T> {
+    fn new(value: T) -> Self {
+        ThreadBound {
+            owner: std::thread::current().id(),
+            value: std::mem::ManuallyDrop::new(value),
+        }
+    }
+
+    fn check_thread(&self) -> PyResult<()> {
+        if std::thread::current().id() == self.owner {
+            Ok(())
+        } else {
+            Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Converter can only be used from the thread that created it",
+            ))
+        }
+    }
+
+    fn get(&self) -> PyResult<&T> {
+        self.check_thread()?;
+        Ok(&self.value)
+    }
+
+    fn get_mut(&mut self) -> PyResult<&mut T> {
+        self.check_thread()?;
+        Ok(&mut self.value)
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        // Like PyO3's `unsendable` classes, leak rather than drop on a foreign thread
+        if std::thread::current().id() == self.owner {
+            // SAFETY: `value` is never used again
+            unsafe { std::mem::ManuallyDrop::drop(&mut self.value) }
+        }
+    }
+}
+
+/// A converter with its own JS engine, default options and LRU result cache.
+///
+/// Keyword options given to the constructor are defaults for every call; per-call
+/// options override them. The engine is bound to the thread that created it.
+/// Use as a context manager or call `close()` to drop the engine deterministically.
+/// Pickling is allowed from any thread and sends the construction options only.
+#[pyclass(module = "tex2typst")]
+pub(super) struct Converter {
+    /// `None` once `close` has dropped the JS runtime
+    inner: ThreadBound<Option<ConverterInstance>>,
+    cache_size: usize,
+    js_version: String,
+    defaults: Py<PyDict>,
+    /// Bundle file the engine was started from by `from_js_file` or
+    /// `reload_js_bundle`, `None` for the active bundle
+    js_path: Option<std::path::PathBuf>,
+}
+
+/// JS options of one `Converter` call, plus the options handled on the Rust side
+struct ConverterCallOptions {
+    js: Option<JsOptions>,
+    trim_input: bool,
+    max_input_length: Option<usize>,
+}
+
+impl Converter {
+    /// Create a converter running the bundle file at `js_path`, or the active bundle
+    fn with_bundle(
+        py: Python<'_>,
+        js_path: Option<std::path::PathBuf>,
+        config: Option<&Bound<PyDict>>,
+        cache_size: Option<usize>,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<Self> {
+        let merged = match config {
+            Some(config) => config.copy()?,
+            None => PyDict::new(py),
+        };
+        if let Some(options) = options {
+            merged.update(options.as_mapping())?;
+        }
+        let cache_size = match (cache_size, merged.get_item("cache_size")?) {
+            (Some(cache_size), _) => cache_size,
+            (None, Some(value)) => value.extract()?,
+            (None, None) => DEFAULT_CONVERTER_CACHE_SIZE,
+        };
+        if merged.contains("cache_size")? {
+            merged.del_item("cache_size")?;
+        }
+
+        for key in merged.keys() {
+            let name: String = key.extract()?;
+            let known = TEX2TYPST_OPTION_NAMES
+                .iter()
+                .chain(TYPST2TEX_OPTION_NAMES)
+                .any(|(py_name, _)| *py_name == name)
+                || CONVERTER_OPTION_NAMES.contains(&name.as_str());
+            if !known {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "Converter() got an unexpected keyword argument '{}'",
+                    name
+                )));
+            }
+        }
+
+        let inner = match &js_path {
+            Some(path) => {
+                ConverterInstance::from_source(Some(&read_bundle_file(path)?), cache_size)?
+            }
+            None => ConverterInstance::with_cache_size(cache_size)?,
+        };
+        Ok(Converter {
+            js_version: inner.js_bundle_version(),
+            inner: ThreadBound::new(Some(inner)),
+            cache_size,
+            defaults: merged.unbind(),
+            js_path,
+        })
+    }
+
+    /// The live JS engine, failing if the converter has been closed
+    fn instance(&self) -> PyResult<&ConverterInstance> {
+        let instance = self.inner.get()?.as_ref().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Converter is closed")
+        })?;
+        if instance.engine.is_poisoned() {
+            return Err(EngineError::new_err(
+                "Converter's JS engine ran out of memory or failed internally; create a new Converter",
+            ));
+        }
+        instance.sync_engine_limits();
+        Ok(instance)
+    }
+
+    /// Merge per-call `options` over the constructor defaults that `names` accepts
+    fn call_options(
+        &self,
+        py: Python<'_>,
+        method: &str,
+        names: &[(&str, &str)],
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<ConverterCallOptions> {
+        let merged = PyDict::new(py);
+        for (key, value) in self.defaults.bind(py).iter() {
+            let name: String = key.extract()?;
+            if names.iter().any(|(py_name, _)| *py_name == name)
+                || CONVERTER_OPTION_NAMES.contains(&name.as_str())
+            {
+                merged.set_item(key, value)?;
+            }
+        }
+        if let Some(options) = options {
+            merged.update(options.as_mapping())?;
+        }
+
+        let trim_input = match merged.get_item("trim_input")? {
+            Some(value) => {
+                merged.del_item("trim_input")?;
+                value.extract::<Option<bool>>()?.unwrap_or(false)
+            }
+            None => false,
+        };
+        let (max_input_length, merged) = take_max_input_length(Some(&merged))?;
+        Ok(ConverterCallOptions {
+            js: kwargs_to_js_options(method, merged.as_ref(), names)?,
+            trim_input,
+            max_input_length,
+        })
+    }
+}
+
+/// Iterator returned by `Converter.tex2typst_batch_generator`, converting one item
+/// per `next()`
+#[pyclass(module = "tex2typst")]
+struct ConverterBatchGenerator {
+    converter: Py<Converter>,
+    /// `None` once the input is exhausted or an error was raised
+    items: Option<Py<PyIterator>>,
+    options: ConverterCallOptions,
+    /// Position of the next item in the input
+    position: usize,
+}
+
+impl ConverterBatchGenerator {
+    fn convert_next(
+        &mut self,
+        py: Python<'_>,
+        items: &Bound<'_, PyIterator>,
+    ) -> PyResult<Option<String>> {
+        let Some(item) = items.clone().next() else {
+            return Ok(None);
+        };
+        let item = item?;
+        let position = self.position;
+        self.position += 1;
+        let tex: String = item.extract().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "tex2typst_batch_generator items must be str, got {} at item {}",
+                item.get_type()
+                    .name()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                position
+            ))
+        })?;
+        check_input_length(&tex, self.options.max_input_length)?;
+        let tex = if self.options.trim_input {
+            tex.trim()
+        } else {
+            &tex
+        };
+        let converter = self.converter.borrow(py);
+        let instance = converter.instance()?;
+        let options = self.options.js.as_ref();
+        instance
+            .cached("tex2typst", tex, options, || {
+                let (mut results, _) =
+                    instance.tex2typst_batch(&[tex], options, false, Some(&[position]))?;
+                Ok(results.remove(0))
+            })
+            .map(Some)
+    }
+}
+
+#[pymethods]
+impl ConverterBatchGenerator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        let Some(items) = self.items.as_ref().map(|items| items.bind(py).clone()) else {
+            return Ok(None);
+        };
+        let result = self.convert_next(py, &items);
+        if !matches!(result, Ok(Some(_))) {
+            self.items = None;
+        }
+        result
+    }
+}
+
+#[pymethods]
+impl Converter {
+    /// Create a converter keeping up to `cache_size` results (0 disables caching).
+    ///
+    /// Other keyword arguments are default options of `tex2typst` and `typst2tex`,
+    /// including `trim_input` and `max_input_length`. `config` is a dict of the same
+    /// keyword arguments, as returned by `__reduce__`; explicit keywords override it.
+    #[new]
+    #[pyo3(signature = (config=None, *, cache_size=None, **options))]
+    fn new(
+        py: Python<'_>,
+        config: Option<&Bound<PyDict>>,
+        cache_size: Option<usize>,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<Self> {
+        Self::with_bundle(py, None, config, cache_size, options)
+    }
+
+    /// Create a converter running the bundle file at `js_path` instead of the
+    /// active bundle, e.g. a development build, in a fresh JS context.
+    ///
+    /// The registered JS extensions run after it. Other arguments are those of the
+    /// constructor. Pickled copies load the file again.
+    ///
+    /// Raises:
+    ///     FileNotFoundError: If the bundle file does not exist
+    ///     EngineError: If the bundle fails to evaluate or does not define the global
+    ///         `tex2typst` and `typst2tex` functions
+    #[classmethod]
+    #[pyo3(signature = (js_path, config=None, *, cache_size=None, **options))]
+    fn from_js_file(
+        cls: &Bound<'_, PyType>,
+        js_path: std::path::PathBuf,
+        config: Option<&Bound<PyDict>>,
+        cache_size: Option<usize>,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<Self> {
+        Self::with_bundle(cls.py(), Some(js_path), config, cache_size, options)
+    }
+
+    /// Convert LaTeX/TeX math to Typst, reusing cached results.
+    ///
+    /// Accepts the same keyword options as `tex2typst`.
+    #[pyo3(signature = (tex, **options))]
+    fn tex2typst(
+        &self,
+        py: Python<'_>,
+        tex: String,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<String> {
+        let options = self.call_options(py, "tex2typst", TEX2TYPST_OPTION_NAMES, options)?;
+        check_input_length(&tex, options.max_input_length)?;
+        let tex = if options.trim_input { tex.trim() } else { &tex };
+        self.instance()?.tex2typst_cached(tex, options.js.as_ref())
+    }
+
+    /// Convert Typst math to LaTeX/TeX, reusing cached results.
+    ///
+    /// Accepts the same keyword options as `typst2tex`.
+    #[pyo3(signature = (typst, **options))]
+    fn typst2tex(
+        &self,
+        py: Python<'_>,
+        typst: String,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<String> {
+        let options = self.call_options(py, "typst2tex", TYPST2TEX_OPTION_NAMES, options)?;
+        check_input_length(&typst, options.max_input_length)?;
+        let typst = if options.trim_input {
+            typst.trim()
+        } else {
+            &typst
+        };
+        self.instance()?
+            .typst2tex_cached(typst, options.js.as_ref())
+    }
+
+    /// Lazily convert an iterable of LaTeX/TeX strings, one item per `next()`.
+    ///
+    /// Accepts the same keyword options as `tex2typst`; they are resolved once, not
+    /// per item. Items are pulled from `items` only as results are requested and go
+    /// through the result cache. A failed item raises `ValueError` naming its
+    /// position and ends the iteration.
+    #[pyo3(signature = (items, **options))]
+    fn tex2typst_batch_generator(
+        slf: &Bound<'_, Self>,
+        items: &Bound<'_, PyAny>,
+        options: Option<&Bound<PyDict>>,
+    ) -> PyResult<ConverterBatchGenerator> {
+        let py = slf.py();
+        let converter = slf.borrow();
+        converter.instance()?;
+        let options = converter.call_options(py, "tex2typst", TEX2TYPST_OPTION_NAMES, options)?;
+        Ok(ConverterBatchGenerator {
+            converter: slf.clone().unbind(),
+            items: Some(items.try_iter()?.unbind()),
+            options,
+            position: 0,
+        })
+    }
+
+    /// Cache statistics, like `functools.lru_cache`'s `cache_info()`.
+    ///
+    /// Returns:
+    ///     dict with "hits", "misses", "size" and "capacity"
+    fn cache_info(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let cache = self.instance()?.cache.borrow();
+        let info = PyDict::new(py);
+        info.set_item("hits", cache.hits)?;
+        info.set_item("misses", cache.misses)?;
+        info.set_item("size", cache.entries.len())?;
+        info.set_item("capacity", self.cache_size)?;
+        Ok(info.into_any().unbind())
+    }
+
+    /// Evaluate a converter bundle again in this converter's JS context, without
+    /// restarting the engine, e.g. to pick up a rebuilt bundle during development.
+    ///
+    /// The registered JS extensions run again after it. Globals of the old bundle
+    /// that the new one does not overwrite stay defined. The result cache is
+    /// cleared; construction options are kept.
+    ///
+    /// Args:
+    ///     new_js_path: Path of the bundle file, or None for the embedded bundle
+    ///
+    /// Raises:
+    ///     FileNotFoundError: If the bundle file does not exist
+    ///     EngineError: If the bundle fails to evaluate or does not define the global
+    ///         `tex2typst` and `typst2tex` functions; the converter then keeps
+    ///         converting with the functions it had
+    #[pyo3(signature = (new_js_path=None))]
+    fn reload_js_bundle(&mut self, new_js_path: Option<std::path::PathBuf>) -> PyResult<()> {
+        let bundle = match &new_js_path {
+            Some(path) => Arc::new(Bundle::new(Cow::Owned(read_bundle_file(path)?))),
+            None => EMBEDDED_BUNDLE.clone(),
+        };
+        self.instance()?;
+        let (extensions, digest) = {
+            let extensions = JS_EXTENSIONS.lock().unwrap();
+            (extensions.sources.clone(), extensions.digest.clone())
+        };
+        let instance = self
+            .inner
+            .get_mut()?
+            .as_mut()
+            .expect("instance() checked the converter is open");
+        instance.engine.reload(&bundle.source, &extensions)?;
+        // Disk cache entries of the old bundle no longer apply
+        instance.bundle_sha256 = bundle.sha256.clone();
+        instance.extensions_digest = digest;
+        instance.cache.borrow_mut().clear();
+        self.js_version = instance.js_bundle_version();
+        self.js_path = new_js_path;
+        Ok(())
+    }
+
+    /// Drop the JS runtime and the result cache; later conversions raise `RuntimeError`.
+    ///
+    /// Calling `close` more than once is a no-op.
+    fn close(&mut self) -> PyResult<()> {
+        *self.inner.get_mut()? = None;
+        Ok(())
+    }
+
+    /// Whether `close` has been called
+    #[getter]
+    fn closed(&self) -> PyResult<bool> {
+        Ok(self.inner.get()?.is_none())
+    }
+
+    fn __repr__(&self) -> String {
+        match self.inner.get() {
+            Ok(Some(inner)) => {
+                let cache = inner.cache.borrow();
+                format!(
+                    "<Converter js_version=\"{}\" cache_hits={} cache_misses={}>",
+                    self.js_version, cache.hits, cache.misses
+                )
+            }
+            Ok(None) => format!("<Converter js_version=\"{}\" closed>", self.js_version),
+            // Cache statistics live with the engine on its own thread
+            Err(_) => format!("<Converter js_version=\"{}\">", self.js_version),
+        }
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    /// Pickle the construction options only; unpickling starts a fresh JS engine
+    /// with an empty cache, so converters can be sent to `multiprocessing` workers.
+    /// A converter running a bundle file loads the file again.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyTuple>)> {
+        let py = slf.py();
+        let converter = slf.borrow();
+        let config = converter.defaults.bind(py).copy()?;
+        config.set_item("cache_size", converter.cache_size)?;
+        let cls = py.get_type::<Self>();
+        match &converter.js_path {
+            Some(path) => Ok((
+                cls.getattr("from_js_file")?,
+                (path, config).into_pyobject(py)?,
+            )),
+            None => Ok((cls.into_any(), (config,).into_pyobject(py)?)),
+        }
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<PyAny>>,
+        _exc_value: Option<&Bound<PyAny>>,
+        _traceback: Option<&Bound<PyAny>>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
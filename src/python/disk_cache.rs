@@ -0,0 +1,239 @@
+//! The process-wide disk cache of conversion results.
+
+use super::*;
+
+/// Default size limit of the disk cache in megabytes
+const DEFAULT_DISK_CACHE_SIZE_MB: f64 = 1024.0;
+
+/// Disk-backed cache of JS conversion results, shared by all threads of the process.
+///
+/// Entries live in append-only `shard-<pid>.log` files in the cache directory, one
+/// `<hex key> <JSON string>` line each. A process only ever appends to its own shard,
+/// forked children included, so concurrent processes cannot corrupt each other's
+/// files; they read all shards on `enable_disk_cache`. A torn last line (from a
+/// process killed mid-write) is skipped.
+pub(super) struct DiskCache {
+    dir: std::path::PathBuf,
+    entries: HashMap<[u8; 32], String>,
+    shard: std::io::BufWriter<std::fs::File>,
+    size_bytes: u64,
+    max_size_bytes: u64,
+    hits: u64,
+    misses: u64,
+}
+
+pub(super) static DISK_CACHE: Mutex<Option<DiskCache>> = Mutex::new(None);
+
+impl DiskCache {
+    fn open(dir: std::path::PathBuf, max_size_bytes: u64) -> std::io::Result<Self> {
+        use std::io::BufRead;
+
+        std::fs::create_dir_all(&dir)?;
+        let mut entries = HashMap::new();
+        let mut size_bytes = 0;
+        let mut shards: Vec<_> = std::fs::read_dir(&dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("shard-") && name.ends_with(".log"))
+            })
+            .collect();
+        shards.sort();
+        for path in shards {
+            let file = std::fs::File::open(&path)?;
+            size_bytes += file.metadata()?.len();
+            for line in std::io::BufReader::new(file).lines() {
+                let Ok(line) = line else { break };
+                if let Some((key, value)) = parse_disk_cache_line(&line) {
+                    entries.insert(key, value);
+                }
+            }
+        }
+        Ok(DiskCache {
+            shard: open_disk_cache_shard(&dir)?,
+            dir,
+            entries,
+            size_bytes,
+            max_size_bytes,
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    /// This cache in the child process after a fork, appending to a shard of its
+    /// own; `None` if that cannot be opened
+    pub(super) fn into_child(self) -> Option<Self> {
+        // Entries still buffered are the parent's to write; flushing them here as
+        // well would write them into its shard twice
+        let _ = self.shard.into_parts();
+        Some(DiskCache {
+            shard: open_disk_cache_shard(&self.dir).ok()?,
+            ..self
+        })
+    }
+
+    /// Append an entry unless the cache has reached its size limit
+    fn insert(&mut self, key: [u8; 32], value: &str) {
+        use std::io::Write;
+
+        if self.entries.contains_key(&key) {
+            return;
+        }
+        let line = format!(
+            "{} {}\n",
+            to_hex(&key),
+            serde_json::Value::String(value.to_string())
+        );
+        if self.size_bytes + line.len() as u64 > self.max_size_bytes {
+            return;
+        }
+        // Entries that cannot be written are simply not cached
+        if self.shard.write_all(line.as_bytes()).is_ok() {
+            self.size_bytes += line.len() as u64;
+            self.entries.insert(key, value.to_string());
+        }
+    }
+}
+
+/// Open this process's shard in the cache directory `dir` for appending
+fn open_disk_cache_shard(
+    dir: &std::path::Path,
+) -> std::io::Result<std::io::BufWriter<std::fs::File>> {
+    let shard = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("shard-{}.log", std::process::id())))?;
+    Ok(std::io::BufWriter::new(shard))
+}
+
+fn parse_disk_cache_line(line: &str) -> Option<([u8; 32], String)> {
+    let (hex, value) = line.split_once(' ')?;
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some((key, serde_json::from_str(value).ok()?))
+}
+
+/// Cache key of one conversion by the bundle with hash `bundle_sha256` and the
+/// extensions with digest `extensions_digest`, or `None` while the disk cache is
+/// disabled
+pub(super) fn disk_cache_key(
+    direction: &str,
+    input: &str,
+    options: Option<&JsOptions>,
+    bundle_sha256: &str,
+    extensions_digest: &str,
+) -> Option<[u8; 32]> {
+    if DISK_CACHE.lock().unwrap().is_none() {
+        return None;
+    }
+    // Sorted by name; the order options are set in does not change the result
+    let options: BTreeMap<_, _> = options.into_iter().flatten().collect();
+    let options = serde_json::to_string(&options).unwrap_or_default();
+    let material = [direction, input, &options, bundle_sha256, extensions_digest].join("\0");
+    Some(sha256(material.as_bytes()))
+}
+
+pub(super) fn disk_cache_get(key: &[u8; 32]) -> Option<String> {
+    let mut cache = DISK_CACHE.lock().unwrap();
+    let cache = cache.as_mut()?;
+    match cache.entries.get(key) {
+        Some(value) => {
+            cache.hits += 1;
+            Some(value.clone())
+        }
+        None => {
+            cache.misses += 1;
+            None
+        }
+    }
+}
+
+pub(super) fn disk_cache_insert(key: [u8; 32], value: &str) {
+    if let Some(cache) = DISK_CACHE.lock().unwrap().as_mut() {
+        cache.insert(key, value);
+    }
+}
+
+/// Write buffered entries to the shard, so other processes see them
+pub(super) fn disk_cache_flush() {
+    use std::io::Write;
+
+    if let Some(cache) = DISK_CACHE.lock().unwrap().as_mut() {
+        let _ = cache.shard.flush();
+    }
+}
+
+/// Cache conversion results on disk across processes and runs.
+///
+/// Every JS conversion made by `tex2typst`, `typst2tex`, their batch variants and
+/// `Converter` looks up its result by a SHA-256 of the input, the options and the JS
+/// bundle, and stores it after a successful conversion. Calling this again switches to
+/// the new directory. Once `max_size_mb` is reached no new entries are written.
+///
+/// Args:
+///     path: Cache directory, created if missing
+///     max_size_mb: Size limit of the directory's shard files in megabytes (default: 1024)
+#[pyfunction]
+#[pyo3(signature = (path, max_size_mb=DEFAULT_DISK_CACHE_SIZE_MB))]
+pub(super) fn enable_disk_cache(
+    py: Python<'_>,
+    path: std::path::PathBuf,
+    max_size_mb: f64,
+) -> PyResult<()> {
+    if max_size_mb.is_nan() || max_size_mb < 0.0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "max_size_mb must be a non-negative number, got {}",
+            max_size_mb
+        )));
+    }
+    let max_size_bytes = (max_size_mb * 1024.0 * 1024.0) as u64;
+    let cache = py.detach(|| DiskCache::open(path, max_size_bytes))?;
+    disable_disk_cache();
+    *DISK_CACHE.lock().unwrap() = Some(cache);
+    Ok(())
+}
+
+/// Flush and stop using the disk cache. Does nothing if it is not enabled.
+#[pyfunction]
+pub(super) fn disable_disk_cache() {
+    disk_cache_flush();
+    DISK_CACHE.lock().unwrap().take();
+}
+
+/// Statistics of the disk cache.
+///
+/// Returns:
+///     dict with "enabled", "path", "entries", "size_bytes", "max_size_bytes", "hits"
+///     and "misses"; "path" is None and the counts are 0 while disabled
+#[pyfunction]
+pub(super) fn disk_cache_stats(py: Python<'_>) -> PyResult<Py<PyAny>> {
+    let cache = DISK_CACHE.lock().unwrap();
+    let stats = PyDict::new(py);
+    stats.set_item("enabled", cache.is_some())?;
+    stats.set_item(
+        "path",
+        cache.as_ref().map(|cache| cache.dir.display().to_string()),
+    )?;
+    stats.set_item(
+        "entries",
+        cache.as_ref().map_or(0, |cache| cache.entries.len()),
+    )?;
+    stats.set_item(
+        "size_bytes",
+        cache.as_ref().map_or(0, |cache| cache.size_bytes),
+    )?;
+    stats.set_item(
+        "max_size_bytes",
+        cache.as_ref().map_or(0, |cache| cache.max_size_bytes),
+    )?;
+    stats.set_item("hits", cache.as_ref().map_or(0, |cache| cache.hits))?;
+    stats.set_item("misses", cache.as_ref().map_or(0, |cache| cache.misses))?;
+    Ok(stats.into_any().unbind())
+}
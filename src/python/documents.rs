@@ -0,0 +1,1078 @@
+//! Finding and converting the math of Typst, LaTeX and Markdown documents.
+
+use super::*;
+
+/// Kind of a math segment found in a Typst document
+#[derive(Clone, Copy, PartialEq)]
+enum TypstMathKind {
+    Inline,
+    Display,
+    /// A `math.equation(...)` call, reported but not converted
+    Equation,
+}
+
+impl TypstMathKind {
+    fn name(self) -> &'static str {
+        match self {
+            TypstMathKind::Inline => "inline",
+            TypstMathKind::Display => "display",
+            TypstMathKind::Equation => "math.equation",
+        }
+    }
+}
+
+/// A math segment of a Typst document, as byte offsets including its delimiters
+struct TypstMathSegment {
+    start: usize,
+    end: usize,
+    kind: TypstMathKind,
+    /// Whether the closing `$` was found
+    terminated: bool,
+}
+
+/// Typst keywords whose statement runs to the end of the line
+const TYPST_STATEMENT_KEYWORDS: &[&str] = &[
+    "let", "set", "show", "import", "include", "if", "for", "while", "return",
+];
+
+/// Finds the `$...$` math of Typst markup, skipping escapes, comments, raw text,
+/// strings and code.
+///
+/// Math inside code is left alone, but content blocks (`[...]`) in code are markup
+/// again. All of the syntax is ASCII, so the scan runs over bytes.
+struct TypstDocumentScanner<'a> {
+    src: &'a [u8],
+    pos: usize,
+    /// Depth of math and `math.equation` calls the scan is inside of; math found
+    /// there is part of the enclosing segment
+    hidden: usize,
+    segments: Vec<TypstMathSegment>,
+}
+
+impl<'a> TypstDocumentScanner<'a> {
+    fn scan(text: &'a str) -> Vec<TypstMathSegment> {
+        let mut scanner = TypstDocumentScanner {
+            src: text.as_bytes(),
+            pos: 0,
+            hidden: 0,
+            segments: Vec::new(),
+        };
+        scanner.markup(false);
+        scanner.segments
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn at(&self, prefix: &[u8]) -> bool {
+        self.src[self.pos..].starts_with(prefix)
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.pos = (self.pos + n).min(self.src.len());
+    }
+
+    fn is_ident_start(c: u8) -> bool {
+        c.is_ascii_alphabetic() || c == b'_' || c >= 0x80
+    }
+
+    fn is_ident_continue(c: u8) -> bool {
+        Self::is_ident_start(c) || c.is_ascii_digit() || c == b'-'
+    }
+
+    /// Markup up to the end of the input, or past the `]` closing a content block
+    /// if `nested`
+    fn markup(&mut self, nested: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.skip(2),
+                // `https://` starts a link, not a comment
+                b'/' if self.at(b"//")
+                    && !self.src[..self.pos].ends_with(b"http:")
+                    && !self.src[..self.pos].ends_with(b"https:") =>
+                {
+                    self.line_comment()
+                }
+                b'/' if self.at(b"/*") => self.block_comment(),
+                b'`' => self.raw(),
+                b'$' => self.math(self.hidden == 0),
+                b'#' => {
+                    self.skip(1);
+                    self.embedded();
+                }
+                b'[' => {
+                    self.skip(1);
+                    self.markup(true);
+                }
+                b']' if nested => {
+                    self.skip(1);
+                    return;
+                }
+                _ => self.skip(1),
+            }
+        }
+    }
+
+    /// Math starting at the current `$`, recorded as a segment if `record`
+    fn math(&mut self, record: bool) {
+        let start = self.pos;
+        self.skip(1);
+        self.hidden += 1;
+        let mut terminated = false;
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.skip(2),
+                b'"' => self.string(),
+                b'/' if self.at(b"//") => self.line_comment(),
+                b'/' if self.at(b"/*") => self.block_comment(),
+                b'#' => {
+                    self.skip(1);
+                    self.embedded();
+                }
+                b'$' => {
+                    self.skip(1);
+                    terminated = true;
+                    break;
+                }
+                _ => self.skip(1),
+            }
+        }
+        self.hidden -= 1;
+        if record {
+            let body = &self.src[start + 1..self.pos - usize::from(terminated)];
+            let display = body.first().is_some_and(u8::is_ascii_whitespace)
+                && body.last().is_some_and(u8::is_ascii_whitespace);
+            self.segments.push(TypstMathSegment {
+                start,
+                end: self.pos,
+                kind: if display {
+                    TypstMathKind::Display
+                } else {
+                    TypstMathKind::Inline
+                },
+                terminated,
+            });
+        }
+    }
+
+    /// The code expression after a `#` in markup or math
+    fn embedded(&mut self) {
+        let Some(c) = self.peek() else {
+            return;
+        };
+        match c {
+            b'{' => {
+                self.skip(1);
+                self.code(b'}');
+            }
+            b'(' => {
+                self.skip(1);
+                self.code(b')');
+            }
+            b'[' => {
+                self.skip(1);
+                self.markup(true);
+            }
+            b'"' => self.string(),
+            c if Self::is_ident_start(c) => {
+                let start = self.pos - 1;
+                let path = self.ident_path();
+                if TYPST_STATEMENT_KEYWORDS.contains(&path) {
+                    self.statement();
+                } else if path == "math.equation" && self.peek() == Some(b'(') {
+                    self.equation(start);
+                } else {
+                    self.postfix();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A dotted identifier such as `math.equation`
+    fn ident_path(&mut self) -> &'a str {
+        let start = self.pos;
+        loop {
+            while self.peek().is_some_and(Self::is_ident_continue) {
+                self.skip(1);
+            }
+            match self.src.get(self.pos..self.pos + 2) {
+                Some([b'.', next]) if Self::is_ident_start(*next) => self.skip(1),
+                _ => break,
+            }
+        }
+        // Both ends sit next to ASCII bytes, so they are char boundaries
+        std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default()
+    }
+
+    /// Arguments, trailing content blocks and method calls after an identifier
+    fn postfix(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b'(') => {
+                    self.skip(1);
+                    self.code(b')');
+                }
+                Some(b'[') => {
+                    self.skip(1);
+                    self.markup(true);
+                }
+                Some(b'.')
+                    if self
+                        .src
+                        .get(self.pos + 1)
+                        .is_some_and(|&c| Self::is_ident_start(c)) =>
+                {
+                    self.skip(1);
+                    self.ident_path();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// A `math.equation(...)` call starting at `start`, reported as skipped
+    fn equation(&mut self, start: usize) {
+        let record = self.hidden == 0;
+        self.hidden += 1;
+        self.postfix();
+        self.hidden -= 1;
+        if record {
+            self.segments.push(TypstMathSegment {
+                start,
+                end: self.pos,
+                kind: TypstMathKind::Equation,
+                terminated: true,
+            });
+        }
+    }
+
+    /// A keyword statement, up to the end of its line
+    fn statement(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == b'\n' || c == b';' {
+                return;
+            }
+            self.code_token();
+        }
+    }
+
+    /// Code up to the end of the input or past `close`
+    fn code(&mut self, close: u8) {
+        while let Some(c) = self.peek() {
+            if c == close {
+                self.skip(1);
+                return;
+            }
+            self.code_token();
+        }
+    }
+
+    fn code_token(&mut self) {
+        let Some(c) = self.peek() else {
+            return;
+        };
+        match c {
+            b'"' => self.string(),
+            b'/' if self.at(b"//") => self.line_comment(),
+            b'/' if self.at(b"/*") => self.block_comment(),
+            b'`' => self.raw(),
+            b'$' => self.math(false),
+            b'(' => {
+                self.skip(1);
+                self.code(b')');
+            }
+            b'{' => {
+                self.skip(1);
+                self.code(b'}');
+            }
+            b'[' => {
+                self.skip(1);
+                self.markup(true);
+            }
+            c if Self::is_ident_start(c) => {
+                let start = self.pos;
+                if self.ident_path() == "math.equation" && self.peek() == Some(b'(') {
+                    self.equation(start);
+                }
+            }
+            _ => self.skip(1),
+        }
+    }
+
+    fn string(&mut self) {
+        self.skip(1);
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.skip(2),
+                b'"' => {
+                    self.skip(1);
+                    return;
+                }
+                _ => self.skip(1),
+            }
+        }
+    }
+
+    fn line_comment(&mut self) {
+        while self.peek().is_some_and(|c| c != b'\n') {
+            self.skip(1);
+        }
+    }
+
+    /// A `/* ... */` comment; these nest
+    fn block_comment(&mut self) {
+        let mut depth = 0;
+        while self.pos < self.src.len() {
+            if self.at(b"/*") {
+                depth += 1;
+                self.skip(2);
+            } else if self.at(b"*/") {
+                depth -= 1;
+                self.skip(2);
+                if depth == 0 {
+                    return;
+                }
+            } else {
+                self.skip(1);
+            }
+        }
+    }
+
+    /// Raw text: `` `...` `` or a block fenced by three or more backticks
+    fn raw(&mut self) {
+        let fence = self.src[self.pos..]
+            .iter()
+            .take_while(|&&c| c == b'`')
+            .count();
+        self.skip(fence);
+        if fence == 2 {
+            // Empty raw text
+            return;
+        }
+        while self.pos < self.src.len() {
+            let run = self.src[self.pos..]
+                .iter()
+                .take_while(|&&c| c == b'`')
+                .count();
+            if run >= fence {
+                self.skip(run);
+                return;
+            }
+            self.skip(run.max(1));
+        }
+    }
+}
+
+/// Math environments `convert_latex_document` converts, reported as `env:NAME` spans
+const LATEX_MATH_ENVIRONMENTS: &[&str] = &[
+    "equation",
+    "equation*",
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "flalign",
+    "flalign*",
+    "gather",
+    "gather*",
+    "multline",
+    "multline*",
+    "eqnarray",
+    "eqnarray*",
+    "math",
+    "displaymath",
+];
+
+/// Environments whose contents `convert_latex_document` leaves alone
+const LATEX_VERBATIM_ENVIRONMENTS: &[&str] =
+    &["verbatim", "verbatim*", "lstlisting", "minted", "comment"];
+
+/// A math segment of a LaTeX or Markdown document, as byte offsets
+struct TexMathSegment {
+    /// Offset of the opening delimiter
+    start: usize,
+    /// Offset just past the closing delimiter
+    end: usize,
+    /// The math between the delimiters
+    body: std::ops::Range<usize>,
+    /// "inline", "display" or "env:NAME"
+    kind: String,
+    /// Whether the closing delimiter was found
+    terminated: bool,
+}
+
+impl TexMathSegment {
+    /// Whether the segment becomes block math
+    fn display(&self) -> bool {
+        self.kind != "inline"
+    }
+
+    /// The TeX to convert: the math between the delimiters, with the rows of a
+    /// multi-line environment in an `aligned` block, which the converter knows
+    fn tex<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut body = &text[self.body.clone()];
+        if self.kind.starts_with("env:alignat") {
+            // The column count, `\begin{alignat}{2}`
+            let trimmed = body.trim_start();
+            let mut cursor = TexCursor::new(trimmed);
+            if cursor.read_group().is_some() {
+                body = &trimmed[cursor.pos..];
+            }
+        }
+        if self.kind.starts_with("env:") && (body.contains('&') || body.contains("\\\\")) {
+            Cow::Owned(format!("\\begin{{aligned}}{}\\end{{aligned}}", body))
+        } else {
+            Cow::Borrowed(body.trim())
+        }
+    }
+}
+
+/// Finds the math of a LaTeX or Markdown document.
+///
+/// LaTeX math is `$...$`, `$$...$$`, `\(...\)`, `\[...\]` and the environments of
+/// [`LATEX_MATH_ENVIRONMENTS`]; comments, `\verb` and verbatim environments are
+/// skipped. Markdown math is `$...$` and `$$...$$`, skipping code spans and fenced
+/// code blocks. All of the syntax is ASCII, so the scan runs over bytes.
+struct TexDocumentScanner<'a> {
+    src: &'a [u8],
+    pos: usize,
+    /// Whether `%` starts a comment, as in LaTeX
+    comments: bool,
+    segments: Vec<TexMathSegment>,
+}
+
+impl<'a> TexDocumentScanner<'a> {
+    fn new(text: &'a str, comments: bool) -> Self {
+        TexDocumentScanner {
+            src: text.as_bytes(),
+            pos: 0,
+            comments,
+            segments: Vec::new(),
+        }
+    }
+
+    fn scan_latex(text: &'a str) -> Vec<TexMathSegment> {
+        let mut scanner = Self::new(text, true);
+        while let Some(c) = scanner.peek() {
+            match c {
+                b'%' => scanner.line_comment(),
+                b'\\' if scanner.at(b"\\(") => scanner.math(2, b"\\)", "inline"),
+                b'\\' if scanner.at(b"\\[") => scanner.math(2, b"\\]", "display"),
+                b'\\' if scanner.at(b"\\begin{") => scanner.environment(),
+                b'\\'
+                    if scanner.at(b"\\verb")
+                        && !scanner
+                            .src
+                            .get(scanner.pos + "\\verb".len())
+                            .is_some_and(u8::is_ascii_alphabetic) =>
+                {
+                    scanner.verb()
+                }
+                b'\\' => scanner.skip(2),
+                b'$' if scanner.at(b"$$") => scanner.math(2, b"$$", "display"),
+                b'$' => scanner.math(1, b"$", "inline"),
+                _ => scanner.skip(1),
+            }
+        }
+        scanner.segments
+    }
+
+    fn scan_markdown(text: &'a str) -> Vec<TexMathSegment> {
+        let mut scanner = Self::new(text, false);
+        while let Some(c) = scanner.peek() {
+            match c {
+                b'\\' => scanner.skip(2),
+                b'`' | b'~' if scanner.fence_length().is_some() => scanner.fenced_code(),
+                b'`' => scanner.code_span(),
+                b'$' if scanner.at(b"$$") => scanner.math(2, b"$$", "display"),
+                b'$' => scanner.inline_dollar(),
+                _ => scanner.skip(1),
+            }
+        }
+        scanner.segments
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn at(&self, prefix: &[u8]) -> bool {
+        self.src[self.pos..].starts_with(prefix)
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.pos = (self.pos + n).min(self.src.len());
+    }
+
+    /// Skip to the end of the line, keeping the line break
+    fn line_comment(&mut self) {
+        while self.peek().is_some_and(|c| c != b'\n') {
+            self.skip(1);
+        }
+    }
+
+    /// Math opened by the `open` bytes at the current position and closed by `close`
+    fn math(&mut self, open: usize, close: &[u8], kind: &str) {
+        let start = self.pos;
+        self.skip(open);
+        let body_start = self.pos;
+        let mut body_end = self.src.len();
+        let mut terminated = false;
+        while let Some(c) = self.peek() {
+            if self.at(close) {
+                body_end = self.pos;
+                self.skip(close.len());
+                terminated = true;
+                break;
+            }
+            match c {
+                b'\\' => self.skip(2),
+                b'%' if self.comments => self.line_comment(),
+                _ => self.skip(1),
+            }
+        }
+        self.segments.push(TexMathSegment {
+            start,
+            end: self.pos,
+            body: body_start..body_end.min(self.pos),
+            kind: kind.to_string(),
+            terminated,
+        });
+    }
+
+    /// The environment started by the `\begin{` at the current position
+    fn environment(&mut self) {
+        let open = self.pos + "\\begin{".len();
+        let Some(length) = self.src[open..].iter().position(|&c| c == b'}') else {
+            self.skip(1);
+            return;
+        };
+        let name = String::from_utf8_lossy(&self.src[open..open + length]).into_owned();
+        let begin = "\\begin{".len() + length + 1;
+        let close = format!("\\end{{{}}}", name);
+        if LATEX_MATH_ENVIRONMENTS.contains(&name.as_str()) {
+            self.math(begin, close.as_bytes(), &format!("env:{}", name));
+        } else if LATEX_VERBATIM_ENVIRONMENTS.contains(&name.as_str()) {
+            self.skip(begin);
+            while self.pos < self.src.len() && !self.at(close.as_bytes()) {
+                self.skip(1);
+            }
+            self.skip(close.len());
+        } else {
+            self.skip(begin);
+        }
+    }
+
+    /// `\verb|...|`, delimited by the character after `\verb` or `\verb*`
+    fn verb(&mut self) {
+        self.skip("\\verb".len());
+        if self.peek() == Some(b'*') {
+            self.skip(1);
+        }
+        let Some(delimiter) = self.peek() else {
+            return;
+        };
+        self.skip(1);
+        while let Some(c) = self.peek() {
+            self.skip(1);
+            if c == delimiter || c == b'\n' {
+                break;
+            }
+        }
+    }
+
+    /// Length of the code fence at the current position: three or more backticks or
+    /// tildes starting a line, after at most three spaces
+    fn fence_length(&self) -> Option<usize> {
+        let line_start = self.src[..self.pos]
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        let indent = &self.src[line_start..self.pos];
+        if indent.len() > 3 || indent.iter().any(|&c| c != b' ') {
+            return None;
+        }
+        let c = self.src[self.pos];
+        let length = self.src[self.pos..].iter().take_while(|&&b| b == c).count();
+        (length >= 3).then_some(length)
+    }
+
+    /// A fenced code block, up to the line closing it with a fence at least as long
+    fn fenced_code(&mut self) {
+        let fence = self.src[self.pos];
+        let length = self.fence_length().unwrap_or(3);
+        self.line_comment();
+        while self.peek().is_some() {
+            self.skip(1);
+            let line = &self.src[self.pos..];
+            let indent = line.iter().take_while(|&&c| c == b' ').count();
+            let run = line[indent..].iter().take_while(|&&c| c == fence).count();
+            self.line_comment();
+            if indent <= 3 && run >= length {
+                return;
+            }
+        }
+    }
+
+    /// A code span, closed by a backtick run of the same length; an unmatched run
+    /// is literal text
+    fn code_span(&mut self) {
+        let length = self.src[self.pos..]
+            .iter()
+            .take_while(|&&c| c == b'`')
+            .count();
+        self.skip(length);
+        let mut end = self.pos;
+        while end < self.src.len() {
+            let run = self.src[end..].iter().take_while(|&&c| c == b'`').count();
+            if run == length {
+                self.pos = end + run;
+                return;
+            }
+            end += run.max(1);
+        }
+    }
+
+    /// Inline `$...$` math, following Pandoc: the opening `$` must not be followed by
+    /// whitespace, and the closing one neither preceded by whitespace nor followed by
+    /// a digit, so prices such as `$5 and $6` stay text. The math ends at a blank
+    /// line.
+    fn inline_dollar(&mut self) {
+        let start = self.pos;
+        if self
+            .src
+            .get(start + 1)
+            .is_none_or(|c| c.is_ascii_whitespace())
+        {
+            self.skip(1);
+            return;
+        }
+        let mut end = start + 1;
+        while end < self.src.len() {
+            match self.src[end] {
+                b'\\' => end += 2,
+                b'\n'
+                    if self.src[end + 1..]
+                        .iter()
+                        .find(|c| !matches!(c, b' ' | b'\t' | b'\r'))
+                        .is_none_or(|&c| c == b'\n') =>
+                {
+                    break;
+                }
+                b'$' if !self.src[end - 1].is_ascii_whitespace()
+                    && !self.src.get(end + 1).is_some_and(u8::is_ascii_digit) =>
+                {
+                    self.pos = end + 1;
+                    self.segments.push(TexMathSegment {
+                        start,
+                        end: self.pos,
+                        body: start + 1..end,
+                        kind: "inline".to_string(),
+                        terminated: true,
+                    });
+                    return;
+                }
+                _ => end += 1,
+            }
+        }
+        self.skip(1);
+    }
+}
+
+/// One math segment of a converted document
+#[pyclass(frozen, get_all, skip_from_py_object, module = "tex2typst")]
+#[derive(Clone)]
+pub(super) struct Span {
+    /// Byte offset of the segment in the input, delimiters included
+    start: usize,
+    /// Byte offset just past the segment
+    end: usize,
+    /// "inline", "display", "env:NAME" for a LaTeX environment or "math.equation"
+    /// (reported, not converted)
+    kind: String,
+    /// Input text of the segment
+    original: String,
+    /// Replacement text of the segment, `None` if it was not converted
+    converted: Option<String>,
+    /// Why the segment failed to convert
+    error: Option<String>,
+}
+
+#[pymethods]
+impl Span {
+    fn __repr__(&self) -> String {
+        format!(
+            "Span(start={}, end={}, kind={:?}, original={:?}, converted={}, error={})",
+            self.start,
+            self.end,
+            self.kind,
+            self.original,
+            match &self.converted {
+                Some(converted) => format!("{:?}", converted),
+                None => "None".to_string(),
+            },
+            match &self.error {
+                Some(error) => format!("{:?}", error),
+                None => "None".to_string(),
+            }
+        )
+    }
+}
+
+/// Output of a document conversion with every math segment found in the input
+#[pyclass(frozen, get_all, module = "tex2typst")]
+pub(super) struct DocumentReport {
+    /// Converted document, `None` with `dry_run=True`
+    output: Option<String>,
+    /// Math segments in input order
+    spans: Vec<Span>,
+}
+
+#[pymethods]
+impl DocumentReport {
+    fn __repr__(&self) -> String {
+        let failed = self
+            .spans
+            .iter()
+            .filter(|span| span.error.is_some())
+            .count();
+        format!(
+            "DocumentReport(spans={}, failed={})",
+            self.spans.len(),
+            failed
+        )
+    }
+}
+
+/// Accepted values for `delimiters` of `convert_typst_document`
+const DOCUMENT_DELIMITERS: &[&str] = &["latex", "dollars"];
+
+/// Accepted values for the `errors` policy of the document converters
+const DOCUMENT_ERROR_POLICIES: &[&str] = &["raise", "keep"];
+
+/// Convert the math of a Typst document to LaTeX, leaving the markup around it.
+///
+/// `$...$` is display math if there is whitespace after the opening and before the
+/// closing `$`, as in Typst, and inline math otherwise. Escaped `\$` and dollars in
+/// raw text, strings, comments and code are skipped; content blocks (`[...]`) in
+/// code are markup and converted. `math.equation(...)` calls are not converted but
+/// reported, as `"math.equation"` spans or with a `UserWarning`.
+///
+/// Args:
+///     text: Typst document
+///     delimiters: "latex" emits `\(...\)` and `\[...\]`, "dollars" `$...$` and
+///         `$$...$$` (default: "latex")
+///     errors: Failure policy: "raise" stops with ValueError naming the byte offset,
+///         "keep" leaves the Typst math unchanged (default: "raise")
+///     report: Return a `DocumentReport` instead of the string (default: False)
+///     dry_run: Return a `DocumentReport` without building the output (default: False)
+///     Remaining keyword arguments are the `typst2tex` conversion options.
+///
+/// Returns:
+///     The converted document, or a `DocumentReport`
+#[pyfunction]
+#[pyo3(signature = (text, *, delimiters="latex".to_string(), errors="raise".to_string(), report=false, dry_run=false, custom_typst_macros=None, js_options=None))]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn convert_typst_document(
+    py: Python<'_>,
+    text: &str,
+    delimiters: String,
+    errors: String,
+    report: bool,
+    dry_run: bool,
+    custom_typst_macros: Option<&Bound<PyDict>>,
+    js_options: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    check_choice("delimiters", &delimiters, DOCUMENT_DELIMITERS)?;
+    check_choice("errors", &errors, DOCUMENT_ERROR_POLICIES)?;
+    let mode_options = |block_math_mode| {
+        let opts = build_typst2tex_options(Some(block_math_mode), custom_typst_macros)?;
+        with_js_options(opts, js_options, TYPST2TEX_OPTION_NAMES)
+    };
+    let inline_options = mode_options(false)?;
+    let display_options = mode_options(true)?;
+
+    let segments = TypstDocumentScanner::scan(text);
+    let spans = with_thread_converter(false, |converter| {
+        let mut spans = Vec::with_capacity(segments.len());
+        for segment in &segments {
+            let original = &text[segment.start..segment.end];
+            // Failures carry the converter's exception, if there was one
+            let result = match segment.kind {
+                TypstMathKind::Equation => None,
+                _ if !segment.terminated => Some(Err(("Unterminated math".to_string(), None))),
+                kind => {
+                    let body = original[1..original.len() - 1].trim();
+                    let options = if kind == TypstMathKind::Display {
+                        display_options.as_ref()
+                    } else {
+                        inline_options.as_ref()
+                    };
+                    Some(
+                        converter
+                            .typst2tex(body, options)
+                            .map(|tex| {
+                                let display = kind == TypstMathKind::Display;
+                                wrap_tex_math(tex, Some(&delimiters), display, false)
+                            })
+                            .map_err(|e| (e.value(py).to_string(), Some(e))),
+                    )
+                }
+            };
+            let (converted, error) = document_span_result(py, segment.start, &errors, result)?;
+            spans.push(Span {
+                start: segment.start,
+                end: segment.end,
+                kind: segment.kind.name().to_string(),
+                original: original.to_string(),
+                converted,
+                error,
+            });
+        }
+        Ok(spans)
+    })?;
+
+    let output = (!dry_run).then(|| document_output(text, &spans));
+    if report || dry_run {
+        return Ok(Py::new(py, DocumentReport { output, spans })?.into_any());
+    }
+    let skipped: Vec<String> = spans
+        .iter()
+        .filter(|span| span.kind == TypstMathKind::Equation.name())
+        .map(|span| span.start.to_string())
+        .collect();
+    if !skipped.is_empty() {
+        warn_user(
+            py,
+            &format!(
+                "math.equation calls are not converted; left unchanged at bytes {}",
+                skipped.join(", ")
+            ),
+        )?;
+    }
+    Ok(output
+        .expect("output is built unless dry_run")
+        .into_pyobject(py)?
+        .into_any()
+        .unbind())
+}
+
+/// Converted text and error of a document span under an `errors` policy: a failure,
+/// with the converter's exception if there was one, raises with "raise" and is
+/// recorded with "keep"
+fn document_span_result(
+    py: Python<'_>,
+    start: usize,
+    errors: &str,
+    result: Option<Result<String, (String, Option<PyErr>)>>,
+) -> PyResult<(Option<String>, Option<String>)> {
+    match result {
+        Some(Ok(converted)) => Ok((Some(converted), None)),
+        Some(Err((message, cause))) if errors == "raise" => {
+            let err = PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "math at byte {}: {}",
+                start, message
+            ));
+            err.set_cause(py, cause);
+            Err(err)
+        }
+        Some(Err((message, _))) => Ok((None, Some(message))),
+        None => Ok((None, None)),
+    }
+}
+
+/// `text` with every converted span replaced; failed spans keep their original text
+fn document_output(text: &str, spans: &[Span]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in spans {
+        output.push_str(&text[last..span.start]);
+        output.push_str(span.converted.as_deref().unwrap_or(&span.original));
+        last = span.end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+/// Shared implementation of `convert_latex_document` and `convert_markdown`
+fn convert_tex_document(
+    py: Python<'_>,
+    text: &str,
+    segments: &[TexMathSegment],
+    errors: &str,
+    report: bool,
+    dry_run: bool,
+    opts: Option<&JsOptions>,
+) -> PyResult<Py<PyAny>> {
+    let spans = with_thread_converter(false, |converter| {
+        let mut spans = Vec::with_capacity(segments.len());
+        for segment in segments {
+            let result = if segment.terminated {
+                converter
+                    .tex2typst(&segment.tex(text), opts)
+                    .map(|typst| {
+                        wrap_typst_math(typst, if segment.display() { "block" } else { "inline" })
+                    })
+                    .map_err(|e| (e.value(py).to_string(), Some(e)))
+            } else {
+                Err(("Unterminated math".to_string(), None))
+            };
+            let (converted, error) = document_span_result(py, segment.start, errors, Some(result))?;
+            spans.push(Span {
+                start: segment.start,
+                end: segment.end,
+                kind: segment.kind.clone(),
+                original: text[segment.start..segment.end].to_string(),
+                converted,
+                error,
+            });
+        }
+        Ok(spans)
+    })?;
+    let output = (!dry_run).then(|| document_output(text, &spans));
+    if report || dry_run {
+        return Ok(Py::new(py, DocumentReport { output, spans })?.into_any());
+    }
+    Ok(output
+        .expect("output is built unless dry_run")
+        .into_pyobject(py)?
+        .into_any()
+        .unbind())
+}
+
+/// Convert the math of a LaTeX document to Typst, leaving the markup around it.
+///
+/// Math is `$...$`, `\(...\)` (inline), `$$...$$`, `\[...\]` (display) and the
+/// `equation`, `align`, `alignat`, `flalign`, `gather`, `multline`, `eqnarray`,
+/// `math` and `displaymath` environments, starred or not (`"env:NAME"` spans).
+/// Multi-line environments become an `aligned` block. Escapes, `%` comments,
+/// `\verb` and the `verbatim`, `lstlisting`, `minted` and `comment` environments
+/// are skipped. Inline math becomes `$...$` and the rest `$ ... $`.
+///
+/// Args:
+///     text: LaTeX document
+///     errors: Failure policy: "raise" stops with ValueError naming the byte offset,
+///         "keep" leaves the LaTeX math unchanged (default: "raise")
+///     report: Return a `DocumentReport` instead of the string (default: False)
+///     dry_run: Return a `DocumentReport` without building the output (default: False)
+///     Remaining keyword arguments are the `tex2typst` conversion options.
+///
+/// Returns:
+///     The converted document, or a `DocumentReport`
+#[pyfunction]
+#[pyo3(signature = (text, *, errors="raise".to_string(), report=false, dry_run=false, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, js_options=None))]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn convert_latex_document(
+    py: Python<'_>,
+    text: &str,
+    errors: String,
+    report: bool,
+    dry_run: bool,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    js_options: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    check_choice("errors", &errors, DOCUMENT_ERROR_POLICIES)?;
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        js_options,
+    )?;
+    let segments = TexDocumentScanner::scan_latex(text);
+    convert_tex_document(py, text, &segments, &errors, report, dry_run, opts.as_ref())
+}
+
+/// Convert the math of a Markdown document to Typst, leaving the markup around it.
+///
+/// Math is `$...$` (inline) and `$$...$$` (display), with Pandoc's rules for
+/// inline math: no whitespace after the opening or before the closing `$`, no
+/// digit after the closing one and no blank line inside, so amounts such as
+/// `$5 and $6` stay text. Escaped `\$`, code spans and fenced code blocks are
+/// skipped. Inline math becomes `$...$` and display math `$ ... $`.
+///
+/// Args:
+///     text: Markdown document
+///     errors: Failure policy: "raise" stops with ValueError naming the byte offset,
+///         "keep" leaves the LaTeX math unchanged (default: "raise")
+///     report: Return a `DocumentReport` instead of the string (default: False)
+///     dry_run: Return a `DocumentReport` without building the output (default: False)
+///     Remaining keyword arguments are the `tex2typst` conversion options.
+///
+/// Returns:
+///     The converted document, or a `DocumentReport`
+#[pyfunction]
+#[pyo3(signature = (text, *, errors="raise".to_string(), report=false, dry_run=false, non_strict=None, prefer_shorthands=None, keep_spaces=None, frac_to_slash=None, infty_to_oo=None, optimize=None, ignore_unknown_commands=None, handle_physics=None, output_unicode=None, handle_text_mode=None, custom_tex_macros=None, replace_unknown_commands=None, symbol_map=None, environment_map=None, js_options=None))]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn convert_markdown(
+    py: Python<'_>,
+    text: &str,
+    errors: String,
+    report: bool,
+    dry_run: bool,
+    non_strict: Option<bool>,
+    prefer_shorthands: Option<bool>,
+    keep_spaces: Option<bool>,
+    frac_to_slash: Option<bool>,
+    infty_to_oo: Option<bool>,
+    optimize: Option<bool>,
+    ignore_unknown_commands: Option<bool>,
+    handle_physics: Option<bool>,
+    output_unicode: Option<bool>,
+    handle_text_mode: Option<String>,
+    custom_tex_macros: Option<&Bound<PyDict>>,
+    replace_unknown_commands: Option<&Bound<PyDict>>,
+    symbol_map: Option<&Bound<PyDict>>,
+    environment_map: Option<&Bound<PyDict>>,
+    js_options: Option<&Bound<PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    check_choice("errors", &errors, DOCUMENT_ERROR_POLICIES)?;
+    let opts = build_tex2typst_options(
+        &tex2typst_options(
+            non_strict,
+            prefer_shorthands,
+            keep_spaces,
+            frac_to_slash,
+            infty_to_oo,
+            optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
+            custom_tex_macros,
+            replace_unknown_commands,
+            symbol_map,
+            environment_map,
+        )?,
+        js_options,
+    )?;
+    let segments = TexDocumentScanner::scan_markdown(text);
+    convert_tex_document(py, text, &segments, &errors, report, dry_run, opts.as_ref())
+}
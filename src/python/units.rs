@@ -0,0 +1,757 @@
+//! `\ce` (mhchem) and siunitx expansion ahead of conversion.
+
+use super::*;
+
+/// mhchem reaction arrows and the TeX they are written as
+const CE_ARROWS: &[(&str, &str)] = &[
+    ("->", "\\rightarrow"),
+    ("<-", "\\leftarrow"),
+    ("<->", "\\leftrightarrow"),
+    ("<=>", "\\rightleftharpoons"),
+    ("=", "="),
+];
+
+/// Physical state annotations written after a formula
+const CE_STATES: &[&str] = &["(aq)", "(s)", "(l)", "(g)"];
+
+fn unsupported_ce(fragment: &str, content: &str) -> String {
+    format!(
+        "Unsupported \\ce construct '{}' in \\ce{{{}}}",
+        fragment, content
+    )
+}
+
+/// Translate one whitespace-separated `\ce` term such as `2H2O`, `SO4^2-` or `Fe^{3+}(aq)`
+fn translate_ce_formula(term: &str, content: &str) -> Result<String, String> {
+    let bytes = term.as_bytes();
+    let mut tex = String::new();
+    let mut i = 0;
+    // Digits right after an atom or group are subscripts, elsewhere coefficients
+    let mut after_atom = false;
+    while i < bytes.len() {
+        let rest = &term[i..];
+        let c = bytes[i];
+        if let Some(state) = CE_STATES.iter().find(|state| rest.starts_with(**state)) {
+            tex.push_str(&format!("\\text{{{}}}", state));
+            i += state.len();
+            after_atom = false;
+        } else if c.is_ascii_alphabetic() {
+            let len = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+            tex.push_str(&format!("\\text{{{}}}", &rest[..len]));
+            i += len;
+            after_atom = true;
+        } else if c.is_ascii_digit() {
+            let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if after_atom {
+                tex.push_str(&format!("_{{{}}}", &rest[..len]));
+            } else {
+                tex.push_str(&rest[..len]);
+                tex.push(' ');
+            }
+            i += len;
+        } else if c == b'(' || c == b')' {
+            tex.push(c as char);
+            i += 1;
+            after_atom = c == b')';
+        } else if c == b'*' && i > 0 {
+            tex.push_str(" \\cdot ");
+            i += 1;
+            after_atom = false;
+        } else if c == b'^' && after_atom {
+            let charge = match rest[1..].strip_prefix('{') {
+                Some(braced) => braced.find('}').map(|end| (&braced[..end], end + 3)),
+                None => {
+                    let len = rest[1..]
+                        .bytes()
+                        .take_while(|b| b.is_ascii_digit() || *b == b'+' || *b == b'-')
+                        .count();
+                    Some((&rest[1..1 + len], len + 1))
+                }
+            };
+            match charge {
+                Some((charge, len))
+                    if !charge.is_empty()
+                        && charge
+                            .bytes()
+                            .all(|b| b.is_ascii_digit() || b == b'+' || b == b'-') =>
+                {
+                    tex.push_str(&format!("^{{{}}}", charge));
+                    i += len;
+                }
+                _ => return Err(unsupported_ce(rest, content)),
+            }
+            after_atom = false;
+        } else if (c == b'+' || c == b'-')
+            && after_atom
+            && rest.bytes().all(|b| b == b'+' || b == b'-')
+        {
+            // A trailing `+` or `-` is a charge, as in `H+` or `OH-`
+            tex.push_str(&format!("^{{{}}}", rest));
+            i = bytes.len();
+        } else {
+            return Err(unsupported_ce(rest, content));
+        }
+    }
+    Ok(tex)
+}
+
+/// Translate the contents of `\ce{...}` into plain TeX math
+fn translate_ce(content: &str) -> Result<String, String> {
+    let mut terms = Vec::new();
+    for term in content.split_whitespace() {
+        if let Some((_, arrow)) = CE_ARROWS.iter().find(|(arrow, _)| *arrow == term) {
+            terms.push(arrow.to_string());
+        } else if CE_ARROWS.iter().any(|(arrow, _)| term.starts_with(arrow)) {
+            // Labelled arrows like `->[heat]`
+            return Err(unsupported_ce(term, content));
+        } else {
+            terms.push(match term {
+                "+" => "+".to_string(),
+                "^" => "\\uparrow".to_string(),
+                "v" => "\\downarrow".to_string(),
+                _ => translate_ce_formula(term, content)?,
+            });
+        }
+    }
+    Ok(terms.join(" "))
+}
+
+/// Rewrite every `\ce{...}` in `tex` into plain TeX math, leaving the rest untouched
+pub(super) fn expand_chemistry(tex: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(tex.len());
+    let mut cursor = TexCursor::new(tex);
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            expanded.push(c);
+            cursor.bump();
+            continue;
+        }
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        if command != "\\ce" {
+            expanded.push_str(command);
+            continue;
+        }
+        cursor.skip_whitespace();
+        let content = cursor
+            .read_group()
+            .ok_or_else(|| "\\ce must be followed by a braced formula".to_string())?;
+        expanded.push('{');
+        expanded.push_str(&translate_ce(content)?);
+        expanded.push('}');
+    }
+    Ok(expanded)
+}
+
+/// Typst output styles for siunitx commands: plain math, or `unify`/`metro` package calls
+pub(super) const UNITS_STYLES: &[&str] = &["plain", "unify", "metro"];
+
+/// Private-use characters that stand in for siunitx output while the JS converter runs
+const SIUNITX_PLACEHOLDERS: std::ops::RangeInclusive<u32> = 0xE000..=0xF8FF;
+
+/// siunitx prefix macros and their symbols
+const SI_PREFIXES: &[(&str, &str)] = &[
+    ("quecto", "q"),
+    ("ronto", "r"),
+    ("yocto", "y"),
+    ("zepto", "z"),
+    ("atto", "a"),
+    ("femto", "f"),
+    ("pico", "p"),
+    ("nano", "n"),
+    ("micro", "µ"),
+    ("milli", "m"),
+    ("centi", "c"),
+    ("deci", "d"),
+    ("deca", "da"),
+    ("deka", "da"),
+    ("hecto", "h"),
+    ("kilo", "k"),
+    ("mega", "M"),
+    ("giga", "G"),
+    ("tera", "T"),
+    ("peta", "P"),
+    ("exa", "E"),
+    ("zetta", "Z"),
+    ("yotta", "Y"),
+    ("ronna", "R"),
+    ("quetta", "Q"),
+];
+
+/// siunitx unit macros, including the common abbreviations, and their symbols
+const SI_UNITS: &[(&str, &str)] = &[
+    ("metre", "m"),
+    ("meter", "m"),
+    ("gram", "g"),
+    ("kilogram", "kg"),
+    ("second", "s"),
+    ("ampere", "A"),
+    ("kelvin", "K"),
+    ("mole", "mol"),
+    ("candela", "cd"),
+    ("radian", "rad"),
+    ("steradian", "sr"),
+    ("hertz", "Hz"),
+    ("newton", "N"),
+    ("pascal", "Pa"),
+    ("joule", "J"),
+    ("watt", "W"),
+    ("coulomb", "C"),
+    ("volt", "V"),
+    ("farad", "F"),
+    ("ohm", "Ω"),
+    ("siemens", "S"),
+    ("weber", "Wb"),
+    ("tesla", "T"),
+    ("henry", "H"),
+    ("degreeCelsius", "°C"),
+    ("lumen", "lm"),
+    ("lux", "lx"),
+    ("becquerel", "Bq"),
+    ("gray", "Gy"),
+    ("sievert", "Sv"),
+    ("katal", "kat"),
+    ("litre", "L"),
+    ("liter", "L"),
+    ("minute", "min"),
+    ("hour", "h"),
+    ("day", "d"),
+    ("degree", "°"),
+    ("arcminute", "′"),
+    ("arcsecond", "″"),
+    ("hectare", "ha"),
+    ("tonne", "t"),
+    ("electronvolt", "eV"),
+    ("dalton", "Da"),
+    ("astronomicalunit", "au"),
+    ("angstrom", "Å"),
+    ("bar", "bar"),
+    ("bel", "B"),
+    ("decibel", "dB"),
+    ("neper", "Np"),
+    ("percent", "%"),
+    ("m", "m"),
+    ("km", "km"),
+    ("cm", "cm"),
+    ("mm", "mm"),
+    ("um", "µm"),
+    ("nm", "nm"),
+    ("s", "s"),
+    ("ms", "ms"),
+    ("us", "µs"),
+    ("ns", "ns"),
+    ("g", "g"),
+    ("kg", "kg"),
+    ("mg", "mg"),
+    ("Hz", "Hz"),
+    ("kHz", "kHz"),
+    ("MHz", "MHz"),
+    ("GHz", "GHz"),
+    ("N", "N"),
+    ("kN", "kN"),
+    ("J", "J"),
+    ("kJ", "kJ"),
+    ("W", "W"),
+    ("mW", "mW"),
+    ("kW", "kW"),
+    ("V", "V"),
+    ("mV", "mV"),
+    ("kV", "kV"),
+    ("A", "A"),
+    ("mA", "mA"),
+    ("K", "K"),
+    ("mol", "mol"),
+    ("Pa", "Pa"),
+    ("kPa", "kPa"),
+    ("eV", "eV"),
+    ("keV", "keV"),
+    ("MeV", "MeV"),
+    ("GeV", "GeV"),
+    ("L", "L"),
+    ("mL", "mL"),
+];
+
+/// One factor of a siunitx unit, such as `\kilo\metre\squared` or a literal `s^{-1}`
+struct UnitFactor {
+    symbol: String,
+    /// siunitx spelling for `unify`/`metro`, e.g. `kilo metre`
+    words: String,
+    power: Option<String>,
+    per: bool,
+}
+
+/// A number as written in `\num` or `\SI`, e.g. `-1.23(4)e-5`
+struct SiNumber {
+    sign: String,
+    mantissa: String,
+    uncertainty: Option<SiUncertainty>,
+    exponent: Option<String>,
+}
+
+enum SiUncertainty {
+    /// `1.23(4)`: digits in the last places of the mantissa
+    Compact(String),
+    /// `1.23+-0.04` or `1.23 \pm 0.04`
+    PlusMinus(String),
+}
+
+/// Split off a leading run of characters matching `f`
+fn take_while_prefix(s: &str, f: impl Fn(char) -> bool) -> (&str, &str) {
+    let end = s.find(|c: char| !f(c)).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+fn parse_si_number(source: &str) -> Result<SiNumber, String> {
+    let invalid = || format!("Invalid siunitx number '{}'", source);
+    let compact: String = source
+        .replace("\\pm", "+-")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let (sign, rest) = match compact.strip_prefix(['+', '-']) {
+        Some(rest) if !rest.starts_with('-') => (&compact[..1], rest),
+        _ => ("", compact.as_str()),
+    };
+    let (mantissa, mut rest) =
+        take_while_prefix(rest, |c| c.is_ascii_digit() || c == '.' || c == ',');
+    if mantissa.matches(['.', ',']).count() > 1 {
+        return Err(invalid());
+    }
+    let mut mantissa = mantissa.replace(',', ".");
+    if mantissa.starts_with('.') {
+        mantissa.insert(0, '0');
+    }
+
+    let mut uncertainty = None;
+    if let Some(inner) = rest.strip_prefix('(') {
+        let (digits, after) = take_while_prefix(inner, |c| c.is_ascii_digit());
+        rest = after
+            .strip_prefix(')')
+            .filter(|_| !digits.is_empty())
+            .ok_or_else(invalid)?;
+        uncertainty = Some(SiUncertainty::Compact(digits.to_string()));
+    } else if let Some(inner) = rest.strip_prefix("+-") {
+        let (value, after) = take_while_prefix(inner, |c| c.is_ascii_digit() || c == '.');
+        if value.is_empty() {
+            return Err(invalid());
+        }
+        rest = after;
+        uncertainty = Some(SiUncertainty::PlusMinus(value.to_string()));
+    }
+
+    let mut exponent = None;
+    if let Some(inner) = rest.strip_prefix(['e', 'E', 'd', 'D']) {
+        let (sign, inner) = match inner.strip_prefix(['+', '-']) {
+            Some(after) => (&inner[..1], after),
+            None => ("", inner),
+        };
+        let (digits, after) = take_while_prefix(inner, |c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(invalid());
+        }
+        rest = after;
+        let sign = if sign == "-" { "-" } else { "" };
+        exponent = Some(format!("{}{}", sign, digits));
+    }
+
+    if !rest.is_empty() || (mantissa.is_empty() && (exponent.is_none() || uncertainty.is_some())) {
+        return Err(invalid());
+    }
+    Ok(SiNumber {
+        sign: sign.to_string(),
+        mantissa,
+        uncertainty,
+        exponent,
+    })
+}
+
+impl SiNumber {
+    /// The uncertainty as an absolute value, e.g. `0.04` for `1.23(4)`
+    fn absolute_uncertainty(&self) -> Option<String> {
+        match self.uncertainty.as_ref()? {
+            SiUncertainty::PlusMinus(value) => Some(value.clone()),
+            SiUncertainty::Compact(digits) => {
+                let decimals = self.mantissa.split_once('.').map_or(0, |(_, f)| f.len());
+                Some(if decimals == 0 {
+                    digits.clone()
+                } else if digits.len() > decimals {
+                    let (int, frac) = digits.split_at(digits.len() - decimals);
+                    format!("{}.{}", int, frac)
+                } else {
+                    format!("0.{}{}", "0".repeat(decimals - digits.len()), digits)
+                })
+            }
+        }
+    }
+
+    /// Typst math, e.g. `1.23(4) times 10^(-5)`
+    fn to_plain(&self) -> String {
+        let mut typst = format!("{}{}", self.sign, self.mantissa);
+        match &self.uncertainty {
+            Some(SiUncertainty::Compact(digits)) => typst.push_str(&format!("({})", digits)),
+            Some(SiUncertainty::PlusMinus(value)) => {
+                typst = format!("{} plus.minus {}", typst, value);
+            }
+            None => {}
+        }
+        let Some(exponent) = &self.exponent else {
+            return typst;
+        };
+        let power = if exponent.starts_with('-') {
+            format!("10^({})", exponent)
+        } else {
+            format!("10^{}", exponent)
+        };
+        match &self.uncertainty {
+            _ if self.mantissa.is_empty() => format!("{}{}", self.sign, power),
+            Some(SiUncertainty::PlusMinus(_)) => format!("({}) times {}", typst, power),
+            _ => format!("{} times {}", typst, power),
+        }
+    }
+
+    /// The number string passed to `unify`/`metro`, e.g. `1.23+-0.04e-5`
+    fn to_package(&self) -> String {
+        let mut number = format!("{}{}", self.sign, self.mantissa);
+        if let Some(uncertainty) = self.absolute_uncertainty() {
+            number.push_str("+-");
+            number.push_str(&uncertainty);
+        }
+        if let Some(exponent) = &self.exponent {
+            number.push('e');
+            number.push_str(exponent);
+        }
+        number
+    }
+}
+
+/// Read a power such as `2`, `-1` or `{-1}` after `^` in a literal unit
+fn read_unit_power(cursor: &mut TexCursor) -> Option<String> {
+    if cursor.peek() == Some('{') {
+        return cursor.read_group().map(|power| power.trim().to_string());
+    }
+    let start = cursor.pos;
+    if cursor.peek() == Some('-') {
+        cursor.bump();
+    }
+    while cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
+        cursor.bump();
+    }
+    (cursor.pos > start).then(|| cursor.src[start..cursor.pos].to_string())
+}
+
+/// Parse a siunitx unit argument, recording unknown unit macros in `warnings`
+fn parse_si_unit(unit: &str, warnings: &mut Vec<String>) -> Result<Vec<UnitFactor>, String> {
+    let mut factors: Vec<UnitFactor> = Vec::new();
+    let (mut prefix_symbol, mut prefix_words) = (String::new(), String::new());
+    let mut pending_power: Option<String> = None;
+    let mut per = false;
+    let mut cursor = TexCursor::new(unit);
+    let missing_factor =
+        |command: &str| format!("{} in unit '{}' has no unit to apply to", command, unit);
+
+    loop {
+        cursor.skip_whitespace();
+        let Some(c) = cursor.peek() else { break };
+        let (symbol, words) = match c {
+            '\\' => {
+                let command = cursor.read_control_sequence().unwrap_or_default();
+                let name = &command[1..];
+                match name {
+                    "per" => {
+                        per = true;
+                        continue;
+                    }
+                    "square" | "cubic" | "raiseto" => {
+                        pending_power = Some(match name {
+                            "square" => "2".to_string(),
+                            "cubic" => "3".to_string(),
+                            _ => cursor
+                                .read_argument()
+                                .ok_or_else(|| missing_factor(command))?
+                                .to_string(),
+                        });
+                        continue;
+                    }
+                    "squared" | "cubed" | "tothe" => {
+                        let power = match name {
+                            "squared" => "2".to_string(),
+                            "cubed" => "3".to_string(),
+                            _ => cursor
+                                .read_argument()
+                                .ok_or_else(|| missing_factor(command))?
+                                .to_string(),
+                        };
+                        factors
+                            .last_mut()
+                            .ok_or_else(|| missing_factor(command))?
+                            .power = Some(power);
+                        continue;
+                    }
+                    _ => {}
+                }
+                if let Some((_, symbol)) = SI_PREFIXES.iter().find(|(prefix, _)| *prefix == name) {
+                    prefix_symbol.push_str(symbol);
+                    prefix_words.push_str(name);
+                    prefix_words.push(' ');
+                    continue;
+                }
+                match SI_UNITS.iter().find(|(unit, _)| *unit == name) {
+                    Some((_, symbol)) => (symbol.to_string(), name.to_string()),
+                    None => {
+                        warnings.push(format!("unknown unit {}", command));
+                        (name.to_string(), name.to_string())
+                    }
+                }
+            }
+            '/' => {
+                cursor.bump();
+                per = true;
+                continue;
+            }
+            '.' | '~' | '*' => {
+                cursor.bump();
+                continue;
+            }
+            '^' => {
+                cursor.bump();
+                let power = read_unit_power(&mut cursor).ok_or_else(|| missing_factor("^"))?;
+                factors.last_mut().ok_or_else(|| missing_factor("^"))?.power = Some(power);
+                continue;
+            }
+            _ => {
+                let start = cursor.pos;
+                while cursor
+                    .peek()
+                    .is_some_and(|c| !c.is_whitespace() && !"\\/.~*^{}".contains(c))
+                {
+                    cursor.bump();
+                }
+                if cursor.pos == start {
+                    return Err(format!("Unsupported character '{}' in unit '{}'", c, unit));
+                }
+                let literal = &cursor.src[start..cursor.pos];
+                (literal.to_string(), literal.to_string())
+            }
+        };
+        factors.push(UnitFactor {
+            symbol: format!("{}{}", prefix_symbol, symbol),
+            words: format!("{}{}", prefix_words, words),
+            power: pending_power.take(),
+            per: std::mem::take(&mut per),
+        });
+        prefix_symbol.clear();
+        prefix_words.clear();
+    }
+    if !prefix_words.is_empty() || pending_power.is_some() || per {
+        return Err(format!("Unit '{}' ends before its last unit", unit));
+    }
+    Ok(factors)
+}
+
+/// Typst math for unit factors, e.g. `upright("m") slash upright("s")^2`
+fn units_to_plain(factors: &[UnitFactor]) -> String {
+    let render = |factor: &UnitFactor, negate: bool| {
+        let mut typst = format!("upright(\"{}\")", factor.symbol.replace('"', "\\\""));
+        let power = match (&factor.power, negate) {
+            (Some(power), false) => Some(power.clone()),
+            (Some(power), true) => Some(format!("-{}", power)),
+            (None, true) => Some("-1".to_string()),
+            (None, false) => None,
+        };
+        match power {
+            Some(power) if power.chars().all(|c| c.is_ascii_digit()) => {
+                typst.push_str(&format!("^{}", power));
+            }
+            Some(power) => typst.push_str(&format!("^({})", power)),
+            None => {}
+        }
+        typst
+    };
+    let (denominator, numerator): (Vec<_>, Vec<_>) = factors.iter().partition(|f| f.per);
+    if numerator.is_empty() {
+        return denominator
+            .iter()
+            .map(|f| render(f, true))
+            .collect::<Vec<_>>()
+            .join(" thin ");
+    }
+    let numerator: Vec<_> = numerator.iter().map(|f| render(f, false)).collect();
+    let denominator: Vec<_> = denominator.iter().map(|f| render(f, false)).collect();
+    match denominator.len() {
+        0 => numerator.join(" thin "),
+        1 => format!("{} slash {}", numerator.join(" thin "), denominator[0]),
+        _ => format!(
+            "{} slash ({})",
+            numerator.join(" thin "),
+            denominator.join(" thin ")
+        ),
+    }
+}
+
+/// The unit string passed to `unify`/`metro`, e.g. `kilo metre per second squared`
+fn units_to_package(factors: &[UnitFactor]) -> String {
+    let words: Vec<String> = factors
+        .iter()
+        .map(|factor| {
+            let mut words = if factor.per {
+                format!("per {}", factor.words)
+            } else {
+                factor.words.clone()
+            };
+            match factor.power.as_deref() {
+                Some("2") => words.push_str(" squared"),
+                Some("3") => words.push_str(" cubed"),
+                Some(power) => words.push_str(&format!("^{}", power)),
+                None => {}
+            }
+            words
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn typst_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `\SI`/`\qty`, `\si`/`\unit` and `\num` replaced by placeholders, with the Typst
+/// to splice in for each placeholder after conversion
+pub(super) struct SiunitxExpansion {
+    pub(super) tex: String,
+    pub(super) splices: Vec<String>,
+    /// Unknown unit macros and ignored options
+    pub(super) warnings: Vec<String>,
+}
+
+/// Replace siunitx commands with placeholders and render their Typst in `style`
+pub(super) fn expand_siunitx(tex: &str, style: &str) -> Result<SiunitxExpansion, String> {
+    if tex
+        .chars()
+        .any(|c| SIUNITX_PLACEHOLDERS.contains(&(c as u32)))
+    {
+        return Err(
+            "Input contains private-use characters U+E000..U+F8FF, which siunitx=True reserves"
+                .to_string(),
+        );
+    }
+    let mut expansion = SiunitxExpansion {
+        tex: String::with_capacity(tex.len()),
+        splices: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let mut cursor = TexCursor::new(tex);
+    while let Some(c) = cursor.peek() {
+        if c != '\\' {
+            expansion.tex.push(c);
+            cursor.bump();
+            continue;
+        }
+        let command = cursor.read_control_sequence().unwrap_or_default();
+        let num_args = match command {
+            "\\SI" | "\\qty" => 2,
+            "\\si" | "\\unit" | "\\num" => 1,
+            _ => {
+                expansion.tex.push_str(command);
+                continue;
+            }
+        };
+        cursor.skip_whitespace();
+        if cursor.peek() == Some('[') {
+            let options = cursor.read_delimited('[', ']').unwrap_or_default();
+            expansion
+                .warnings
+                .push(format!("{}[{}]: options are ignored", command, options));
+        }
+        let args: Vec<&str> = (0..num_args)
+            .map(|_| {
+                cursor.skip_whitespace();
+                cursor.read_group()
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                format!(
+                    "{} must be followed by {} braced argument{}",
+                    command,
+                    num_args,
+                    if num_args > 1 { "s" } else { "" }
+                )
+            })?;
+
+        let package = style != "plain";
+        let typst = match command {
+            "\\num" => {
+                let number = parse_si_number(args[0])?;
+                if package {
+                    format!("num({})", typst_string(&number.to_package()))
+                } else {
+                    number.to_plain()
+                }
+            }
+            "\\si" | "\\unit" => {
+                let factors = parse_si_unit(args[0], &mut expansion.warnings)?;
+                if package {
+                    format!("unit({})", typst_string(&units_to_package(&factors)))
+                } else {
+                    units_to_plain(&factors)
+                }
+            }
+            _ => {
+                let number = parse_si_number(args[0])?;
+                let factors = parse_si_unit(args[1], &mut expansion.warnings)?;
+                if package {
+                    format!(
+                        "qty({}, {})",
+                        typst_string(&number.to_package()),
+                        typst_string(&units_to_package(&factors))
+                    )
+                } else {
+                    format!("{} thin {}", number.to_plain(), units_to_plain(&factors))
+                }
+            }
+        };
+        let placeholder = SIUNITX_PLACEHOLDERS
+            .clone()
+            .nth(expansion.splices.len())
+            .and_then(char::from_u32)
+            .ok_or_else(|| "Too many siunitx commands in one input".to_string())?;
+        expansion.tex.push(placeholder);
+        expansion.splices.push(typst);
+    }
+    Ok(expansion)
+}
+
+/// Validate `units_style`, which only applies with `siunitx=True`
+pub(super) fn check_units_style(units_style: Option<&str>, siunitx: bool) -> PyResult<()> {
+    let Some(units_style) = units_style else {
+        return Ok(());
+    };
+    check_choice("units_style", units_style, UNITS_STYLES)?;
+    if !siunitx {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "units_style requires siunitx=True",
+        ));
+    }
+    Ok(())
+}
+
+/// Put the Typst rendered by `expand_siunitx` in place of its placeholders
+pub(super) fn splice_siunitx(typst: &str, splices: &[String]) -> String {
+    let chars: Vec<char> = typst.chars().collect();
+    let mut spliced = String::with_capacity(typst.len());
+    for (i, c) in chars.iter().enumerate() {
+        let index = (*c as u32).wrapping_sub(*SIUNITX_PLACEHOLDERS.start()) as usize;
+        let Some(replacement) = splices.get(index) else {
+            spliced.push(*c);
+            continue;
+        };
+        // Group multi-term output that is an operand of `/`, `^` or `_`; Typst drops
+        // the parentheses when rendering the fraction or script
+        let operand = |c: Option<&char>| c.is_some_and(|c| "/^_".contains(*c));
+        let before = i.checked_sub(1).and_then(|i| chars.get(i));
+        if replacement.contains(' ') && (operand(before) || operand(chars.get(i + 1))) {
+            spliced.push_str(&format!("({})", replacement));
+        } else {
+            spliced.push_str(replacement);
+        }
+    }
+    spliced
+}
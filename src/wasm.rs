@@ -0,0 +1,90 @@
+//! WebAssembly bindings (`wasm` feature, `wasm32-unknown-unknown` target).
+//!
+//! Instead of embedding QuickJS, the bundle is evaluated once in the host's own JS
+//! engine (browser or Node), and the exports call its functions through `js-sys`.
+//! Options are passed through unchanged as a JS object with the JS option names.
+
+use js_sys::{Function, JsString, Reflect};
+use std::cell::OnceCell;
+use wasm_bindgen::prelude::*;
+
+/// The bundle followed by the wrappers `core` evaluates after it
+const JS_SOURCES: &[&str] = &[
+    include_str!("../js/tex2typst.bundle.js"),
+    include_str!("../js/map_options.js"),
+    include_str!("../js/physics.js"),
+];
+
+/// The bundle's `tex2typst` and `typst2tex` functions
+struct Functions {
+    tex2typst: Function,
+    typst2tex: Function,
+}
+
+thread_local! {
+    static FUNCTIONS: OnceCell<Result<Functions, JsValue>> = const { OnceCell::new() };
+}
+
+/// Evaluate the bundle in the host engine and look up the converter functions
+fn load_functions() -> Result<Functions, JsValue> {
+    for source in JS_SOURCES {
+        Function::new_no_args(source).call0(&JsValue::UNDEFINED)?;
+    }
+    let global = js_sys::global();
+    let resolve = |name: &str| -> Result<Function, JsValue> {
+        Reflect::get(&global, &JsValue::from_str(name))?
+            .dyn_into::<Function>()
+            .map_err(|_| {
+                JsError::new(&format!(
+                    "Global function '{}' not found; the JS bundle appears corrupted",
+                    name
+                ))
+                .into()
+            })
+    };
+    Ok(Functions {
+        tex2typst: resolve("tex2typst")?,
+        typst2tex: resolve("typst2tex")?,
+    })
+}
+
+/// Call the converter function `select` picks on `input` and `options`, loading the
+/// bundle on first use
+fn convert(
+    select: impl FnOnce(&Functions) -> &Function,
+    input: &str,
+    options: &JsValue,
+) -> Result<JsString, JsValue> {
+    FUNCTIONS.with(|functions| {
+        let functions = functions
+            .get_or_init(load_functions)
+            .as_ref()
+            .map_err(Clone::clone)?;
+        let result =
+            select(functions).call2(&JsValue::UNDEFINED, &JsValue::from_str(input), options)?;
+        result
+            .dyn_into::<JsString>()
+            .map_err(|_| JsError::new("converter returned a non-string value").into())
+    })
+}
+
+/// Convert LaTeX/TeX math to Typst.
+///
+/// `options` is `undefined` or an object of JS options such as `{ fracToSlash: false }`;
+/// errors thrown by the converter are rethrown.
+#[wasm_bindgen]
+pub fn tex2typst(tex: &str, options: &JsValue) -> Result<JsString, JsValue> {
+    convert(|functions| &functions.tex2typst, tex, options)
+}
+
+/// Convert Typst math to LaTeX/TeX, with options as for [`tex2typst`]
+#[wasm_bindgen]
+pub fn typst2tex(typst: &str, options: &JsValue) -> Result<JsString, JsValue> {
+    convert(|functions| &functions.typst2tex, typst, options)
+}
+
+/// Version of this crate
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
@@ -0,0 +1,184 @@
+//! The `capi` exports, called from Rust and from a C program linked against the cdylib.
+//!
+//! Run with `cargo test --no-default-features --features capi`; the C program is
+//! skipped when the `python` feature is on, as the cdylib then needs libpython.
+#![cfg(feature = "capi")]
+
+use _tex2typst_core::capi::{
+    T2T_ERR_ARGUMENT, T2T_ERR_CONVERSION, T2T_ERR_OPTIONS, T2T_OK, t2t_convert_tex,
+    t2t_convert_tex_into, t2t_free_string, t2t_version,
+};
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::path::PathBuf;
+use std::process::Command;
+use std::ptr;
+
+/// Run `t2t_convert_tex` and take ownership of whichever string it returned
+fn convert(tex: Option<&str>, options: Option<&str>) -> (c_int, Option<String>, Option<String>) {
+    let tex = tex.map(|tex| CString::new(tex).unwrap());
+    let options = options.map(|options| CString::new(options).unwrap());
+    let as_ptr = |value: &Option<CString>| value.as_ref().map_or(ptr::null(), |v| v.as_ptr());
+    let mut out: *mut c_char = ptr::null_mut();
+    let mut err: *mut c_char = ptr::null_mut();
+    let code = unsafe { t2t_convert_tex(as_ptr(&tex), as_ptr(&options), &mut out, &mut err) };
+    let take = |value: *mut c_char| {
+        (!value.is_null()).then(|| {
+            let text = unsafe { CStr::from_ptr(value) }
+                .to_str()
+                .unwrap()
+                .to_string();
+            unsafe { t2t_free_string(value) };
+            text
+        })
+    };
+    (code, take(out), take(err))
+}
+
+#[test]
+fn convert_tex() {
+    assert_eq!(
+        convert(Some(r"\frac{1}{2}"), None),
+        (T2T_OK, Some("1/2".into()), None)
+    );
+    assert_eq!(
+        convert(
+            Some(r"\frac{1}{\infty}"),
+            Some(r#"{"frac_to_slash": false, "infty_to_oo": true}"#)
+        ),
+        (T2T_OK, Some("frac(1, oo)".into()), None)
+    );
+}
+
+#[test]
+fn symbol_map() {
+    assert_eq!(
+        convert(
+            Some(r"\epsilon + \foo"),
+            Some(r#"{"symbol_map": {"epsilon": "E", "foo": "star"}}"#)
+        ),
+        (T2T_OK, Some("E + star".into()), None)
+    );
+}
+
+#[test]
+fn environment_map() {
+    assert_eq!(
+        convert(
+            Some(r"\begin{v} x \end{v}"),
+            Some(r#"{"environment_map": {"v": "vec"}}"#)
+        ),
+        (T2T_OK, Some("vec(x)".into()), None)
+    );
+}
+
+#[test]
+fn errors() {
+    let (code, out, err) = convert(Some(r"\frac{1}{"), None);
+    assert_eq!((code, out), (T2T_ERR_CONVERSION, None));
+    assert!(err.unwrap().starts_with("Conversion failed: "));
+
+    let (code, _, err) = convert(None, None);
+    assert_eq!(
+        (code, err.as_deref()),
+        (T2T_ERR_ARGUMENT, Some("tex must not be NULL"))
+    );
+
+    for options in [
+        "[]",
+        "{",
+        r#"{"frac_to_slash": 1}"#,
+        r#"{"bogus": true}"#,
+        r#"{"handle_text_mode": "bogus"}"#,
+        r#"{"symbol_map": {"foo": 1}}"#,
+        r#"{"environment_map": ["v"]}"#,
+    ] {
+        let (code, out, err) = convert(Some("x"), Some(options));
+        assert_eq!((code, out), (T2T_ERR_OPTIONS, None), "{}", options);
+        assert!(err.is_some());
+    }
+
+    // A NULL `err` only drops the message
+    let tex = CString::new(r"\frac{1}{").unwrap();
+    let mut out: *mut c_char = ptr::null_mut();
+    let code = unsafe { t2t_convert_tex(tex.as_ptr(), ptr::null(), &mut out, ptr::null_mut()) };
+    assert_eq!((code, out), (T2T_ERR_CONVERSION, ptr::null_mut()));
+}
+
+/// Run `t2t_convert_tex_into` with a buffer of `len` bytes
+fn convert_into(tex: Option<&str>, len: usize) -> (c_int, Vec<u8>) {
+    let tex = tex.map(|tex| CString::new(tex).unwrap());
+    let tex = tex.as_ref().map_or(ptr::null(), |tex| tex.as_ptr());
+    let mut buf = vec![0xffu8; len];
+    let out_buf = if len == 0 {
+        ptr::null_mut()
+    } else {
+        buf.as_mut_ptr().cast::<c_char>()
+    };
+    let code = unsafe { t2t_convert_tex_into(tex, ptr::null(), out_buf, len) };
+    (code, buf)
+}
+
+#[test]
+fn convert_tex_into() {
+    assert_eq!(convert_into(Some(r"\frac{1}{2}"), 8).0, 3);
+    assert_eq!(&convert_into(Some(r"\frac{1}{2}"), 8).1[..4], b"1/2\0");
+    // Cut short, but NUL-terminated and still reporting the full length
+    assert_eq!(convert_into(Some(r"\frac{1}{2}"), 3), (3, b"1/\0".to_vec()));
+    // Measure only
+    assert_eq!(convert_into(Some(r"\frac{1}{2}"), 0), (3, Vec::new()));
+
+    assert_eq!(convert_into(Some(r"\frac{1}{"), 8).0, -T2T_ERR_CONVERSION);
+    assert_eq!(convert_into(None, 8).0, -T2T_ERR_ARGUMENT);
+    let code = unsafe { t2t_convert_tex_into(c"x".as_ptr(), ptr::null(), ptr::null_mut(), 8) };
+    assert_eq!(code, -T2T_ERR_ARGUMENT);
+}
+
+#[test]
+fn version() {
+    let version = unsafe { CStr::from_ptr(t2t_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+#[cfg_attr(feature = "python", ignore = "the cdylib links against libpython")]
+fn c_program() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Test binaries and the freshly built cdylib both land in target/<profile>/deps
+    let lib_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let exe = lib_dir.join("capi-convert");
+
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&cc)
+        .arg(manifest_dir.join("tests/capi/convert.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-l_tex2typst_core")
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .unwrap_or_else(|e| panic!("could not run {}: {}", cc, e));
+    assert!(status.success(), "compiling tests/capi/convert.c failed");
+
+    // cargo puts target/<profile> first on the library path, where a stale cdylib
+    // from an earlier `cargo build` may sit
+    let output = Command::new(&exe)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        env!("CARGO_PKG_VERSION")
+    );
+}
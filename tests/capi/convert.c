@@ -0,0 +1,51 @@
+/* Converts \frac{1}{2} through the C API; built and run by tests/capi.rs */
+#include <stdio.h>
+#include <string.h>
+
+#include "tex2typst.h"
+
+int main(void) {
+    char *out = NULL;
+    char *err = NULL;
+
+    int code = t2t_convert_tex("\\frac{1}{2}", NULL, &out, &err);
+    if (code != T2T_OK || err != NULL || strcmp(out, "1/2") != 0) {
+        fprintf(stderr, "default options: code %d, out %s, err %s\n", code, out, err);
+        return 1;
+    }
+    t2t_free_string(out);
+
+    code = t2t_convert_tex("\\frac{1}{2}", "{\"frac_to_slash\": false}", &out, &err);
+    if (code != T2T_OK || strcmp(out, "frac(1, 2)") != 0) {
+        fprintf(stderr, "frac_to_slash: code %d, out %s, err %s\n", code, out, err);
+        return 1;
+    }
+    t2t_free_string(out);
+
+    code = t2t_convert_tex("\\frac{1}{", NULL, &out, &err);
+    if (code != T2T_ERR_CONVERSION || out != NULL || err == NULL) {
+        fprintf(stderr, "invalid input: code %d\n", code);
+        return 1;
+    }
+    t2t_free_string(err);
+
+    char buf[16];
+    int len = t2t_convert_tex_into("\\frac{\\alpha}{2}", NULL, buf, sizeof buf);
+    if (len != 7 || strcmp(buf, "alpha/2") != 0) {
+        fprintf(stderr, "caller buffer: length %d, buf %s\n", len, buf);
+        return 1;
+    }
+    len = t2t_convert_tex_into("\\frac{\\alpha}{2}", NULL, buf, 4);
+    if (len != 7 || strcmp(buf, "alp") != 0) {
+        fprintf(stderr, "short caller buffer: length %d, buf %s\n", len, buf);
+        return 1;
+    }
+    len = t2t_convert_tex_into("\\frac{1}{", NULL, buf, sizeof buf);
+    if (len != -T2T_ERR_CONVERSION) {
+        fprintf(stderr, "caller buffer, invalid input: %d\n", len);
+        return 1;
+    }
+
+    printf("%s\n", t2t_version());
+    return 0;
+}
@@ -0,0 +1,120 @@
+//! The `tex2typst` command, run as a subprocess.
+//!
+//! Run with `cargo test --no-default-features --features cli`.
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Run the command with `args`, feeding it `stdin`
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tex2typst"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn arguments() {
+    let output = run(&[r"\frac{1}{2}", r"\alpha"], "");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1/2\nalpha\n");
+
+    let output = run(
+        &["--no-frac-to-slash", "--infty-to-oo", r"\frac{1}{\infty}"],
+        "",
+    );
+    assert_eq!(stdout(&output), "frac(1, oo)\n");
+
+    let output = run(&["--custom-macro", r"\RR=\mathbb{R}", r"x \in \RR"], "");
+    assert_eq!(stdout(&output), "x in RR\n");
+
+    let output = run(&["--reverse", "--custom-macro=RR=NN", "alpha", "RR"], "");
+    assert_eq!(stdout(&output), "\\alpha\n\\mathbb{N}\n");
+
+    let output = run(&["--", "-x"], "");
+    assert_eq!(stdout(&output), "-x\n");
+}
+
+#[test]
+fn stdin_stream() {
+    let output = run(&["--stdin"], "x^2\r\n\\beta\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "x^2\nbeta\n");
+
+    let output = run(&["--stdin"], "x\n\\frac{1}{\ny\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "x\n");
+    assert!(stderr(&output).starts_with("tex2typst: line 2: Conversion failed: "));
+
+    let output = run(&["--stdin", "--errors", "skip"], "x\n\\frac{1}{\ny\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "x\n\ny\n");
+    assert!(stderr(&output).contains("line 2"));
+}
+
+#[test]
+fn batch_file() {
+    let path = std::env::temp_dir().join(format!("tex2typst-cli-{}.jsonl", std::process::id()));
+    std::fs::write(
+        &path,
+        "{\"tex\": \"\\\\alpha\", \"id\": 1}\n\n\"x^2\"\n{\"id\": 3}\n",
+    )
+    .unwrap();
+    let path_arg = path.to_str().unwrap();
+
+    let output = run(&["--batch", path_arg], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "alpha\nx^2\n");
+    assert!(stderr(&output).contains(":4: missing field 'tex'"));
+
+    let output = run(&["--batch", path_arg, "--errors", "skip"], "");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "alpha\nx^2\n\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn usage_errors() {
+    for args in [
+        &[][..],
+        &["--bogus"],
+        &["--stdin", "x"],
+        &["--errors", "maybe", "x"],
+        &["--custom-macro", "nobody", "x"],
+        &["--optimize=yes", "x"],
+    ] {
+        let output = run(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(stdout(&output).is_empty());
+        assert!(stderr(&output).contains("Usage: tex2typst"));
+    }
+
+    let output = run(&["--handle-text-mode", "bogus", "x"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("handle_text_mode"));
+
+    let output = run(&["--version"], "");
+    assert_eq!(
+        stdout(&output),
+        format!("tex2typst {}\n", env!("CARGO_PKG_VERSION"))
+    );
+}
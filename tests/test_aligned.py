@@ -0,0 +1,54 @@
+"""Test converting aligned multi-line equations."""
+
+import unittest
+import tex2typst
+
+
+class TestTex2TypstAligned(unittest.TestCase):
+    """Test tex2typst_aligned row and cell splitting"""
+
+    def test_rows_and_cells(self):
+        self.assertEqual(
+            tex2typst.tex2typst_aligned(r"a &= b \\ &= \frac{1}{2}"),
+            "a &= b \\\n&= 1/2",
+        )
+        self.assertEqual(
+            tex2typst.tex2typst_aligned(r"x &= 1 && y &= 2"), "x &= 1 & & y &= 2"
+        )
+        self.assertEqual(tex2typst.tex2typst_aligned(r"\alpha"), "alpha")
+
+    def test_nested_environments_keep_separators(self):
+        tex = r"f(x) &= \begin{cases} 1 & x > 0 \\ 0 \end{cases} \\ " \
+            r"&= \begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}"
+        self.assertEqual(
+            tex2typst.tex2typst_aligned(tex),
+            "f(x) &= cases(1 & x > 0, 0) \\\n&= mat(1, 2; 3, 4)",
+        )
+
+    def test_text_and_escapes_keep_ampersand(self):
+        self.assertEqual(
+            tex2typst.tex2typst_aligned(r"a &= \text{x & y} \\ & \& b"),
+            'a &= "x & y" \\\n& \\& b',
+        )
+
+    def test_environment_and_row_skips(self):
+        for env in ["align", "align*", "aligned", "split"]:
+            with self.subTest(env=env):
+                tex = rf"\begin{{{env}}} a &= b \\[2pt] c &= d \\ \end{{{env}}}"
+                self.assertEqual(
+                    tex2typst.tex2typst_aligned(tex), "a &= b \\\nc &= d"
+                )
+
+    def test_options_and_errors(self):
+        self.assertEqual(
+            tex2typst.tex2typst_aligned(r"a &= \frac{1}{2}", frac_to_slash=False),
+            "a &= frac(1, 2)",
+        )
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_aligned(r"a &= \frac{1}{")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_aligned(r"a &= b", max_input_length=3)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,79 @@
+"""Test pyarrow array support."""
+
+import unittest
+import tex2typst
+
+try:
+    import pyarrow as pa
+except ImportError:
+    pa = None
+
+HAS_FEATURE = hasattr(tex2typst._tex2typst_core, "tex2typst_arrow")
+
+
+@unittest.skipIf(pa is None, "pyarrow is not installed")
+@unittest.skipUnless(HAS_FEATURE, "built without the 'arrow' feature")
+class TestArrowInput(unittest.TestCase):
+    """Test tex2typst_arrow"""
+
+    def test_array(self):
+        result = tex2typst.tex2typst_arrow(pa.array([r"\alpha", None, "", "x^2"]))
+        self.assertEqual(result.type, pa.string())
+        self.assertEqual(result.to_pylist(), ["alpha", None, "", "x^2"])
+
+    def test_large_string_and_slice(self):
+        array = pa.array([r"\beta", r"\alpha", None], type=pa.large_string())
+        result = tex2typst.tex2typst_arrow(array.slice(1))
+        self.assertEqual(result.to_pylist(), ["alpha", None])
+
+    def test_chunked_array(self):
+        chunked = pa.chunked_array([[r"\alpha"], [None, r"\frac{1}{2}"]])
+        result = tex2typst.tex2typst_arrow(chunked, frac_to_slash=False)
+        self.assertIsInstance(result, pa.ChunkedArray)
+        self.assertEqual(result.num_chunks, 2)
+        self.assertEqual(result.to_pylist(), ["alpha", None, "frac(1, 2)"])
+
+    def test_shared_options(self):
+        array = pa.array([r"\phi \leq \dv{f}{x}", r"\begin{myenv} a \end{myenv}"])
+        result = tex2typst.tex2typst_arrow(
+            array,
+            handle_physics=True,
+            output_unicode=True,
+            symbol_map={"phi": "phi.alt"},
+            environment_map={"myenv": "f"},
+        )
+        self.assertEqual(result.to_pylist(), ["ϕ ≤ (dif f)/(dif x)", "f(a)"])
+
+    def test_errors(self):
+        array = pa.array(["x", r"\frac{1}{"])
+        with self.assertRaisesRegex(ValueError, "item 1 "):
+            tex2typst.tex2typst_arrow(array)
+        result = tex2typst.tex2typst_arrow(array, keep_original_on_error=True)
+        self.assertEqual(result.to_pylist(), ["x", r"\frac{1}{"])
+        with self.assertRaisesRegex(ValueError, "item 2 "):
+            tex2typst.tex2typst_arrow(pa.chunked_array([["x"], ["y", r"\frac{"]]))
+
+    def test_invalid_utf8(self):
+        offsets = pa.array([0, 1, 2], type=pa.int32()).buffers()[1]
+        array = pa.Array.from_buffers(
+            pa.string(), 2, [None, offsets, pa.py_buffer(b"x\xff")]
+        )
+        with self.assertRaisesRegex(ValueError, "item 1 is not valid UTF-8"):
+            tex2typst.tex2typst_arrow(array)
+
+    def test_type_errors(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst_arrow(pa.array([1, 2]))
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst_arrow([r"\alpha"])
+
+
+@unittest.skipIf(HAS_FEATURE, "built with the 'arrow' feature")
+class TestArrowUnavailable(unittest.TestCase):
+    def test_raises_import_error(self):
+        with self.assertRaises(ImportError):
+            tex2typst.tex2typst_arrow([r"\alpha"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,69 @@
+"""Test tex2typst_batch_mixed."""
+
+import unittest
+import tex2typst
+
+
+class TestBatchMixed(unittest.TestCase):
+    """Test batches returning exceptions in place of failed items"""
+
+    def test_mixed(self):
+        results = tex2typst.tex2typst_batch_mixed(
+            [r"\alpha", r"\frac{1}{", r"\beta", r"\sqrt{"]
+        )
+        self.assertEqual(results[0], "alpha")
+        self.assertEqual(results[2], "beta")
+        for index in [1, 3]:
+            self.assertIsInstance(results[index], ValueError)
+        self.assertEqual(results[1].input, r"\frac{1}{")
+        self.assertEqual(results[1].js_message, "Unmatched '\\{'")
+
+    def test_matches_single_conversion(self):
+        [error] = tex2typst.tex2typst_batch_mixed([r"\frac{1}{"])
+        with self.assertRaises(ValueError) as cm:
+            tex2typst.tex2typst(r"\frac{1}{")
+        self.assertEqual(type(error), type(cm.exception))
+        self.assertEqual(str(error), str(cm.exception))
+
+    def test_options(self):
+        self.assertEqual(
+            tex2typst.tex2typst_batch_mixed([r"\frac{1}{2}"], frac_to_slash=False),
+            ["frac(1, 2)"],
+        )
+        results = tex2typst.tex2typst_batch_mixed(
+            ["", r"\frac{1}{", r"\alpha"],
+            skip_empty=True,
+            empty_replacement="-",
+            dedupe=True,
+            wrap="inline",
+        )
+        self.assertEqual(results[0], "-")
+        self.assertIsInstance(results[1], ValueError)
+        self.assertEqual(results[2], "$alpha$")
+
+    def test_all_ok(self):
+        self.assertEqual(tex2typst.tex2typst_batch_mixed([]), [])
+        self.assertEqual(
+            tex2typst.tex2typst_batch_mixed([r"\alpha", r"\alpha"], dedupe=True),
+            ["alpha", "alpha"],
+        )
+
+    def test_invalid_combinations(self):
+        for option in [
+            "keep_original_on_error",
+            "return_failed_indices",
+            "return_summary",
+            "atomic",
+        ]:
+            with self.assertRaisesRegex(ValueError, "return_exceptions"):
+                tex2typst.tex2typst_batch_mixed([r"\alpha"], **{option: True})
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst_batch_mixed(r"\alpha")
+
+    def test_pre_conversion_errors_raise(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_batch_mixed(["x" * 10], max_input_length=5)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,62 @@
+"""Test return_summary and BatchResult."""
+
+import unittest
+import tex2typst
+
+
+class TestBatchResult(unittest.TestCase):
+    """Test the summary returned by return_summary=True"""
+
+    def test_mixed_batch(self):
+        items = [r"\alpha", r"\frac{1}{", r"\beta", r"\left(", r"x^2"]
+        summary = tex2typst.tex2typst(items, keep_original_on_error=True, return_summary=True)
+        self.assertIsInstance(summary, tex2typst.BatchResult)
+        self.assertEqual(len(summary), 5)
+        self.assertEqual(summary.results, ["alpha", r"\frac{1}{", "beta", r"\left(", "x^2"])
+        self.assertEqual([index for index, _ in summary.errors], [1, 3])
+        self.assertEqual(len(summary.results) - len(summary.errors), 3)
+        for _, message in summary.errors:
+            self.assertIsInstance(message, str)
+            self.assertTrue(message)
+        self.assertGreater(summary.elapsed, 0)
+        self.assertAlmostEqual(summary.items_per_second, 5 / summary.elapsed)
+        self.assertGreaterEqual(summary.engine_inits, 0)
+        self.assertIn("errors=2", repr(summary))
+
+    def test_matches_js_message(self):
+        summary = tex2typst.tex2typst(
+            [r"\frac{1}{"], keep_original_on_error=True, return_summary=True
+        )
+        with self.assertRaises(ValueError) as cm:
+            tex2typst.tex2typst(r"\frac{1}{")
+        self.assertEqual(summary.errors, [(0, cm.exception.js_message)])
+
+    def test_indices_with_skip_empty_and_dedupe(self):
+        summary = tex2typst.tex2typst(
+            ["", r"\frac{1}{", r"\alpha", r"\frac{1}{"],
+            keep_original_on_error=True,
+            skip_empty=True,
+            dedupe=True,
+            return_summary=True,
+        )
+        self.assertEqual(summary.results, [None, r"\frac{1}{", "alpha", r"\frac{1}{"])
+        self.assertEqual([index for index, _ in summary.errors], [1, 3])
+
+    def test_failure_raises_without_keep_original(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst([r"\alpha", r"\frac{1}{"], return_summary=True)
+
+    def test_typst2tex(self):
+        summary = tex2typst.typst2tex(["alpha", "beta"], return_summary=True)
+        self.assertEqual(summary.results, [r"\alpha", r"\beta"])
+        self.assertEqual(summary.errors, [])
+
+    def test_rejected_combinations(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst([r"\alpha"], return_summary=True, return_failed_indices=True)
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\alpha", return_summary=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,3 +1,4 @@
+import tracemalloc
 import unittest
 import time
 import tex2typst
@@ -57,6 +58,64 @@ class TestPerformanceFunctionAPI(unittest.TestCase):
         latex = r"\\int_{-\\infty}^{\\infty} e^{-x^2} dx = \sqrt{\\pi} \\quad \\text{where } x \\in \\mathbb{R}"
         self.benchmark("Gaussian Integral", latex, iterations=100)
 
+    def test_perf_batch(self):
+        # Many tiny items: per-call overhead on the Rust side dominates
+        items = ["x"] * 10000
+        tex2typst.tex2typst(items[:100])
+
+        start_time = time.perf_counter()
+        results = tex2typst.tex2typst(items)
+        total_time = time.perf_counter() - start_time
+        throughput_qps = len(items) / total_time
+
+        print("\n--- Benchmark (Function API): Batch of Tiny Items ---")
+        print(f"Items      : {len(items)}")
+        print(f"Total Time : {total_time:.4f} s")
+        print(f"Throughput : {throughput_qps:.0f} items/sec")
+
+        self.assertEqual(results[-1], "x")
+        self.assertGreater(throughput_qps, 10, "Throughput is surprisingly low!")
+
+    def test_perf_isolated(self):
+        # Every isolated call loads the bundle into a fresh context
+        latex = "\\frac{-b \\pm \\sqrt{b^2 - 4ac}}{2a}"
+        iterations = 20
+        tex2typst.tex2typst(latex, isolated=True)
+
+        start_time = time.perf_counter()
+        for _ in range(iterations):
+            tex2typst.tex2typst(latex, isolated=True)
+        isolated_ms = (time.perf_counter() - start_time) / iterations * 1000
+
+        start_time = time.perf_counter()
+        for _ in range(iterations):
+            tex2typst.tex2typst(latex, js_options={})
+        shared_ms = (time.perf_counter() - start_time) / iterations * 1000
+
+        print("\n--- Benchmark (Function API): Isolated Context ---")
+        print(f"Shared     : {shared_ms:.4f} ms/op")
+        print(f"Isolated   : {isolated_ms:.4f} ms/op")
+
+        self.assertGreater(isolated_ms, 0)
+
+    def test_perf_iter_memory(self):
+        # Streaming a million items keeps only one chunk of results alive
+        count = 1_000_000
+        tracemalloc.start()
+        try:
+            converted = 0
+            for typst in tex2typst.tex2typst_iter(
+                ("x" for _ in range(count)), chunk_size=1000
+            ):
+                converted += 1
+            _, peak = tracemalloc.get_traced_memory()
+        finally:
+            tracemalloc.stop()
+        self.assertEqual(converted, count)
+        self.assertEqual(typst, "x")
+        # A materialized list of a million results alone takes about 8 MB
+        self.assertLess(peak, 1_000_000)
+
 
 if __name__ == "__main__":
     unittest.main(argv=["first-arg-is-ignored"], verbosity=2)
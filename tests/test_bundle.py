@@ -0,0 +1,118 @@
+"""Test loading an alternative JS bundle at runtime."""
+
+import hashlib
+import os
+import tempfile
+import threading
+import unittest
+import tex2typst
+
+BUNDLE_PATH = os.path.join(os.path.dirname(__file__), "..", "js", "tex2typst.bundle.js")
+
+
+def embedded_source():
+    with open(BUNDLE_PATH, encoding="utf-8") as f:
+        return f.read()
+
+
+def forked_source():
+    # The embedded bundle with `tex2typst` patched to upper-case its output
+    return embedded_source() + (
+        "\n(() => { const base = tex2typst;"
+        " globalThis.tex2typst = (tex, options) => base(tex, options).toUpperCase(); })();"
+    )
+
+
+def sha256(source):
+    return hashlib.sha256(source.encode("utf-8")).hexdigest()
+
+
+class TestLoadBundle(unittest.TestCase):
+    """Test load_bundle, reset_bundle and bundle_sha256"""
+
+    def tearDown(self):
+        tex2typst.reset_bundle()
+
+    def test_embedded_sha256(self):
+        self.assertEqual(tex2typst.bundle_sha256(), sha256(embedded_source()))
+
+    def test_load_source(self):
+        source = forked_source()
+        tex2typst.load_bundle(source, is_path=False)
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "ALPHA")
+        self.assertEqual(tex2typst.tex2typst([r"\beta"]), ["BETA"])
+        self.assertEqual(tex2typst.bundle_sha256(), sha256(source))
+
+        tex2typst.reset_bundle()
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "alpha")
+        self.assertEqual(tex2typst.bundle_sha256(), sha256(embedded_source()))
+
+    def test_load_path(self):
+        with tempfile.TemporaryDirectory() as tmp:
+            path = os.path.join(tmp, "fork.bundle.js")
+            with open(path, "w", encoding="utf-8") as f:
+                f.write(forked_source())
+            tex2typst.load_bundle(path)
+        self.assertEqual(tex2typst.tex2typst("x"), "X")
+        with self.assertRaises(FileNotFoundError):
+            tex2typst.load_bundle(os.path.join(tmp, "missing.js"))
+
+    def test_other_threads(self):
+        results = []
+
+        def work():
+            results.append(tex2typst.tex2typst(["x"]))
+
+        tex2typst.load_bundle(forked_source(), is_path=False)
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertEqual(results, [["X"]])
+
+    def test_broken_bundle_keeps_active(self):
+        tex2typst.load_bundle(forked_source(), is_path=False)
+        active = tex2typst.bundle_sha256()
+        with self.assertRaisesRegex(tex2typst.EngineError, "'typst2tex' not found"):
+            tex2typst.load_bundle("function tex2typst(tex) { return tex; }", is_path=False)
+        with self.assertRaisesRegex(tex2typst.EngineError, "JS Load Error"):
+            tex2typst.load_bundle("function (", is_path=False)
+        self.assertEqual(tex2typst.bundle_sha256(), active)
+        self.assertEqual(tex2typst.tex2typst("y"), "Y")
+
+    def test_extensions_run_after_bundle(self):
+        try:
+            tex2typst.register_js_extension(
+                "(() => { const base = tex2typst;"
+                " tex2typst = (tex, options) => base(tex, options) + '!'; })();"
+            )
+            tex2typst.load_bundle(forked_source(), is_path=False)
+            self.assertEqual(tex2typst.tex2typst("x"), "X!")
+        finally:
+            tex2typst.clear_js_extensions()
+
+    def test_version_of_loaded_bundle(self):
+        tex2typst.load_bundle(
+            "function tex2typst(tex) { return tex; } function typst2tex(typst) { return typst; }",
+            is_path=False,
+        )
+        self.assertEqual(tex2typst.get_js_bundle_version(), "unknown")
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), r"\alpha")
+
+    def test_list_global_functions(self):
+        names = tex2typst.list_global_functions()
+        self.assertIn("tex2typst", names)
+        self.assertIn("typst2tex", names)
+        self.assertNotIn("parseInt", names)
+        self.assertEqual(names, sorted(names))
+        tex2typst.load_bundle(
+            "function tex2typst(tex) { return tex; } function typst2tex(typst) { return typst; }"
+            " var helper = () => 1; var answer = 42;",
+            is_path=False,
+        )
+        self.assertEqual(
+            tex2typst.list_global_functions(), ["helper", "tex2typst", "typst2tex"]
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,57 @@
+"""Test check_installation."""
+
+import json
+import unittest
+import tex2typst
+
+
+class TestCheckInstallation(unittest.TestCase):
+    """Test the installation self-test"""
+
+    def test_passes(self):
+        report = tex2typst.check_installation()
+        self.assertTrue(report["ok"])
+        self.assertTrue(report["module_path"])
+        self.assertEqual(
+            report["versions"]["rust_crate_version"], tex2typst.__version__
+        )
+        names = [check["name"] for check in report["checks"]]
+        self.assertEqual(names[:2], ["import", "converter_init"])
+        self.assertIn("global_function:typst2tex", names)
+        self.assertIn(r"tex2typst:\frac{1}{2}", names)
+        self.assertIn("typst2tex:alpha", names)
+        for check in report["checks"]:
+            self.assertEqual((check["ok"], check["error"]), (True, None))
+
+    def test_machine_readable(self):
+        report = tex2typst.check_installation()
+        self.assertEqual(json.loads(json.dumps(report)), report)
+
+    def test_ignores_default_options(self):
+        tex2typst.set_default_options(frac_to_slash=True)
+        try:
+            self.assertTrue(tex2typst.check_installation()["ok"])
+        finally:
+            tex2typst.reset_default_options()
+
+    def test_failures_collected(self):
+        tex2typst.register_js_extension("tex2typst = (tex, options) => 'wrong';")
+        try:
+            report = tex2typst.check_installation()
+            self.assertFalse(report["ok"])
+            failed = {c["name"]: c["error"] for c in report["checks"] if not c["ok"]}
+            self.assertEqual(
+                sorted(failed), [r"tex2typst:\alpha", r"tex2typst:\frac{1}{2}"]
+            )
+            self.assertEqual(failed[r"tex2typst:\alpha"], 'expected "alpha", got "wrong"')
+
+            with self.assertRaises(tex2typst.EngineError) as cm:
+                tex2typst.check_installation(raise_on_failure=True)
+            self.assertIn(r"tex2typst:\alpha", str(cm.exception))
+        finally:
+            tex2typst.clear_js_extensions()
+        self.assertTrue(tex2typst.check_installation(raise_on_failure=True)["ok"])
+
+
+if __name__ == "__main__":
+    unittest.main()
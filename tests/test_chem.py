@@ -0,0 +1,73 @@
+"""Test mhchem \\ce{} translation with chem=True."""
+
+import unittest
+import tex2typst
+
+
+class TestChem(unittest.TestCase):
+    """Test the chem option of tex2typst"""
+
+    def test_formulas(self):
+        cases = {
+            r"\ce{H2O}": '"H"_2 "O"',
+            r"\ce{NaCl}": '"NaCl"',
+            r"\ce{(NH4)2SO4}": '("NH"_4)_2 "SO"_4',
+            r"\ce{CuSO4*5H2O}": '"CuSO"_4 dot.op 5 "H"_2 "O"',
+        }
+        for tex, expected in cases.items():
+            with self.subTest(tex=tex):
+                self.assertEqual(tex2typst.tex2typst(tex, chem=True), expected)
+
+    def test_charges(self):
+        self.assertEqual(tex2typst.tex2typst(r"\ce{SO4^2-}", chem=True), '"SO"_4^(2 -)')
+        self.assertEqual(
+            tex2typst.tex2typst(r"\ce{Fe^{3+}(aq)}", chem=True), '"Fe"^(3 +) "(aq)"'
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\ce{H+ + OH- <=> H2O}", chem=True),
+            '"H"^+ + "OH"^- harpoons.rtlb "H"_2 "O"',
+        )
+
+    def test_reaction(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\ce{CO2 + C -> 2 CO}", chem=True),
+            '"CO"_2 + "C" -> 2 "CO"',
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\ce{2H2 + O2 -> 2H2O}", chem=True),
+            '2 "H"_2 + "O"_2 -> 2 "H"_2 "O"',
+        )
+
+    def test_mixed_with_math(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"x^2 + \ce{NaCl} = \frac{1}{2}", chem=True),
+            'x^2 + "NaCl" = 1/2',
+        )
+        self.assertEqual(
+            tex2typst.tex2typst([r"\ce{O2}", r"\alpha"], chem=True),
+            ['"O"_2', "alpha"],
+        )
+
+    def test_unsupported(self):
+        with self.assertRaisesRegex(ValueError, r"'->\[heat\]'"):
+            tex2typst.tex2typst(r"\ce{A ->[heat] B}", chem=True)
+        with self.assertRaisesRegex(ValueError, r"'\^14C'"):
+            tex2typst.tex2typst(r"\ce{^14C}", chem=True)
+        with self.assertRaisesRegex(ValueError, "item 1: "):
+            tex2typst.tex2typst([r"\ce{H2}", r"\ce{A ->[x] B}"], chem=True)
+
+    def test_handle_mhchem_alias(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\ce{H2O}", handle_mhchem=True), '"H"_2 "O"'
+        )
+        self.assertEqual(
+            tex2typst.tex2typst([r"\ce{H2O}", r"\ce{NaCl}"], handle_mhchem=True),
+            ['"H"_2 "O"', '"NaCl"'],
+        )
+
+    def test_disabled_by_default(self):
+        self.assertNotEqual(tex2typst.tex2typst(r"\ce{H2O}"), '"H"_2 "O"')
+
+
+if __name__ == "__main__":
+    unittest.main()
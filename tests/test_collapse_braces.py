@@ -0,0 +1,47 @@
+"""Test the collapse_redundant_braces option."""
+
+import unittest
+import tex2typst
+
+# Typst a symbol map entry is pasted as, leaving redundant groups behind
+GROUPED = {"two": "(2)", "half": "(1)/(2)", "ratio": "(a)/b", "call": "f(a)/b"}
+
+
+class TestCollapseRedundantBraces(unittest.TestCase):
+    """Test removing grouping parentheses Typst ignores"""
+
+    def convert(self, tex, **kwargs):
+        return tex2typst.tex2typst(tex, symbol_map=GROUPED, **kwargs)
+
+    def test_default_keeps_groups(self):
+        self.assertEqual(self.convert(r"x^\two"), "x^(2)")
+
+    def test_attachments_and_fractions(self):
+        self.assertEqual(self.convert(r"x^\two", collapse_redundant_braces=True), "x^2")
+        self.assertEqual(self.convert(r"y_\two", collapse_redundant_braces=True), "y_2")
+        self.assertEqual(self.convert(r"\half", collapse_redundant_braces=True), "1/2")
+        self.assertEqual(self.convert(r"\ratio", collapse_redundant_braces=True), "a/b")
+
+    def test_meaningful_parentheses_kept(self):
+        self.assertEqual(self.convert(r"\call", collapse_redundant_braces=True), "f(a)/b")
+        self.assertEqual(
+            tex2typst.tex2typst(r"e^{i \pi}", collapse_redundant_braces=True), "e^(i pi)"
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"x_{(1)}", collapse_redundant_braces=True), "x_((1))"
+        )
+        self.assertEqual(self.convert(r"\two x", collapse_redundant_braces=True), "(2) x")
+
+    def test_batch(self):
+        self.assertEqual(
+            self.convert(
+                [r"x^\two", r"\half", r"\frac{1}{"],
+                collapse_redundant_braces=True,
+                keep_original_on_error=True,
+            ),
+            ["x^2", "1/2", r"\frac{1}{"],
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,43 @@
+"""Test stripping LaTeX comments from TeX input."""
+
+import unittest
+import tex2typst
+
+
+class TestStripComments(unittest.TestCase):
+    """Test strip_comments"""
+
+    def test_comment_lines(self):
+        tex = "a + % first term\n  b % second term\n+ c"
+        self.assertEqual(tex2typst.tex2typst(tex, strip_comments=True), "a + b + c")
+        # Without the option the comment leaks into the Typst output
+        self.assertEqual(tex2typst.tex2typst("x^{2%\n}"), "x^(2 //\n)")
+
+    def test_escaped_percent(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\text{50\% off} + x % note", strip_comments=True),
+            '"50% off" + x',
+        )
+        # `\\` is a line break, so the following `%` starts a comment
+        self.assertEqual(
+            tex2typst.tex2typst("a \\\\ % c\nb", strip_comments=True),
+            tex2typst.tex2typst("a \\\\ b"),
+        )
+
+    def test_comment_inside_argument(self):
+        self.assertEqual(
+            tex2typst.tex2typst("\\frac{a % numerator\n}{b % denominator\n}", strip_comments=True),
+            "a/b",
+        )
+        # A trailing `%` joins the lines without a space
+        self.assertEqual(tex2typst.tex2typst(["x^{2%\n}"], strip_comments=True), ["x^2"])
+
+    def test_windows_line_endings(self):
+        self.assertEqual(
+            tex2typst.tex2typst("a + % one\r\n  b %\r\n+ c", strip_comments=True),
+            "a + b + c",
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
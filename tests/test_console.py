@@ -0,0 +1,79 @@
+"""Test capturing the JS engines' console output."""
+
+import threading
+import unittest
+import tex2typst
+
+WARN_EXTENSION = """
+(() => {
+    const base = tex2typst;
+    tex2typst = (tex, options) => {
+        console.warn("converting", tex, tex.length);
+        return base(tex, options);
+    };
+})();
+"""
+
+
+class TestConsoleOutput(unittest.TestCase):
+    """Test get_pending_warnings and clear_warnings"""
+
+    def setUp(self):
+        tex2typst.clear_warnings()
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+        tex2typst.clear_warnings()
+
+    def test_no_output(self):
+        tex2typst.tex2typst(r"\alpha")
+        self.assertEqual(tex2typst.get_pending_warnings(), [])
+
+    def test_captured_in_order(self):
+        tex2typst.register_js_extension(WARN_EXTENSION)
+        self.assertEqual(tex2typst.tex2typst(["x", r"\beta"]), ["x", "beta"])
+        self.assertEqual(
+            tex2typst.get_pending_warnings(),
+            ["converting x 1", "converting \\beta 5"],
+        )
+        tex2typst.clear_warnings()
+        self.assertEqual(tex2typst.get_pending_warnings(), [])
+
+    def test_all_methods(self):
+        tex2typst.register_js_extension(
+            "console.log('a'); console.info('b', {}); console.debug();"
+            " console.error(null, 1.5); console.warn([1, 2]);"
+        )
+        tex2typst.tex2typst("x")
+        self.assertEqual(
+            tex2typst.get_pending_warnings(),
+            ["a", "b [object Object]", "", "null 1.5", "1,2"],
+        )
+
+    def test_bounded(self):
+        tex2typst.register_js_extension(
+            "for (let i = 0; i < 1005; i++) console.log(String(i));"
+        )
+        tex2typst.tex2typst("x")
+        warnings = tex2typst.get_pending_warnings()
+        self.assertEqual(len(warnings), 1000)
+        self.assertEqual(warnings[0], "5")
+        self.assertEqual(warnings[-1], "1004")
+
+    def test_per_thread(self):
+        tex2typst.register_js_extension(WARN_EXTENSION)
+        results = []
+
+        def work():
+            tex2typst.tex2typst("y")
+            results.append(tex2typst.get_pending_warnings())
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertEqual(results, [["converting y 1"]])
+        self.assertEqual(tex2typst.get_pending_warnings(), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
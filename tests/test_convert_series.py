@@ -0,0 +1,76 @@
+"""Test convert_series on dataframe columns and plain iterables."""
+
+import math
+import unittest
+import tex2typst
+
+try:
+    import pandas as pd
+except ImportError:
+    pd = None
+
+HAS_ARROW = hasattr(tex2typst._tex2typst_core, "tex2typst_arrow")
+
+
+def to_list(result):
+    return result.to_pylist() if hasattr(result, "to_pylist") else result
+
+
+class TestConvertSeries(unittest.TestCase):
+    """Test convert_series without a dataframe library"""
+
+    def test_list(self):
+        self.assertEqual(
+            tex2typst.convert_series([r"\alpha", None, math.nan, r"\frac{1}{2}"]),
+            ["alpha", None, None, "1/2"],
+        )
+        self.assertEqual(tex2typst.convert_series(iter([])), [])
+
+    def test_to_list(self):
+        class Column:
+            def to_list(self):
+                return [r"\beta", None]
+
+        self.assertEqual(tex2typst.convert_series(Column()), ["beta", None])
+
+    def test_shared_options(self):
+        result = tex2typst.convert_series(
+            [r"\phi \leq \dv{f}{x}", None, r"\begin{myenv} a \end{myenv}"],
+            handle_physics=True,
+            output_unicode=True,
+            symbol_map={"phi": "phi.alt"},
+            environment_map={"myenv": "f"},
+        )
+        self.assertEqual(result, ["ϕ ≤ (dif f)/(dif x)", None, "f(a)"])
+
+    def test_mixed_types(self):
+        with self.assertRaisesRegex(TypeError, "got int at position 2"):
+            tex2typst.convert_series(["x", None, 3, 4.5])
+
+    def test_errors(self):
+        with self.assertRaisesRegex(ValueError, "item 2"):
+            tex2typst.convert_series(["x", None, r"\frac{1}{"])
+        self.assertEqual(
+            tex2typst.convert_series(
+                ["x", None, r"\frac{1}{"], keep_original_on_error=True
+            ),
+            ["x", None, r"\frac{1}{"],
+        )
+
+
+@unittest.skipIf(pd is None, "pandas is not installed")
+class TestConvertPandasSeries(unittest.TestCase):
+    """Test convert_series on pandas Series"""
+
+    def test_nan_holes(self):
+        s = pd.Series([r"\alpha", float("nan"), None, r"\frac{1}{2}", pd.NA])
+        result = tex2typst.convert_series(s, frac_to_slash=False)
+        self.assertEqual(to_list(result), ["alpha", None, None, "frac(1, 2)", None])
+
+    def test_mixed_dtype(self):
+        with self.assertRaisesRegex(TypeError, "position 1"):
+            tex2typst.convert_series(pd.Series([r"\alpha", 2.5, "x"]))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,369 @@
+"""Test the Converter class and its result cache."""
+
+import os
+import pickle
+import tempfile
+import threading
+import unittest
+import tex2typst
+
+
+class TestConverter(unittest.TestCase):
+    """Test Converter conversions and default options"""
+
+    def test_convert(self):
+        conv = tex2typst.Converter()
+        self.assertEqual(conv.tex2typst(r"\frac{1}{2}"), "1/2")
+        self.assertEqual(conv.typst2tex("alpha"), "\\alpha")
+
+    def test_default_options(self):
+        conv = tex2typst.Converter(frac_to_slash=False, trim_input=True)
+        self.assertEqual(conv.tex2typst(r"  \frac{1}{2} "), "frac(1, 2)")
+        self.assertEqual(conv.tex2typst(r"\frac{1}{2}", frac_to_slash=True), "1/2")
+        # tex2typst defaults do not leak into typst2tex
+        self.assertEqual(conv.typst2tex(" alpha "), "\\alpha")
+
+    def test_max_input_length(self):
+        conv = tex2typst.Converter(max_input_length=3)
+        with self.assertRaises(ValueError):
+            conv.tex2typst(r"\alpha")
+        self.assertEqual(conv.tex2typst(r"\alpha", max_input_length=None), "alpha")
+
+    def test_invalid_options(self):
+        with self.assertRaises(TypeError):
+            tex2typst.Converter(bogus=True)
+        with self.assertRaises(TypeError):
+            tex2typst.Converter().typst2tex("x", frac_to_slash=True)
+
+    def test_errors_propagate(self):
+        with self.assertRaises(ValueError):
+            tex2typst.Converter().tex2typst(r"\frac{1}{")
+
+
+class TestConverterRepr(unittest.TestCase):
+    """Test Converter.__repr__ and __str__"""
+
+    def test_repr(self):
+        version = tex2typst.get_js_bundle_version()
+        self.assertRegex(version, r"^\d+\.\d+\.\d+$")
+        conv = tex2typst.Converter()
+        conv.tex2typst("x")
+        conv.tex2typst("x")
+        conv.tex2typst("y")
+        expected = f'<Converter js_version="{version}" cache_hits=1 cache_misses=2>'
+        self.assertEqual(repr(conv), expected)
+        self.assertEqual(str(conv), expected)
+
+    def test_repr_closed(self):
+        conv = tex2typst.Converter()
+        conv.close()
+        self.assertTrue(repr(conv).endswith(" closed>"))
+
+
+class TestConverterInfo(unittest.TestCase):
+    """Test get_converter_info"""
+
+    def test_fields(self):
+        info = tex2typst.get_converter_info()
+        self.assertEqual(info["rust_crate_version"], tex2typst.__version__)
+        self.assertEqual(info["js_bundle_version"], tex2typst.get_js_bundle_version())
+        for key in ["quickjs_version", "rquickjs_version"]:
+            self.assertRegex(info[key], r"^\d+\.\d+\.\d+")
+
+    def test_does_not_initialize(self):
+        infos = []
+
+        def work():
+            infos.append(tex2typst.get_converter_info())
+            infos.append(tex2typst.get_converter_info())
+            tex2typst.typst2tex(["a", "b"])
+            infos.append(tex2typst.get_converter_info())
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertFalse(infos[1]["thread_converter_initialized"])
+        self.assertEqual(infos[1]["thread_conversion_count"], 0)
+        self.assertEqual(infos[1]["js_bundle_version"], infos[2]["js_bundle_version"])
+        self.assertTrue(infos[2]["thread_converter_initialized"])
+        self.assertEqual(infos[2]["thread_conversion_count"], 2)
+
+class TestConverterClose(unittest.TestCase):
+    """Test Converter.close and context manager support"""
+
+    def test_context_manager(self):
+        with tex2typst.Converter() as conv:
+            self.assertIsInstance(conv, tex2typst.Converter)
+            self.assertEqual(conv.tex2typst(r"\alpha"), "alpha")
+            self.assertFalse(conv.closed)
+        self.assertTrue(conv.closed)
+        with self.assertRaises(RuntimeError):
+            conv.tex2typst(r"\alpha")
+        with self.assertRaises(RuntimeError):
+            conv.typst2tex("alpha")
+
+    def test_close_is_idempotent(self):
+        conv = tex2typst.Converter()
+        conv.close()
+        conv.close()
+        self.assertTrue(conv.closed)
+
+    def test_exception_propagates(self):
+        with self.assertRaises(KeyError):
+            with tex2typst.Converter() as conv:
+                raise KeyError("boom")
+        self.assertTrue(conv.closed)
+
+
+class TestConverterCache(unittest.TestCase):
+    """Test the Converter LRU cache"""
+
+    def test_hits_and_misses(self):
+        conv = tex2typst.Converter(cache_size=8)
+        conv.tex2typst("x^2")
+        conv.tex2typst("x^2")
+        conv.tex2typst("x^2", frac_to_slash=False)
+        conv.typst2tex("x^2")
+        self.assertEqual(
+            conv.cache_info(), {"hits": 1, "misses": 3, "size": 3, "capacity": 8}
+        )
+
+    def test_eviction_is_lru(self):
+        conv = tex2typst.Converter(cache_size=2)
+        conv.tex2typst("a")
+        conv.tex2typst("b")
+        conv.tex2typst("a")  # "b" is now least recently used
+        conv.tex2typst("c")
+        conv.tex2typst("a")
+        self.assertEqual(conv.cache_info()["hits"], 2)
+        conv.tex2typst("b")
+        info = conv.cache_info()
+        self.assertEqual(info["misses"], 4)
+        self.assertEqual(info["size"], 2)
+
+    def test_failures_not_cached(self):
+        conv = tex2typst.Converter()
+        for _ in range(2):
+            with self.assertRaises(ValueError):
+                conv.tex2typst(r"\frac{1}{")
+        self.assertEqual(conv.cache_info()["size"], 0)
+
+    def test_disabled(self):
+        conv = tex2typst.Converter(cache_size=0)
+        conv.tex2typst("x")
+        conv.tex2typst("x")
+        self.assertEqual(
+            conv.cache_info(), {"hits": 0, "misses": 2, "size": 0, "capacity": 0}
+        )
+
+
+class TestConverterPickle(unittest.TestCase):
+    """Test pickling a Converter through __reduce__"""
+
+    def test_round_trip(self):
+        conv = tex2typst.Converter(cache_size=16, frac_to_slash=False, trim_input=True)
+        conv.tex2typst("x")
+        copy = pickle.loads(pickle.dumps(conv))
+        self.assertIsNot(copy, conv)
+        self.assertEqual(copy.tex2typst(r" \frac{1}{2} "), "frac(1, 2)")
+        # Only the options travel; the cache starts empty
+        self.assertEqual(
+            copy.cache_info(), {"hits": 0, "misses": 1, "size": 1, "capacity": 16}
+        )
+
+    def test_reduce(self):
+        conv = tex2typst.Converter(cache_size=4, max_input_length=10)
+        self.assertEqual(
+            conv.__reduce__(),
+            (tex2typst.Converter, ({"max_input_length": 10, "cache_size": 4},)),
+        )
+
+    def test_other_thread(self):
+        # multiprocessing pickles tasks on a separate handler thread
+        conv = tex2typst.Converter(frac_to_slash=False)
+        results = {}
+
+        def work():
+            results["pickled"] = pickle.dumps(conv)
+            try:
+                conv.tex2typst("x")
+            except RuntimeError as e:
+                results["error"] = e
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertIsInstance(results["error"], RuntimeError)
+        copy = pickle.loads(results["pickled"])
+        self.assertEqual(copy.tex2typst(r"\frac{1}{2}"), "frac(1, 2)")
+
+    def test_config_dict(self):
+        conv = tex2typst.Converter({"cache_size": 2, "frac_to_slash": False}, cache_size=3)
+        self.assertEqual(conv.cache_info()["capacity"], 3)
+        self.assertEqual(conv.tex2typst(r"\frac{1}{2}"), "frac(1, 2)")
+        with self.assertRaises(TypeError):
+            tex2typst.Converter({"bogus": 1})
+
+
+class TestConverterBatchGenerator(unittest.TestCase):
+    """Test Converter.tex2typst_batch_generator"""
+
+    def test_lazy(self):
+        pulled = []
+
+        def source():
+            for tex in [r"\alpha", r" \frac{1}{2}", r"\alpha"]:
+                pulled.append(tex)
+                yield tex
+
+        conv = tex2typst.Converter(frac_to_slash=False)
+        results = conv.tex2typst_batch_generator(source(), trim_input=True)
+        self.assertEqual(pulled, [])
+        self.assertEqual(next(results), "alpha")
+        self.assertEqual(len(pulled), 1)
+        self.assertEqual(list(results), ["frac(1, 2)", "alpha"])
+        self.assertEqual(conv.cache_info()["hits"], 1)
+
+    def test_errors(self):
+        conv = tex2typst.Converter()
+        results = conv.tex2typst_batch_generator(["x", r"\frac{1}{", "y"])
+        self.assertEqual(next(results), "x")
+        with self.assertRaisesRegex(ValueError, "item 1 "):
+            next(results)
+        self.assertEqual(list(results), [])
+        with self.assertRaisesRegex(TypeError, "got int at item 1"):
+            list(conv.tex2typst_batch_generator(["x", 2]))
+        with self.assertRaises(TypeError):
+            conv.tex2typst_batch_generator([], bogus=True)
+        conv.close()
+        with self.assertRaises(RuntimeError):
+            conv.tex2typst_batch_generator(["x"])
+
+
+
+# Stands in for a rebuilt bundle
+RELOADED_BUNDLE = """
+var tex2typstVersion = "9.9.9";
+function tex2typst(tex) { return "reloaded " + tex; }
+function typst2tex(typst) { return typst; }
+"""
+
+
+class TestConverterReload(unittest.TestCase):
+    """Test Converter.reload_js_bundle"""
+
+    def setUp(self):
+        fd, self.path = tempfile.mkstemp(suffix=".js")
+        with os.fdopen(fd, "w") as f:
+            f.write(RELOADED_BUNDLE)
+
+    def tearDown(self):
+        os.remove(self.path)
+
+    def test_reload_from_file(self):
+        conv = tex2typst.Converter()
+        self.assertEqual(conv.tex2typst("x"), "x")
+        conv.reload_js_bundle(self.path)
+        self.assertEqual(conv.cache_info()["size"], 0)
+        self.assertEqual(conv.tex2typst("x"), "reloaded x")
+        self.assertIn('js_version="9.9.9"', repr(conv))
+        # Other converters keep their bundle
+        self.assertEqual(tex2typst.Converter().tex2typst("x"), "x")
+        self.assertEqual(tex2typst.tex2typst("x"), "x")
+
+    def test_reload_embedded(self):
+        conv = tex2typst.Converter()
+        conv.reload_js_bundle(self.path)
+        conv.reload_js_bundle()
+        self.assertEqual(conv.tex2typst(r"\alpha"), "alpha")
+        self.assertIn(f'js_version="{tex2typst.get_js_bundle_version()}"', repr(conv))
+
+    def test_reload_changes_disk_cache_key(self):
+        with tempfile.TemporaryDirectory() as cache_dir:
+            tex2typst.enable_disk_cache(cache_dir)
+            try:
+                conv = tex2typst.Converter(cache_size=0)
+                self.assertEqual(conv.tex2typst("x"), "x")
+                conv.reload_js_bundle(self.path)
+                self.assertEqual(conv.tex2typst("x"), "reloaded x")
+                conv.reload_js_bundle()
+                self.assertEqual(conv.tex2typst("x"), "x")
+                stats = tex2typst.disk_cache_stats()
+                self.assertEqual((stats["entries"], stats["hits"]), (2, 1))
+            finally:
+                tex2typst.disable_disk_cache()
+
+    def test_errors(self):
+        conv = tex2typst.Converter()
+        with self.assertRaises(FileNotFoundError):
+            conv.reload_js_bundle(self.path + ".missing")
+        with open(self.path, "w") as f:
+            f.write("function (")
+        with self.assertRaisesRegex(tex2typst.EngineError, "JS Load Error"):
+            conv.reload_js_bundle(self.path)
+        self.assertEqual(conv.tex2typst(r"\alpha"), "alpha")
+        conv.close()
+        with self.assertRaises(RuntimeError):
+            conv.reload_js_bundle()
+
+
+class TestConverterFromJsFile(unittest.TestCase):
+    """Test Converter.from_js_file and tex2typst_from_js_file"""
+
+    def setUp(self):
+        fd, self.path = tempfile.mkstemp(suffix=".js")
+        with os.fdopen(fd, "w") as f:
+            f.write(RELOADED_BUNDLE)
+
+    def tearDown(self):
+        os.remove(self.path)
+
+    def test_from_js_file(self):
+        conv = tex2typst.Converter.from_js_file(self.path, cache_size=8)
+        self.assertEqual(conv.tex2typst("x"), "reloaded x")
+        self.assertEqual(conv.cache_info()["capacity"], 8)
+        self.assertIn('js_version="9.9.9"', repr(conv))
+        # The module-level functions keep the embedded bundle
+        self.assertEqual(tex2typst.tex2typst("x"), "x")
+
+    def test_module_function(self):
+        conv = tex2typst.tex2typst_from_js_file(self.path)
+        self.assertIsInstance(conv, tex2typst.Converter)
+        self.assertEqual(conv.typst2tex("y"), "y")
+        self.assertEqual(conv.tex2typst("x"), "reloaded x")
+
+    def test_pickle_loads_file(self):
+        conv = tex2typst.Converter.from_js_file(self.path, cache_size=8)
+        copy = pickle.loads(pickle.dumps(conv))
+        self.assertEqual(copy.tex2typst("x"), "reloaded x")
+        self.assertEqual(copy.cache_info()["capacity"], 8)
+        conv.reload_js_bundle()
+        copy = pickle.loads(pickle.dumps(conv))
+        self.assertEqual(copy.tex2typst(r"\alpha"), "alpha")
+
+    def test_disk_cache_keyed_by_bundle(self):
+        with tempfile.TemporaryDirectory() as cache_dir:
+            tex2typst.enable_disk_cache(cache_dir)
+            try:
+                self.assertEqual(tex2typst.Converter(cache_size=0).tex2typst("x"), "x")
+                conv = tex2typst.Converter.from_js_file(self.path, cache_size=0)
+                self.assertEqual(conv.tex2typst("x"), "reloaded x")
+                self.assertEqual(tex2typst.Converter(cache_size=0).tex2typst("x"), "x")
+                stats = tex2typst.disk_cache_stats()
+                self.assertEqual((stats["entries"], stats["hits"]), (2, 1))
+            finally:
+                tex2typst.disable_disk_cache()
+
+    def test_errors(self):
+        with self.assertRaises(FileNotFoundError):
+            tex2typst.Converter.from_js_file(self.path + ".missing")
+        with open(self.path, "w") as f:
+            f.write("var tex2typst = 1;")
+        with self.assertRaises(tex2typst.EngineError):
+            tex2typst.Converter.from_js_file(self.path)
+        with self.assertRaises(TypeError):
+            tex2typst.Converter.from_js_file(self.path, bogus=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,60 @@
+"""Test converting duplicate batch inputs once."""
+
+import unittest
+import tex2typst
+
+
+class TestDedupe(unittest.TestCase):
+    """Test dedupe=True for list input"""
+
+    def test_fan_out_order_and_js_calls(self):
+        unique = [f"x_{{{i}}}" for i in range(100)]
+        items = [unique[(i * 37) % 100] for i in range(100_000)]
+        tex2typst.reset_stats()
+        results = tex2typst.tex2typst(items, dedupe=True)
+        self.assertEqual(tex2typst.get_stats()["tex2typst_conversions"], 100)
+        expected = {tex: tex2typst.tex2typst(tex) for tex in unique}
+        self.assertEqual(results, [expected[tex] for tex in items])
+
+    def test_matches_plain_batch(self):
+        items = [r"\alpha", "", r"\frac{1}{", r"\alpha", "", r"\frac{1}{", "x"]
+        options = dict(
+            keep_original_on_error=True, return_failed_indices=True, skip_empty=True
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(items, dedupe=True, **options),
+            tex2typst.tex2typst(items, **options),
+        )
+        results, failed = tex2typst.tex2typst(items, dedupe=True, **options)
+        self.assertEqual(failed, [2, 5])
+
+    def test_error_names_first_item(self):
+        with self.assertRaisesRegex(ValueError, "item 1 "):
+            tex2typst.tex2typst(["a", r"\frac{1}{", "b", r"\frac{1}{"], dedupe=True)
+
+    def test_typst2tex(self):
+        items = ["alpha", "beta", "alpha", "", "beta"]
+        tex2typst.reset_stats()
+        results = tex2typst.typst2tex(items, dedupe=True, skip_empty=True)
+        self.assertEqual(results, ["\\alpha", "\\beta", "\\alpha", None, "\\beta"])
+        self.assertEqual(tex2typst.get_stats()["typst2tex_conversions"], 2)
+
+    def test_hooks_see_every_item(self):
+        seen = []
+
+        def post(typst):
+            seen.append(typst)
+            return typst
+
+        tex2typst.tex2typst(["a", "a", "b"], dedupe=True, post_process=post)
+        self.assertEqual(seen, ["a", "a", "b"])
+
+    def test_requires_list(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst("a", dedupe=True)
+        with self.assertRaises(TypeError):
+            tex2typst.typst2tex("a", dedupe=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
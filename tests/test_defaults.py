@@ -0,0 +1,184 @@
+"""Test process-wide default options."""
+
+import inspect
+import unittest
+import tex2typst
+
+
+class TestDefaultOptions(unittest.TestCase):
+    """Test set_default_options / get_default_options / reset_default_options"""
+
+    def tearDown(self):
+        tex2typst.reset_default_options()
+
+    def test_get_and_reset(self):
+        tex2typst.set_default_options(non_strict=True, infty_to_oo=True)
+        self.assertEqual(
+            tex2typst.get_default_options(), {"non_strict": True, "infty_to_oo": True}
+        )
+
+        tex2typst.reset_default_options()
+        self.assertEqual(tex2typst.get_default_options(), {})
+
+    def test_none_removes_default(self):
+        tex2typst.set_default_options(infty_to_oo=True, optimize=False)
+        tex2typst.set_default_options(infty_to_oo=None)
+        self.assertEqual(tex2typst.get_default_options(), {"optimize": False})
+
+    def test_override_precedence_matrix(self):
+        # (default, per-call, expected infty_to_oo behavior)
+        cases = [
+            (None, None, False),
+            (None, True, True),
+            (None, False, False),
+            (True, None, True),
+            (True, True, True),
+            (True, False, False),
+            (False, None, False),
+            (False, True, True),
+            (False, False, False),
+        ]
+        for default, per_call, expected in cases:
+            with self.subTest(default=default, per_call=per_call):
+                tex2typst.reset_default_options()
+                if default is not None:
+                    tex2typst.set_default_options(infty_to_oo=default)
+
+                expected_output = "oo" if expected else "infinity"
+                self.assertEqual(
+                    tex2typst.tex2typst(r"\infty", infty_to_oo=per_call),
+                    expected_output,
+                )
+                self.assertEqual(
+                    tex2typst.tex2typst([r"\infty"], infty_to_oo=per_call),
+                    [expected_output],
+                )
+
+    def test_ignore_unknown_commands_overridden_per_call(self):
+        tex2typst.set_default_options(ignore_unknown_commands=True)
+        self.assertEqual(tex2typst.tex2typst(r"\notacommand{x}"), r"\notacommand x")
+        self.assertEqual(
+            tex2typst.tex2typst(r"\notacommand{x}", ignore_unknown_commands=False),
+            "notacommand x",
+        )
+        self.assertEqual(
+            tex2typst.tex2typst([r"\notacommand{x}"], ignore_unknown_commands=False),
+            ["notacommand x"],
+        )
+
+    def test_defaults_invalidate_cache(self):
+        self.assertEqual(tex2typst.tex2typst(r"\infty"), "infinity")
+        tex2typst.set_default_options(infty_to_oo=True)
+        self.assertEqual(tex2typst.tex2typst(r"\infty"), "oo")
+
+    def test_custom_tex_macros_default(self):
+        tex2typst.set_default_options(custom_tex_macros={r"\myop": r"\operatorname{myop}"})
+        self.assertIn('op("myop")', tex2typst.tex2typst(r"\myop x"))
+
+    def test_typst2tex_defaults(self):
+        tex2typst.set_default_options(block_math_mode=False)
+        self.assertEqual(
+            tex2typst.typst2tex("x"), tex2typst.typst2tex("x", block_math_mode=False)
+        )
+        self.assertEqual(
+            tex2typst.typst2tex(["x"]), [tex2typst.typst2tex("x", block_math_mode=False)]
+        )
+
+    def test_string_option_default(self):
+        tex2typst.set_default_options(handle_text_mode="verbatim")
+        self.assertEqual(tex2typst.get_default_options(), {"handle_text_mode": "verbatim"})
+        with self.assertRaises(ValueError):
+            tex2typst.set_default_options(handle_text_mode="bogus")
+
+    def test_unknown_option(self):
+        with self.assertRaises(TypeError):
+            tex2typst.set_default_options(nonstrict=True)
+
+    def test_wrong_type(self):
+        with self.assertRaises(TypeError):
+            tex2typst.set_default_options(non_strict="yes")
+        self.assertEqual(tex2typst.get_default_options(), {})
+
+
+
+class TestMergeOptions(unittest.TestCase):
+    """Test merge_options"""
+
+    def test_later_dicts_win(self):
+        merged = tex2typst.merge_options(
+            {"frac_to_slash": False, "non_strict": True},
+            {"frac_to_slash": True, "wrap": "block"},
+        )
+        self.assertEqual(merged, {"frac_to_slash": True, "non_strict": True, "wrap": "block"})
+        self.assertEqual(tex2typst.merge_options(), {})
+        self.assertEqual(tex2typst.tex2typst(r"\frac{1}{2}", **merged), "$ 1/2 $")
+
+    def test_macros_merge_by_key(self):
+        first = {"custom_tex_macros": {r"\R": r"\mathbb{R}", r"\N": "N"}}
+        merged = tex2typst.merge_options(first, {"custom_tex_macros": {r"\N": r"\mathbb{N}"}})
+        self.assertEqual(
+            merged["custom_tex_macros"], {r"\R": r"\mathbb{R}", r"\N": r"\mathbb{N}"}
+        )
+        # The inputs are left untouched
+        self.assertEqual(first["custom_tex_macros"][r"\N"], "N")
+
+    def test_none_removes(self):
+        self.assertEqual(
+            tex2typst.merge_options({"optimize": True, "trim_input": True}, {"optimize": None}),
+            {"trim_input": True},
+        )
+
+    def test_validation(self):
+        with self.assertRaisesRegex(TypeError, "did you mean 'non_strict'"):
+            tex2typst.merge_options({"nonstrict": True})
+        with self.assertRaisesRegex(TypeError, "Unknown option 'bogus'$"):
+            tex2typst.merge_options({"bogus": 1})
+        bad_types = [
+            {"frac_to_slash": 1},
+            {"trim_input": "yes"},
+            {"max_input_length": -1},
+            {"custom_tex_macros": {r"\R": 1}},
+            {"custom_tex_macros": [r"\R"]},
+            {"post_process": "upper"},
+        ]
+        for options in bad_types:
+            with self.subTest(options=options):
+                with self.assertRaises(TypeError):
+                    tex2typst.merge_options({}, options)
+        with self.assertRaises(ValueError):
+            tex2typst.merge_options({"wrap": "display"})
+        with self.assertRaisesRegex(TypeError, "argument 2 must be a dict"):
+            tex2typst.merge_options({}, [("optimize", True)])
+
+
+class TestSupportedOptions(unittest.TestCase):
+    """Test get_supported_options"""
+
+    def test_matches_signatures(self):
+        options = tex2typst.get_supported_options()
+        for name, function in [("tex2typst", tex2typst.tex2typst), ("typst2tex", tex2typst.typst2tex)]:
+            params = set(inspect.signature(function).parameters) - {"tex", "typst"}
+            listed = {n for n, info in options.items() if name in info["functions"]}
+            self.assertEqual(listed, params, name)
+
+    def test_entries(self):
+        options = tex2typst.get_supported_options()
+        self.assertEqual(
+            options["frac_to_slash"],
+            {
+                "type": "bool",
+                "default": True,
+                "functions": ["tex2typst"],
+                "description": "Convert fractions to slash notation",
+            },
+        )
+        self.assertEqual(options["max_nesting_depth"]["default"], 512)
+        self.assertEqual(options["handle_text_mode"]["choices"], ["verbatim", "convert", "strip"])
+        # Every listed option passes validation with its own default
+        for name, info in options.items():
+            if info["default"] is not None:
+                tex2typst.merge_options({name: info["default"]})
+
+
+if __name__ == "__main__":
+    unittest.main()
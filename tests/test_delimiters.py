@@ -0,0 +1,161 @@
+"""Test stripping math delimiters from TeX input."""
+
+import unittest
+import tex2typst
+
+
+class TestAutoStripDelimiters(unittest.TestCase):
+    """Test auto_strip_delimiters and wrap='auto'"""
+
+    def test_strip_each_delimiter(self):
+        for tex in ["$x^2$", "$$x^2$$", r"\(x^2\)", r"\[x^2\]"]:
+            with self.subTest(tex=tex):
+                self.assertEqual(
+                    tex2typst.tex2typst(tex, auto_strip_delimiters=True), "x^2"
+                )
+
+    def test_whitespace_padded(self):
+        self.assertEqual(
+            tex2typst.tex2typst("  $ x^2 $\n", auto_strip_delimiters=True), "x^2"
+        )
+        self.assertEqual(
+            tex2typst.tex2typst("\t\\[\n  x^2\n\\]  ", auto_strip_delimiters=True),
+            "x^2",
+        )
+
+    def test_undelimited_input_unchanged(self):
+        self.assertEqual(tex2typst.tex2typst("x^2", auto_strip_delimiters=True), "x^2")
+        # A line break `\\[2pt]` is not a display math delimiter
+        self.assertEqual(
+            tex2typst.tex2typst(r"a \\[2pt] b", auto_strip_delimiters=True),
+            tex2typst.tex2typst(r"a \\[2pt] b"),
+        )
+
+    def test_currency_dollar(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\text{price: $5} + x", auto_strip_delimiters=True),
+            '"price: $5" + x',
+        )
+        # An escaped dollar inside delimited math is not a delimiter
+        self.assertEqual(
+            tex2typst.tex2typst(r"$\text{costs \$5}$", auto_strip_delimiters=True),
+            tex2typst.tex2typst(r"\text{costs \$5}"),
+        )
+        with self.assertRaisesRegex(ValueError, "never closed"):
+            tex2typst.tex2typst("$5 + x", auto_strip_delimiters=True)
+
+    def test_unbalanced(self):
+        cases = {
+            "$x": "never closed",
+            "x$": "never opened",
+            r"\(x": "never closed",
+            r"x \]": "never opened",
+            r"\[x\)": r"closed by '\\\)'",
+            "$": "never closed",
+        }
+        for tex, message in cases.items():
+            with self.subTest(tex=tex):
+                with self.assertRaisesRegex(ValueError, message):
+                    tex2typst.tex2typst(tex, auto_strip_delimiters=True)
+
+    def test_nested(self):
+        for tex in ["$x$ + $y$", r"\[ \[x\] \]", r"$$ \(x\) $$"]:
+            with self.subTest(tex=tex):
+                with self.assertRaisesRegex(ValueError, "Nested or repeated"):
+                    tex2typst.tex2typst(tex, auto_strip_delimiters=True)
+
+    def test_batch_error_names_item(self):
+        with self.assertRaisesRegex(ValueError, "item 1: "):
+            tex2typst.tex2typst(["$x$", "$y"], auto_strip_delimiters=True)
+
+    def test_wrap_auto(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                ["$x^2$", "$$ y $$", r"\(a\)", r"\[b\]", "z", ""],
+                auto_strip_delimiters=True,
+                wrap="auto",
+                skip_empty=True,
+            ),
+            ["$x^2$", "$ y $", "$a$", "$ b $", "z", None],
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\[x\]", auto_strip_delimiters=True, wrap="auto"),
+            "$ x $",
+        )
+        # An explicit mode overrides the style of the stripped delimiters
+        self.assertEqual(
+            tex2typst.tex2typst(r"\[x\]", auto_strip_delimiters=True, wrap="inline"),
+            "$x$",
+        )
+
+    def test_wrap_auto_requires_strip(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", wrap="auto")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["x"], wrap="auto")
+
+
+class TestTypst2TexDelimiters(unittest.TestCase):
+    """Test delimiters and trailing_newline of typst2tex"""
+
+    def test_latex(self):
+        self.assertEqual(
+            tex2typst.typst2tex("x^2", block_math_mode=False, delimiters="latex"),
+            r"\(x^2\)",
+        )
+        self.assertEqual(
+            tex2typst.typst2tex("x^2", block_math_mode=True, delimiters="latex"),
+            r"\[x^2\]",
+        )
+
+    def test_dollars(self):
+        self.assertEqual(
+            tex2typst.typst2tex("x^2", block_math_mode=False, delimiters="dollars"),
+            "$x^2$",
+        )
+        self.assertEqual(
+            tex2typst.typst2tex("x^2", block_math_mode=True, delimiters="dollars"),
+            "$$x^2$$",
+        )
+
+    def test_block_math_mode_defaults_to_display(self):
+        self.assertEqual(tex2typst.typst2tex("x", delimiters="latex"), r"\[x\]")
+
+    def test_none(self):
+        self.assertEqual(tex2typst.typst2tex("x^2", delimiters="none"), "x^2")
+
+    def test_trailing_newline(self):
+        self.assertEqual(
+            tex2typst.typst2tex("x", delimiters="latex", trailing_newline=True),
+            "\\[x\\]\n",
+        )
+        # Inline and bare output never get one
+        self.assertEqual(
+            tex2typst.typst2tex(
+                "x", block_math_mode=False, delimiters="latex", trailing_newline=True
+            ),
+            r"\(x\)",
+        )
+        self.assertEqual(tex2typst.typst2tex("x", trailing_newline=True), "x")
+
+    def test_batch(self):
+        self.assertEqual(
+            tex2typst.typst2tex(
+                ["alpha", "", "x"],
+                block_math_mode=False,
+                delimiters="dollars",
+                skip_empty=True,
+                post_process=str.upper,
+            ),
+            ["$\\ALPHA$", None, "$X$"],
+        )
+
+    def test_invalid(self):
+        with self.assertRaises(ValueError):
+            tex2typst.typst2tex("x", delimiters="brackets")
+        with self.assertRaises(ValueError):
+            tex2typst.typst2tex(["x"], delimiters="brackets")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,123 @@
+"""Test input format detection and automatic conversion."""
+
+import unittest
+import tex2typst
+
+# Labeled snippets: (input, expected detect_format result)
+LABELED_SNIPPETS = [
+    # TeX
+    (r"\frac{1}{2}", "tex"),
+    (r"\alpha + \beta", "tex"),
+    (r"x^{2}", "tex"),
+    (r"\sqrt{x}", "tex"),
+    (r"\sum_{i=1}^{n} i", "tex"),
+    (r"\int_0^1 f(x)\,dx", "tex"),
+    (r"\left( x \right)", "tex"),
+    (r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}", "tex"),
+    (r"\mathbb{R}", "tex"),
+    (r"e^{i\pi}", "tex"),
+    (r"a_{ij}", "tex"),
+    (r"\lim_{x \to 0} \frac{\sin x}{x}", "tex"),
+    (r"\text{if } x > 0", "tex"),
+    (r"\hat{x}", "tex"),
+    (r"{a \over b}", "tex"),
+    (r"\infty", "tex"),
+    (r"x \leq y", "tex"),
+    (r"a \cdot b", "tex"),
+    (r"\{1, 2\} \cup A", "tex"),
+    (r"\operatorname{max}(a, b)", "tex"),
+    (r"\mathrm{d}x", "tex"),
+    (r"f'(x) = \lim_{h \to 0}", "tex"),
+    # Typst
+    ("frac(1, 2)", "typst"),
+    ("alpha + beta", "typst"),
+    ("x^(2 n)", "typst"),
+    ("sqrt(x)", "typst"),
+    ("sum_(i=1)^n i", "typst"),
+    ("integral_0^1 f(x) dif x", "typst"),
+    ("lr(( x ))", "typst"),
+    ("mat(a, b; c, d)", "typst"),
+    ("e^(i pi)", "typst"),
+    ("a_(i j)", "typst"),
+    ("lim_(x -> 0)", "typst"),
+    ('"if" x > 0', "typst"),
+    ("hat(x)", "typst"),
+    ("infinity", "typst"),
+    ("x <= y", "typst"),
+    ("a dot b", "typst"),
+    ("#sym.arrow", "typst"),
+    ("x arrow.r y", "typst"),
+    ("a != b", "typst"),
+    ("floor(x/2)", "typst"),
+    ("x in RR", "typst"),
+    ("x |-> y", "typst"),
+    ("binom(n, k)", "typst"),
+    # Ambiguous
+    ("x + y", "ambiguous"),
+    ("a = b", "ambiguous"),
+    ("1/2", "ambiguous"),
+    ("x^2", "ambiguous"),
+    ("x_1", "ambiguous"),
+    ("f(x)", "ambiguous"),
+    ("", "ambiguous"),
+    (r"\alpha -> beta", "ambiguous"),
+]
+
+# Minimum share of labeled snippets that must be classified exactly
+MIN_ACCURACY = 0.9
+
+
+class TestDetectFormat(unittest.TestCase):
+    """Test detect_format over a labeled corpus"""
+
+    def test_accuracy(self):
+        correct = sum(
+            tex2typst.detect_format(snippet) == label
+            for snippet, label in LABELED_SNIPPETS
+        )
+        self.assertGreaterEqual(correct / len(LABELED_SNIPPETS), MIN_ACCURACY)
+
+    def test_never_reversed(self):
+        """TeX is never reported as Typst or vice versa"""
+        for snippet, label in LABELED_SNIPPETS:
+            if label == "ambiguous":
+                continue
+            with self.subTest(snippet=snippet):
+                self.assertIn(tex2typst.detect_format(snippet), (label, "ambiguous"))
+
+
+class TestConvertAuto(unittest.TestCase):
+    """Test convert_auto"""
+
+    def test_tex_to_typst(self):
+        self.assertEqual(tex2typst.convert_auto(r"\frac{1}{2}"), "1/2")
+
+    def test_already_target(self):
+        self.assertEqual(tex2typst.convert_auto("frac(1, 2)"), "frac(1, 2)")
+        self.assertEqual(
+            tex2typst.convert_auto(r"\alpha + \beta", target="tex"), r"\alpha + \beta"
+        )
+
+    def test_typst_to_tex(self):
+        self.assertEqual(tex2typst.convert_auto("alpha + beta", target="tex"), r"\alpha + \beta")
+
+    def test_options(self):
+        result = tex2typst.convert_auto(r"\frac{1}{2}", frac_to_slash=False)
+        self.assertIn("frac", result)
+
+        with self.assertRaises(TypeError):
+            tex2typst.convert_auto(r"\frac{1}{2}", target="tex", frac_to_slash=False)
+
+    def test_ambiguous(self):
+        self.assertEqual(tex2typst.convert_auto("x + y"), "x + y")
+
+        with self.assertRaises(ValueError):
+            tex2typst.convert_auto("x + y", strict_detection=True)
+
+    def test_invalid_target(self):
+        with self.assertRaises(ValueError):
+            tex2typst.convert_auto("x", target="markdown")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,88 @@
+"""Test the persistent disk cache."""
+
+import os
+import subprocess
+import sys
+import tempfile
+import unittest
+import tex2typst
+
+
+class TestDiskCache(unittest.TestCase):
+    """Test enable_disk_cache, disk_cache_stats and disable_disk_cache"""
+
+    def setUp(self):
+        self.tmpdir = tempfile.TemporaryDirectory()
+        self.path = os.path.join(self.tmpdir.name, "cache")
+        tex2typst.clear_cache()
+
+    def tearDown(self):
+        tex2typst.disable_disk_cache()
+        tex2typst.clear_cache()
+        self.tmpdir.cleanup()
+
+    def test_disabled_stats(self):
+        tex2typst.disable_disk_cache()
+        stats = tex2typst.disk_cache_stats()
+        self.assertFalse(stats["enabled"])
+        self.assertIsNone(stats["path"])
+
+    def test_hits_across_enables(self):
+        tex2typst.enable_disk_cache(self.path)
+        self.assertEqual(tex2typst.tex2typst([r"\alpha", r"\frac{1}{2}"]), ["alpha", "1/2"])
+        self.assertEqual(tex2typst.typst2tex(["beta"]), ["\\beta"])
+        stats = tex2typst.disk_cache_stats()
+        self.assertEqual((stats["entries"], stats["hits"], stats["misses"]), (3, 0, 3))
+        self.assertGreater(stats["size_bytes"], 0)
+
+        # A fresh enable reads the shard back, as a new process would
+        tex2typst.disable_disk_cache()
+        tex2typst.enable_disk_cache(self.path)
+        self.assertEqual(tex2typst.disk_cache_stats()["entries"], 3)
+        self.assertEqual(tex2typst.tex2typst([r"\frac{1}{2}", "x"]), ["1/2", "x"])
+        stats = tex2typst.disk_cache_stats()
+        self.assertEqual((stats["hits"], stats["misses"]), (1, 1))
+
+    def test_options_are_part_of_the_key(self):
+        tex2typst.enable_disk_cache(self.path)
+        self.assertEqual(tex2typst.tex2typst([r"\frac{1}{2}"]), ["1/2"])
+        self.assertEqual(
+            tex2typst.tex2typst([r"\frac{1}{2}"], frac_to_slash=False), ["frac(1, 2)"]
+        )
+        self.assertEqual(tex2typst.disk_cache_stats()["hits"], 0)
+
+    def test_failures_not_cached(self):
+        tex2typst.enable_disk_cache(self.path)
+        results = tex2typst.tex2typst([r"\frac{1}{"], keep_original_on_error=True)
+        self.assertEqual(results, [r"\frac{1}{"])
+        self.assertEqual(tex2typst.disk_cache_stats()["entries"], 0)
+
+    def test_size_limit(self):
+        tex2typst.enable_disk_cache(self.path, max_size_mb=0)
+        self.assertEqual(tex2typst.tex2typst([r"\alpha"]), ["alpha"])
+        self.assertEqual(tex2typst.disk_cache_stats()["entries"], 0)
+        with self.assertRaises(ValueError):
+            tex2typst.enable_disk_cache(self.path, max_size_mb=-1)
+
+    def test_torn_lines_are_skipped(self):
+        os.makedirs(self.path)
+        with open(os.path.join(self.path, "shard-1.log"), "w") as f:
+            f.write("not a cache line\n" + "ab" * 32 + ' "trunc')
+        tex2typst.enable_disk_cache(self.path)
+        self.assertEqual(tex2typst.disk_cache_stats()["entries"], 0)
+        self.assertEqual(tex2typst.tex2typst([r"\alpha"]), ["alpha"])
+
+    def test_shared_between_processes(self):
+        script = (
+            "import sys, tex2typst;"
+            "tex2typst.enable_disk_cache(sys.argv[1]);"
+            "tex2typst.tex2typst([r'\\gamma'])"
+        )
+        subprocess.run([sys.executable, "-c", script, self.path], check=True)
+        tex2typst.enable_disk_cache(self.path)
+        self.assertEqual(tex2typst.tex2typst([r"\gamma"]), ["gamma"])
+        self.assertEqual(tex2typst.disk_cache_stats()["hits"], 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
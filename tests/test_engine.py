@@ -0,0 +1,54 @@
+"""Test engine initialization errors."""
+
+import unittest
+import tex2typst
+
+HAS_FEATURE = hasattr(tex2typst._tex2typst_core, "_tex2typst_with_bundle")
+
+
+class TestEngineError(unittest.TestCase):
+    """Test the EngineError exception type"""
+
+    def test_is_runtime_error(self):
+        self.assertTrue(issubclass(tex2typst.EngineError, RuntimeError))
+        self.assertIs(tex2typst.EngineError, tex2typst._tex2typst_core.EngineError)
+
+
+@unittest.skipUnless(HAS_FEATURE, "built without the 'test-bundle' feature")
+class TestBrokenBundle(unittest.TestCase):
+    """Test converters started from a replacement bundle"""
+
+    def convert(self, source, tex="x"):
+        return tex2typst._tex2typst_core._tex2typst_with_bundle(source, tex)
+
+    def test_working_bundle(self):
+        source = (
+            "function tex2typst(tex) { return tex.toUpperCase(); }\n"
+            "function typst2tex(typst) { return typst; }"
+        )
+        self.assertEqual(self.convert(source, "abc"), "ABC")
+
+    def test_missing_function(self):
+        source = "function tex2typst(tex) { return tex; }"
+        with self.assertRaisesRegex(
+            tex2typst.EngineError, "'typst2tex' not found; the JS bundle appears corrupted"
+        ):
+            self.convert(source)
+
+    def test_not_a_function(self):
+        source = "var tex2typst = 1; function typst2tex(typst) { return typst; }"
+        with self.assertRaisesRegex(tex2typst.EngineError, "'tex2typst' not found"):
+            self.convert(source)
+
+    def test_syntax_error(self):
+        with self.assertRaisesRegex(tex2typst.EngineError, "JS Load Error"):
+            self.convert("function tex2typst(")
+
+    def test_builtin_converter_unaffected(self):
+        with self.assertRaises(tex2typst.EngineError):
+            self.convert("")
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "alpha")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,76 @@
+"""Test translating TeX environments with environment_map."""
+
+import unittest
+import tex2typst
+
+
+class TestEnvironmentMap(unittest.TestCase):
+    def test_single_cell(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\begin{myenv} x+1 \end{myenv}^2", environment_map={"myenv": "myenv"}
+            ),
+            "myenv(x + 1)^2",
+        )
+
+    def test_rows_and_cells(self):
+        # A trailing row separator does not add an empty row
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\begin{grid} a & \frac{1}{2} \\ c & {d \& e} \\ \end{grid}",
+                environment_map={"grid": "mat"},
+            ),
+            "mat(a, 1/2; c, d \\& e)",
+        )
+
+    def test_nesting(self):
+        environments = {"outer": "f", "inner": "g"}
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\begin{outer} \begin{inner} a & b \end{inner} & \alpha \end{outer}",
+                environment_map=environments,
+                symbol_map={"alpha": "A"},
+            ),
+            "f(g(a, b), A)",
+        )
+        # Known environments keep their own row and cell handling
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\begin{outer} \begin{cases} a & b \\ c & d \end{cases} \end{outer}",
+                environment_map=environments,
+            ),
+            "f(cases(a & b, c & d))",
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\begin{cases} \begin{inner} x \end{inner} & y \end{cases}",
+                environment_map=environments,
+            ),
+            "cases(g(x) & y)",
+        )
+
+    def test_list_and_converter(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                [r"\begin{v} x \end{v}", "y"], environment_map={"v": "vec"}
+            ),
+            ["vec(x)", "y"],
+        )
+        conv = tex2typst.Converter(environment_map={"v": "vec"})
+        self.assertEqual(conv.tex2typst(r"\begin{v} 1 \\ 2 \end{v}"), "vec(1; 2)")
+
+    def test_unmapped_and_unclosed(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\begin{pmatrix} a \end{pmatrix}", environment_map={"v": "vec"}),
+            tex2typst.tex2typst(r"\begin{pmatrix} a \end{pmatrix}"),
+        )
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(r"\begin{v} x", environment_map={"v": "vec"})
+
+    def test_invalid_values(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\begin{v} x \end{v}", environment_map={"v": None})
+
+
+if __name__ == "__main__":
+    unittest.main()
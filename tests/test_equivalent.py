@@ -0,0 +1,60 @@
+"""Test typst_equivalent, typst_equivalent_batch and diff_typst."""
+
+import unittest
+import tex2typst
+
+# Pairs that differ only cosmetically
+EQUIVALENT = [
+    ("a+b=c", "a  +  b\n= c"),
+    ("lr((a + b))/2", "(a+b)/2"),
+    ("mat(1,2;3,4)", "mat( 1 , 2 ; 3 , 4 )"),
+    ("0.50 x", "0.5 x"),
+    ("f(x) = - x", "f(x)=-x"),
+]
+
+# Pairs that differ in meaning
+DIFFERENT = [
+    ("a b", "ab"),
+    ('"a  b"', '"a b"'),
+    ("x^2", "x^3"),
+    ("f(x)", "f (x)"),
+    ("1/2", "2/1"),
+]
+
+
+class TestTypstEquivalent(unittest.TestCase):
+    """Test comparing Typst math by meaning"""
+
+    def test_equivalent(self):
+        for a, b in EQUIVALENT:
+            with self.subTest(a=a, b=b):
+                self.assertTrue(tex2typst.typst_equivalent(a, b))
+                self.assertIsNone(tex2typst.diff_typst(a, b))
+
+    def test_different(self):
+        for a, b in DIFFERENT:
+            with self.subTest(a=a, b=b):
+                self.assertFalse(tex2typst.typst_equivalent(a, b))
+                self.assertIsNotNone(tex2typst.diff_typst(a, b))
+
+    def test_numbers_as_written(self):
+        self.assertFalse(tex2typst.typst_equivalent("0.50", "0.5", numbers_by_value=False))
+        self.assertTrue(tex2typst.typst_equivalent("0.5", "0.5", numbers_by_value=False))
+
+    def test_diff(self):
+        self.assertEqual(tex2typst.diff_typst("x^2 + 1", "x^3+1"), "x^[-2-]{+3+} + 1")
+        self.assertEqual(tex2typst.diff_typst("a + b", "a + b + c"), "a + b {++ c+}")
+
+    def test_batch(self):
+        list_a = [a for a, _ in EQUIVALENT + DIFFERENT]
+        list_b = [b for _, b in EQUIVALENT + DIFFERENT]
+        self.assertEqual(
+            tex2typst.typst_equivalent_batch(list_a, list_b),
+            [True] * len(EQUIVALENT) + [False] * len(DIFFERENT),
+        )
+        with self.assertRaises(ValueError):
+            tex2typst.typst_equivalent_batch(["a"], [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -10,3 +10,65 @@ class TestTexError(unittest.TestCase):
             )
         except Exception as e:
             self.assertIn("Conversion failed:", str(e))
+
+
+class TestErrorInput(unittest.TestCase):
+    """Test that errors quote a preview of the input and attach the full input"""
+
+    def test_large_batch_item(self):
+        big = "}" + " " * 1_000_000
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.tex2typst(["a", "", big], skip_empty=True)
+        message = str(ctx.exception)
+        self.assertLess(len(message), 500)
+        self.assertIn("item 2 (1000001 bytes, '}", message)
+        self.assertIn("...'", message)
+        self.assertEqual(ctx.exception.input, big)
+
+    def test_large_single_input(self):
+        big = "}" + " " * 1_000_000
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.tex2typst(big)
+        self.assertLess(len(str(ctx.exception)), 500)
+        self.assertEqual(ctx.exception.input, big)
+
+    def test_short_input_not_truncated(self):
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.tex2typst([r"\alpha", r"\frac{1}{"])
+        self.assertIn("item 1 (9 bytes, '\\frac{1}{')", str(ctx.exception))
+        self.assertEqual(ctx.exception.input, r"\frac{1}{")
+
+
+class TestJsExceptionFields(unittest.TestCase):
+    """Test the structured JS exception attributes"""
+
+    def assert_fields(self, exception, message):
+        self.assertEqual(exception.js_message, message)
+        self.assertTrue(exception.js_stack.lstrip().startswith("at "))
+        self.assertIn(exception.js_stack, str(exception))
+        line, column = exception.js_position
+        self.assertIn(f":{line}:{column}", exception.js_stack.splitlines()[0])
+
+    def test_tex2typst(self):
+        for tex in [r"\frac{1}{", [r"\alpha", r"\frac{1}{"]]:
+            with self.subTest(tex=tex):
+                with self.assertRaises(ValueError) as ctx:
+                    tex2typst.tex2typst(tex)
+                self.assert_fields(ctx.exception, "Unmatched '\\{'")
+
+    def test_typst2tex(self):
+        for typst in ["(", ["x", "("]]:
+            with self.subTest(typst=typst):
+                with self.assertRaises(ValueError) as ctx:
+                    tex2typst.typst2tex(typst)
+                self.assert_fields(ctx.exception, "Unmatched '('")
+
+    def test_converter(self):
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.Converter().tex2typst(r"\frac{1}{")
+        self.assertEqual(ctx.exception.js_message, "Unmatched '\\{'")
+
+    def test_not_from_js(self):
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.tex2typst(r"\alpha", max_input_length=1)
+        self.assertFalse(hasattr(ctx.exception, "js_message"))
@@ -0,0 +1,38 @@
+"""Test explain_conversion."""
+
+import unittest
+import tex2typst
+
+
+class TestExplainConversion(unittest.TestCase):
+    """Test the annotated conversion output"""
+
+    def test_input_comment(self):
+        self.assertEqual(
+            tex2typst.explain_conversion(r"\frac{1}{2}"),
+            "/* input: \\frac{1}{2} */\n1/2",
+        )
+
+    def test_options_forwarded(self):
+        explained = tex2typst.explain_conversion(r"\frac{1}{2}", frac_to_slash=False)
+        self.assertTrue(explained.endswith("\nfrac(1, 2)"))
+        explained = tex2typst.explain_conversion(
+            r"\myop", custom_tex_macros={r"\myop": r"\operatorname{myop}"}
+        )
+        self.assertTrue(explained.endswith('\nop("myop")'))
+
+    def test_comment_cannot_close_early(self):
+        explained = tex2typst.explain_conversion(r"\text{*/ or /*}")
+        comment, _ = explained.split("\n", 1)
+        self.assertEqual(comment.count("*/"), 1)
+        self.assertEqual(comment.count("/*"), 1)
+
+    def test_errors(self):
+        with self.assertRaises(ValueError):
+            tex2typst.explain_conversion(r"\frac{1}{")
+        with self.assertRaises(ValueError):
+            tex2typst.explain_conversion(r"\alpha", max_input_length=2)
+
+
+if __name__ == "__main__":
+    unittest.main()
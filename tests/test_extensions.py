@@ -0,0 +1,82 @@
+"""Test JavaScript extensions evaluated after the bundle."""
+
+import threading
+import unittest
+import tex2typst
+
+TAG_EXTENSION = """
+(() => {
+    const base = tex2typst;
+    tex2typst = (tex, options) => base(tex, options) + " /* ext */";
+})();
+"""
+
+
+class TestJsExtensions(unittest.TestCase):
+    """Test register_js_extension and clear_js_extensions"""
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+
+    def test_override_tags_output(self):
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "alpha")
+        tex2typst.register_js_extension(TAG_EXTENSION)
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "alpha /* ext */")
+        self.assertEqual(tex2typst.tex2typst([r"\beta"]), ["beta /* ext */"])
+
+    def test_registration_order(self):
+        tex2typst.register_js_extension(TAG_EXTENSION)
+        tex2typst.register_js_extension(
+            "(() => { const base = tex2typst;"
+            " tex2typst = (tex, options) => '[' + base(tex, options) + ']'; })();"
+        )
+        self.assertEqual(tex2typst.tex2typst("x"), "[x /* ext */]")
+
+    def test_other_threads(self):
+        tex2typst.tex2typst("x")
+        results = []
+        ready, registered = threading.Event(), threading.Event()
+
+        def work():
+            results.append(tex2typst.typst2tex(["x"]))
+            ready.set()
+            registered.wait()
+            results.append(tex2typst.tex2typst(["x"]))
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        ready.wait()
+        tex2typst.register_js_extension(TAG_EXTENSION)
+        registered.set()
+        thread.join()
+        # The thread's converter was started before and is replaced lazily
+        self.assertEqual(results, [["x"], ["x /* ext */"]])
+
+    def test_clear(self):
+        tex2typst.register_js_extension(TAG_EXTENSION)
+        self.assertEqual(tex2typst.tex2typst("y"), "y /* ext */")
+        tex2typst.clear_js_extensions()
+        self.assertEqual(tex2typst.tex2typst("y"), "y")
+
+    def test_error_not_registered(self):
+        with self.assertRaisesRegex(tex2typst.EngineError, "boom") as ctx:
+            tex2typst.register_js_extension("function fail() { throw new Error('boom'); }\nfail();")
+        self.assertIn("Stack trace", str(ctx.exception))
+        with self.assertRaises(tex2typst.EngineError):
+            tex2typst.register_js_extension("this is not javascript")
+        self.assertEqual(tex2typst.tex2typst("z"), "z")
+
+    def test_removing_converter_function(self):
+        with self.assertRaisesRegex(tex2typst.EngineError, "'typst2tex' not found"):
+            tex2typst.register_js_extension("typst2tex = undefined;")
+        self.assertEqual(tex2typst.typst2tex("alpha"), "\\alpha")
+
+    def test_existing_converter_unaffected(self):
+        conv = tex2typst.Converter()
+        tex2typst.register_js_extension(TAG_EXTENSION)
+        self.assertEqual(conv.tex2typst("x"), "x")
+        self.assertEqual(tex2typst.Converter().tex2typst("x"), "x /* ext */")
+
+
+if __name__ == "__main__":
+    unittest.main()
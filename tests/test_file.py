@@ -0,0 +1,85 @@
+"""Test converting text files with one formula per line."""
+
+import os
+import pathlib
+import tempfile
+import unittest
+import tex2typst
+
+
+class TestTex2TypstFile(unittest.TestCase):
+    """Test tex2typst_file"""
+
+    def setUp(self):
+        self.tmpdir = tempfile.TemporaryDirectory()
+        self.input_path = os.path.join(self.tmpdir.name, "in.tex")
+        self.output_path = os.path.join(self.tmpdir.name, "out.typ")
+
+    def tearDown(self):
+        self.tmpdir.cleanup()
+
+    def write_input(self, lines):
+        with open(self.input_path, "w", encoding="utf-8") as f:
+            f.write("\n".join(lines) + "\n")
+
+    def read_output(self):
+        with open(self.output_path, encoding="utf-8") as f:
+            return [line.rstrip("\n") for line in f]
+
+    def test_basic_conversion(self):
+        self.write_input([r"\alpha", "", r"\frac{1}{2}", "   ", r"x^2"])
+        count = tex2typst.tex2typst_file(self.input_path, self.output_path)
+        self.assertEqual(count, 3)
+        self.assertEqual(self.read_output(), ["alpha", "", "1/2", "", "x^2"])
+
+    def test_path_objects_and_options(self):
+        self.write_input([r"\frac{1}{2}"])
+        count = tex2typst.tex2typst_file(
+            pathlib.Path(self.input_path),
+            pathlib.Path(self.output_path),
+            frac_to_slash=False,
+        )
+        self.assertEqual(count, 1)
+        self.assertEqual(self.read_output(), ["frac(1, 2)"])
+
+    def test_errors_keep_and_empty(self):
+        self.write_input([r"\alpha", r"\frac{1}{", r"\beta"])
+        count = tex2typst.tex2typst_file(self.input_path, self.output_path)
+        self.assertEqual(count, 2)
+        self.assertEqual(self.read_output(), ["alpha", r"\frac{1}{", "beta"])
+
+        count = tex2typst.tex2typst_file(
+            self.input_path, self.output_path, errors="empty"
+        )
+        self.assertEqual(count, 2)
+        self.assertEqual(self.read_output(), ["alpha", "", "beta"])
+
+    def test_errors_raise(self):
+        self.write_input([r"\alpha", r"\frac{1}{"])
+        with self.assertRaisesRegex(ValueError, "^line 2: "):
+            tex2typst.tex2typst_file(self.input_path, self.output_path, errors="raise")
+        self.assertFalse(os.path.exists(self.output_path))
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_file(self.input_path, self.output_path, errors="bogus")
+
+    def test_max_input_length(self):
+        self.write_input(["x", r"\alpha"])
+        count = tex2typst.tex2typst_file(
+            self.input_path, self.output_path, errors="empty", max_input_length=3
+        )
+        self.assertEqual(count, 1)
+        self.assertEqual(self.read_output(), ["x", ""])
+        with self.assertRaisesRegex(ValueError, "line 2: 6 bytes"):
+            tex2typst.tex2typst_file(
+                self.input_path, self.output_path, errors="raise", max_input_length=3
+            )
+
+    def test_missing_input(self):
+        with self.assertRaises(FileNotFoundError):
+            tex2typst.tex2typst_file(
+                os.path.join(self.tmpdir.name, "missing.tex"), self.output_path
+            )
+
+
+if __name__ == "__main__":
+    unittest.main()
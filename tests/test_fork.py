@@ -0,0 +1,76 @@
+"""Test converting in processes forked after the parent converted."""
+
+import multiprocessing
+import os
+import tempfile
+import unittest
+import tex2typst
+
+
+def convert_in_child(tex):
+    tex2typst.reset_stats()
+    typst = tex2typst.typst2tex(tex)
+    return typst, tex2typst.get_stats()["engines_initialized"]
+
+
+def convert_with_disk_cache(tex):
+    tex2typst.tex2typst(tex)
+    return os.getpid()
+
+
+def run_in_child(context, target, *args):
+    """Run target(*args) in a forked child and return its result."""
+    receiver, sender = context.Pipe(duplex=False)
+    child = context.Process(target=lambda: sender.send(target(*args)))
+    child.start()
+    result = receiver.recv()
+    child.join()
+    return result
+
+
+def use_worker(worker):
+    try:
+        return worker.convert("x")
+    except RuntimeError as e:
+        return str(e)
+
+
+@unittest.skipUnless(
+    "fork" in multiprocessing.get_all_start_methods(), "fork is not available"
+)
+class TestFork(unittest.TestCase):
+    def setUp(self):
+        self.context = multiprocessing.get_context("fork")
+
+    def test_child_starts_its_own_engine(self):
+        self.assertEqual(tex2typst.typst2tex("alpha"), r"\alpha")
+        # One child per conversion, each inheriting this thread's engine
+        with self.context.Pool(2, maxtasksperchild=1) as pool:
+            results = pool.map(convert_in_child, ["beta", "gamma", "x^2"])
+        self.assertEqual(results, [(r"\beta", 1), (r"\gamma", 1), ("x^2", 1)])
+        self.assertEqual(tex2typst.typst2tex("delta"), r"\delta")
+
+    def test_child_writes_its_own_disk_cache_shard(self):
+        with tempfile.TemporaryDirectory() as cache_dir:
+            tex2typst.enable_disk_cache(cache_dir)
+            try:
+                self.assertEqual(tex2typst.tex2typst(r"\omega"), "omega")
+                child = run_in_child(self.context, convert_with_disk_cache, r"\chi")
+                self.assertEqual(tex2typst.tex2typst(r"\psi"), "psi")
+            finally:
+                tex2typst.disable_disk_cache()
+            lines = {}
+            for name in os.listdir(cache_dir):
+                with open(os.path.join(cache_dir, name)) as f:
+                    lines[name] = f.read().splitlines()
+        self.assertEqual(len(lines[f"shard-{os.getpid()}.log"]), 2)
+        self.assertEqual(len(lines[f"shard-{child}.log"]), 1)
+
+    def test_inherited_worker_refuses_jobs(self):
+        with tex2typst.WorkerConverter() as worker:
+            self.assertIn("os.fork()", run_in_child(self.context, use_worker, worker))
+            self.assertEqual(worker.convert(r"\alpha"), "alpha")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,64 @@
+"""Test format_typst_math and normalize_output."""
+
+import unittest
+import tex2typst
+
+# Equivalent spellings of the same Typst math and their canonical form
+EQUIVALENT = [
+    (["a+b=c", "a + b = c", "a  +  b\n= c"], "a + b = c"),
+    (["lr((a + b))", "(a+b)", "lr(lr(( a + b )))"], "(a + b)"),
+    (["x ^ 2 _ i", "x^2_i"], "x^2_i"),
+    (["(a+b) / 2", "(a + b)/2", "lr((a+b))/2"], "(a + b)/2"),
+    (["mat(1,2;3,4)", "mat( 1 , 2 ; 3 , 4 )"], "mat(1, 2; 3, 4)"),
+    (["f(x) = - x", "f(x)=-x"], "f(x) = -x"),
+    (["x->y", "x  ->  y"], "x -> y"),
+    (["a times -b", "a times - b"], "a times -b"),
+    (["[ 1/2 ]", "lr([1/2])"], "[1/2]"),
+]
+
+SAMPLES = [
+    "sum_(i = 1)^n i^2",
+    'f(x) = cases(x "if " x > 0, 0 "otherwise")',
+    "lr(|x|) + lr((0, 1]) + lr(angle.l x angle.r)",
+    "a &= b \\ &= c",
+    '"a  b"  +  #h(1em) x',
+    "1.5 times 10^(-10)",
+    "f (x) + f(x)",
+    "integral_0^1 x dif x",
+]
+
+
+class TestFormatTypstMath(unittest.TestCase):
+    """Test the canonical Typst formatter"""
+
+    def test_equivalent_pairs(self):
+        for spellings, canonical in EQUIVALENT:
+            for typst in spellings:
+                with self.subTest(typst=typst):
+                    self.assertEqual(tex2typst.format_typst_math(typst), canonical)
+
+    def test_idempotent(self):
+        for typst in SAMPLES + [s for spellings, _ in EQUIVALENT for s in spellings]:
+            with self.subTest(typst=typst):
+                once = tex2typst.format_typst_math(typst)
+                self.assertEqual(tex2typst.format_typst_math(once), once)
+
+    def test_meaningful_spaces_kept(self):
+        # A space before `(` makes juxtaposition of a call; strings are verbatim
+        self.assertEqual(tex2typst.format_typst_math("f (x) + f(x)"), "f (x) + f(x)")
+        self.assertEqual(tex2typst.format_typst_math('"a  b"  + x y'), '"a  b" + x y')
+        self.assertEqual(tex2typst.format_typst_math("lr(|x|)"), "lr(|x|)")
+        self.assertEqual(tex2typst.format_typst_math("lr((0, 1])"), "lr((0, 1])")
+
+    def test_normalize_output(self):
+        tex = r"\left[ \frac{1}{2} \right]"
+        self.assertEqual(tex2typst.tex2typst(tex), "[ 1/2 ]")
+        self.assertEqual(tex2typst.tex2typst(tex, normalize_output=True), "[1/2]")
+        self.assertEqual(
+            tex2typst.tex2typst([tex, r"\frac{1}{"], normalize_output=True, keep_original_on_error=True),
+            ["[1/2]", r"\frac{1}{"],
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
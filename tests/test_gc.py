@@ -0,0 +1,81 @@
+"""Test JS garbage collection control."""
+
+import sys
+import threading
+import unittest
+import tex2typst
+
+# Leaves a reference cycle behind on every conversion, which only a collection frees
+CYCLE_EXTENSION = r"""
+(() => {
+    const base = tex2typst;
+    tex2typst = (tex, options) => {
+        const a = {};
+        a.self = { a };
+        return base(tex, options);
+    };
+})();
+"""
+
+ITEMS = ["x"] * 100_000
+BOUND = 4 * 1024 * 1024
+
+
+class TestGc(unittest.TestCase):
+    """Test run_gc, memory_usage and the collection threshold and interval"""
+
+    def setUp(self):
+        self.threshold = tex2typst.get_js_gc_threshold()
+        self.interval = tex2typst.get_js_gc_interval()
+        tex2typst.register_js_extension(CYCLE_EXTENSION)
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+        tex2typst.set_js_gc_threshold(self.threshold)
+        tex2typst.set_js_gc_interval(self.interval)
+
+    def test_settings_round_trip(self):
+        self.assertEqual(self.threshold, 256 * 1024)
+        self.assertEqual(self.interval, 10_000)
+        tex2typst.set_js_gc_threshold(None)
+        self.assertIsNone(tex2typst.get_js_gc_threshold())
+        tex2typst.set_js_gc_interval(0)
+        self.assertEqual(tex2typst.get_js_gc_interval(), 0)
+
+    def test_run_gc_reclaims_cycles(self):
+        tex2typst.set_js_gc_threshold(None)
+        tex2typst.set_js_gc_interval(0)
+        tex2typst.tex2typst(["x"] * 1000)
+        before = tex2typst.memory_usage()
+        tex2typst.run_gc()
+        after = tex2typst.memory_usage()
+        self.assertLess(after["obj_count"], before["obj_count"] - 1000)
+        self.assertLess(after["malloc_size"], before["malloc_size"])
+
+    def test_batch_without_gc_grows(self):
+        tex2typst.set_js_gc_threshold(None)
+        tex2typst.set_js_gc_interval(0)
+        tex2typst.tex2typst(ITEMS)
+        self.assertGreater(tex2typst.memory_usage()["malloc_size"], BOUND)
+
+    def test_batch_interval_bounds_memory(self):
+        tex2typst.set_js_gc_threshold(None)
+        tex2typst.set_js_gc_interval(1000)
+        tex2typst.tex2typst(ITEMS)
+        self.assertLess(tex2typst.memory_usage()["malloc_size"], BOUND)
+
+    def test_without_engine(self):
+        results = []
+
+        def worker():
+            results.append(tex2typst.memory_usage())
+            tex2typst.run_gc()
+
+        thread = threading.Thread(target=worker)
+        thread.start()
+        thread.join()
+        self.assertEqual(results, [None])
+
+
+if __name__ == "__main__":
+    sys.exit(unittest.main())
@@ -0,0 +1,71 @@
+"""Test pre_process and post_process hooks on batch conversion."""
+
+import unittest
+import tex2typst
+
+
+class TestBatchHooks(unittest.TestCase):
+    """Test pre_process/post_process for list input"""
+
+    def test_post_process_uppercase(self):
+        self.assertEqual(
+            tex2typst.tex2typst([r"\alpha", r"\frac{1}{2}"], post_process=str.upper),
+            ["ALPHA", "1/2"],
+        )
+        self.assertEqual(
+            tex2typst.typst2tex(["alpha", "beta"], post_process=str.upper),
+            ["\\ALPHA", "\\BETA"],
+        )
+
+    def test_pre_process(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                [r"\R", r"x \in \R"], pre_process=lambda s: s.replace(r"\R", r"\mathbb{R}")
+            ),
+            ["RR", "x in RR"],
+        )
+        self.assertEqual(
+            tex2typst.typst2tex(["RR"], pre_process=lambda s: s.replace("RR", "ZZ")),
+            ["\\mathbb{Z}"],
+        )
+
+    def test_post_process_before_wrap_and_skipped_items(self):
+        seen = []
+
+        def record(typst):
+            seen.append(typst)
+            return typst
+
+        results, failed = tex2typst.tex2typst(
+            ["x", "", r"\frac{1}{"],
+            wrap="inline",
+            skip_empty=True,
+            keep_original_on_error=True,
+            return_failed_indices=True,
+            post_process=record,
+        )
+        self.assertEqual(results, ["$x$", None, r"\frac{1}{"])
+        self.assertEqual(failed, [2])
+        self.assertEqual(seen, ["x"])
+
+    def test_callback_error_names_item(self):
+        def fail_on_beta(typst):
+            if typst == "beta":
+                raise RuntimeError("boom")
+            return typst
+
+        with self.assertRaisesRegex(ValueError, "item 1: post_process raised") as ctx:
+            tex2typst.tex2typst([r"\alpha", r"\beta"], post_process=fail_on_beta)
+        self.assertIsInstance(ctx.exception.__cause__, RuntimeError)
+        with self.assertRaisesRegex(ValueError, "item 0: pre_process raised"):
+            tex2typst.typst2tex(["x"], pre_process=lambda s: 1)
+
+    def test_string_input_rejected(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\alpha", post_process=str.upper)
+        with self.assertRaises(TypeError):
+            tex2typst.typst2tex("alpha", pre_process=str.upper)
+
+
+if __name__ == "__main__":
+    unittest.main()
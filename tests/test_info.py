@@ -0,0 +1,49 @@
+"""Test conversion metadata API."""
+
+import unittest
+import tex2typst
+
+
+class TestConversionInfo(unittest.TestCase):
+    """Test tex2typst_with_info"""
+
+    def test_basic_info(self):
+        info = tex2typst.tex2typst_with_info(r"\alpha + \beta + \alpha")
+
+        self.assertIsInstance(info, tex2typst.ConversionInfo)
+        self.assertEqual(info.typst, "alpha + beta + alpha")
+        self.assertEqual(info.symbols, ["alpha", "beta"])
+        self.assertEqual(info.required_packages, [])
+
+    def test_matches_plain_conversion(self):
+        latex = r"\int_{-\infty}^{\infty} e^{-x^2} dx"
+        info = tex2typst.tex2typst_with_info(latex)
+        self.assertEqual(info.typst, tex2typst.tex2typst(latex))
+        self.assertIn("integral", info.symbols)
+        self.assertIn("infinity", info.symbols)
+
+    def test_string_literals_ignored(self):
+        info = tex2typst.tex2typst_with_info(
+            r"\myop x", custom_tex_macros={r"\myop": r"\operatorname{myop}"}
+        )
+        self.assertIn("op", info.symbols)
+        self.assertNotIn("myop", info.symbols)
+
+    def test_required_packages(self):
+        info = tex2typst.tex2typst_with_info(r"\pdv{f}{x} + \ket{\psi}")
+        self.assertEqual(info.required_packages, ["physica"])
+
+        info = tex2typst.tex2typst_with_info(r"\alpha")
+        self.assertEqual(info.required_packages, [])
+
+    def test_batch_with_info(self):
+        infos = tex2typst.tex2typst_batch_with_info([r"\alpha", r"\frac{1}{2}"])
+
+        self.assertEqual(len(infos), 2)
+        self.assertEqual(infos[0].typst, "alpha")
+        self.assertEqual(infos[0].symbols, ["alpha"])
+        self.assertEqual(infos[1].symbols, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,84 @@
+"""Test set_converter_init_hook."""
+
+import threading
+import unittest
+import tex2typst
+
+
+def on_new_thread(work):
+    """Run work() on a thread without an engine yet and return its result."""
+    result = {}
+
+    def run():
+        try:
+            result["value"] = work()
+        except BaseException as e:
+            result["error"] = e
+
+    thread = threading.Thread(target=run)
+    thread.start()
+    thread.join()
+    if "error" in result:
+        raise result["error"]
+    return result["value"]
+
+
+class TestConverterInitHook(unittest.TestCase):
+    """Test the hook run when a thread's converter starts"""
+
+    def test_runs_once_per_engine(self):
+        def work():
+            calls = []
+            tex2typst.set_converter_init_hook(lambda: calls.append(1))
+            tex2typst.tex2typst([r"\alpha"])
+            tex2typst.tex2typst([r"\beta"])
+            tex2typst.warmup()
+            return len(calls)
+
+        self.assertEqual(on_new_thread(work), 1)
+
+    def test_hook_can_convert(self):
+        def work():
+            seen = []
+            tex2typst.set_converter_init_hook(
+                lambda: seen.append(tex2typst.tex2typst([r"\gamma"])[0])
+            )
+            return tex2typst.tex2typst([r"\delta"]), seen
+
+        self.assertEqual(on_new_thread(work), (["delta"], ["gamma"]))
+
+    def test_exception_propagates(self):
+        def fail():
+            raise RuntimeError("setup failed")
+
+        def work():
+            tex2typst.set_converter_init_hook(fail)
+            tex2typst.warmup()
+
+        with self.assertRaisesRegex(RuntimeError, "setup failed"):
+            on_new_thread(work)
+
+    def test_other_threads_and_clear(self):
+        calls = []
+        tex2typst.set_converter_init_hook(lambda: calls.append(1))
+        try:
+            on_new_thread(tex2typst.warmup)
+        finally:
+            tex2typst.clear_converter_init_hook()
+        self.assertEqual(calls, [])
+
+        def work():
+            tex2typst.set_converter_init_hook(lambda: calls.append(1))
+            tex2typst.clear_converter_init_hook()
+            tex2typst.warmup()
+
+        on_new_thread(work)
+        self.assertEqual(calls, [])
+
+    def test_not_callable(self):
+        with self.assertRaises(TypeError):
+            tex2typst.set_converter_init_hook(42)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,87 @@
+"""Test conversions on isolated JS contexts."""
+
+import unittest
+import tex2typst
+
+# Stands in for a converter bug: converting "\clobber" rewrites a global that every
+# later conversion on the same context reads
+CLOBBER_EXTENSION = r"""
+var clobbered = false;
+(() => {
+    const base = tex2typst;
+    tex2typst = (tex, options) => {
+        if (tex === "\\clobber") {
+            clobbered = true;
+        }
+        return clobbered ? "clobbered" : base(tex, options);
+    };
+})();
+"""
+
+
+class TestIsolated(unittest.TestCase):
+    """Test the isolated option of tex2typst and typst2tex"""
+
+    def setUp(self):
+        tex2typst.register_js_extension(CLOBBER_EXTENSION)
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+
+    def test_clobber_does_not_leak(self):
+        self.assertEqual(tex2typst.tex2typst(r"\clobber", isolated=True), "clobbered")
+        # Neither the thread's context nor the next isolated one saw it
+        self.assertEqual(tex2typst.tex2typst(r"\alpha", isolated=True), "alpha")
+        self.assertEqual(tex2typst.tex2typst(r"\beta"), "beta")
+
+    def test_shared_context_is_not_seen(self):
+        self.assertEqual(tex2typst.tex2typst(r"\clobber"), "clobbered")
+        self.assertEqual(tex2typst.tex2typst(r"\gamma"), "clobbered")
+        self.assertEqual(tex2typst.tex2typst(r"\gamma", isolated=True), "gamma")
+
+    def test_batch_per_item(self):
+        for isolated in ["per_item", True]:
+            self.assertEqual(
+                tex2typst.tex2typst([r"\clobber", r"\delta"], isolated=isolated),
+                ["clobbered", "delta"],
+            )
+        self.assertEqual(tex2typst.tex2typst([r"\zeta"]), ["zeta"])
+
+    def test_batch_per_batch(self):
+        self.assertEqual(
+            tex2typst.tex2typst([r"\clobber", "x"], isolated="per_batch"),
+            ["clobbered", "clobbered"],
+        )
+        # All chunks share the batch's context
+        self.assertEqual(
+            tex2typst.tex2typst(
+                [r"\clobber", "x", "y"], isolated="per_batch", chunk_size=1
+            ),
+            ["clobbered"] * 3,
+        )
+        self.assertEqual(tex2typst.tex2typst(["x"], isolated="per_batch"), ["x"])
+
+    def test_errors_name_item(self):
+        with self.assertRaises(ValueError) as cm:
+            tex2typst.tex2typst(["x", r"\frac{1}{"], isolated="per_item")
+        self.assertIn("item 1", str(cm.exception))
+        results = tex2typst.tex2typst_batch_mixed(["x", r"\frac{1}{"], isolated=True)
+        self.assertEqual(results[0], "x")
+        self.assertIsInstance(results[1], ValueError)
+
+    def test_typst2tex(self):
+        self.assertEqual(tex2typst.typst2tex("alpha", isolated=True), r"\alpha")
+        self.assertEqual(
+            tex2typst.typst2tex(["alpha", "beta"], isolated="per_item"),
+            [r"\alpha", r"\beta"],
+        )
+
+    def test_invalid(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["x"], isolated="per_call")
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(["x"], isolated=1)
+
+
+if __name__ == "__main__":
+    unittest.main()
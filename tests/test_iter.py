@@ -0,0 +1,91 @@
+"""Test the lazy tex2typst_iter streaming API."""
+
+import unittest
+import tex2typst
+
+
+class TestTex2TypstIter(unittest.TestCase):
+    """Test tex2typst_iter chunking and errors"""
+
+    def test_matches_batch(self):
+        items = [rf"\alpha_{i}" for i in range(10)]
+        for chunk_size in [1, 3, 10, 1024]:
+            with self.subTest(chunk_size=chunk_size):
+                results = tex2typst.tex2typst_iter(iter(items), chunk_size=chunk_size)
+                self.assertEqual(list(results), tex2typst.tex2typst(items))
+
+    def test_lazy(self):
+        pulled = []
+
+        def source():
+            for i in range(5):
+                pulled.append(i)
+                yield "x"
+
+        results = tex2typst.tex2typst_iter(source(), chunk_size=2)
+        self.assertEqual(pulled, [])
+        self.assertEqual(next(results), "x")
+        self.assertEqual(pulled, [0, 1])
+        self.assertEqual(next(results), "x")
+        self.assertEqual(next(results), "x")
+        self.assertEqual(pulled, [0, 1, 2, 3])
+        self.assertEqual(list(results), ["x", "x"])
+        self.assertEqual(list(results), [])
+
+    def test_empty(self):
+        self.assertEqual(list(tex2typst.tex2typst_iter([])), [])
+
+    def test_options(self):
+        results = tex2typst.tex2typst_iter([r"\frac{1}{2}"], frac_to_slash=False)
+        self.assertEqual(list(results), ["frac(1, 2)"])
+
+    def test_errors_raise(self):
+        results = tex2typst.tex2typst_iter(
+            ["a", "b", "c", r"\frac{1}{", "d"], chunk_size=2
+        )
+        self.assertEqual([next(results) for _ in range(3)], ["a", "b", "c"])
+        with self.assertRaisesRegex(ValueError, "item 3 ") as ctx:
+            next(results)
+        self.assertEqual(ctx.exception.input, r"\frac{1}{")
+        self.assertEqual(list(results), [])
+
+    def test_errors_keep_and_null(self):
+        items = ["a", r"\frac{1}{", "b"]
+        self.assertEqual(
+            list(tex2typst.tex2typst_iter(items, errors="keep")),
+            ["a", r"\frac{1}{", "b"],
+        )
+        self.assertEqual(
+            list(tex2typst.tex2typst_iter(items, errors="null")), ["a", None, "b"]
+        )
+
+    def test_source_error(self):
+        def source():
+            yield "a"
+            yield "b"
+            raise KeyError("source failed")
+
+        results = tex2typst.tex2typst_iter(source(), chunk_size=10)
+        self.assertEqual(next(results), "a")
+        self.assertEqual(next(results), "b")
+        with self.assertRaises(KeyError):
+            next(results)
+        self.assertEqual(list(results), [])
+
+    def test_non_string_item(self):
+        results = tex2typst.tex2typst_iter(["a", 5, "b"])
+        self.assertEqual(next(results), "a")
+        with self.assertRaisesRegex(TypeError, "got int at item 1"):
+            next(results)
+
+    def test_invalid_arguments(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_iter([], chunk_size=0)
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_iter([], errors="skip")
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst_iter(5)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,109 @@
+"""Test raw JS converter options passed through js_options."""
+
+import json
+import unittest
+import tex2typst
+
+# Make the converters return the options object they were called with
+ECHO_EXTENSION = """
+tex2typst = (tex, options) => JSON.stringify(options === undefined ? null : options);
+typst2tex = tex2typst;
+"""
+
+
+class TestJsOptions(unittest.TestCase):
+    def setUp(self):
+        tex2typst.register_js_extension(ECHO_EXTENSION)
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+
+    def echo(self, result):
+        return json.loads(result)
+
+    def test_values_reach_js(self):
+        options = {
+            "maxExpansionDepth": 8,
+            "precision": 0.5,
+            "flag": True,
+            "mode": "x",
+            "nested": {"a": 1, "b": None, "c": [1, "two"]},
+            "items": [1, 2],
+            "skipped": None,
+        }
+        self.assertEqual(
+            self.echo(tex2typst.tex2typst("x", js_options=options)),
+            {
+                "maxExpansionDepth": 8,
+                "precision": 0.5,
+                "flag": True,
+                "mode": "x",
+                "nested": {"a": 1, "b": None, "c": [1, "two"]},
+                "items": [1, 2],
+            },
+        )
+
+    def test_js_types(self):
+        # Report typeof and integer-ness of each option as JS received it
+        tex2typst.register_js_extension(
+            "const describe = (v) => Array.isArray(v) ? v.map(describe)"
+            " : [typeof v, Number.isInteger(v), v];"
+            "tex2typst = (tex, options) => JSON.stringify(describe(options[tex]));"
+        )
+        cases = [
+            (3, ["number", True, 3]),
+            (2**40, ["number", True, 2**40]),
+            (0.25, ["number", False, 0.25]),
+            (2.0, ["number", True, 2]),
+            ([1, 1.5, "a", False], [
+                ["number", True, 1],
+                ["number", False, 1.5],
+                ["string", False, "a"],
+                ["boolean", False, False],
+            ]),
+        ]
+        for value, expected in cases:
+            with self.subTest(value=value):
+                result = tex2typst.tex2typst("probe", js_options={"probe": value})
+                self.assertEqual(self.echo(result), expected)
+
+    def test_merged_with_typed_options(self):
+        result = tex2typst.tex2typst(
+            ["x"], frac_to_slash=False, js_options={"maxExpansionDepth": 3}
+        )
+        self.assertEqual(
+            self.echo(result[0]), {"fracToSlash": False, "maxExpansionDepth": 3}
+        )
+        self.assertEqual(
+            self.echo(tex2typst.typst2tex("x", js_options={"depth": 2})), {"depth": 2}
+        )
+        self.assertEqual(
+            self.echo(tex2typst.typst2tex(["x"], js_options={"depth": 2})[0]),
+            {"depth": 2},
+        )
+
+    def test_empty_is_noop(self):
+        self.assertIsNone(self.echo(tex2typst.tex2typst("x", js_options={})))
+
+    def test_typed_option_names_rejected(self):
+        with self.assertRaisesRegex(ValueError, "duplicates the 'frac_to_slash' option"):
+            tex2typst.tex2typst("x", js_options={"fracToSlash": False})
+        with self.assertRaisesRegex(ValueError, "'block_math_mode'"):
+            tex2typst.typst2tex(["x"], js_options={"blockMathMode": True})
+        # Only the names of the function's own options are reserved
+        self.assertEqual(
+            self.echo(tex2typst.typst2tex("x", js_options={"fracToSlash": True})),
+            {"fracToSlash": True},
+        )
+
+    def test_invalid_values(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst("x", js_options={"bad": object()})
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", js_options={"big": 2**70})
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", js_options={"nan": float("nan")})
+
+
+if __name__ == "__main__":
+    unittest.main()
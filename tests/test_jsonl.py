@@ -0,0 +1,138 @@
+"""Test JSON Lines file conversion."""
+
+import json
+import os
+import tempfile
+import unittest
+import tex2typst
+
+
+class TestConvertJsonl(unittest.TestCase):
+    """Test convert_jsonl"""
+
+    def setUp(self):
+        self.tmpdir = tempfile.TemporaryDirectory()
+        self.input_path = os.path.join(self.tmpdir.name, "in.jsonl")
+        self.output_path = os.path.join(self.tmpdir.name, "out.jsonl")
+
+    def tearDown(self):
+        self.tmpdir.cleanup()
+
+    def write_input(self, lines):
+        with open(self.input_path, "w", encoding="utf-8") as f:
+            f.write("\n".join(lines) + "\n")
+
+    def read_output(self):
+        with open(self.output_path, encoding="utf-8") as f:
+            return [line.rstrip("\n") for line in f]
+
+    def test_basic_conversion(self):
+        self.write_input(
+            [
+                json.dumps({"id": 1, "tex": r"\alpha"}),
+                json.dumps({"id": 2, "tex": r"\frac{1}{2}"}),
+            ]
+        )
+
+        summary = tex2typst.convert_jsonl(self.input_path, self.output_path)
+
+        self.assertEqual(summary, {"converted": 2, "failed": 0, "errors": []})
+        records = [json.loads(line) for line in self.read_output()]
+        self.assertEqual(records[0], {"id": 1, "tex": r"\alpha", "typst": "alpha"})
+        self.assertEqual(records[1]["typst"], "1/2")
+
+    def test_unknown_fields_untouched(self):
+        line = '{"z": 1.50, "tex": "\\\\beta", "nested": {"b": [1, 2], "a": null}}'
+        self.write_input([line])
+
+        tex2typst.convert_jsonl(self.input_path, self.output_path)
+
+        output = self.read_output()[0]
+        self.assertTrue(output.startswith(line[:-1]))
+        self.assertEqual(json.loads(output)["typst"], "beta")
+
+    def test_custom_fields_and_options(self):
+        self.write_input([json.dumps({"formula": r"\frac{1}{2}"})])
+
+        tex2typst.convert_jsonl(
+            self.input_path,
+            self.output_path,
+            tex_field="formula",
+            out_field="out",
+            frac_to_slash=False,
+        )
+
+        record = json.loads(self.read_output()[0])
+        self.assertIn("frac", record["out"])
+
+    def test_errors_skip(self):
+        self.write_input(
+            [
+                json.dumps({"tex": r"\alpha"}),
+                json.dumps({"other": "x"}),
+                "not json",
+                json.dumps({"tex": r"\frac{1}{"}),
+            ]
+        )
+
+        summary = tex2typst.convert_jsonl(self.input_path, self.output_path)
+
+        self.assertEqual(summary["converted"], 1)
+        self.assertEqual(summary["failed"], 3)
+        self.assertTrue(summary["errors"][0].startswith("line 2:"))
+        self.assertIn("missing field 'tex'", summary["errors"][0])
+        self.assertTrue(summary["errors"][1].startswith("line 3:"))
+        self.assertEqual(len(self.read_output()), 1)
+
+    def test_errors_null(self):
+        self.write_input([json.dumps({"tex": r"\frac{1}{"}), json.dumps({"tex": "x"})])
+
+        summary = tex2typst.convert_jsonl(
+            self.input_path, self.output_path, errors="null"
+        )
+
+        self.assertEqual(summary["failed"], 1)
+        records = [json.loads(line) for line in self.read_output()]
+        self.assertIsNone(records[0]["typst"])
+        self.assertEqual(records[1]["typst"], "x")
+
+    def test_errors_raise(self):
+        self.write_input([json.dumps({"tex": "x"}), json.dumps({"tex": r"\frac{1}{"})])
+
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.convert_jsonl(self.input_path, self.output_path, errors="raise")
+        self.assertIn("line 2:", str(ctx.exception))
+
+    def test_error_messages_capped(self):
+        self.write_input([json.dumps({"id": i}) for i in range(50)])
+
+        summary = tex2typst.convert_jsonl(self.input_path, self.output_path)
+
+        self.assertEqual(summary["failed"], 50)
+        self.assertLessEqual(len(summary["errors"]), 10)
+
+    def test_max_input_length(self):
+        self.write_input([json.dumps({"tex": r"\alpha"}), json.dumps({"tex": "x"})])
+
+        summary = tex2typst.convert_jsonl(
+            self.input_path, self.output_path, max_input_length=3
+        )
+
+        self.assertEqual(summary["converted"], 1)
+        self.assertIn("max_input_length=3", summary["errors"][0])
+        self.assertEqual(json.loads(self.read_output()[0])["typst"], "x")
+
+    def test_missing_input(self):
+        with self.assertRaises(FileNotFoundError):
+            tex2typst.convert_jsonl(
+                os.path.join(self.tmpdir.name, "missing.jsonl"), self.output_path
+            )
+
+    def test_invalid_policy(self):
+        self.write_input([])
+        with self.assertRaises(ValueError):
+            tex2typst.convert_jsonl(self.input_path, self.output_path, errors="bogus")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,77 @@
+"""Test keep_original and strip_original_comments."""
+
+import sys
+import unittest
+import tex2typst
+
+
+class TestKeepOriginal(unittest.TestCase):
+    """Test the original-TeX comment returned with the conversion"""
+
+    def test_line_comment(self):
+        result, comment = tex2typst.tex2typst(r"\alpha", keep_original="comment")
+        self.assertEqual(result, "alpha")
+        self.assertEqual(comment, r"// tex: \alpha")
+
+    def test_line_comment_escapes_newlines(self):
+        _, comment = tex2typst.tex2typst("a\n+ b", keep_original="comment")
+        self.assertEqual(comment, r"// tex: a\n+ b")
+
+    def test_block_comment(self):
+        result, comment = tex2typst.tex2typst(
+            "a\n+ b", keep_original="comment", wrap="block"
+        )
+        self.assertTrue(result.startswith("$ "))
+        self.assertEqual(comment, "/* tex: a\n+ b */")
+
+    def test_block_comment_escapes_delimiters(self):
+        _, comment = tex2typst.tex2typst(
+            "a */ b /* c", keep_original="comment", wrap="block"
+        )
+        self.assertEqual(comment, "/* tex: a * / b / * c */")
+
+    def test_tuple_order(self):
+        result = tex2typst.tex2typst(
+            r"\foo x",
+            keep_original="comment",
+            report_unknown=True,
+            ignore_unknown_commands=True,
+        )
+        self.assertEqual(result[1], [r"\foo"])
+        self.assertEqual(result[2], r"// tex: \foo x")
+
+    def test_list_input_rejected(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(["x"], keep_original="comment")
+
+    def test_unknown_mode_rejected(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", keep_original="inline")
+
+
+class TestStripOriginalComments(unittest.TestCase):
+    """Test removing the comments again"""
+
+    def test_round_trip_line(self):
+        result, comment = tex2typst.tex2typst(
+            "x^2", keep_original="comment", wrap="inline"
+        )
+        text = f"Let {result} {comment}\nnext"
+        self.assertEqual(
+            tex2typst.strip_original_comments(text), "Let $x^2$\nnext"
+        )
+
+    def test_round_trip_block(self):
+        result, comment = tex2typst.tex2typst(
+            "x^2", keep_original="comment", wrap="block"
+        )
+        text = f"{comment}\n{result}\n"
+        self.assertEqual(tex2typst.strip_original_comments(text), "$ x^2 $\n")
+
+    def test_other_comments_kept(self):
+        text = "// note\n/* block */ $x$"
+        self.assertEqual(tex2typst.strip_original_comments(text), text)
+
+
+if __name__ == "__main__":
+    sys.exit(unittest.main())
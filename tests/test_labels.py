@@ -0,0 +1,82 @@
+"""Test \\label extraction and \\ref rewriting."""
+
+import unittest
+import warnings
+import tex2typst
+
+
+class TestLabels(unittest.TestCase):
+    """Test the labels option"""
+
+    def test_attach_to_block(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"e^{i\pi} + 1 = 0 \label{eq:euler}", labels=True, wrap="block"
+            ),
+            "$ e^(i pi) + 1 = 0 $ <eq:euler>",
+        )
+        # Without a block equation there is nothing to attach the label to
+        self.assertEqual(tex2typst.tex2typst(r"x \label{eq:x}", labels=True), "x")
+        self.assertEqual(
+            tex2typst.tex2typst(r"x \label{eq:x}", labels=True, wrap="inline"), "$x$"
+        )
+
+    def test_sanitize_and_deduplicate(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                [r"a \label{eq 1}", r"b \label{eq 1}", r"c \label{sec:}"],
+                labels=True,
+                wrap="block",
+            ),
+            ["$ a $ <eq-1>", "$ b $ <eq-1-2>", "$ c $ <sec>"],
+        )
+
+    def test_multiple_labels_warn(self):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            result = tex2typst.tex2typst(
+                r"a \label{first} = b \label{second}", labels=True, wrap="block"
+            )
+        self.assertEqual(result, "$ a = b $ <first>")
+        self.assertIn("<second>", str(caught[0].message))
+
+    def test_with_info(self):
+        info = tex2typst.tex2typst_with_info(r"x \label{eq:a}", labels=True)
+        self.assertEqual(info.typst, "x")
+        self.assertEqual(info.labels, [("eq:a", "eq:a")])
+        infos = tex2typst.tex2typst_batch_with_info(
+            [r"x \label{a}", "", r"y \label{a}"], labels=True, skip_empty=True
+        )
+        self.assertEqual(infos[0].labels, [("a", "a")])
+        self.assertIsNone(infos[1])
+        self.assertEqual(infos[2].labels, [("a", "a-2")])
+        self.assertEqual(tex2typst.tex2typst_with_info("x").labels, [])
+
+
+class TestConvertRefs(unittest.TestCase):
+    """Test convert_refs"""
+
+    def test_rewrite(self):
+        self.assertEqual(
+            tex2typst.convert_refs(r"By \eqref{eq:euler} and Fig.~\ref{fig 1}."),
+            "By @eq:euler and Fig.~@fig-1.",
+        )
+
+    def test_mapping(self):
+        infos = tex2typst.tex2typst_batch_with_info(
+            [r"x \label{a}", r"y \label{b}"], labels=True
+        )
+        mapping = dict(infos[1].labels)
+        self.assertEqual(
+            tex2typst.convert_refs(r"\autoref{b} vs \ref{a}", {"b": "renamed"}),
+            "@renamed vs @a",
+        )
+        self.assertEqual(tex2typst.convert_refs(r"\ref{b}", mapping), "@b")
+
+    def test_other_commands_untouched(self):
+        text = r"\textbf{bold} \reference{x}"
+        self.assertEqual(tex2typst.convert_refs(text), text)
+
+
+if __name__ == "__main__":
+    unittest.main()
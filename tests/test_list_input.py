@@ -81,6 +81,92 @@ class TestListInput(unittest.TestCase):
         # Batch API doesn't go through Python-level cache
         self.assertEqual(info["tex2typst"].hits, 0, "Lists use batch API, not cache")
 
+    def test_keep_original_on_error(self):
+        """Failed items fall back to their input instead of raising"""
+        inputs = [r"\alpha", r"\frac{1}{", r"\beta"]
+
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(inputs)
+
+        results = tex2typst.tex2typst(inputs, keep_original_on_error=True)
+        self.assertEqual(results, ["alpha", r"\frac{1}{", "beta"])
+
+    def test_return_failed_indices(self):
+        """Failed indices are reported alongside the results"""
+        inputs = [r"\frac{1}{", r"\alpha", r"\frac{"]
+        results, failed = tex2typst.tex2typst(
+            inputs, keep_original_on_error=True, return_failed_indices=True
+        )
+
+        self.assertEqual(results, [r"\frac{1}{", "alpha", r"\frac{"])
+        self.assertEqual(failed, [0, 2])
+
+        results, failed = tex2typst.tex2typst(
+            [r"\alpha"], return_failed_indices=True
+        )
+        self.assertEqual(results, ["alpha"])
+        self.assertEqual(failed, [])
+
+    def test_atomic(self):
+        """An atomic batch raises the first failure even with keep_original_on_error"""
+        inputs = [r"\alpha", r"\frac{1}{", r"\beta", r"\frac{"]
+        self.assertEqual(
+            tex2typst.tex2typst([r"\alpha", r"\beta"], atomic=True), ["alpha", "beta"]
+        )
+        with self.assertRaisesRegex(ValueError, "item 1 ") as ctx:
+            tex2typst.tex2typst(
+                inputs,
+                atomic=True,
+                keep_original_on_error=True,
+                return_failed_indices=True,
+            )
+        self.assertEqual(ctx.exception.input, r"\frac{1}{")
+        with self.assertRaisesRegex(ValueError, "item 2 "):
+            tex2typst.tex2typst(
+                ["", *inputs[2:]], atomic=True, skip_empty=True, chunk_size=1
+            )
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\alpha", atomic=True)
+
+    def test_keep_original_requires_list(self):
+        """Batch-only flags are rejected for string input"""
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\alpha", keep_original_on_error=True)
+
+    def test_skip_empty(self):
+        """Empty strings are skipped and the output keeps the input length"""
+        inputs = [r"\alpha", "", r"\beta", ""]
+
+        self.assertEqual(
+            tex2typst.tex2typst(inputs, skip_empty=True), ["alpha", None, "beta", None]
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(inputs, skip_empty=True, empty_replacement=""),
+            ["alpha", "", "beta", ""],
+        )
+        self.assertEqual(
+            tex2typst.typst2tex(["", "alpha"], skip_empty=True, empty_replacement="-"),
+            ["-", r"\alpha"],
+        )
+        self.assertEqual(tex2typst.tex2typst(["", ""], skip_empty=True), [None, None])
+
+    def test_skip_empty_failed_indices(self):
+        """Failed indices refer to positions in the original list"""
+        results, failed = tex2typst.tex2typst(
+            ["", r"\frac{1}{", r"\alpha"],
+            skip_empty=True,
+            keep_original_on_error=True,
+            return_failed_indices=True,
+        )
+
+        self.assertEqual(results, [None, r"\frac{1}{", "alpha"])
+        self.assertEqual(failed, [1])
+
+    def test_skip_empty_with_info(self):
+        infos = tex2typst.tex2typst_batch_with_info([r"\alpha", ""], skip_empty=True)
+        self.assertEqual(infos[0].typst, "alpha")
+        self.assertIsNone(infos[1])
+
     def test_invalid_type(self):
         """Test that invalid types raise TypeError"""
         with self.assertRaises(TypeError):
@@ -0,0 +1,83 @@
+"""Test loading and saving custom_tex_macros files."""
+
+import os
+import pathlib
+import tempfile
+import unittest
+import tex2typst
+
+
+class TestMacroFiles(unittest.TestCase):
+    """Test load_macros_from_file and save_macros_to_file"""
+
+    def setUp(self):
+        self.tmpdir = tempfile.TemporaryDirectory()
+
+    def tearDown(self):
+        self.tmpdir.cleanup()
+
+    def path(self, name):
+        return os.path.join(self.tmpdir.name, name)
+
+    def write(self, name, text):
+        with open(self.path(name), "w", encoding="utf-8") as f:
+            f.write(text)
+        return self.path(name)
+
+    def test_load_json(self):
+        path = self.write("macros.json", '{"\\\\R": "\\\\mathbb{R}"}')
+        macros = tex2typst.load_macros_from_file(path)
+        self.assertEqual(macros, {"\\R": "\\mathbb{R}"})
+        self.assertEqual(
+            tex2typst.tex2typst(r"x \in \R", custom_tex_macros=macros), "x in RR"
+        )
+
+    def test_load_toml(self):
+        path = self.write("macros.toml", '"\\\\R" = "\\\\mathbb{R}"\n')
+        try:
+            macros = tex2typst.load_macros_from_file(pathlib.Path(path))
+        except ModuleNotFoundError:
+            self.skipTest("no TOML parser available")
+        self.assertEqual(macros, {"\\R": "\\mathbb{R}"})
+
+    def test_round_trip(self):
+        macros = {"\\R": "\\mathbb{R}", "\\q": 'say "hi"\n', "\\e": "é"}
+        for name in ["out.json", "out.toml"]:
+            with self.subTest(name=name):
+                tex2typst.save_macros_to_file(macros, self.path(name))
+                try:
+                    loaded = tex2typst.load_macros_from_file(self.path(name))
+                except ModuleNotFoundError:
+                    self.skipTest("no TOML parser available")
+                self.assertEqual(loaded, macros)
+        tex2typst.save_macros_to_file({}, self.path("empty.json"))
+        self.assertEqual(tex2typst.load_macros_from_file(self.path("empty.json")), {})
+
+    def test_missing_file(self):
+        with self.assertRaisesRegex(FileNotFoundError, "missing.json"):
+            tex2typst.load_macros_from_file(self.path("missing.json"))
+
+    def test_bad_format(self):
+        cases = {
+            "broken.json": "{",
+            "list.json": '["\\\\R"]',
+            "broken.toml": "= 1",
+            "macros.yaml": "R: x",
+        }
+        for name, text in cases.items():
+            with self.subTest(name=name):
+                with self.assertRaises(ValueError):
+                    tex2typst.load_macros_from_file(self.write(name, text))
+        with self.assertRaises(ValueError):
+            tex2typst.save_macros_to_file({}, self.path("macros.txt"))
+
+    def test_non_string_values(self):
+        path = self.write("macros.json", '{"\\\\R": 1}')
+        with self.assertRaisesRegex(KeyError, "got number"):
+            tex2typst.load_macros_from_file(path)
+        with self.assertRaises(KeyError):
+            tex2typst.save_macros_to_file({"\\R": None}, self.path("out.json"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,81 @@
+"""Test set_js_memory_limit and recovery from the engine running out of memory."""
+
+import threading
+import unittest
+import tex2typst
+
+# Converting this takes several MB of engine memory
+LARGE = "x^2 + " * 50000 + "y"
+
+
+class TestMemoryLimit(unittest.TestCase):
+    """Test that exhausting the engine's memory raises instead of aborting"""
+
+    def setUp(self):
+        tex2typst.set_js_memory_limit(256 * 1024)
+
+    def tearDown(self):
+        tex2typst.set_js_memory_limit(None)
+
+    def test_get_limit(self):
+        self.assertEqual(tex2typst.get_js_memory_limit(), 256 * 1024)
+        tex2typst.set_js_memory_limit(None)
+        self.assertIsNone(tex2typst.get_js_memory_limit())
+
+    def test_memory_error_then_recovers(self):
+        tex2typst.warmup()
+        started = tex2typst.get_stats()["engines_initialized"]
+        for i in range(3):
+            with self.assertRaises(MemoryError) as cm:
+                tex2typst.tex2typst(LARGE)
+            self.assertIn("out of memory", str(cm.exception))
+            # The very next conversion on this thread runs on a fresh engine; the
+            # inputs differ so none is served from the result cache
+            self.assertEqual(tex2typst.tex2typst(rf"\frac{{1}}{{{i}}}"), f"1/{i}")
+        self.assertEqual(tex2typst.get_stats()["engines_initialized"] - started, 3)
+
+    def test_typst2tex(self):
+        with self.assertRaises(MemoryError):
+            tex2typst.typst2tex("x^2 + " * 50000 + "y")
+        self.assertEqual(tex2typst.typst2tex("alpha"), r"\alpha")
+
+    def test_parse(self):
+        with self.assertRaises(MemoryError):
+            tex2typst.parse_tex(LARGE)
+        self.assertEqual(tex2typst.parse_tex("a")["type"], "terminal")
+
+    def test_batch_not_kept_as_failure(self):
+        with self.assertRaises(MemoryError):
+            tex2typst.tex2typst([r"\alpha", LARGE], keep_original_on_error=True)
+        self.assertEqual(tex2typst.tex2typst([r"\alpha", "x"]), ["alpha", "x"])
+
+    def test_other_thread(self):
+        results = []
+
+        def convert():
+            try:
+                tex2typst.tex2typst(LARGE)
+            except MemoryError:
+                results.append(tex2typst.tex2typst("x^2"))
+
+        thread = threading.Thread(target=convert)
+        thread.start()
+        thread.join()
+        self.assertEqual(results, ["x^2"])
+
+    def test_converter_poisoned(self):
+        converter = tex2typst.Converter()
+        with self.assertRaises(MemoryError):
+            converter.tex2typst(LARGE)
+        with self.assertRaises(tex2typst.EngineError):
+            converter.tex2typst("x")
+        self.assertEqual(tex2typst.Converter().tex2typst("x"), "x")
+
+    def test_limit_lifted(self):
+        self.assertEqual(tex2typst.tex2typst("x"), "x")
+        tex2typst.set_js_memory_limit(None)
+        self.assertEqual(tex2typst.tex2typst(r"\alpha + " * 2000 + "y")[:8], "alpha + ")
+
+
+if __name__ == "__main__":
+    unittest.main()
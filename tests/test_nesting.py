@@ -0,0 +1,74 @@
+"""Test that deeply nested input raises instead of crashing the interpreter."""
+
+import unittest
+import tex2typst
+
+
+def nested_braces(depth):
+    return "{" * depth + "x" + "}" * depth
+
+
+def nested_fracs(depth):
+    return r"\frac{1}{" * depth + "x" + "}" * depth
+
+
+class TestMaxNestingDepth(unittest.TestCase):
+    """Test the max_nesting_depth pre-check"""
+
+    def test_rejects_10k_braces(self):
+        with self.assertRaisesRegex(
+            tex2typst.TexParseError, "10000 levels deep, exceeding max_nesting_depth=512"
+        ):
+            tex2typst.tex2typst(nested_braces(10_000))
+        self.assertEqual(tex2typst.tex2typst(r"\frac{1}{2}"), "1/2")
+
+    def test_rejects_10k_fracs(self):
+        with self.assertRaises(tex2typst.TexParseError):
+            tex2typst.tex2typst(nested_fracs(10_000))
+        self.assertEqual(tex2typst.tex2typst(r"\alpha"), "alpha")
+
+    def test_counts_environments_and_delimiters(self):
+        tex = r"\left(" * 20 + "x" + r"\right)" * 20
+        with self.assertRaisesRegex(tex2typst.TexParseError, "20 levels"):
+            tex2typst.tex2typst(tex, max_nesting_depth=10)
+        tex = r"\begin{matrix}" * 3 + "x" + r"\end{matrix}" * 3
+        with self.assertRaisesRegex(tex2typst.TexParseError, "3 levels"):
+            tex2typst.tex2typst(tex, max_nesting_depth=2)
+        # Escaped braces are not groups
+        self.assertIsInstance(
+            tex2typst.tex2typst(r"\{" * 20 + "x", max_nesting_depth=1), str
+        )
+
+    def test_batch_lists_offenders(self):
+        with self.assertRaises(tex2typst.TexParseError) as ctx:
+            tex2typst.tex2typst(
+                ["x", nested_braces(600), "y", nested_fracs(700)]
+            )
+        self.assertIn("item 1 (600 levels)", str(ctx.exception))
+        self.assertIn("item 3 (700 levels)", str(ctx.exception))
+        self.assertEqual(tex2typst.tex2typst(["x"]), ["x"])
+
+
+class TestEngineStackOverflow(unittest.TestCase):
+    """Test input that passes the pre-check but is too deep for the engine"""
+
+    def test_recursion_error(self):
+        for tex in [nested_braces(600), nested_fracs(600)]:
+            with self.assertRaisesRegex(RecursionError, "Maximum call stack size exceeded"):
+                tex2typst.tex2typst(tex, max_nesting_depth=None)
+            self.assertEqual(tex2typst.tex2typst(r"\frac{1}{2}"), "1/2")
+
+    def test_batch_keep_original(self):
+        deep = nested_braces(600)
+        results, failed = tex2typst.tex2typst(
+            ["x", deep],
+            max_nesting_depth=None,
+            keep_original_on_error=True,
+            return_failed_indices=True,
+        )
+        self.assertEqual(results, ["x", deep])
+        self.assertEqual(failed, [1])
+
+
+if __name__ == "__main__":
+    unittest.main()
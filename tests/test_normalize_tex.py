@@ -0,0 +1,77 @@
+"""Test normalize_tex."""
+
+import unittest
+import tex2typst
+
+# Spellings that must normalize to the same TeX
+EQUIVALENT = [
+    ([r"\frac {1} {2}", r"\frac{1}{2}", r"{\frac{1}{2}}", r"{{ \frac{1}{2} }}"], r"\frac{1}{2}"),
+    ([r"x ^ {2}", r"x^{2}", "x^2", r"x^{ 2 }"], "x^2"),
+    ([r"\left ( x \right )", r"\left(x\right)"], r"\left(x\right)"),
+    ([r"\alpha  b", r"\alpha b", "\\alpha\nb"], r"\alpha b"),
+    (["a + b % sum\n", "a+b", "  a +\tb  "], "a+b"),
+    ([r"\sqrt [3] {x}", r"\sqrt[3]{x}"], r"\sqrt[3]{x}"),
+]
+
+# Fixture corpus for the property normalize_tex keeps the conversion
+CORPUS = [
+    r"\frac {1} {2}",
+    r"{\frac{1}{2}}",
+    r"\text{a  b} + \text{ x }",
+    r"\mathrm{a b} \mathrm{ab}",
+    r"\operatorname{a b} \operatorname*{lim} x",
+    "1 2 + 12 + 1 .5",
+    r"x^{1}2 + x^{2}a + x^{a}b",
+    r"\alpha2 \alpha b \alpha{}b",
+    r"\sum_{i = 1}^{n} i^{2}",
+    r"\int_0^1 f(x) \, \mathrm{d} x",
+    r"\left\{ a \right\} \left. x \right|",
+    r"\begin{matrix} a & b \\ c & d \end{matrix}",
+    r"\begin{pmatrix} 1 \\ [2pt] 2 \end{pmatrix}",
+    r"a\ b \quad c ~ d",
+    r"\hat {x} + \vec{v}' + x '",
+    r"e^{i \pi} + 1 = 0 % Euler",
+    r"f \left( x \right) = - 1",
+    r"\mathbb {R}^{n}",
+]
+
+
+class TestNormalizeTex(unittest.TestCase):
+    """Test the canonical form of TeX input"""
+
+    def test_equivalent_spellings(self):
+        for spellings, expected in EQUIVALENT:
+            for tex in spellings:
+                with self.subTest(tex=tex):
+                    self.assertEqual(tex2typst.normalize_tex(tex), expected)
+
+    def test_conversion_unchanged(self):
+        for tex in CORPUS:
+            with self.subTest(tex=tex):
+                normalized = tex2typst.normalize_tex(tex)
+                # Comments, kept as Typst comments otherwise, are not part of the math
+                self.assertEqual(
+                    tex2typst.tex2typst(normalized),
+                    tex2typst.tex2typst(tex, strip_comments=True),
+                )
+                self.assertEqual(tex2typst.normalize_tex(normalized), normalized)
+
+    def test_significant_spacing_kept(self):
+        self.assertEqual(tex2typst.normalize_tex(r"\text{a  b}"), r"\text{a  b}")
+        self.assertEqual(tex2typst.normalize_tex("1 2"), "1 2")
+        self.assertEqual(tex2typst.normalize_tex(r"x^{1} 2"), "x^{1}2")
+        self.assertEqual(tex2typst.normalize_tex(r"\{a\}"), r"\{a\}")
+        self.assertEqual(tex2typst.normalize_tex(r"{a}{b}"), "{a}{b}")
+
+    def test_expand_macros(self):
+        macros = {r"\R": r"\mathbb{R}", r"\norm": r"\left\| #1 \right\|"}
+        self.assertEqual(
+            tex2typst.normalize_tex(r"\norm{x} \in \R", expand_macros=macros),
+            r"\left\|x\right\|\in\mathbb{R}",
+        )
+        with self.assertRaises(ValueError):
+            tex2typst.normalize_tex(r"\loop", expand_macros={r"\loop": r"\loop"})
+
+
+if __name__ == "__main__":
+    unittest.main()
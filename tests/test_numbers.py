@@ -0,0 +1,47 @@
+"""Test formatting numbers as Typst and TeX math."""
+
+import math
+import unittest
+import tex2typst
+
+
+class TestNumberFormatting(unittest.TestCase):
+    """Test num_to_typst and num_to_tex"""
+
+    def test_plain_numbers(self):
+        for n, expected in [(0.25, "0.25"), (3, "3"), (-12.5, "-12.5"), (1e15, "1000000000000000")]:
+            with self.subTest(n=n):
+                self.assertEqual(tex2typst.num_to_typst(n), expected)
+                self.assertEqual(tex2typst.num_to_tex(n), expected)
+
+    def test_e_notation(self):
+        self.assertEqual(tex2typst.num_to_typst(1.5e-10), "1.5 times 10^(-10)")
+        self.assertEqual(tex2typst.num_to_tex(1.5e-10), r"1.5 \times 10^{-10}")
+        self.assertEqual(tex2typst.num_to_typst(-2.5e20), "-2.5 times 10^20")
+        self.assertEqual(tex2typst.num_to_tex(-2.5e20), r"-2.5 \times 10^{20}")
+        # A mantissa of 1 is left out
+        self.assertEqual(tex2typst.num_to_typst(1e-5), "10^(-5)")
+        self.assertEqual(tex2typst.num_to_tex(1e16), "10^{16}")
+
+    def test_zero(self):
+        for n in [0, 0.0, -0.0]:
+            with self.subTest(n=n):
+                self.assertEqual(tex2typst.num_to_typst(n), "0")
+                self.assertEqual(tex2typst.num_to_tex(n), "0")
+
+    def test_special_values(self):
+        self.assertEqual(tex2typst.num_to_typst(math.inf), "oo")
+        self.assertEqual(tex2typst.num_to_typst(-math.inf), "-oo")
+        self.assertEqual(tex2typst.num_to_tex(-math.inf), r"-\infty")
+        self.assertEqual(tex2typst.num_to_typst(math.nan), '"NaN"')
+        self.assertEqual(tex2typst.num_to_tex(math.nan), r"\mathrm{NaN}")
+
+    def test_output_converts(self):
+        self.assertEqual(
+            tex2typst.tex2typst(tex2typst.num_to_tex(6.02e23)),
+            tex2typst.num_to_typst(6.02e23),
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,65 @@
+"""Test that the options object reaches the JS converter in a fixed order."""
+
+import unittest
+import tex2typst
+
+# Replaces the converter with one that returns its options object as JSON, which
+# lists properties in the order they were set
+ECHO_OPTIONS = "tex2typst = (tex, options) => JSON.stringify(options);"
+
+
+class TestOptionsOrder(unittest.TestCase):
+    """Test the insertion order of defaults, named options and js_options"""
+
+    def setUp(self):
+        tex2typst.register_js_extension(ECHO_OPTIONS)
+        tex2typst.set_default_options(keep_spaces=True)
+
+    def tearDown(self):
+        tex2typst.reset_default_options()
+        tex2typst.clear_js_extensions()
+
+    def test_deterministic(self):
+        expected = (
+            '{"keepSpaces":true,"nonStrict":true,"fracToSlash":false,'
+            '"customTexMacros":{"\\\\a":"a","\\\\b":"b"},"zeta":1,"alpha":2}'
+        )
+        for _ in range(100):
+            self.assertEqual(
+                tex2typst.tex2typst(
+                    "x",
+                    frac_to_slash=False,
+                    non_strict=True,
+                    custom_tex_macros={r"\b": "b", r"\a": "a"},
+                    js_options={"zeta": 1, "alpha": 2},
+                ),
+                expected,
+            )
+
+    def test_converter_kwargs_in_signature_order(self):
+        converter = tex2typst.Converter(frac_to_slash=False)
+        try:
+            outputs = {
+                converter.tex2typst("x", non_strict=True, optimize=False),
+                converter.tex2typst("x", optimize=False, non_strict=True),
+            }
+        finally:
+            converter.close()
+        self.assertEqual(
+            outputs,
+            {'{"keepSpaces":true,"nonStrict":true,"fracToSlash":false,"optimize":false}'},
+        )
+
+    def test_precedence(self):
+        # An explicit option replaces the default in its place
+        self.assertEqual(
+            tex2typst.tex2typst("x", non_strict=True, keep_spaces=False, js_options={}),
+            '{"keepSpaces":false,"nonStrict":true}',
+        )
+        # js_options come last and may not restate a named option
+        with self.assertRaisesRegex(ValueError, "duplicates the 'keep_spaces' option"):
+            tex2typst.tex2typst("x", js_options={"keepSpaces": False})
+
+
+if __name__ == "__main__":
+    unittest.main()
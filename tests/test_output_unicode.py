@@ -0,0 +1,53 @@
+"""Test the output_unicode option."""
+
+import unittest
+import tex2typst
+
+
+class TestOutputUnicode(unittest.TestCase):
+    """Test Unicode characters in place of Typst symbol names"""
+
+    def test_symbols(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\alpha \times \beta", output_unicode=True), "α × β"
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\sum_{i \in \mathbb{N}} x_i \to \infty", output_unicode=True),
+            "∑_(i ∈ NN) x_i → ∞",
+        )
+        self.assertEqual(tex2typst.tex2typst(r"a \leq b \neq c", output_unicode=True), "a ≤ b ≠ c")
+
+    def test_default_keeps_names(self):
+        self.assertEqual(tex2typst.tex2typst(r"\alpha \times \beta"), "alpha times beta")
+
+    def test_strings_and_calls_untouched(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\text{alpha} \alpha", output_unicode=True), '"alpha" α'
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\sqrt{\pi} \hat{x}", output_unicode=True), "sqrt(π) hat(x)"
+        )
+
+    def test_batch(self):
+        self.assertEqual(
+            tex2typst.tex2typst([r"\alpha", r"\Omega \cdot x"], output_unicode=True),
+            ["α", "Ω ⋅ x"],
+        )
+
+    def test_native_bundle_support(self):
+        # An extension honouring outputUnicode itself is not post-processed
+        tex2typst.register_js_extension(
+            "const base = tex2typst;"
+            "tex2typst = (tex, options) => options && options.outputUnicode"
+            " ? (tex === '\\\\alpha' ? '\\u03b1' : 'native ' + base(tex)) : base(tex, options);"
+        )
+        try:
+            self.assertEqual(
+                tex2typst.tex2typst(r"\beta", output_unicode=True), "native beta"
+            )
+        finally:
+            tex2typst.clear_js_extensions()
+
+
+if __name__ == "__main__":
+    unittest.main()
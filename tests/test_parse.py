@@ -0,0 +1,92 @@
+"""Test AST parsing API."""
+
+import unittest
+import tex2typst
+
+
+class TestParseTex(unittest.TestCase):
+    """Test parse_tex"""
+
+    def test_frac_shape(self):
+        """\\frac{a}{b} is a node with two children"""
+        node = tex2typst.parse_tex(r"\frac{a}{b}")
+
+        self.assertIsInstance(node, dict)
+        self.assertEqual(node["type"], "funcCall")
+        self.assertEqual(node["head"]["value"], r"\frac")
+        self.assertEqual(len(node["args"]), 2)
+        self.assertEqual(node["args"][0]["head"]["value"], "a")
+        self.assertEqual(node["args"][1]["head"]["value"], "b")
+
+    def test_custom_tex_macros(self):
+        """Macros are expanded before parsing"""
+        node = tex2typst.parse_tex(r"\half", custom_tex_macros={r"\half": r"\frac{1}{2}"})
+        self.assertEqual(node["head"]["value"], r"\frac")
+
+    def test_depth_cap(self):
+        """Deeply nested trees raise a clear error instead of blowing up"""
+        tex = "x"
+        for _ in range(80):
+            tex = r"\frac{" + tex + "}{y}"
+
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.parse_tex(tex)
+        self.assertIn("exceeds the maximum", str(ctx.exception))
+
+        # Converter remains usable afterwards
+        self.assertEqual(tex2typst.parse_tex("a")["type"], "terminal")
+
+    def test_input_depth_cap(self):
+        """Input too deep for the JS parser is rejected before it is parsed"""
+        with self.assertRaisesRegex(ValueError, "TeX nesting depth 300 exceeds the maximum"):
+            tex2typst.parse_tex("{" * 300 + "x" + "}" * 300)
+        self.assertEqual(tex2typst.parse_tex("{" * 50 + "x" + "}" * 50)["type"], "terminal")
+
+    def test_parse_error(self):
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.parse_tex(r"\frac{a")
+        self.assertIn("Parse failed:", str(ctx.exception))
+
+
+class TestParseTypst(unittest.TestCase):
+    """Test parse_typst"""
+
+    def test_frac_shape(self):
+        node = tex2typst.parse_typst("frac(a, b)")
+
+        self.assertEqual(node["type"], "funcCall")
+        self.assertEqual(node["head"]["value"], "frac")
+        self.assertEqual(len(node["args"]), 2)
+
+
+
+class TestConvertedAst(unittest.TestCase):
+    """Test tex2typst_ast and typst2tex_ast"""
+
+    def tearDown(self):
+        tex2typst.clear_js_extensions()
+
+    def test_not_in_bundle(self):
+        with self.assertRaisesRegex(NotImplementedError, "tex2typstAST"):
+            tex2typst.tex2typst_ast(r"\alpha")
+        with self.assertRaisesRegex(NotImplementedError, "typst2texAST"):
+            tex2typst.typst2tex_ast("alpha")
+
+    def test_provided_by_extension(self):
+        tex2typst.register_js_extension(
+            "globalThis.tex2typstAST = (tex) => ({ type: 'converted', args: [parseTex(tex)] });"
+            "globalThis.typst2texAST = (typst) => ({ type: 'converted', text: typst2tex(typst) });"
+        )
+        node = tex2typst.tex2typst_ast(r"\frac{a}{b}")
+        self.assertEqual(node["type"], "converted")
+        self.assertEqual(node["args"][0]["head"]["value"], r"\frac")
+        self.assertEqual(tex2typst.typst2tex_ast("alpha"), {"type": "converted", "text": "\\alpha"})
+
+    def test_errors_propagate(self):
+        tex2typst.register_js_extension("globalThis.tex2typstAST = (tex) => parseTex(tex);")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_ast(r"\frac{1}{")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,56 @@
+"""Test rewriting physics package macros with handle_physics."""
+
+import unittest
+import tex2typst
+
+
+class TestPhysics(unittest.TestCase):
+    def convert(self, tex, **options):
+        return tex2typst.tex2typst(tex, handle_physics=True, **options)
+
+    def test_derivatives(self):
+        cases = {
+            r"\dv{f}{x}": "(dif f)/(dif x)",
+            r"\dv[2]{f}{x}": "(dif^2 f)/(dif x^2)",
+            r"\dv{x}": "dif/(dif x)",
+            r"\pdv{f}{x}": "(diff f)/(diff x)",
+            r"\pdv{f}{x}{y}": "(diff^2 f)/(diff x diff y)",
+        }
+        for tex, expected in cases.items():
+            with self.subTest(tex=tex):
+                self.assertEqual(self.convert(tex), expected)
+
+    def test_delimiters(self):
+        self.assertEqual(self.convert(r"\abs{x} + \norm*{\vb{v}}"), "lr(|x|) + norm(upright(bold(v)))")
+        self.assertEqual(self.convert(r"\qty(\frac{1}{2})^2"), "(1/2)^2")
+        self.assertEqual(self.convert(r"\qty[a+b] \qty|x|"), "[a + b] lr(|x|)")
+        self.assertEqual(self.convert(r"\abs{\abs{x}}"), "lr(|lr(|x|)|)")
+
+    def test_quantum(self):
+        self.assertEqual(
+            self.convert(r"\braket{a}{b} \ket{0} \expval{H}"),
+            "chevron.l a|b chevron.r lr(|0 chevron.r) lr(chevron.l H chevron.r)",
+        )
+        self.assertEqual(self.convert(r"\comm{A}{B}"), "[A, B]")
+
+    def test_operators(self):
+        self.assertEqual(self.convert(r"\dd x \grad f \tr A"), 'dif x nabla f op("tr") A')
+
+    def test_off_by_default(self):
+        self.assertNotEqual(tex2typst.tex2typst(r"\dv{f}{x}"), self.convert(r"\dv{f}{x}"))
+
+    def test_custom_macros_win(self):
+        self.assertEqual(
+            self.convert(r"\abs{x}", custom_tex_macros={r"\abs": "A"}), "A x"
+        )
+
+    def test_list_and_converter(self):
+        self.assertEqual(
+            self.convert([r"\abs{x}", r"\dv{y}{t}"]), ["lr(|x|)", "(dif y)/(dif t)"]
+        )
+        conv = tex2typst.Converter(handle_physics=True)
+        self.assertEqual(conv.tex2typst(r"\norm{x}"), "norm(x)")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,119 @@
+"""Test harvesting macro definitions from a LaTeX preamble."""
+
+import unittest
+import warnings
+import tex2typst
+
+
+class TestParsePreamble(unittest.TestCase):
+    """Test parse_preamble"""
+
+    def test_newcommand_forms(self):
+        result = tex2typst.parse_preamble(
+            r"\newcommand{\R}{\mathbb{R}} \newcommand*\sq[1]{#1^2}"
+            "\n"
+            r"\renewcommand{\vec}[1]{\mathbf{#1}} \providecommand{\eps}{\varepsilon}"
+        )
+        self.assertEqual(
+            result["macros"],
+            {
+                r"\R": r"\mathbb{R}",
+                r"\sq": "#1^2",
+                r"\vec": r"\mathbf{#1}",
+                r"\eps": r"\varepsilon",
+            },
+        )
+        self.assertEqual(
+            result["num_args"], {r"\R": 0, r"\sq": 1, r"\vec": 1, r"\eps": 0}
+        )
+        self.assertEqual(result["warnings"], [])
+
+    def test_def(self):
+        result = tex2typst.parse_preamble(r"\def\pair#1#2{(#1, #2)} \def\one{1}")
+        self.assertEqual(result["macros"], {r"\pair": "(#1, #2)", r"\one": "1"})
+        self.assertEqual(result["num_args"], {r"\pair": 2, r"\one": 0})
+
+    def test_nested_braces(self):
+        result = tex2typst.parse_preamble(
+            r"\newcommand{\half}{\frac{1}{2}} \newcommand{\set}[1]{\{ #1 \}}"
+        )
+        self.assertEqual(result["macros"][r"\half"], r"\frac{1}{2}")
+        self.assertEqual(result["macros"][r"\set"], r"\{ #1 \}")
+
+    def test_comments(self):
+        result = tex2typst.parse_preamble(
+            "% \\newcommand{\\hidden}{x}\n"
+            "\\newcommand{\\pct}{50\\%} % trailing {unbalanced\n"
+            "\\newcommand{\\after}{y}\n"
+        )
+        self.assertEqual(result["macros"], {r"\pct": r"50\%", r"\after": "y"})
+
+    def test_unsupported_declarations(self):
+        result = tex2typst.parse_preamble(
+            r"\NewDocumentCommand{\foo}{m}{#1}"
+            r"\newcommand{\opt}[2][x]{#1+#2}"
+            r"\def\delim#1.{#1}"
+            r"\newcommand{\ok}{1}"
+        )
+        self.assertEqual(result["macros"], {r"\ok": "1"})
+        self.assertEqual(len(result["warnings"]), 3)
+        self.assertIn(r"\NewDocumentCommand{\foo}", result["warnings"][0])
+        self.assertIn("optional arguments", result["warnings"][1])
+        self.assertIn("delimited parameters", result["warnings"][2])
+
+
+class TestPreambleOption(unittest.TestCase):
+    """Test the preamble option of tex2typst"""
+
+    PREAMBLE = r"""
+    \newcommand{\R}{\mathbb{R}} \newcommand{\sq}[1]{#1^2}  % squares
+    \def\pair#1#2{\left(#1, #2\right)}
+    \newcommand{\half}{\frac{1}{\sq{2}}}
+    """
+
+    def tearDown(self):
+        tex2typst.reset_default_options()
+        tex2typst.clear_cache()
+
+    def test_expand_macros(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\sq{x} \in \R", preamble=self.PREAMBLE),
+            "x^2 in RR",
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(r"\pair{a}{\sq b}", preamble=self.PREAMBLE),
+            "(a, b^2)",
+        )
+
+    def test_argument_macro_inside_plain_macro(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\half", preamble=self.PREAMBLE),
+            "1/(2^2)",
+        )
+
+    def test_explicit_macros_take_precedence(self):
+        result = tex2typst.tex2typst(
+            [r"\R", r"\sq{y}"],
+            preamble=self.PREAMBLE,
+            custom_tex_macros={r"\R": r"\mathbb{Z}"},
+        )
+        self.assertEqual(result, ["ZZ", "y^2"])
+
+    def test_unsupported_declaration_warns(self):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            result = tex2typst.tex2typst(
+                r"\alpha", preamble=r"\NewDocumentCommand{\foo}{m}{#1}"
+            )
+        self.assertEqual(result, "alpha")
+        self.assertEqual(len(caught), 1)
+        self.assertIs(caught[0].category, UserWarning)
+        self.assertIn(r"\NewDocumentCommand", str(caught[0].message))
+
+    def test_recursive_macro(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(r"\loop{x}", preamble=r"\newcommand{\loop}[1]{\loop{#1}}")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,164 @@
+"""Test progress callbacks and chunked list conversions."""
+
+import unittest
+import tex2typst
+
+
+class TestProgressCallback(unittest.TestCase):
+    """Test progress_callback and callback_every_n"""
+
+    def test_called_every_n(self):
+        calls = []
+        results = tex2typst.tex2typst(
+            ["x"] * 250,
+            progress_callback=lambda done, total: calls.append((done, total)),
+        )
+        self.assertEqual(results, ["x"] * 250)
+        self.assertEqual(calls, [(100, 250), (200, 250), (250, 250)])
+
+    def test_custom_interval(self):
+        calls = []
+        tex2typst.tex2typst(
+            ["a", "b", "c", "d"],
+            progress_callback=lambda done, total: calls.append(done),
+            callback_every_n=2,
+        )
+        self.assertEqual(calls, [2, 4])
+
+    def test_results_match_plain_batch(self):
+        items = [r"\alpha", r"\frac{1}{", "", r"\beta", "x^2"]
+        expected = tex2typst.tex2typst(
+            items, keep_original_on_error=True, return_failed_indices=True, skip_empty=True
+        )
+        calls = []
+        actual = tex2typst.tex2typst(
+            items,
+            keep_original_on_error=True,
+            return_failed_indices=True,
+            skip_empty=True,
+            progress_callback=lambda done, total: calls.append((done, total)),
+            callback_every_n=1,
+        )
+        self.assertEqual(actual, expected)
+        # The skipped empty item is not counted
+        self.assertEqual(calls, [(1, 4), (2, 4), (3, 4), (4, 4)])
+
+    def test_error_names_original_index(self):
+        with self.assertRaisesRegex(ValueError, "item 3 "):
+            tex2typst.tex2typst(
+                ["a", "", "b", r"\frac{1}{"],
+                skip_empty=True,
+                progress_callback=lambda done, total: None,
+                callback_every_n=1,
+            )
+
+    def test_callback_may_convert(self):
+        seen = []
+        tex2typst.tex2typst(
+            ["a", "b"],
+            progress_callback=lambda done, total: seen.append(tex2typst.tex2typst(r"\alpha")),
+            callback_every_n=1,
+        )
+        self.assertEqual(seen, ["alpha", "alpha"])
+
+    def test_exception_propagates(self):
+        def fail(done, total):
+            raise KeyError("stop")
+
+        with self.assertRaises(KeyError):
+            tex2typst.tex2typst(["a", "b"], progress_callback=fail, callback_every_n=1)
+
+    def test_invalid_arguments(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["a"], progress_callback=print, callback_every_n=0)
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst("a", progress_callback=print)
+
+
+
+class TestChunkSize(unittest.TestCase):
+    """Test chunk_size"""
+
+    def test_results_match_unchunked(self):
+        items = [r"\alpha", "", r"\frac{1}{", "x^2", r"\beta"] * 7
+        options = dict(keep_original_on_error=True, return_failed_indices=True, skip_empty=True)
+        expected = tex2typst.tex2typst(items, **options)
+        for chunk_size in [1, 2, 3, 100]:
+            with self.subTest(chunk_size=chunk_size):
+                self.assertEqual(
+                    tex2typst.tex2typst(items, chunk_size=chunk_size, **options), expected
+                )
+
+    def test_error_names_original_index(self):
+        with self.assertRaisesRegex(ValueError, "item 4 "):
+            tex2typst.tex2typst(["a", "b", "c", "d", r"\frac{1}{"], chunk_size=2)
+
+    def test_progress_boundaries_kept(self):
+        calls = []
+        tex2typst.tex2typst(
+            ["x"] * 7,
+            chunk_size=2,
+            progress_callback=lambda done, total: calls.append(done),
+            callback_every_n=3,
+        )
+        self.assertEqual(calls, [3, 6, 7])
+
+    def test_requires_list(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst("x", chunk_size=2)
+
+
+
+class FakeBar:
+    """Records the counts passed to update(), like a tqdm bar would"""
+
+    def __init__(self):
+        self.updates = []
+
+    def update(self, n):
+        self.updates.append(n)
+
+
+class TestBatchProgress(unittest.TestCase):
+    """Test tex2typst_batch_progress"""
+
+    def test_updates_every_chunk(self):
+        bar = FakeBar()
+        results = tex2typst.tex2typst_batch_progress(["x"] * 250, bar)
+        self.assertEqual(results, ["x"] * 250)
+        self.assertEqual(bar.updates, [100, 100, 50])
+
+    def test_custom_chunk_size(self):
+        bar = FakeBar()
+        tex2typst.tex2typst_batch_progress(["a", "b", "c"], progress_bar=bar, chunk_size=2)
+        self.assertEqual(bar.updates, [2, 1])
+
+    def test_without_bar(self):
+        items = [r"\alpha", r"\frac{1}{", "x^2"]
+        self.assertEqual(
+            tex2typst.tex2typst_batch_progress(items, keep_original_on_error=True),
+            tex2typst.tex2typst(items, keep_original_on_error=True),
+        )
+
+    def test_counts_converted_items(self):
+        bar = FakeBar()
+        tex2typst.tex2typst_batch_progress(["a", "", "a", "b"], bar, skip_empty=True, dedupe=True)
+        self.assertEqual(bar.updates, [2])
+
+    def test_update_error_propagates(self):
+        class BrokenBar:
+            def update(self, n):
+                raise RuntimeError("closed")
+
+        with self.assertRaisesRegex(RuntimeError, "closed"):
+            tex2typst.tex2typst_batch_progress(["x"], BrokenBar())
+        with self.assertRaises(AttributeError):
+            tex2typst.tex2typst_batch_progress(["x"], object())
+
+    def test_requires_list(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst_batch_progress("x", FakeBar())
+
+
+if __name__ == "__main__":
+    unittest.main()
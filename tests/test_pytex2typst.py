@@ -123,6 +123,173 @@ class TestTex2TypstOptions(unittest.TestCase):
         self.assertIn("frac", result)
         self.assertIn("oo", result)
 
+    def test_ignore_unknown_commands(self):
+        latex = "\\alpha + \\notacommand"
+        result = tex2typst.tex2typst(latex, ignore_unknown_commands=True)
+        print(
+            f"\n[Test Options ignore_unknown_commands=True] Input: {latex} -> Output: {result}"
+        )
+        self.assertEqual(result, "alpha + \\notacommand")
+
+    def test_ignore_unknown_commands_passes_through(self):
+        latex = "\\alpha + \\notacommand{x} y"
+        result = tex2typst.tex2typst(latex, ignore_unknown_commands=True)
+        self.assertIn("\\notacommand", result)
+        self.assertEqual(result, "alpha + \\notacommand x y")
+        self.assertEqual(tex2typst.tex2typst(latex), "alpha + notacommand x y")
+
+    def test_ignore_unknown_commands_keeps_defined(self):
+        result = tex2typst.tex2typst(
+            "\\R + \\foo + \\qux + \\dropme",
+            ignore_unknown_commands=True,
+            custom_tex_macros={"\\R": "\\mathbb{R}"},
+            symbol_map={"foo": "star"},
+            replace_unknown_commands={"dropme": None},
+        )
+        self.assertEqual(result, "RR + star + \\qux +")
+
+    def test_ignore_unknown_commands_batch(self):
+        latex = ["\\alpha", "\\notacommand", "\\qux{x}"]
+        results = tex2typst.tex2typst(latex, ignore_unknown_commands=True)
+        self.assertEqual(results, ["alpha", "\\notacommand", "\\qux x"])
+
+    def test_replace_unknown_commands(self):
+        latex = "\\alpha + \\notacommand"
+        result = tex2typst.tex2typst(
+            latex, replace_unknown_commands={"notacommand": "beta", "dropme": None}
+        )
+        print(
+            f"\n[Test Options replace_unknown_commands] Input: {latex} -> Output: {result}"
+        )
+        self.assertEqual(result, "alpha + beta")
+
+    def test_replace_unknown_commands_drops_none(self):
+        latex = "\\alpha + \\dropme{x} y"
+        result = tex2typst.tex2typst(
+            latex, replace_unknown_commands={"dropme": None}
+        )
+        self.assertEqual(result, "alpha + x y")
+
+    def test_replace_unknown_commands_only_unknown(self):
+        # Known commands and `symbol_map` entries are not replaced
+        result = tex2typst.tex2typst(
+            "\\alpha + \\foo + \\notacommand",
+            replace_unknown_commands={
+                "alpha": "gamma",
+                "foo": "x",
+                "\\notacommand": "beta",
+            },
+            symbol_map={"foo": "star"},
+        )
+        self.assertEqual(result, "alpha + star + beta")
+
+    def test_replace_unknown_commands_batch(self):
+        results = tex2typst.tex2typst(
+            ["\\notacommand", "\\dropme x"],
+            replace_unknown_commands={"notacommand": "beta", "dropme": None},
+        )
+        self.assertEqual(results, ["beta", "x"])
+
+    def test_replace_unknown_commands_empty_is_noop(self):
+        latex = "\\alpha + \\notacommand"
+        self.assertEqual(
+            tex2typst.tex2typst(latex, replace_unknown_commands={}),
+            tex2typst.tex2typst(latex),
+        )
+        self.assertEqual(
+            tex2typst.tex2typst([latex], replace_unknown_commands={}),
+            tex2typst.tex2typst([latex]),
+        )
+
+    def test_handle_text_mode_verbatim(self):
+        latex = "x \\text{ if } y"
+        result = tex2typst.tex2typst(latex, handle_text_mode="verbatim")
+        print(f"\n[Test Options handle_text_mode] Input: {latex} -> Output: {result}")
+        self.assertEqual(result, tex2typst.tex2typst(latex))
+        self.assertIn('" if "', result)
+
+    def test_handle_text_mode_convert(self):
+        latex = "x \\text{ if } y"
+        result = tex2typst.tex2typst(latex, handle_text_mode="convert")
+        self.assertEqual(result, "x i f y")
+        self.assertNotEqual(
+            result, tex2typst.tex2typst(latex, handle_text_mode="verbatim")
+        )
+
+    def test_handle_text_mode_strip(self):
+        latex = "x \\text{ if } y"
+        result = tex2typst.tex2typst(latex, handle_text_mode="strip")
+        self.assertEqual(result, "x y")
+        self.assertNotEqual(
+            result, tex2typst.tex2typst(latex, handle_text_mode="convert")
+        )
+        self.assertEqual(
+            tex2typst.tex2typst([latex, "\\text{a}"], handle_text_mode="strip"),
+            ["x y", ""],
+        )
+
+    def test_handle_text_mode_invalid(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("\\text{a}", handle_text_mode="bogus")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["\\text{a}"], handle_text_mode="bogus")
+
+    def test_trim_input(self):
+        result = tex2typst.tex2typst("  \\alpha  ", keep_spaces=True, trim_input=True)
+        self.assertEqual(result, "alpha")
+
+        results = tex2typst.tex2typst(
+            ["  \\alpha ", "   "], trim_input=True, skip_empty=True
+        )
+        self.assertEqual(results, ["alpha", None])
+
+    def test_max_input_length(self):
+        self.assertEqual(tex2typst.tex2typst("\\alpha", max_input_length=6), "alpha")
+        with self.assertRaisesRegex(ValueError, "7 bytes.*max_input_length=6"):
+            tex2typst.tex2typst("\\alpha ", max_input_length=6)
+        # Bytes, not characters
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("\u03b1\u03b2\u03b3", max_input_length=4)
+
+        with self.assertRaises(ValueError) as ctx:
+            tex2typst.tex2typst(
+                ["x", "\\alpha", "y", "\\beta"], max_input_length=4
+            )
+        self.assertIn("item 1 (6 bytes)", str(ctx.exception))
+        self.assertIn("item 3 (5 bytes)", str(ctx.exception))
+        self.assertNotIn("item 0", str(ctx.exception))
+
+        self.assertEqual(
+            tex2typst.tex2typst_with_info("xx", max_input_length=2).typst, "x x"
+        )
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst_batch_with_info(["xxx"], max_input_length=2)
+        with self.assertRaises(ValueError):
+            tex2typst.convert_auto("\\frac{1}{2}", max_input_length=3)
+        with tex2typst.WorkerConverter() as conv:
+            with self.assertRaises(ValueError):
+                conv.convert_many(["x", "xyz"], max_input_length=2)
+
+
+    def test_wrap(self):
+        self.assertEqual(tex2typst.tex2typst("x^2", wrap="inline"), "$x^2$")
+        self.assertEqual(tex2typst.tex2typst("x^2", wrap="block"), "$ x^2 $")
+        self.assertEqual(tex2typst.tex2typst("x^2", wrap="none"), "x^2")
+        self.assertEqual(tex2typst.tex2typst("x^2"), "x^2")
+
+        results = tex2typst.tex2typst(
+            ["a", "\\frac{1}{", ""],
+            wrap="block",
+            keep_original_on_error=True,
+            skip_empty=True,
+        )
+        self.assertEqual(results, ["$ a $", "\\frac{1}{", None])
+
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", wrap="display")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["x"], wrap="display")
+
 
 class TestTypst2TexOptions(unittest.TestCase):
     """Test typst2tex with various options"""
@@ -143,6 +310,46 @@ class TestTypst2TexOptions(unittest.TestCase):
         )
         self.assertIsInstance(result, str)
 
+    def test_trim_input(self):
+        typst = "  alpha  "
+        self.assertEqual(tex2typst.typst2tex(typst), "  \\alpha  ")
+        self.assertEqual(tex2typst.typst2tex(typst, trim_input=True), "\\alpha")
+        self.assertEqual(
+            tex2typst.typst2tex([" alpha", "beta\n"], trim_input=True),
+            ["\\alpha", "\\beta"],
+        )
+
+    def test_max_input_length(self):
+        self.assertEqual(tex2typst.typst2tex("alpha", max_input_length=5), "\\alpha")
+        with self.assertRaises(ValueError):
+            tex2typst.typst2tex("alpha", max_input_length=4)
+        with self.assertRaisesRegex(ValueError, "item 0 \\(5 bytes\\)"):
+            tex2typst.typst2tex(["alpha", "x"], max_input_length=4)
+
+    def test_custom_typst_macros(self):
+        macros = {"vv": "bold", "RR": "ZZ", "arrow.r": "arrow.l"}
+        self.assertEqual(
+            tex2typst.typst2tex("vv(x) in RR", custom_typst_macros=macros),
+            "\\boldsymbol{x} \\in \\mathbb{Z}",
+        )
+        # Whole dotted names only; string literals are untouched
+        self.assertEqual(
+            tex2typst.typst2tex(
+                ['arrow.r "RR" RR_1 arrow'], custom_typst_macros=macros
+            ),
+            ["\\leftarrow \\text{RR} \\mathbb{Z}_1 \\vec"],
+        )
+        conv = tex2typst.Converter(custom_typst_macros={"RR": "NN"})
+        self.assertEqual(conv.typst2tex("RR"), "\\mathbb{N}")
+
+    def test_tex2typst_only_options_rejected(self):
+        # The JS typst2tex has no use for these `tex2typst` options
+        for option in ("prefer_shorthands", "keep_spaces"):
+            with self.assertRaises(TypeError):
+                tex2typst.typst2tex("x + y", **{option: True})
+            with self.assertRaises(TypeError):
+                tex2typst.typst2tex(["alpha"], **{option: False})
+
 
 if __name__ == "__main__":
     unittest.main()
@@ -0,0 +1,61 @@
+"""Test roundtrip_check and roundtrip_diff."""
+
+import unittest
+import tex2typst
+
+
+class TestRoundtripCheck(unittest.TestCase):
+    """Test the TeX -> Typst -> TeX equivalence check"""
+
+    def test_equivalent(self):
+        for tex in [r"\frac{1}{2}", r"\alpha+\beta", r"\int_0^1 x\,dx", "x^{2}", r"\sqrt[3]{x}"]:
+            with self.subTest(tex=tex):
+                self.assertTrue(tex2typst.roundtrip_check(tex))
+
+    def test_control_word_space_is_significant(self):
+        report = tex2typst.roundtrip_diff(r"\alpha b")
+        self.assertEqual(report["output"], r"\alpha b")
+        self.assertTrue(report["equivalent"])
+
+    def test_not_equivalent(self):
+        self.assertFalse(tex2typst.roundtrip_check(r"\dfrac{1}{2}"))
+
+    def test_conversion_error(self):
+        self.assertFalse(tex2typst.roundtrip_check(r"\frac{1}{"))
+
+    def test_max_input_length(self):
+        with self.assertRaises(ValueError):
+            tex2typst.roundtrip_check(r"\alpha", max_input_length=2)
+
+
+class TestRoundtripDiff(unittest.TestCase):
+    """Test the round-trip report"""
+
+    def test_report(self):
+        self.assertEqual(
+            tex2typst.roundtrip_diff(r"\dfrac{1}{2}"),
+            {
+                "input": r"\dfrac{1}{2}",
+                "intermediate": "frac(1, 2)",
+                "output": r"\frac{1}{2}",
+                "equivalent": False,
+                "diff": "- \\dfrac{1}{2}\n+ \\frac{1}{2}\n?  ^",
+                "error": None,
+            },
+        )
+
+    def test_equivalent_has_empty_diff(self):
+        report = tex2typst.roundtrip_diff(r"\alpha + \beta")
+        self.assertTrue(report["equivalent"])
+        self.assertEqual(report["diff"], "")
+
+    def test_error(self):
+        report = tex2typst.roundtrip_diff(r"\frac{1}{")
+        self.assertIsNone(report["intermediate"])
+        self.assertIsNone(report["output"])
+        self.assertFalse(report["equivalent"])
+        self.assertTrue(report["error"].startswith("tex2typst: Conversion failed"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,67 @@
+"""Test pandas Series support."""
+
+import unittest
+import tex2typst
+
+try:
+    import pandas as pd
+except ImportError:
+    pd = None
+
+HAS_FEATURE = hasattr(tex2typst._tex2typst_core, "tex2typst_series")
+
+
+@unittest.skipIf(pd is None, "pandas is not installed")
+@unittest.skipUnless(HAS_FEATURE, "built without the 'pandas' feature")
+class TestSeriesInput(unittest.TestCase):
+    """Test tex2typst_series"""
+
+    def test_preserves_index_and_name(self):
+        s = pd.Series([r"\alpha", r"\beta"], index=["a", "b"], name="tex")
+        result = tex2typst.tex2typst_series(s)
+
+        self.assertIsInstance(result, pd.Series)
+        self.assertEqual(result.to_list(), ["alpha", "beta"])
+        self.assertEqual(list(result.index), ["a", "b"])
+        self.assertEqual(result.name, "tex")
+
+    def test_with_options(self):
+        s = pd.Series([r"\frac{1}{2}"])
+        result = tex2typst.tex2typst_series(s, frac_to_slash=False)
+        self.assertIn("frac", result[0])
+
+    def test_shared_options(self):
+        s = pd.Series([r"\phi \leq \dv{f}{x}", r"\begin{myenv} a \end{myenv}"])
+        result = tex2typst.tex2typst_series(
+            s,
+            handle_physics=True,
+            output_unicode=True,
+            symbol_map={"phi": "phi.alt"},
+            environment_map={"myenv": "f"},
+        )
+        self.assertEqual(result.to_list(), ["ϕ ≤ (dif f)/(dif x)", "f(a)"])
+
+    def test_skip_empty(self):
+        s = pd.Series([r"\alpha", ""], index=[10, 20])
+        result = tex2typst.tex2typst_series(s, skip_empty=True, empty_replacement="")
+        self.assertEqual(result.to_list(), ["alpha", ""])
+        self.assertEqual(list(result.index), [10, 20])
+
+    def test_dedupe(self):
+        s = pd.Series([r"\alpha", "", r"\beta", r"\alpha"], name="tex")
+        result = tex2typst.tex2typst_series(s, dedupe=True, skip_empty=True)
+        self.assertEqual(result.to_list(), ["alpha", None, "beta", "alpha"])
+        self.assertEqual(result.name, "tex")
+        with self.assertRaisesRegex(ValueError, "item 1 "):
+            tex2typst.tex2typst_series(pd.Series(["x", r"\frac{", r"\frac{"]), dedupe=True)
+
+
+@unittest.skipIf(HAS_FEATURE, "built with the 'pandas' feature")
+class TestSeriesUnavailable(unittest.TestCase):
+    def test_raises_import_error(self):
+        with self.assertRaises(ImportError):
+            tex2typst.tex2typst_series([r"\alpha"])
+
+
+if __name__ == "__main__":
+    unittest.main()
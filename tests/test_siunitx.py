@@ -0,0 +1,125 @@
+"""Test siunitx quantities, units and numbers with siunitx=True."""
+
+import unittest
+import warnings
+import tex2typst
+
+
+def convert(tex, **options):
+    return tex2typst.tex2typst(tex, siunitx=True, **options)
+
+
+class TestSiunitxPlain(unittest.TestCase):
+    """Test the default plain math output"""
+
+    def test_quantities(self):
+        self.assertEqual(
+            convert(r"\SI{3.0e8}{\metre\per\second}"),
+            '3.0 times 10^8 thin upright("m") slash upright("s")',
+        )
+        self.assertEqual(convert(r"\qty{5}{\kilo\gram}"), '5 thin upright("kg")')
+
+    def test_numbers(self):
+        cases = {
+            r"\num{1.23(4)}": "1.23(4)",
+            r"\num{1.23(4)e-5}": "1.23(4) times 10^(-5)",
+            r"\num{1.5+-0.2e3}": "(1.5 plus.minus 0.2) times 10^3",
+            r"\num{1.5 \pm 0.2}": "1.5 plus.minus 0.2",
+            r"\num{e8}": "10^8",
+            r"\num{.5}": "0.5",
+        }
+        for tex, expected in cases.items():
+            with self.subTest(tex=tex):
+                self.assertEqual(convert(tex), expected)
+
+    def test_units(self):
+        cases = {
+            r"\si{\metre\per\second\squared}": 'upright("m") slash upright("s")^2',
+            r"\si{\per\second}": 'upright("s")^(-1)',
+            r"\unit{\kilo\gram\metre\per\square\second}": (
+                'upright("kg") thin upright("m") slash upright("s")^2'
+            ),
+            r"\si{kg.m/s^2}": 'upright("kg") thin upright("m") slash upright("s")^2',
+        }
+        for tex, expected in cases.items():
+            with self.subTest(tex=tex):
+                self.assertEqual(convert(tex), expected)
+
+    def test_mixed_with_math(self):
+        self.assertEqual(
+            convert(r"E = \qty{1.2}{\electronvolt} + x^2"),
+            'E = 1.2 thin upright("eV") + x^2',
+        )
+        self.assertEqual(convert(r"\frac{\SI{1}{\m}}{2}"), '(1 thin upright("m"))/2')
+
+
+class TestSiunitxPackages(unittest.TestCase):
+    """Test the unify and metro package styles"""
+
+    def test_calls(self):
+        for style in ["unify", "metro"]:
+            with self.subTest(style=style):
+                self.assertEqual(
+                    convert(r"\SI{3.0e8}{\metre\per\second}", units_style=style),
+                    'qty("3.0e8", "metre per second")',
+                )
+                self.assertEqual(
+                    convert(r"\num{1.23(4)}", units_style=style), 'num("1.23+-0.04")'
+                )
+                self.assertEqual(
+                    convert(r"\si{\kilo\gram\squared}", units_style=style),
+                    'unit("kilo gram squared")',
+                )
+
+    def test_handle_siunitx_alias(self):
+        tex = r"\SI{9.8}{\metre\per\second\squared}"
+        expected = '9.8 thin upright("m") slash upright("s")^2'
+        self.assertEqual(tex2typst.tex2typst(tex, handle_siunitx=True), expected)
+        self.assertEqual(tex2typst.tex2typst([tex], handle_siunitx=True), [expected])
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\si{\kilo\gram}", handle_siunitx=True, units_style="unify"
+            ),
+            'unit("kilo gram")',
+        )
+
+    def test_requires_siunitx(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", units_style="unify")
+        with self.assertRaises(ValueError):
+            convert("x", units_style="bogus")
+
+
+class TestSiunitxErrors(unittest.TestCase):
+    """Test warnings and errors"""
+
+    def test_unknown_unit_warns(self):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            result = convert(r"\SI{1}{\furlong\per\fortnight}")
+        self.assertEqual(result, '1 thin upright("furlong") slash upright("fortnight")')
+        self.assertEqual(len(caught), 1)
+        self.assertIn(r"\furlong", str(caught[0].message))
+        self.assertIn(r"\fortnight", str(caught[0].message))
+
+    def test_invalid_number(self):
+        with self.assertRaisesRegex(ValueError, "Invalid siunitx number '1.2.3'"):
+            convert(r"\num{1.2.3}")
+        with self.assertRaises(ValueError):
+            convert(r"\SI{1}")
+
+    def test_batch(self):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            result = convert(
+                [r"\SI{1}{\m}", r"\frac{\SI{2}{\s}}{", r"\si{\bogus}"],
+                keep_original_on_error=True,
+            )
+        self.assertEqual(
+            result, ['1 thin upright("m")', r"\frac{\SI{2}{\s}}{", 'upright("bogus")']
+        )
+        self.assertIn("item 2: unknown unit \\bogus", str(caught[0].message))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,94 @@
+"""Test get_stats, reset_stats and the timing option."""
+
+import threading
+import unittest
+import tex2typst
+
+
+class TestStats(unittest.TestCase):
+    """Test the process-wide conversion counters"""
+
+    def setUp(self):
+        tex2typst.reset_stats()
+
+    def test_counts_conversions(self):
+        tex2typst.tex2typst([r"\alpha", r"\beta", "x"])
+        tex2typst.typst2tex(["alpha", "beta"])
+        stats = tex2typst.get_stats()
+        self.assertEqual(stats["tex2typst_conversions"], 3)
+        self.assertEqual(stats["typst2tex_conversions"], 2)
+        self.assertEqual(stats["calls"], 2)
+        self.assertGreater(stats["total_time_seconds"], 0)
+        self.assertGreaterEqual(stats["total_time_seconds"], stats["max_call_time_seconds"])
+        self.assertGreater(stats["js_memory_used_bytes"], 0)
+
+    def test_reset(self):
+        tex2typst.typst2tex(["alpha"])
+        tex2typst.reset_stats()
+        stats = tex2typst.get_stats()
+        for key, value in stats.items():
+            if key != "js_memory_used_bytes":
+                self.assertEqual(value, 0, key)
+
+    def test_aggregates_threads(self):
+        def work():
+            tex2typst.typst2tex(["a", "b"])
+
+        threads = [threading.Thread(target=work) for _ in range(4)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        stats = tex2typst.get_stats()
+        self.assertEqual(stats["typst2tex_conversions"], 8)
+        self.assertEqual(stats["engines_initialized"], 4)
+        self.assertGreater(stats["js_init_time_seconds"], 0)
+
+    def test_memory_without_engine(self):
+        stats = {}
+
+        def work():
+            stats.update(tex2typst.get_stats())
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertIsNone(stats["js_memory_used_bytes"])
+
+    def test_converter_cache(self):
+        conv = tex2typst.Converter()
+        conv.tex2typst("x")
+        conv.tex2typst("x")
+        stats = tex2typst.get_stats()
+        self.assertEqual((stats["cache_hits"], stats["cache_misses"]), (1, 1))
+        self.assertEqual(stats["engines_initialized"], 1)
+
+
+class TestTiming(unittest.TestCase):
+    """Test tex2typst(..., timing=True)"""
+
+    def test_returns_elapsed(self):
+        result, elapsed = tex2typst.tex2typst(r"\frac{1}{2}", timing=True)
+        self.assertEqual(result, "1/2")
+        self.assertIsInstance(elapsed, float)
+        self.assertGreater(elapsed, 0)
+
+    def test_bypasses_cache(self):
+        tex2typst.tex2typst(r"\gamma")
+        tex2typst.reset_stats()
+        tex2typst.tex2typst(r"\gamma", timing=True)
+        self.assertEqual(tex2typst.get_stats()["tex2typst_conversions"], 1)
+
+    def test_with_report(self):
+        (result, unknown), _ = tex2typst.tex2typst(
+            r"\pdv f x", report_unknown=True, timing=True
+        )
+        self.assertEqual(unknown, ["\\pdv"])
+
+    def test_list_input(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(["x"], timing=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
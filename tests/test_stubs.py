@@ -0,0 +1,71 @@
+"""Test that the type stubs shipped in the package match the modules."""
+
+import ast
+import inspect
+import os
+import sys
+import unittest
+import tex2typst
+from tex2typst import _tex2typst_core
+
+PACKAGE_DIR = os.path.dirname(tex2typst.__file__)
+
+
+def stub_definitions(filename):
+    """Map the top-level names defined in a stub file to their AST nodes."""
+    with open(os.path.join(PACKAGE_DIR, filename), encoding="utf-8") as f:
+        tree = ast.parse(f.read())
+    definitions = {}
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.ClassDef)):
+            definitions.setdefault(node.name, node)
+        elif isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+            definitions[node.target.id] = node
+    return definitions
+
+
+def stub_parameters(node):
+    """The (name, kind) of each parameter of a stub function."""
+    args = node.args
+    parameters = [(a.arg, inspect.Parameter.POSITIONAL_OR_KEYWORD) for a in args.args]
+    if args.vararg:
+        parameters.append((args.vararg.arg, inspect.Parameter.VAR_POSITIONAL))
+    parameters += [(a.arg, inspect.Parameter.KEYWORD_ONLY) for a in args.kwonlyargs]
+    if args.kwarg:
+        parameters.append((args.kwarg.arg, inspect.Parameter.VAR_KEYWORD))
+    return parameters
+
+
+class TestStubs(unittest.TestCase):
+    """Test tex2typst/__init__.pyi, tex2typst/_tex2typst_core.pyi and py.typed"""
+
+    def test_py_typed(self):
+        self.assertTrue(os.path.isfile(os.path.join(PACKAGE_DIR, "py.typed")))
+
+    def test_core_names(self):
+        definitions = stub_definitions("_tex2typst_core.pyi")
+        names = [name for name in dir(_tex2typst_core) if not name.startswith("_")]
+        self.assertEqual([name for name in names if name not in definitions], [])
+
+    def test_core_signatures(self):
+        definitions = stub_definitions("_tex2typst_core.pyi")
+        for name in dir(_tex2typst_core):
+            function = getattr(_tex2typst_core, name)
+            if name.startswith("_") or isinstance(function, type):
+                continue
+            with self.subTest(name):
+                expected = [
+                    (parameter.name, parameter.kind)
+                    for parameter in inspect.signature(function).parameters.values()
+                ]
+                self.assertEqual(stub_parameters(definitions[name]), expected)
+
+    def test_package_names(self):
+        definitions = stub_definitions("__init__.pyi")
+        self.assertEqual(
+            [name for name in tex2typst.__all__ if name not in definitions], []
+        )
+
+
+if __name__ == "__main__":
+    sys.exit(unittest.main())
@@ -0,0 +1,53 @@
+"""Test list_supported_commands and find_unsupported_commands."""
+
+import unittest
+import tex2typst
+
+
+class TestSupportedCommands(unittest.TestCase):
+    """Test screening TeX for commands the converter cannot handle"""
+
+    def test_list(self):
+        commands = tex2typst.list_supported_commands()
+        self.assertEqual(commands, sorted(commands))
+        for command in [r"\frac", r"\alpha", r"\sqrt", r"\left", r"\mathbb"]:
+            self.assertIn(command, commands)
+        self.assertNotIn(r"\definitelynotacommand", commands)
+
+    def test_find(self):
+        self.assertEqual(
+            tex2typst.find_unsupported_commands(
+                r"\frac{1}{2} + \definitelynotacommand + \pdv{f}{x} + \definitelynotacommand"
+            ),
+            [r"\definitelynotacommand", r"\pdv"],
+        )
+        self.assertEqual(tex2typst.find_unsupported_commands(r"\{ a \} \\ b"), [])
+
+    def test_custom_macros_ignored(self):
+        self.assertEqual(
+            tex2typst.find_unsupported_commands(
+                r"\R + \pdv{f}{x}", custom_tex_macros={r"\R": r"\mathbb{R}"}
+            ),
+            [r"\pdv"],
+        )
+
+    def test_matches_report_unknown(self):
+        tex = r"\alpha + \pdv{f}{x} + \vb{v}"
+        _, unknown = tex2typst.tex2typst(tex, report_unknown=True)
+        self.assertEqual(tex2typst.find_unsupported_commands(tex), unknown)
+
+    def test_extension_commands(self):
+        tex2typst.register_js_extension(
+            "const base = tex2typst;"
+            "tex2typst = (tex, options) => tex === '\\\\mycommand' ? 'mine' : base(tex, options);"
+        )
+        try:
+            self.assertIn(r"\mycommand", tex2typst.list_supported_commands())
+            self.assertEqual(tex2typst.find_unsupported_commands(r"\mycommand"), [])
+        finally:
+            tex2typst.clear_js_extensions()
+        self.assertNotIn(r"\mycommand", tex2typst.list_supported_commands())
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,61 @@
+"""Test overriding built-in symbol translations with symbol_map."""
+
+import unittest
+import tex2typst
+
+
+class TestSymbolMap(unittest.TestCase):
+    def test_override(self):
+        self.assertEqual(tex2typst.tex2typst(r"\epsilon"), "epsilon.alt")
+        self.assertEqual(
+            tex2typst.tex2typst(r"\epsilon + x", symbol_map={"epsilon": "epsilon"}),
+            "epsilon + x",
+        )
+        # Keys may keep their backslash; other commands are untouched
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"\alpha \beta_1 \alphabet", symbol_map={r"\alpha": "a", "beta": "b"}
+            ),
+            "a b_1 alphabet",
+        )
+
+    def test_operands_are_grouped(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\frac{\phi}{2} + x^\phi", symbol_map={"phi": "a + b"}),
+            "(a + b)/2 + x^(a + b)",
+        )
+
+    def test_list_and_converter(self):
+        self.assertEqual(
+            tex2typst.tex2typst([r"\phi", "x", r"\phi^2"], symbol_map={"phi": "Phi"}),
+            ["Phi", "x", "Phi^2"],
+        )
+        conv = tex2typst.Converter(symbol_map={"infty": "oo"})
+        self.assertEqual(conv.tex2typst(r"-\infty"), "-oo")
+
+    def test_default_option(self):
+        tex2typst.set_default_options(symbol_map={"infty": "oo"})
+        try:
+            self.assertEqual(tex2typst.tex2typst([r"\infty"]), ["oo"])
+        finally:
+            tex2typst.set_default_options(symbol_map=None)
+        self.assertEqual(tex2typst.tex2typst([r"\infty"]), ["infinity"])
+
+    def test_empty_is_noop(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\phi", symbol_map={}), tex2typst.tex2typst(r"\phi")
+        )
+
+    def test_with_private_use_siunitx_placeholders(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"\num{3} \phi", siunitx=True, symbol_map={"phi": "Q"}),
+            "3 Q",
+        )
+
+    def test_invalid_values(self):
+        with self.assertRaises(TypeError):
+            tex2typst.tex2typst(r"\phi", symbol_map={"phi": 1})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,87 @@
+"""Test extracting \\tag{} from TeX formulas."""
+
+import unittest
+import warnings
+import tex2typst
+
+COUNTER_FIX = "#counter(math.equation).update(n => n - 1)"
+
+
+class TestTag(unittest.TestCase):
+    """Test \\tag{} and \\tag*{} handling"""
+
+    def tearDown(self):
+        tex2typst.clear_cache()
+
+    def test_plain_text_tag(self):
+        self.assertEqual(
+            tex2typst.tex2typst(r"x = 1 \tag{3a}", wrap="block", tags=True),
+            "#math.equation(block: true, numbering: _ => [(3a)], $ x = 1 $)"
+            + COUNTER_FIX,
+        )
+        info = tex2typst.tex2typst_with_info(r"x \tag{3a}", tags=True)
+        self.assertEqual(info.tag, "(3a)")
+        info = tex2typst.tex2typst_with_info(r"x \tag*{A_1}", tags=True)
+        self.assertEqual(info.tag, "A\\_1")
+        self.assertIsNone(tex2typst.tex2typst_with_info("x", tags=True).tag)
+
+    def test_math_tag(self):
+        info = tex2typst.tex2typst_with_info(r"x \tag{$\ast$}", tags=True)
+        self.assertEqual(info.tag, "($*$)")
+        self.assertEqual(
+            tex2typst.tex2typst_with_info(
+                r"x \tag*{$\alpha'$ and \%}", tags=True
+            ).tag,
+            "$alpha'$ and %",
+        )
+
+    def test_label_follows_equation(self):
+        self.assertEqual(
+            tex2typst.tex2typst(
+                r"x \label{eq:x} \tag*{$\dagger$}",
+                wrap="block",
+                labels=True,
+                tags=True,
+            ),
+            "#math.equation(block: true, numbering: _ => [$dagger$], $ x $) <eq:x>"
+            + COUNTER_FIX,
+        )
+
+    def test_dropped_without_block(self):
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            self.assertEqual(tex2typst.tex2typst(r"y \tag{2}", tags=True), "y")
+        self.assertEqual(len(caught), 1)
+        self.assertIn("dropped (2)", str(caught[0].message))
+
+    def test_multiple_tags(self):
+        with self.assertRaisesRegex(ValueError, "single \\\\tag"):
+            tex2typst.tex2typst(r"x \tag{1} \tag{2}", tags=True)
+        with self.assertRaisesRegex(ValueError, "item 1: "):
+            tex2typst.tex2typst(["x", r"y \tag{1} \tag*{2}"], tags=True)
+        with self.assertRaisesRegex(ValueError, "Unbalanced"):
+            tex2typst.tex2typst_with_info(r"x \tag{$a}", tags=True)
+
+    def test_kept_by_default(self):
+        with warnings.catch_warnings():
+            warnings.simplefilter("error")
+            self.assertEqual(
+                tex2typst.tex2typst(r"x = 1 \tag{3a}", wrap="block"),
+                "$ x = 1 tag 3 a $",
+            )
+            self.assertIsNone(tex2typst.tex2typst_with_info(r"x \tag{3a}").tag)
+
+    def test_batch(self):
+        self.assertEqual(
+            tex2typst.tex2typst([r"a \tag{1}", "b"], wrap="block", tags=True),
+            [
+                "#math.equation(block: true, numbering: _ => [(1)], $ a $)" + COUNTER_FIX,
+                "$ b $",
+            ],
+        )
+        infos = tex2typst.tex2typst_batch_with_info([r"a \tag{1}", "b"], tags=True)
+        self.assertEqual([info.tag for info in infos], ["(1)", None])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,117 @@
+"""Test converting the math of LaTeX and Markdown documents."""
+
+import sys
+import unittest
+import tex2typst
+
+
+class TestConvertLatexDocument(unittest.TestCase):
+    """Test convert_latex_document"""
+
+    def test_inline_and_display(self):
+        text = "Let $x^2$ and \\(y\\) be\n$$\\alpha$$ \\[\\beta\\]\n"
+        self.assertEqual(
+            tex2typst.convert_latex_document(text),
+            "Let $x^2$ and $y$ be\n$ alpha $ $ beta $\n",
+        )
+
+    def test_environments(self):
+        text = (
+            "\\begin{equation*}\\alpha\\end{equation*}\n"
+            "\\begin{align} a &= b \\\\ c &= d \\end{align}\n"
+            "\\begin{alignat}{2} x &= 1 \\end{alignat}"
+        )
+        report = tex2typst.convert_latex_document(text, report=True)
+        self.assertEqual(
+            [span.kind for span in report.spans],
+            ["env:equation*", "env:align", "env:alignat"],
+        )
+        self.assertEqual(report.output, "$ alpha $\n$ a &= b \\ c &= d $\n$ x &= 1 $")
+
+    def test_skipped_math(self):
+        text = (
+            "costs \\$5 % $comment$\n"
+            "\\verb|$verb$| \\begin{verbatim}$block$\\end{verbatim}\n"
+            "\\begin{figure}\\end{figure}\n"
+        )
+        self.assertEqual(tex2typst.convert_latex_document(text), text)
+
+    def test_report_offsets_are_bytes(self):
+        text = "é \\(\\alpha\\) ü $$\\beta$$"
+        report = tex2typst.convert_latex_document(text, report=True)
+        data = text.encode()
+        self.assertEqual([span.kind for span in report.spans], ["inline", "display"])
+        for span in report.spans:
+            self.assertEqual(data[span.start : span.end].decode(), span.original)
+        self.assertEqual(report.spans[0].start, 3)
+        self.assertEqual(report.spans[0].converted, "$alpha$")
+        self.assertEqual(report.output, "é $alpha$ ü $ beta $")
+
+    def test_errors_raise(self):
+        with self.assertRaisesRegex(ValueError, "math at byte 2"):
+            tex2typst.convert_latex_document("a $\\frac{1$ b")
+        with self.assertRaisesRegex(ValueError, "Unterminated math"):
+            tex2typst.convert_latex_document("a \\[x")
+
+    def test_errors_keep(self):
+        report = tex2typst.convert_latex_document(
+            "a $\\frac{1$ b $x$", errors="keep", report=True
+        )
+        self.assertEqual(report.output, "a $\\frac{1$ b $x$")
+        self.assertIsNotNone(report.spans[0].error)
+        self.assertIsNone(report.spans[0].converted)
+        self.assertEqual(report.spans[1].converted, "$x$")
+
+    def test_dry_run(self):
+        report = tex2typst.convert_latex_document("$\\alpha$", dry_run=True)
+        self.assertIsInstance(report, tex2typst.DocumentReport)
+        self.assertIsNone(report.output)
+        self.assertEqual(report.spans[0].converted, "$alpha$")
+
+    def test_options_passed_on(self):
+        self.assertEqual(
+            tex2typst.convert_latex_document("$\\infty$", infty_to_oo=True), "$oo$"
+        )
+
+    def test_bad_choice(self):
+        with self.assertRaises(ValueError):
+            tex2typst.convert_latex_document("$x$", errors="ignore")
+
+
+class TestConvertMarkdown(unittest.TestCase):
+    """Test convert_markdown"""
+
+    def test_inline_and_display(self):
+        self.assertEqual(
+            tex2typst.convert_markdown("Let $\\alpha$ be\n\n$$\\beta$$\n"),
+            "Let $alpha$ be\n\n$ beta $\n",
+        )
+
+    def test_pandoc_dollar_rules(self):
+        for text in ["costs $5 and $6", "$ x$", "$y $", "$1$2", "$a\n\nb$"]:
+            self.assertEqual(tex2typst.convert_markdown(text), text)
+
+    def test_skipped_math(self):
+        text = "\\$5 `$code$` and\n```\n$fenced$\n```\n~~~~\n$x$\n~~~~\n"
+        self.assertEqual(tex2typst.convert_markdown(text), text)
+
+    def test_report_offsets_are_bytes(self):
+        text = "é $\\alpha$ ü $$\\beta$$"
+        report = tex2typst.convert_markdown(text, report=True)
+        data = text.encode()
+        self.assertEqual([span.kind for span in report.spans], ["inline", "display"])
+        for span in report.spans:
+            self.assertEqual(data[span.start : span.end].decode(), span.original)
+        self.assertEqual(report.output, "é $alpha$ ü $ beta $")
+
+    def test_errors_keep_and_dry_run(self):
+        report = tex2typst.convert_markdown(
+            "$\\frac{1$ and $x$", errors="keep", dry_run=True
+        )
+        self.assertIsNone(report.output)
+        self.assertIsNotNone(report.spans[0].error)
+        self.assertEqual(report.spans[1].converted, "$x$")
+
+
+if __name__ == "__main__":
+    sys.exit(unittest.main())
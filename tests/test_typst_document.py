@@ -0,0 +1,111 @@
+"""Test converting the math of Typst documents."""
+
+import sys
+import unittest
+import warnings
+import tex2typst
+
+
+class TestConvertTypstDocument(unittest.TestCase):
+    """Test convert_typst_document"""
+
+    def test_inline_and_display(self):
+        text = "Let $x^2$ be\n$ alpha + beta $\n"
+        self.assertEqual(
+            tex2typst.convert_typst_document(text),
+            "Let \\(x^2\\) be\n\\[\\alpha + \\beta\\]\n",
+        )
+
+    def test_dollar_delimiters(self):
+        self.assertEqual(
+            tex2typst.convert_typst_document("$x$ and $ y $", delimiters="dollars"),
+            "$x$ and $$y$$",
+        )
+
+    def test_space_on_one_side_is_inline(self):
+        self.assertEqual(tex2typst.convert_typst_document("$ x$"), "\\(x\\)")
+
+    def test_skipped_dollars(self):
+        text = (
+            "costs \\$5 `$raw$` and\n"
+            "```\n$block$\n```\n"
+            "// $comment$\n"
+            "/* $block /* nested */ comment$ */\n"
+            '#let f = $a$\n#text("$str$")\n'
+        )
+        self.assertEqual(tex2typst.convert_typst_document(text), text)
+
+    def test_content_block_in_code(self):
+        self.assertEqual(
+            tex2typst.convert_typst_document('#figure(caption: "$c$")[$alpha$] <fig>'),
+            '#figure(caption: "$c$")[\\(\\alpha\\)] <fig>',
+        )
+
+    def test_url_is_not_comment(self):
+        self.assertEqual(
+            tex2typst.convert_typst_document("see https://example.org $pi$"),
+            "see https://example.org \\(\\pi\\)",
+        )
+
+    def test_math_equation_reported(self):
+        text = "#math.equation(block: true, $y$) and $x$"
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            output = tex2typst.convert_typst_document(text)
+        self.assertEqual(output, "#math.equation(block: true, $y$) and \\(x\\)")
+        self.assertEqual(len(caught), 1)
+        self.assertIn("math.equation", str(caught[0].message))
+
+        report = tex2typst.convert_typst_document(text, report=True)
+        span = report.spans[0]
+        self.assertEqual(span.kind, "math.equation")
+        self.assertEqual(span.original, "#math.equation(block: true, $y$)")
+        self.assertIsNone(span.converted)
+        self.assertIsNone(span.error)
+
+    def test_report_offsets_are_bytes(self):
+        text = "é $alpha$ ü $ beta $"
+        report = tex2typst.convert_typst_document(text, report=True)
+        data = text.encode()
+        self.assertEqual([span.kind for span in report.spans], ["inline", "display"])
+        for span in report.spans:
+            self.assertEqual(data[span.start : span.end].decode(), span.original)
+        self.assertEqual(report.spans[0].start, 3)
+        self.assertEqual(report.spans[0].converted, "\\(\\alpha\\)")
+        self.assertEqual(report.output, "é \\(\\alpha\\) ü \\[\\beta\\]")
+
+    def test_errors_raise(self):
+        with self.assertRaisesRegex(ValueError, "math at byte 2"):
+            tex2typst.convert_typst_document("a $frac(1$ b")
+        with self.assertRaisesRegex(ValueError, "Unterminated math"):
+            tex2typst.convert_typst_document("a $x")
+
+    def test_errors_keep(self):
+        report = tex2typst.convert_typst_document(
+            "a $frac(1$ b $x$", errors="keep", report=True
+        )
+        self.assertEqual(report.output, "a $frac(1$ b \\(x\\)")
+        self.assertIsNotNone(report.spans[0].error)
+        self.assertIsNone(report.spans[0].converted)
+
+    def test_dry_run(self):
+        report = tex2typst.convert_typst_document("$x$", dry_run=True)
+        self.assertIsInstance(report, tex2typst.DocumentReport)
+        self.assertIsNone(report.output)
+        self.assertEqual(report.spans[0].converted, "\\(x\\)")
+
+    def test_options_passed_on(self):
+        self.assertEqual(
+            tex2typst.convert_typst_document(
+                "$vv(x)$", custom_typst_macros={"vv": "bold"}
+            ),
+            "\\(\\boldsymbol{x}\\)",
+        )
+
+    def test_bad_choice(self):
+        with self.assertRaises(ValueError):
+            tex2typst.convert_typst_document("$x$", delimiters="brackets")
+
+
+if __name__ == "__main__":
+    sys.exit(unittest.main())
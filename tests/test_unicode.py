@@ -0,0 +1,61 @@
+"""Test normalizing Unicode math characters in TeX input."""
+
+import unittest
+import tex2typst
+
+
+class TestNormalizeUnicode(unittest.TestCase):
+    """Test normalize_unicode and unicode_report"""
+
+    def test_pasted_characters(self):
+        self.assertEqual(
+            tex2typst.tex2typst("f′(x) − α × β ⋅ γ", normalize_unicode=True),
+            tex2typst.tex2typst(r"f'(x) - \alpha \times \beta \cdot \gamma"),
+        )
+        self.assertEqual(tex2typst.tex2typst("x ∈ ℝ", normalize_unicode=True), "x in RR")
+
+    def test_control_word_spacing(self):
+        # `αx` must not become the unknown command `\alphax`
+        self.assertEqual(tex2typst.tex2typst("αx", normalize_unicode=True), "alpha x")
+
+    def test_spaces_and_nfc(self):
+        self.assertEqual(tex2typst.tex2typst("a b", normalize_unicode=True), "a b")
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("a b")
+        self.assertEqual(
+            tex2typst.tex2typst("é", normalize_unicode=True),
+            tex2typst.tex2typst("é"),
+        )
+
+    def test_report(self):
+        self.assertEqual(
+            tex2typst.tex2typst("x − y − z", normalize_unicode=True, unicode_report=True),
+            ("x - y - z", [("−", "-")]),
+        )
+        self.assertEqual(
+            tex2typst.tex2typst(
+                ["x − y", "y", "≤"], normalize_unicode=True, unicode_report=True
+            ),
+            (["x - y", "y", "<="], {0: [("−", "-")], 2: [("≤", r"\leq")]}),
+        )
+        typst, unknown, substitutions = tex2typst.tex2typst(
+            r"− \foo", normalize_unicode=True, unicode_report=True, report_unknown=True
+        )
+        self.assertEqual(unknown, [r"\foo"])
+        self.assertEqual(substitutions, [("−", "-")])
+
+    def test_report_requires_normalize(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x", unicode_report=True)
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["x"], unicode_report=True)
+
+    def test_surrogate_is_clean_error(self):
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst("x\ud800", normalize_unicode=True)
+        with self.assertRaises(ValueError):
+            tex2typst.tex2typst(["x\ud800"], normalize_unicode=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,74 @@
+"""Test reporting of unrecognized TeX commands."""
+
+import unittest
+import tex2typst
+
+
+class TestReportUnknown(unittest.TestCase):
+    """Test report_unknown and fail_on_unknown"""
+
+    def tearDown(self):
+        tex2typst.reset_default_options()
+
+    def test_report_single(self):
+        result, unknown = tex2typst.tex2typst(
+            r"\pdv{f}{x} + \alpha + \foo", report_unknown=True
+        )
+        self.assertEqual(result, "pdv f x + alpha + foo")
+        self.assertEqual(unknown, [r"\pdv", r"\foo"])
+
+    def test_report_known_only(self):
+        result, unknown = tex2typst.tex2typst(
+            r"\frac{1}{2} + \sqrt{x} \bar{y} \\ \{a\}", report_unknown=True
+        )
+        self.assertEqual(unknown, [])
+
+    def test_custom_macros_not_reported(self):
+        macros = {r"\myop": r"\operatorname{myop}"}
+        _, unknown = tex2typst.tex2typst(
+            r"\myop x", custom_tex_macros=macros, report_unknown=True
+        )
+        self.assertEqual(unknown, [])
+
+        tex2typst.set_default_options(custom_tex_macros=macros)
+        _, unknown = tex2typst.tex2typst(r"\myop x", report_unknown=True)
+        self.assertEqual(unknown, [])
+
+    def test_report_batch(self):
+        results, unknown = tex2typst.tex2typst(
+            [r"\alpha", r"\pdv{f}{x}", "", r"\foo \pdv"],
+            report_unknown=True,
+            skip_empty=True,
+        )
+        self.assertEqual(results, ["alpha", "pdv f x", None, "foo pdv"])
+        self.assertEqual(unknown, {1: [r"\pdv"], 3: [r"\foo", r"\pdv"]})
+
+    def test_report_batch_with_failed_indices(self):
+        results, failed, unknown = tex2typst.tex2typst(
+            [r"\frac{1}{", r"\foo"],
+            report_unknown=True,
+            keep_original_on_error=True,
+            return_failed_indices=True,
+        )
+        self.assertEqual(failed, [0])
+        self.assertEqual(unknown, {1: [r"\foo"]})
+
+    def test_fail_on_unknown(self):
+        with self.assertRaises(tex2typst.TexParseError) as ctx:
+            tex2typst.tex2typst(r"\pdv{f}{x} + \foo", fail_on_unknown=True)
+        self.assertIn(r"\pdv", str(ctx.exception))
+        self.assertIn(r"\foo", str(ctx.exception))
+
+        self.assertEqual(tex2typst.tex2typst(r"\alpha", fail_on_unknown=True), "alpha")
+
+    def test_fail_on_unknown_batch(self):
+        with self.assertRaises(tex2typst.TexParseError) as ctx:
+            tex2typst.tex2typst([r"\alpha", r"\pdv{f}{x}"], fail_on_unknown=True)
+        self.assertIn(r"item 1: \pdv", str(ctx.exception))
+
+    def test_is_value_error(self):
+        self.assertTrue(issubclass(tex2typst.TexParseError, ValueError))
+
+
+if __name__ == "__main__":
+    unittest.main()
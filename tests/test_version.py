@@ -0,0 +1,43 @@
+"""Test the version attributes and version_info."""
+
+import threading
+import unittest
+import tex2typst
+
+
+class TestVersion(unittest.TestCase):
+    """Test __rust_version__, __js_bundle_version__ and version_info"""
+
+    def test_attributes(self):
+        self.assertEqual(tex2typst.__rust_version__, tex2typst.__version__)
+        self.assertEqual(
+            tex2typst.__js_bundle_version__, tex2typst.get_js_bundle_version()
+        )
+
+    def test_version_info(self):
+        info = tex2typst.version_info()
+        self.assertIsInstance(info, tuple)
+        self.assertEqual(info.rust, tex2typst.__version__)
+        self.assertEqual(info.js_bundle, tex2typst.__js_bundle_version__)
+        for version in [info.quickjs, info.rquickjs]:
+            self.assertRegex(version, r"^\d+\.\d+\.\d+")
+
+    def test_does_not_initialize(self):
+        infos = []
+
+        def work():
+            tex2typst.version_info()
+            infos.append(tex2typst.get_converter_info())
+
+        thread = threading.Thread(target=work)
+        thread.start()
+        thread.join()
+        self.assertFalse(infos[0]["thread_converter_initialized"])
+
+    def test_unknown_attribute(self):
+        with self.assertRaises(AttributeError):
+            tex2typst.__missing_version__
+
+
+if __name__ == "__main__":
+    unittest.main()
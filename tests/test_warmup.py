@@ -0,0 +1,103 @@
+"""Test warmup, warmup_background and TEX2TYPST_EAGER_INIT."""
+
+import os
+import subprocess
+import sys
+import threading
+import unittest
+import tex2typst
+
+
+def on_new_thread(work):
+    """Run work() on a thread without an engine yet and return its result."""
+    result = {}
+
+    def run():
+        result["value"] = work()
+
+    thread = threading.Thread(target=run)
+    thread.start()
+    thread.join()
+    return result["value"]
+
+
+class TestWarmup(unittest.TestCase):
+    """Test warmup and warmup_background"""
+
+    def setUp(self):
+        tex2typst.reset_stats()
+
+    def test_no_first_call_penalty(self):
+        def work():
+            elapsed = tex2typst.warmup()
+            warmed = tex2typst.get_stats()
+            tex2typst.typst2tex(["alpha", "beta"])
+            return elapsed, warmed, tex2typst.get_stats(), tex2typst.warmup()
+
+        elapsed, warmed, converted, again = on_new_thread(work)
+        self.assertGreater(elapsed, 0)
+        self.assertEqual(warmed["engines_initialized"], 1)
+        self.assertEqual(warmed["calls"], 0)
+        # The conversion ran on the engine warmup() started
+        self.assertEqual(converted["engines_initialized"], 1)
+        self.assertEqual(converted["js_init_time_seconds"], warmed["js_init_time_seconds"])
+        self.assertEqual(converted["typst2tex_conversions"], 2)
+        self.assertEqual(again, 0.0)
+
+    def test_restarts_after_extension_change(self):
+        def work():
+            tex2typst.warmup()
+            tex2typst.clear_js_extensions()
+            return tex2typst.warmup()
+
+        self.assertGreater(on_new_thread(work), 0)
+
+    def test_concurrent_first_calls(self):
+        # Threads starting at the same moment each get an engine of their own
+        barrier = threading.Barrier(8)
+        results = [None] * 8
+
+        def work(index):
+            barrier.wait()
+            results[index] = (tex2typst.warmup(), tex2typst.tex2typst([r"\alpha"]))
+
+        threads = [threading.Thread(target=work, args=(i,)) for i in range(8)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        for elapsed, typst in results:
+            self.assertGreater(elapsed, 0)
+            self.assertEqual(typst, ["alpha"])
+        self.assertEqual(tex2typst.get_stats()["engines_initialized"], 8)
+
+    def test_background(self):
+        future = tex2typst.warmup_background()
+        self.assertGreater(future.result(timeout=60), 0)
+        self.assertEqual(tex2typst.get_stats()["engines_initialized"], 1)
+
+
+class TestEagerInit(unittest.TestCase):
+    """Test the TEX2TYPST_EAGER_INIT environment variable"""
+
+    def run_import(self, value):
+        script = (
+            "import tex2typst;"
+            "print(tex2typst.eager_init, tex2typst.get_stats()['engines_initialized'])"
+        )
+        env = dict(os.environ, TEX2TYPST_EAGER_INIT=value)
+        result = subprocess.run(
+            [sys.executable, "-c", script], env=env, capture_output=True, text=True, check=True
+        )
+        return result.stdout.split()
+
+    def test_enabled(self):
+        self.assertEqual(self.run_import("1"), ["True", "1"])
+
+    def test_disabled(self):
+        self.assertEqual(self.run_import("0"), ["False", "0"])
+        self.assertFalse(tex2typst.eager_init)
+
+
+if __name__ == "__main__":
+    unittest.main()
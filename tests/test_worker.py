@@ -0,0 +1,72 @@
+"""Test the dedicated worker-thread converter."""
+
+import threading
+import unittest
+import tex2typst
+
+
+class TestWorkerConverter(unittest.TestCase):
+    """Test WorkerConverter"""
+
+    def setUp(self):
+        self.conv = tex2typst.WorkerConverter()
+
+    def tearDown(self):
+        self.conv.close()
+
+    def test_convert(self):
+        self.assertEqual(self.conv.convert(r"\alpha + \beta"), "alpha + beta")
+
+    def test_convert_with_options(self):
+        result = self.conv.convert(r"\frac{1}{2}", frac_to_slash=False)
+        self.assertIn("frac", result)
+
+    def test_convert_many(self):
+        results = self.conv.convert_many([r"\alpha", r"\beta", r"\gamma"])
+        self.assertEqual(results, ["alpha", "beta", "gamma"])
+
+    def test_conversion_error(self):
+        with self.assertRaises(ValueError):
+            self.conv.convert(r"\frac{1}{")
+        # The worker keeps serving after a failed job
+        self.assertEqual(self.conv.convert(r"\alpha"), "alpha")
+
+    def test_unknown_option(self):
+        with self.assertRaises(TypeError):
+            self.conv.convert(r"\alpha", fractoslash=True)
+
+    def test_shared_across_threads(self):
+        results = {}
+
+        def work(i):
+            results[i] = self.conv.convert(r"\frac{%d}{2}" % i)
+
+        threads = [threading.Thread(target=work, args=(i,)) for i in range(8)]
+        for t in threads:
+            t.start()
+        for t in threads:
+            t.join()
+
+        self.assertEqual(results, {i: f"{i}/2" for i in range(8)})
+
+    def test_close(self):
+        self.assertFalse(self.conv.closed)
+        self.conv.close()
+        self.assertTrue(self.conv.closed)
+        self.conv.close()
+
+        with self.assertRaises(RuntimeError):
+            self.conv.convert(r"\alpha")
+        with self.assertRaises(RuntimeError):
+            self.conv.convert_many([r"\alpha"])
+
+    def test_context_manager(self):
+        with tex2typst.WorkerConverter() as conv:
+            self.assertEqual(conv.convert(r"\beta"), "beta")
+        self.assertTrue(conv.closed)
+        with self.assertRaises(RuntimeError):
+            conv.convert(r"\beta")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -12,14 +12,42 @@ Usage:
     >>> tex2typst.clear_cache()  # Clear cache
 """
 
+import os
+import threading
+from concurrent.futures import Future
 from functools import lru_cache
-from typing import Optional, Dict, Union, List, overload
+from typing import (
+    Any,
+    Callable,
+    Optional,
+    Dict,
+    Union,
+    List,
+    Literal,
+    NamedTuple,
+    Tuple,
+    Iterable,
+    Iterator,
+    overload,
+)
 from . import _tex2typst_core
+from ._tex2typst_core import (
+    BatchResult,
+    ConversionInfo,
+    Converter,
+    DocumentReport,
+    EngineError,
+    Span,
+    TexParseError,
+    WorkerConverter,
+)
 
 __version__ = _tex2typst_core.__version__
+#: Version of the Rust crate, the same as ``__version__``
+__rust_version__ = _tex2typst_core.__version__
 
 
-def _make_hashable(d: Optional[Dict[str, str]]) -> Optional[tuple]:
+def _make_hashable(d: Optional[Dict[str, Optional[str]]]) -> Optional[tuple]:
     """Convert dict to hashable tuple for caching."""
     return tuple(sorted(d.items())) if d is not None else None
 
@@ -33,10 +61,35 @@ def _tex2typst_cached(
     frac_to_slash: Optional[bool],
     infty_to_oo: Optional[bool],
     optimize: Optional[bool],
+    ignore_unknown_commands: Optional[bool],
+    handle_physics: Optional[bool],
+    output_unicode: Optional[bool],
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]],
     custom_tex_macros: Optional[tuple],
+    replace_unknown_commands: Optional[tuple],
+    symbol_map: Optional[tuple],
+    environment_map: Optional[tuple],
+    trim_input: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    max_nesting_depth: Optional[int] = 512,
 ) -> str:
     """Internal cached function with hashable parameters."""
     macros = dict(custom_tex_macros) if custom_tex_macros else None
+    replacements = (
+        dict(replace_unknown_commands) if replace_unknown_commands is not None else None
+    )
     return _tex2typst_core.tex2typst(
         tex,
         non_strict=non_strict,
@@ -45,7 +98,29 @@ def _tex2typst_cached(
         frac_to_slash=frac_to_slash,
         infty_to_oo=infty_to_oo,
         optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
         custom_tex_macros=macros,
+        replace_unknown_commands=replacements,
+        symbol_map=dict(symbol_map) if symbol_map else None,
+        environment_map=dict(environment_map) if environment_map else None,
+        trim_input=trim_input,
+        preamble=preamble,
+        max_input_length=max_input_length,
+        wrap=wrap,
+        auto_strip_delimiters=auto_strip_delimiters,
+        chem=chem,
+        siunitx=siunitx,
+        units_style=units_style,
+        labels=labels,
+        tags=tags,
+        normalize_unicode=normalize_unicode,
+        strip_comments=strip_comments,
+        collapse_redundant_braces=collapse_redundant_braces,
+        normalize_output=normalize_output,
+        max_nesting_depth=max_nesting_depth,
     )
 
 
@@ -59,10 +134,441 @@ def tex2typst(
     frac_to_slash: Optional[bool] = None,
     infty_to_oo: Optional[bool] = None,
     optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
     custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: None = None,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
 ) -> str: ...
 
 
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[True],
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: None = None,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
+) -> Tuple[str, List[str]]: ...
+
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: Literal["comment"],
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
+) -> Tuple[str, str]: ...
+
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[True],
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: Optional[Literal["comment"]] = None,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
+) -> Tuple[Any, ...]: ...
+
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[True],
+    keep_original: Optional[Literal["comment"]] = None,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
+) -> Tuple[Any, float]: ...
+
+
+@overload
+def tex2typst(
+    tex: List[str],
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[False] = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> List[Optional[str]]: ...
+
+
+@overload
+def tex2typst(
+    tex: List[str],
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[False] = False,
+    return_summary: Literal[True],
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> BatchResult: ...
+
+
+@overload
+def tex2typst(
+    tex: List[str],
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[True],
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> Tuple[List[Optional[str]], List[int]]: ...
+
+
+@overload
+def tex2typst(
+    tex: List[str],
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: Literal[True],
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> Tuple[Any, ...]: ...
+
+
 @overload
 def tex2typst(
     tex: List[str],
@@ -73,8 +579,49 @@ def tex2typst(
     frac_to_slash: Optional[bool] = None,
     infty_to_oo: Optional[bool] = None,
     optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
     custom_tex_macros: Optional[Dict[str, str]] = None,
-) -> List[str]: ...
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[True],
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> Tuple[Any, ...]: ...
 
 
 def tex2typst(
@@ -86,8 +633,51 @@ def tex2typst(
     frac_to_slash: Optional[bool] = None,
     infty_to_oo: Optional[bool] = None,
     optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
     custom_tex_macros: Optional[Dict[str, str]] = None,
-) -> Union[str, List[str]]:
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    wrap: Optional[Literal["inline", "block", "none", "auto"]] = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Optional[Literal["plain", "unify", "metro"]] = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: bool = False,
+    keep_original: Optional[Literal["comment"]] = None,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: Optional[int] = 512,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> Union[str, List[Optional[str]], Tuple[Any, ...], BatchResult]:
     """
     Convert LaTeX/TeX to Typst format (with LRU caching).
 
@@ -102,10 +692,152 @@ def tex2typst(
         frac_to_slash: Convert fractions to slash notation
         infty_to_oo: Convert infinity symbol to oo
         optimize: Optimize output
+        ignore_unknown_commands: Pass commands the converter does not recognize
+            through as ``\\cmd``
+        handle_physics: Rewrite macros of the ``physics`` package (``\\dv``,
+            ``\\pdv``, ``\\qty``, ``\\abs``, ``\\norm``, ``\\ket``, ...) into plain TeX first
+        output_unicode: Emit Unicode characters (``α``, ``×``, ``≤``, ``→``, ...)
+            instead of Typst symbol names and shorthands; string literals and
+            function calls are left alone
+        handle_text_mode: How to treat `\\text{...}` contents: "verbatim", "convert" or "strip"
         custom_tex_macros: Custom TeX macro definitions
+        replace_unknown_commands: Typst fallbacks for unknown commands (name
+            without backslash); a ``None`` value drops the command silently
+        symbol_map: Typst translations overriding the built-in ones of TeX
+            symbols, keyed by command name with or without backslash, e.g.
+            ``{"phi": "phi.alt"}``
+        environment_map: Typst functions standing in for TeX environments,
+            keyed by environment name; with ``{"myenv": "f"}``,
+            ``\\begin{myenv} a & b \\\\ c & d \\end{myenv}`` becomes ``f(a, b; c, d)``
+        trim_input: Strip leading/trailing whitespace from each input before conversion
+        report_unknown: Also return the commands the converter did not recognize:
+            a list for string input, a dict mapping item index to its list for
+            list input (appended after the failed indices, if requested)
+        fail_on_unknown: Raise :class:`TexParseError` listing unrecognized commands
+        preamble: LaTeX preamble whose ``\\newcommand``, ``\\renewcommand`` and
+            ``\\def`` definitions are applied; explicit ``custom_tex_macros``
+            take precedence. Unsupported declarations emit a ``UserWarning``
+        max_input_length: Raise ``ValueError`` for input longer than this many
+            bytes, before any conversion work; for list input every item is
+            checked and all items over the limit are reported
+        wrap: Surround the Typst output with ``$...$`` (``"inline"``),
+            ``$ ... $`` (``"block"``) or nothing (``"none"``, the default);
+            originals kept by ``keep_original_on_error`` are not wrapped;
+            ``"auto"`` re-wraps in the style removed by ``auto_strip_delimiters``
+        auto_strip_delimiters: Remove one pair of ``$...$``, ``$$...$$``,
+            ``\\(...\\)`` or ``\\[...\\]`` around the (trimmed) input before
+            conversion; unbalanced or nested delimiters raise ``ValueError``
+        chem: Translate mhchem ``\\ce{...}`` formulas (subscripts, charges,
+            arrows and stoichiometric coefficients) before conversion; the
+            surrounding math is converted as usual. Unsupported constructs
+            raise ``ValueError`` naming the offending fragment
+        handle_mhchem: Same as ``chem``
+        siunitx: Convert siunitx ``\\SI``/``\\qty``, ``\\si``/``\\unit`` and
+            ``\\num`` commands, including ``e``-notation and uncertainties such
+            as ``1.23(4)``; unknown unit macros are listed in a ``UserWarning``
+        handle_siunitx: Same as ``siunitx``
+        units_style: Typst output for siunitx commands: ``"plain"`` math (the
+            default), or ``"unify"``/``"metro"`` package ``num``/``qty``/``unit``
+            calls; requires ``siunitx=True``
+        labels: Remove ``\\label{...}`` from the math and attach the label to
+            block-wrapped output as ``<name>``; names are sanitized for Typst and
+            duplicates (within a list) get ``-2``, ``-3`` suffixes. Use
+            :func:`tex2typst_with_info` to get the mapping
+        tags: Remove ``\\tag{...}`` and ``\\tag*{...}`` from the math; a tag
+            becomes the numbering of a block-wrapped ``math.equation`` and is
+            dropped with a ``UserWarning`` by the other ``wrap`` modes
+        normalize_unicode: NFC-normalize the input and replace Unicode math
+            characters pasted from PDFs (``−``, ``′``, ``α``, ``×``, ``⋅``, ...)
+            and non-breaking spaces with their TeX equivalents before conversion
+        unicode_report: Also return the ``(character, tex)`` substitutions made
+            by ``normalize_unicode``; for list input a dict mapping item index
+            to its substitutions. Requires ``normalize_unicode=True``
+        strip_comments: Remove ``%`` comments (``\\%`` is kept) before any other
+            processing; as in TeX, a comment also swallows its line break and the
+            indentation of the next line
+        collapse_redundant_braces: Remove grouping parentheses Typst drops anyway,
+            around a single name, number or string after ``^`` or ``_`` or as an
+            operand of ``/``: ``x^(2)`` becomes ``x^2`` and ``(a)/(b)`` ``a/b``.
+            Function calls and groups the next token would merge with are kept
+        normalize_output: Canonicalize the spacing of the Typst output with
+            :func:`format_typst_math`, e.g. for snapshot tests that should survive
+            converter upgrades; originals kept by ``keep_original_on_error`` are
+            left alone
+        timing: String input only. Return ``(result, elapsed_seconds)``, timing
+            the whole call; the LRU cache is bypassed so the conversion really runs
+        keep_original: String input only. With ``"comment"``, also return a Typst
+            comment quoting the input for reviewers: ``/* tex: ... */`` to put
+            before block-wrapped output, ``// tex: ...`` (line breaks written as
+            ``\\n``) to put after any other. Comments cannot go inside ``$...$``;
+            :func:`strip_original_comments` removes them again
+        keep_original_on_error: List input only. Return the original TeX for items
+            that fail to convert instead of raising
+        return_failed_indices: List input only. Also return the indices of the items
+            that fell back to their original TeX
+        return_summary: List input only. Return a :class:`BatchResult` with the
+            results, the ``(index, message)`` of every failed item, the elapsed
+            time, the throughput and the JS engines started; failures only land
+            in its ``errors`` with ``keep_original_on_error``. Cannot be combined
+            with ``return_failed_indices``, ``report_unknown`` or ``unicode_report``
+        skip_empty: List input only. Do not convert empty strings; put
+            ``empty_replacement`` at their positions instead
+        empty_replacement: Value returned for skipped empty strings (default ``None``)
+        pre_process: List input only. Called with every input string before any
+            other processing; its return value is converted instead
+        post_process: List input only. Called with every converted Typst string
+            (before ``wrap``); its return value replaces the result. Originals
+            kept by ``keep_original_on_error`` are not passed to it
+        progress_callback: List input only. Called as ``(completed, total)``
+            after every ``callback_every_n`` converted items and once more when
+            the batch is done; items left out by ``skip_empty`` are not counted
+        callback_every_n: How many items to convert between progress callbacks
+            (default 100)
+        chunk_size: List input only. Convert the list in sub-batches of this many
+            items, each in a separate JS engine call; ``0`` (the default)
+            converts it in one
+        dedupe: List input only. Convert each distinct input once and copy the
+            result to every position holding it; worthwhile for lists with many
+            repeats. ``progress_callback`` then counts distinct inputs, and a
+            conversion error names the first item with the failing input
+        atomic: List input only. Either every item converts or the call raises
+            the first failure and returns nothing, even when
+            ``keep_original_on_error`` is set
+        max_nesting_depth: Raise :class:`TexParseError` for input whose ``{...}``
+            groups, ``\\left``/``\\right`` pairs and environments nest deeper
+            than this, before it reaches the JS engine; ``None`` disables the check.
+            Input that still exhausts the engine's stack raises ``RecursionError``
+        js_options: Further options for the JS converter under their JS
+            (camelCase) names, e.g. integer options of a newer bundle; values are
+            passed as they are, ``int`` as a JS number. The typed options above
+            cannot be set this way. Bypasses the LRU cache
+        isolated: Convert on a fresh JavaScript context, with the bundle and
+            extensions loaded again, that is dropped afterwards, so untrusted
+            input can neither see nor change the JS globals other conversions
+            use. For list input, ``"per_batch"`` isolates the whole list in one
+            context and ``"per_item"`` (or ``True``) every item in its own. Costs
+            a bundle load per context; bypasses the LRU cache
+
+    Callbacks run from Rust inside the batch, so the list is still converted in one
+    pass. Every call is a Python function call, though: a heavy callback quickly
+    costs more than the batching saves. An exception raised by a callback aborts
+    the batch with ``ValueError`` naming the item index, chained to the original.
 
     Returns:
-        Converted Typst string or list of strings (matches input type)
+        Converted Typst string or list of strings (matches input type); a
+        ``(results, failed_indices)`` tuple if ``return_failed_indices`` is set;
+        a :class:`BatchResult` if ``return_summary`` is set; for string input
+        a tuple with the unknown commands, substitutions and original-TeX comment
+        appended, as requested; ``(result, elapsed_seconds)`` if ``timing`` is set
+
+    Raises:
+        TexParseError: If ``fail_on_unknown`` is set and unrecognized commands are
+            used, or the input nests deeper than ``max_nesting_depth``
+        RecursionError: If the input is nested too deeply for the JS engine
+        ValueError: If an input is longer than ``max_input_length`` bytes, a
+            formula has more than one ``\\tag`` with ``tags``, or the conversion
+            fails. The message quotes at most the first 120 characters of the
+            input; the full input is the exception's ``input`` attribute. Errors
+            raised by the JS converter also carry ``js_message``, ``js_stack`` and ``js_position``
 
     Examples:
         >>> tex2typst(r"\\frac{1}{2}")
@@ -114,6 +846,74 @@ def tex2typst(
         ['alpha', 'beta']
     """
     if isinstance(tex, str):
+        if (
+            keep_original_on_error
+            or return_failed_indices
+            or return_summary
+            or skip_empty
+            or pre_process is not None
+            or post_process is not None
+            or progress_callback is not None
+            or chunk_size
+            or dedupe
+            or atomic
+        ):
+            raise TypeError(
+                "keep_original_on_error, return_failed_indices, return_summary, skip_empty, "
+                "pre_process, post_process, progress_callback, chunk_size, "
+                "dedupe and atomic require list input"
+            )
+        if (
+            report_unknown
+            or fail_on_unknown
+            or unicode_report
+            or timing
+            or keep_original is not None
+            or js_options is not None
+            or isolated
+        ):
+            # Uncached: the unknown-command scan, substitutions, timing and comment
+            # are part of the result, js_options may hold unhashable values, and an
+            # isolated call must actually run on its own context
+            return _tex2typst_core.tex2typst(
+                tex,
+                non_strict=non_strict,
+                prefer_shorthands=prefer_shorthands,
+                keep_spaces=keep_spaces,
+                frac_to_slash=frac_to_slash,
+                infty_to_oo=infty_to_oo,
+                optimize=optimize,
+                ignore_unknown_commands=ignore_unknown_commands,
+                handle_physics=handle_physics,
+                output_unicode=output_unicode,
+                handle_text_mode=handle_text_mode,
+                custom_tex_macros=custom_tex_macros,
+                replace_unknown_commands=replace_unknown_commands,
+                symbol_map=symbol_map,
+                environment_map=environment_map,
+                trim_input=trim_input,
+                report_unknown=report_unknown,
+                fail_on_unknown=fail_on_unknown,
+                preamble=preamble,
+                max_input_length=max_input_length,
+                wrap=wrap,
+                auto_strip_delimiters=auto_strip_delimiters,
+                chem=chem or handle_mhchem,
+                siunitx=siunitx or handle_siunitx,
+                units_style=units_style,
+                labels=labels,
+                tags=tags,
+                normalize_unicode=normalize_unicode,
+                unicode_report=unicode_report,
+                strip_comments=strip_comments,
+                collapse_redundant_braces=collapse_redundant_braces,
+                normalize_output=normalize_output,
+                timing=timing,
+                keep_original=keep_original,
+                max_nesting_depth=max_nesting_depth,
+                js_options=js_options,
+                isolated=bool(isolated),
+            )
         # Single string: use cached function
         macros_tuple = _make_hashable(custom_tex_macros)
         return _tex2typst_cached(
@@ -124,9 +924,35 @@ def tex2typst(
             frac_to_slash,
             infty_to_oo,
             optimize,
+            ignore_unknown_commands,
+            handle_physics,
+            output_unicode,
+            handle_text_mode,
             macros_tuple,
+            _make_hashable(replace_unknown_commands),
+            _make_hashable(symbol_map),
+            _make_hashable(environment_map),
+            trim_input,
+            preamble,
+            max_input_length,
+            wrap,
+            auto_strip_delimiters,
+            chem or handle_mhchem,
+            siunitx or handle_siunitx,
+            units_style,
+            labels,
+            tags,
+            normalize_unicode,
+            strip_comments,
+            collapse_redundant_braces,
+            normalize_output,
+            max_nesting_depth,
         )
     elif isinstance(tex, list):
+        if timing:
+            raise TypeError("timing requires string input")
+        if keep_original is not None:
+            raise TypeError("keep_original requires string input")
         # List: use batch processing API for better performance
         # Batch API processes all items in one Rust/JS context entry, reducing overhead
         return _tex2typst_core.tex2typst_batch(
@@ -137,19 +963,125 @@ def tex2typst(
             frac_to_slash=frac_to_slash,
             infty_to_oo=infty_to_oo,
             optimize=optimize,
+            ignore_unknown_commands=ignore_unknown_commands,
+            handle_physics=handle_physics,
+            output_unicode=output_unicode,
+            handle_text_mode=handle_text_mode,
             custom_tex_macros=custom_tex_macros,
+            replace_unknown_commands=replace_unknown_commands,
+            symbol_map=symbol_map,
+            environment_map=environment_map,
+            trim_input=trim_input,
+            report_unknown=report_unknown,
+            fail_on_unknown=fail_on_unknown,
+            preamble=preamble,
+            max_input_length=max_input_length,
+            wrap=wrap,
+            auto_strip_delimiters=auto_strip_delimiters,
+            chem=chem or handle_mhchem,
+            siunitx=siunitx or handle_siunitx,
+            units_style=units_style,
+            labels=labels,
+            tags=tags,
+            normalize_unicode=normalize_unicode,
+            unicode_report=unicode_report,
+            strip_comments=strip_comments,
+            collapse_redundant_braces=collapse_redundant_braces,
+            normalize_output=normalize_output,
+            keep_original_on_error=keep_original_on_error,
+            return_failed_indices=return_failed_indices,
+            return_summary=return_summary,
+            skip_empty=skip_empty,
+            empty_replacement=empty_replacement,
+            pre_process=pre_process,
+            post_process=post_process,
+            progress_callback=progress_callback,
+            callback_every_n=callback_every_n,
+            chunk_size=chunk_size,
+            dedupe=dedupe,
+            atomic=atomic,
+            max_nesting_depth=max_nesting_depth,
+            js_options=js_options,
+            isolated=isolated,
         )
     else:
         raise TypeError(f"Expected str or list, got {type(tex).__name__}")
 
 
+def tex2typst_batch_mixed(
+    tex: List[str], **kwargs: Any
+) -> List[Union[str, Exception, None]]:
+    """
+    Convert a list of LaTeX/TeX strings, returning the exception of each item that
+    fails to convert in its place instead of raising.
+
+    Takes the options of :func:`tex2typst` for list input, except
+    ``keep_original_on_error``, ``return_failed_indices``, ``return_summary`` and
+    ``atomic``. Failures are the exceptions :func:`tex2typst` raises for that item
+    alone, e.g. :class:`ValueError` with ``input`` and ``js_message``. Problems found
+    before conversion, such as an item over ``max_input_length``, still raise for
+    the whole list.
+
+    Returns:
+        One ``str`` or exception per item (an ``(items, ...)`` tuple with
+        ``report_unknown`` or ``unicode_report``); items left out by ``skip_empty``
+        hold ``empty_replacement``
+
+    Example:
+        >>> results = tex2typst_batch_mixed([r"\\alpha", r"\\frac{1}{"])
+        >>> results[0], isinstance(results[1], ValueError)
+        ('alpha', True)
+    """
+    if not isinstance(tex, list):
+        raise TypeError(f"Expected list, got {type(tex).__name__}")
+    return _tex2typst_core.tex2typst_batch(tex, return_exceptions=True, **kwargs)
+
+
+def tex2typst_batch_progress(
+    tex: List[str], progress_bar: Optional[Any] = None, **kwargs: Any
+) -> Union[List[Optional[str]], Tuple[Any, ...], BatchResult]:
+    """
+    Convert a list of LaTeX/TeX strings, advancing a progress bar as chunks finish.
+
+    Takes the options of :func:`tex2typst` for list input. ``progress_bar`` is any
+    tqdm-compatible object: its ``update(n)`` is called after every ``chunk_size``
+    items (default 100) with the number of items just converted. Without a progress
+    bar this is the same as :func:`tex2typst` on the list.
+
+    Example:
+        >>> from tqdm import tqdm
+        >>> items = [r"\\alpha"] * 1000
+        >>> with tqdm(total=len(items)) as bar:
+        ...     results = tex2typst_batch_progress(items, bar)
+    """
+    if not isinstance(tex, list):
+        raise TypeError(f"Expected list, got {type(tex).__name__}")
+    if progress_bar is None:
+        return tex2typst(tex, **kwargs)
+    kwargs.setdefault("chunk_size", 100)
+    return _tex2typst_core.tex2typst_batch(tex, progress_bar=progress_bar, **kwargs)
+
+
 @lru_cache(maxsize=1024)
 def _typst2tex_cached(
     typst: str,
     block_math_mode: Optional[bool],
+    trim_input: bool = False,
+    max_input_length: Optional[int] = None,
+    custom_typst_macros: Optional[tuple] = None,
+    delimiters: Optional[str] = None,
+    trailing_newline: bool = False,
 ) -> str:
     """Internal cached function."""
-    return _tex2typst_core.typst2tex(typst, block_math_mode=block_math_mode)
+    return _tex2typst_core.typst2tex(
+        typst,
+        block_math_mode=block_math_mode,
+        custom_typst_macros=dict(custom_typst_macros) if custom_typst_macros else None,
+        trim_input=trim_input,
+        max_input_length=max_input_length,
+        delimiters=delimiters,
+        trailing_newline=trailing_newline,
+    )
 
 
 @overload
@@ -157,6 +1089,13 @@ def typst2tex(
     typst: str,
     *,
     block_math_mode: Optional[bool] = None,
+    custom_typst_macros: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    max_input_length: Optional[int] = None,
+    delimiters: Optional[Literal["dollars", "latex", "none"]] = None,
+    trailing_newline: bool = False,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: bool = False,
 ) -> str: ...
 
 
@@ -165,14 +1104,61 @@ def typst2tex(
     typst: List[str],
     *,
     block_math_mode: Optional[bool] = None,
-) -> List[str]: ...
+    custom_typst_macros: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    max_input_length: Optional[int] = None,
+    delimiters: Optional[Literal["dollars", "latex", "none"]] = None,
+    trailing_newline: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    dedupe: bool = False,
+    return_summary: Literal[False] = False,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> List[Optional[str]]: ...
+
+
+@overload
+def typst2tex(
+    typst: List[str],
+    *,
+    block_math_mode: Optional[bool] = None,
+    custom_typst_macros: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    max_input_length: Optional[int] = None,
+    delimiters: Optional[Literal["dollars", "latex", "none"]] = None,
+    trailing_newline: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    dedupe: bool = False,
+    return_summary: Literal[True],
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> BatchResult: ...
 
 
 def typst2tex(
     typst: Union[str, List[str]],
     *,
     block_math_mode: Optional[bool] = None,
-) -> Union[str, List[str]]:
+    custom_typst_macros: Optional[Dict[str, str]] = None,
+    trim_input: bool = False,
+    max_input_length: Optional[int] = None,
+    delimiters: Optional[Literal["dollars", "latex", "none"]] = None,
+    trailing_newline: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    pre_process: Optional[Callable[[str], str]] = None,
+    post_process: Optional[Callable[[str], str]] = None,
+    dedupe: bool = False,
+    return_summary: bool = False,
+    js_options: Optional[Dict[str, Any]] = None,
+    isolated: Union[bool, Literal["per_item", "per_batch"]] = False,
+) -> Union[str, List[Optional[str]], BatchResult]:
     """
     Convert Typst to LaTeX/TeX format (with LRU caching).
 
@@ -182,9 +1168,34 @@ def typst2tex(
     Args:
         typst: Typst math string or list of strings to convert
         block_math_mode: Use block math mode
+        custom_typst_macros: Typst identifiers (e.g. ``"RR"`` or ``"arrow.r"``)
+            replaced by the given Typst before conversion
+        trim_input: Strip leading/trailing whitespace from each input before conversion
+        skip_empty: List input only. Do not convert empty strings; put
+            ``empty_replacement`` at their positions instead
+        empty_replacement: Value returned for skipped empty strings (default ``None``)
+        max_input_length: Raise ``ValueError`` for input longer than this many
+            bytes; for list input all items over the limit are reported
+        delimiters: Surround the output with ``\\(...\\)`` (``"latex"``) or
+            ``$...$`` (``"dollars"``), in block math mode with ``\\[...\\]`` or
+            ``$$...$$``; ``"none"`` or ``None`` leaves it bare. Nothing is added
+            inside the delimiters
+        trailing_newline: End delimited display math with a line break
+        pre_process: List input only. Called with every input string before
+            conversion; see :func:`tex2typst`
+        post_process: List input only. Called with every converted TeX string;
+            its return value replaces the result
+        dedupe: List input only. Convert each distinct input once; see
+            :func:`tex2typst`
+        return_summary: List input only. Return a :class:`BatchResult`; see
+            :func:`tex2typst`. Any failure raises, so its ``errors`` is empty
+        js_options: Further options for the JS converter under their JS names;
+            see :func:`tex2typst`
+        isolated: Convert on fresh JavaScript contexts; see :func:`tex2typst`
 
     Returns:
-        Converted LaTeX/TeX string or list of strings (matches input type)
+        Converted LaTeX/TeX string or list of strings (matches input type), or
+        a :class:`BatchResult` if ``return_summary`` is set
 
     Examples:
         >>> typst2tex("1/2")
@@ -193,51 +1204,2127 @@ def typst2tex(
         ['\\\\alpha', '\\\\beta']
     """
     if isinstance(typst, str):
-        return _typst2tex_cached(typst, block_math_mode)
+        if (
+            skip_empty
+            or pre_process is not None
+            or post_process is not None
+            or dedupe
+            or return_summary
+        ):
+            raise TypeError(
+                "skip_empty, pre_process, post_process, dedupe and return_summary "
+                "require list input"
+            )
+        if js_options is not None or isolated:
+            return _tex2typst_core.typst2tex(
+                typst,
+                block_math_mode=block_math_mode,
+                custom_typst_macros=custom_typst_macros,
+                trim_input=trim_input,
+                max_input_length=max_input_length,
+                delimiters=delimiters,
+                trailing_newline=trailing_newline,
+                js_options=js_options,
+                isolated=bool(isolated),
+            )
+        return _typst2tex_cached(
+            typst,
+            block_math_mode,
+            trim_input,
+            max_input_length,
+            _make_hashable(custom_typst_macros),
+            delimiters,
+            trailing_newline,
+        )
     elif isinstance(typst, list):
         # List: use batch processing API internally for better performance
         return _tex2typst_core.typst2tex_batch(
             typst,
             block_math_mode=block_math_mode,
+            custom_typst_macros=custom_typst_macros,
+            trim_input=trim_input,
+            skip_empty=skip_empty,
+            empty_replacement=empty_replacement,
+            max_input_length=max_input_length,
+            delimiters=delimiters,
+            trailing_newline=trailing_newline,
+            pre_process=pre_process,
+            post_process=post_process,
+            dedupe=dedupe,
+            return_summary=return_summary,
+            js_options=js_options,
+            isolated=isolated,
         )
     else:
         raise TypeError(f"Expected str or list, got {type(typst).__name__}")
 
 
-def clear_cache() -> None:
+def tex2typst_with_info(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    max_input_length: Optional[int] = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> ConversionInfo:
     """
-    Clear all cached conversion results.
+    Convert LaTeX/TeX to Typst format and report metadata about the output.
 
-    Example:
-        >>> clear_cache()
+    Accepts the same options as :func:`tex2typst`. Results are not cached.
+
+    Args:
+        tex: LaTeX/TeX math string to convert
+        labels: Remove ``\\label{...}`` before conversion and report the
+            ``(tex_name, typst_label)`` pairs in ``.labels``
+        tags: Remove ``\\tag{...}`` and report it in ``.tag`` as Typst markup
+
+    Returns:
+        ConversionInfo with ``.typst``, ``.symbols`` (distinct symbol and
+        function names in the output), ``.required_packages`` (Typst
+        packages the output needs to be imported) and ``.labels``
+
+    Examples:
+        >>> info = tex2typst_with_info(r"\\alpha + \\beta")
+        >>> info.typst
+        'alpha + beta'
+        >>> info.symbols
+        ['alpha', 'beta']
     """
-    _tex2typst_cached.cache_clear()
-    _typst2tex_cached.cache_clear()
+    return _tex2typst_core.tex2typst_with_info(
+        tex,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        max_input_length=max_input_length,
+        labels=labels,
+        tags=tags,
+    )
 
 
-def cache_info() -> Dict[str, object]:
+def tex2typst_batch_with_info(
+    tex: List[str],
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> List[Optional[ConversionInfo]]:
     """
-    Get cache statistics.
+    Convert multiple LaTeX/TeX strings and report metadata for each output.
+
+    Args:
+        tex: List of LaTeX/TeX math strings to convert
+        skip_empty: Do not convert empty strings; their positions hold ``None``,
+            or the info for ``empty_replacement`` when one is given
+        empty_replacement: Typst string reported for skipped empty strings
+        labels: Remove ``\\label{...}`` before conversion; labels are unique
+            across the whole list
+        tags: Remove ``\\tag{...}`` and report it in ``.tag``
 
     Returns:
-        Dictionary with cache info for tex2typst and typst2tex
+        List of ConversionInfo, one per input
+    """
+    return _tex2typst_core.tex2typst_batch_with_info(
+        tex,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        skip_empty=skip_empty,
+        empty_replacement=empty_replacement,
+        max_input_length=max_input_length,
+        labels=labels,
+        tags=tags,
+    )
 
-    Example:
-        >>> info = cache_info()
-        >>> print(f"tex2typst hits: {info['tex2typst'].hits}")
-        >>> print(f"tex2typst misses: {info['tex2typst'].misses}")
-        >>> print(f"Cache size: {info['tex2typst'].currsize}/{info['tex2typst'].maxsize}")
+
+def tex2typst_aligned(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    max_input_length: Optional[int] = None,
+) -> str:
     """
-    return {
-        "tex2typst": _tex2typst_cached.cache_info(),
-        "typst2tex": _typst2tex_cached.cache_info(),
-    }
+    Convert an aligned multi-line equation such as ``a &= b \\\\ &= c``.
 
+    Rows are split on ``\\\\`` and cells on ``&``, except inside braces and nested
+    environments (``cases``, matrices, ``\\text{...}``). Each cell is converted on
+    its own and the rows are joined into ``&``-aligned Typst math, one per line.
+    An enclosing ``align``, ``align*``, ``aligned``, ``split`` or ``eqnarray``
+    environment is unwrapped first. Accepts the same options as :func:`tex2typst`.
 
-__all__ = [
-    "tex2typst",
-    "typst2tex",
-    "clear_cache",
-    "cache_info",
-    "__version__",
-]
+    Args:
+        tex: Aligned LaTeX/TeX math, with or without its environment
+
+    Returns:
+        Typst math without ``$`` delimiters
+
+    Examples:
+        >>> print(tex2typst_aligned(r"f(x) &= (x+1)^2 \\\\ &= x^2 + 2x + 1"))
+        f(x) &= (x + 1)^2 \\
+        &= x^2 + 2 x + 1
+    """
+    return _tex2typst_core.tex2typst_aligned(
+        tex,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        max_input_length=max_input_length,
+    )
+
+
+def explain_conversion(
+    tex: str,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    max_input_length: Optional[int] = None,
+) -> str:
+    """
+    Convert LaTeX/TeX math to Typst annotated with ``/* ... */`` comments.
+
+    Meant for tutorials and diff viewers. A JS bundle that can annotate its
+    output (through an ``explainConversion`` function or option) places the
+    comments at the transformation points; the bundled upstream library cannot,
+    so the original TeX is recorded in a leading ``/* input: ... */`` comment.
+    Accepts the same options as :func:`tex2typst`.
+
+    Args:
+        tex: LaTeX/TeX math string to convert
+
+    Returns:
+        Typst math with comments; still valid Typst
+
+    Examples:
+        >>> print(explain_conversion(r"\\frac{1}{2}"))
+        /* input: \\frac{1}{2} */
+        1/2
+    """
+    return _tex2typst_core.explain_conversion(
+        tex,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        max_input_length=max_input_length,
+    )
+
+
+def roundtrip_check(tex: str, *, max_input_length: Optional[int] = None) -> bool:
+    """
+    Check that TeX survives a TeX -> Typst -> TeX round trip.
+
+    The input and the final TeX are compared after dropping insignificant
+    whitespace and the braces of single-character scripts, so ``x^{2}`` and
+    ``x^2`` match. A conversion error in either direction returns ``False``;
+    use :func:`roundtrip_diff` to see what went wrong.
+
+    Args:
+        tex: LaTeX/TeX math string
+        max_input_length: Raise ``ValueError`` for input longer than this many bytes
+
+    Examples:
+        >>> roundtrip_check(r"\\frac{1}{2}")
+        True
+        >>> roundtrip_check(r"\\dfrac{1}{2}")
+        False
+    """
+    return _tex2typst_core.roundtrip_check(tex, max_input_length=max_input_length)
+
+
+def roundtrip_diff(tex: str, *, max_input_length: Optional[int] = None) -> Dict[str, Any]:
+    """
+    Report a TeX -> Typst -> TeX round trip in detail.
+
+    Args:
+        tex: LaTeX/TeX math string
+        max_input_length: Raise ``ValueError`` for input longer than this many bytes
+
+    Returns:
+        dict with ``input``, ``intermediate`` (the Typst), ``output`` (the final
+        TeX), ``equivalent`` (as :func:`roundtrip_check`), ``diff`` (an
+        ``ndiff``-style comparison of the normalized input and output, empty
+        when equivalent) and ``error`` (message of the failed step, or
+        ``None``); ``intermediate`` and ``output`` are ``None`` when their step
+        failed or did not run
+
+    Examples:
+        >>> print(roundtrip_diff(r"\\dfrac{1}{2}")["diff"])
+        - \\dfrac{1}{2}
+        + \\frac{1}{2}
+        ?  ^
+    """
+    return _tex2typst_core.roundtrip_diff(tex, max_input_length=max_input_length)
+
+
+def convert_refs(text: str, labels: Optional[Dict[str, str]] = None) -> str:
+    """
+    Rewrite ``\\ref``, ``\\eqref`` and ``\\autoref`` in LaTeX prose to Typst
+    ``@label`` references.
+
+    Args:
+        text: LaTeX prose surrounding the converted equations
+        labels: TeX label names mapped to Typst labels, e.g.
+            ``dict(info.labels)``; other names are sanitized the same way
+            ``labels=True`` sanitizes them
+
+    Returns:
+        The text with references rewritten
+
+    Examples:
+        >>> convert_refs(r"see \\eqref{eq:euler}")
+        'see @eq:euler'
+    """
+    return _tex2typst_core.convert_refs(text, labels)
+
+
+def strip_original_comments(text: str) -> str:
+    """
+    Remove the original-TeX comments added by ``keep_original="comment"``.
+
+    ``// tex: ...`` comments are dropped along with the spaces before them and
+    ``/* tex: ... */`` comments along with the line break after them; other
+    comments are left alone.
+
+    Examples:
+        >>> strip_original_comments("$x^2$ // tex: x^2")
+        '$x^2$'
+    """
+    return _tex2typst_core.strip_original_comments(text)
+
+
+def convert_typst_document(
+    text: str,
+    *,
+    delimiters: Literal["latex", "dollars"] = "latex",
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    custom_typst_macros: Optional[Dict[str, str]] = None,
+    js_options: Optional[Dict[str, Any]] = None,
+) -> Union[str, DocumentReport]:
+    """
+    Convert the math of a Typst document, such as a ``.typ`` file, to LaTeX.
+
+    Every ``$...$`` in the markup is converted with :func:`typst2tex`; the markup
+    around it is left as it is. As in Typst, math with whitespace after the opening
+    and before the closing ``$`` is display math, other math inline. Escaped
+    ``\\$`` and dollars in raw text, strings, comments and code are skipped, but
+    content blocks (``[...]``) in code are markup and converted.
+    ``math.equation(...)`` calls are not converted; they are reported as
+    ``"math.equation"`` spans, or with a ``UserWarning`` if no report is asked for.
+
+    Args:
+        text: Typst document
+        delimiters: ``"latex"`` emits ``\\(...\\)`` and ``\\[...\\]``,
+            ``"dollars"`` ``$...$`` and ``$$...$$``, as :func:`typst2tex` does
+        errors: ``"raise"`` stops with ``ValueError`` naming the byte offset of
+            the failed math; ``"keep"`` leaves it unchanged
+        report: Return a :class:`DocumentReport` with the output and a
+            :class:`Span` for every math segment
+        dry_run: Return the :class:`DocumentReport` without building the output
+        Remaining keyword arguments are the options of :func:`typst2tex`.
+
+    Returns:
+        The document with its math converted, or a :class:`DocumentReport`
+
+    Examples:
+        >>> convert_typst_document("Let $x^2$ be")
+        'Let \\\\(x^2\\\\) be'
+    """
+    return _tex2typst_core.convert_typst_document(
+        text,
+        delimiters=delimiters,
+        errors=errors,
+        report=report,
+        dry_run=dry_run,
+        custom_typst_macros=custom_typst_macros,
+        js_options=js_options,
+    )
+
+
+def convert_latex_document(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    js_options: Optional[Dict[str, Any]] = None,
+) -> Union[str, DocumentReport]:
+    """
+    Convert the math of a LaTeX document, such as a ``.tex`` file, to Typst.
+
+    Every math segment is converted with :func:`tex2typst`; the markup around it
+    is left as it is. Math is ``$...$`` and ``\\(...\\)`` (inline),
+    ``$$...$$`` and ``\\[...\\]`` (display), and the ``equation``, ``align``,
+    ``alignat``, ``flalign``, ``gather``, ``multline``, ``eqnarray``, ``math``
+    and ``displaymath`` environments, starred or not, reported as
+    ``"env:NAME"`` spans. Multi-line environments become an ``aligned`` block.
+    Escapes, ``%`` comments, ``\\verb`` and the ``verbatim``, ``lstlisting``,
+    ``minted`` and ``comment`` environments are skipped. Inline math becomes
+    ``$...$`` and the rest ``$ ... $``.
+
+    Args:
+        text: LaTeX document
+        errors: ``"raise"`` stops with ``ValueError`` naming the byte offset of
+            the failed math; ``"keep"`` leaves it unchanged
+        report: Return a :class:`DocumentReport` with the output and a
+            :class:`Span` for every math segment
+        dry_run: Return the :class:`DocumentReport` without building the output
+        Remaining keyword arguments are the options of :func:`tex2typst`.
+
+    Returns:
+        The document with its math converted, or a :class:`DocumentReport`
+
+    Examples:
+        >>> convert_latex_document(r"Let \\(x^2\\) be")
+        'Let $x^2$ be'
+    """
+    return _tex2typst_core.convert_latex_document(
+        text,
+        errors=errors,
+        report=report,
+        dry_run=dry_run,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        js_options=js_options,
+    )
+
+
+def convert_markdown(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    js_options: Optional[Dict[str, Any]] = None,
+) -> Union[str, DocumentReport]:
+    """
+    Convert the math of a Markdown document to Typst.
+
+    Every ``$...$`` (inline) and ``$$...$$`` (display) is converted with
+    :func:`tex2typst`; the markup around it is left as it is. Inline math follows
+    Pandoc: no whitespace after the opening or before the closing ``$``, no digit
+    after the closing one and no blank line inside, so amounts such as
+    ``$5 and $6`` stay text. Escaped ``\\$``, code spans and fenced code blocks
+    are skipped. Inline math becomes ``$...$`` and display math ``$ ... $``.
+
+    Args:
+        text: Markdown document
+        errors: ``"raise"`` stops with ``ValueError`` naming the byte offset of
+            the failed math; ``"keep"`` leaves it unchanged
+        report: Return a :class:`DocumentReport` with the output and a
+            :class:`Span` for every math segment
+        dry_run: Return the :class:`DocumentReport` without building the output
+        Remaining keyword arguments are the options of :func:`tex2typst`.
+
+    Returns:
+        The document with its math converted, or a :class:`DocumentReport`
+
+    Examples:
+        >>> convert_markdown(r"Let $\\alpha$ be")
+        'Let $alpha$ be'
+    """
+    return _tex2typst_core.convert_markdown(
+        text,
+        errors=errors,
+        report=report,
+        dry_run=dry_run,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        js_options=js_options,
+    )
+
+
+def num_to_typst(n: float) -> str:
+    """
+    Format a number as Typst math without going through the converter.
+
+    ``e``-notation (where ``repr`` would use it) becomes a power of ten; ``inf``
+    becomes ``oo`` and ``nan`` the string ``"NaN"``.
+
+    Examples:
+        >>> num_to_typst(1.5e-10)
+        '1.5 times 10^(-10)'
+        >>> num_to_typst(-float("inf"))
+        '-oo'
+    """
+    return _tex2typst_core.num_to_typst(n)
+
+
+def format_typst_math(typst: str) -> str:
+    """
+    Canonicalize the spacing of Typst math without going through the converter.
+
+    Whitespace outside string literals collapses to single spaces; ``=``, ``+``,
+    ``->`` and the other relations get one space on each side (a sign only
+    before it), ``,`` and ``;`` one after, and brackets, ``^``, ``_`` and ``/``
+    none. Spaces between names, or between a function and its arguments, are
+    kept, and ``lr(...)`` around a single bracket group is dropped. Formatting
+    twice gives the same result as once.
+
+    Examples:
+        >>> format_typst_math("lr((a+b))/ 2")
+        '(a + b)/2'
+        >>> format_typst_math("f (x),g(x)")
+        'f (x), g(x)'
+    """
+    return _tex2typst_core.format_typst_math(typst)
+
+
+def typst_equivalent(a: str, b: str, *, numbers_by_value: bool = True) -> bool:
+    """
+    Whether two Typst math strings mean the same, e.g. the output of two
+    converter versions.
+
+    Both are canonicalized as by :func:`format_typst_math` and compared token by
+    token, so spacing and redundant ``lr(...)`` do not matter. String literals
+    compare exactly; numbers compare by value, so ``0.50`` equals ``0.5``.
+
+    Args:
+        a: Typst math
+        b: Typst math to compare with
+        numbers_by_value: Compare numbers by value rather than as written
+
+    Examples:
+        >>> typst_equivalent("lr((a+b))/ 2", "(a + b)/2")
+        True
+        >>> typst_equivalent("0.50 x", "0.5 x", numbers_by_value=False)
+        False
+    """
+    return _tex2typst_core.typst_equivalent(a, b, numbers_by_value=numbers_by_value)
+
+
+def typst_equivalent_batch(
+    list_a: List[str], list_b: List[str], *, numbers_by_value: bool = True
+) -> List[bool]:
+    """
+    :func:`typst_equivalent` for each pair of ``list_a`` and ``list_b``, e.g. to
+    compare a whole corpus converted before and after an upgrade.
+
+    Raises:
+        ValueError: If the lists differ in length
+    """
+    return _tex2typst_core.typst_equivalent_batch(
+        list_a, list_b, numbers_by_value=numbers_by_value
+    )
+
+
+def diff_typst(a: str, b: str, *, numbers_by_value: bool = True) -> Optional[str]:
+    """
+    Token-level diff of two Typst math strings, or ``None`` if they are
+    :func:`typst_equivalent`.
+
+    The diff is of the canonical forms, in the style of ``git diff --word-diff``:
+    tokens only in ``a`` are marked ``[-...-]`` and tokens only in ``b``
+    ``{+...+}``.
+
+    Example:
+        >>> diff_typst("x^2 + 1", "x^3+1")
+        'x^[-2-]{+3+} + 1'
+    """
+    return _tex2typst_core.diff_typst(a, b, numbers_by_value=numbers_by_value)
+
+
+def normalize_tex(tex: str, *, expand_macros: Optional[Dict[str, str]] = None) -> str:
+    """
+    Bring TeX math into a canonical form, e.g. to hash inputs for deduplication.
+
+    ``%`` comments are stripped and whitespace is dropped except where it is
+    significant: after a control word before a letter, between digits, and in
+    the arguments of text commands such as ``\\text`` and ``\\operatorname``.
+    ``^{x}`` and ``_{x}`` lose the braces around a single character, and braces
+    around the whole input are removed. The result converts like the input with
+    ``strip_comments=True``.
+
+    Args:
+        tex: TeX math to normalize
+        expand_macros: Macros to expand first, keyed like ``custom_tex_macros``
+            (``{"\\R": "\\mathbb{R}"}``); bodies refer to arguments as
+            ``#1``..``#9``
+
+    Raises:
+        ValueError: If expanding ``expand_macros`` does not terminate
+
+    Examples:
+        >>> normalize_tex(r"{\\frac {1} {2}}")
+        '\\\\frac{1}{2}'
+        >>> normalize_tex(r"\\norm{x} ^ {2}", expand_macros={r"\\norm": r"\\|#1\\|"})
+        '\\\\|x\\\\|^2'
+    """
+    return _tex2typst_core.normalize_tex(tex, expand_macros=expand_macros)
+
+
+def num_to_tex(n: float) -> str:
+    """
+    Format a number as TeX math without going through the converter.
+
+    ``e``-notation (where ``repr`` would use it) becomes a power of ten; ``inf``
+    becomes ``\\infty`` and ``nan`` ``\\mathrm{NaN}``.
+
+    Examples:
+        >>> num_to_tex(1.5e-10)
+        '1.5 \\times 10^{-10}'
+        >>> num_to_tex(0.25)
+        '0.25'
+    """
+    return _tex2typst_core.num_to_tex(n)
+
+
+def convert_jsonl(
+    input_path: Union[str, "os.PathLike[str]"],
+    output_path: Union[str, "os.PathLike[str]"],
+    *,
+    tex_field: str = "tex",
+    out_field: str = "typst",
+    errors: Literal["skip", "null", "raise"] = "skip",
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    max_input_length: Optional[int] = None,
+) -> Dict[str, Any]:
+    """
+    Convert one field of every record in a JSON Lines file from TeX to Typst.
+
+    The file is streamed line by line in Rust with the GIL released, so memory
+    use does not grow with the file size. Each output record is the input
+    record with ``out_field`` added; all other fields are copied untouched.
+    Blank lines are skipped. Conversion options are the same as for
+    :func:`tex2typst`; results are not cached.
+
+    Args:
+        input_path: Path of the JSON Lines file to read
+        output_path: Path of the JSON Lines file to write
+        tex_field: Name of the field holding the TeX input
+        out_field: Name of the field the Typst output is written to
+        errors: What to do with records that fail: ``"skip"`` leaves them out
+            of the output, ``"null"`` writes them with ``out_field`` set to
+            null, ``"raise"`` stops with ``ValueError``
+        max_input_length: Records whose TeX is longer than this many bytes are
+            treated as failures under the ``errors`` policy
+
+    Returns:
+        Summary dict with ``converted`` and ``failed`` counts and ``errors``,
+        the first few error messages prefixed with their line numbers
+
+    Examples:
+        >>> convert_jsonl("formulas.jsonl", "converted.jsonl")
+        {'converted': 1000, 'failed': 2, 'errors': [...]}
+    """
+    return _tex2typst_core.convert_jsonl(
+        input_path,
+        output_path,
+        tex_field=tex_field,
+        out_field=out_field,
+        errors=errors,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        max_input_length=max_input_length,
+    )
+
+
+def tex2typst_file(
+    input_path: Union[str, "os.PathLike[str]"],
+    output_path: Union[str, "os.PathLike[str]"],
+    *,
+    errors: Literal["keep", "empty", "raise"] = "keep",
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    max_input_length: Optional[int] = None,
+) -> int:
+    """
+    Convert a text file with one TeX formula per line to a file of Typst lines.
+
+    Non-empty lines are converted in a single batch; blank lines stay blank, so
+    line ``n`` of the output always belongs to line ``n`` of the input. Conversion
+    options are the same as for :func:`tex2typst`; results are not cached.
+
+    Args:
+        input_path: Path of the text file to read
+        output_path: Path of the text file to write
+        errors: What to write for lines that fail: ``"keep"`` copies the TeX
+            line unchanged, ``"empty"`` writes a blank line, ``"raise"`` stops
+            with ``ValueError`` before the output file is created
+        max_input_length: Lines longer than this many bytes are treated as
+            failures under the ``errors`` policy
+
+    Returns:
+        Number of lines converted successfully
+
+    Examples:
+        >>> tex2typst_file("formulas.tex", "formulas.typ")
+        998
+    """
+    return _tex2typst_core.tex2typst_file(
+        input_path,
+        output_path,
+        errors=errors,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        max_input_length=max_input_length,
+    )
+
+
+def tex2typst_iter(
+    iterable: Iterable[str],
+    *,
+    chunk_size: int = 1024,
+    errors: Literal["raise", "keep", "null"] = "raise",
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+) -> Iterator[Optional[str]]:
+    """
+    Lazily convert an iterable of TeX strings to Typst.
+
+    Up to ``chunk_size`` items are pulled from ``iterable`` at a time and converted
+    in one batch with the GIL released; their results are yielded before the next
+    chunk is pulled. Neither the whole input nor the whole output is ever held in
+    memory, so generators of millions of formulas can be streamed. Conversion
+    options are the same as for :func:`tex2typst`; results are not cached.
+
+    Args:
+        iterable: TeX strings, e.g. a generator reading them from a database
+        chunk_size: Number of items pulled and converted at a time
+        errors: What to yield for items that fail: ``"raise"`` yields the items
+            before it and then raises ``ValueError`` naming the item's position,
+            ``"keep"`` yields the TeX unchanged, ``"null"`` yields ``None``
+
+    Returns:
+        Iterator over the results, in input order
+
+    Raises:
+        TypeError: When the iterable produces something other than a string
+        ValueError: If ``chunk_size`` is 0, and for failed items with
+            ``errors="raise"``
+
+    An exception raised by ``iterable`` itself, like the ``TypeError`` for a
+    non-string item, is raised after the items pulled before it have been yielded.
+    The iterator is exhausted after raising.
+
+    Examples:
+        >>> formulas = (rf"\\alpha_{i}" for i in range(3))
+        >>> list(tex2typst_iter(formulas, chunk_size=2))
+        ['alpha_0', 'alpha_1', 'alpha_2']
+    """
+    return _tex2typst_core.tex2typst_iter(
+        iterable,
+        chunk_size=chunk_size,
+        errors=errors,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+    )
+
+
+def tex2typst_series(
+    s: Any,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    skip_empty: bool = False,
+    empty_replacement: Optional[str] = None,
+    max_input_length: Optional[int] = None,
+    dedupe: bool = False,
+) -> Any:
+    """
+    Convert a pandas Series of LaTeX/TeX strings to Typst format.
+
+    The whole Series is converted in one batch; the result is a new Series with
+    the same index and name. Requires the extension to be built with the
+    ``pandas`` feature (``maturin build --features pandas``).
+
+    Args:
+        s: pandas Series of LaTeX/TeX math strings
+        non_strict: Allow non-strict parsing
+        prefer_shorthands: Prefer shorthand notation
+        keep_spaces: Preserve spaces in output
+        frac_to_slash: Convert fractions to slash notation
+        infty_to_oo: Convert infinity symbol to oo
+        optimize: Optimize output
+        ignore_unknown_commands: Pass commands the converter does not recognize
+            through as ``\\cmd``
+        handle_physics: Rewrite macros of the ``physics`` package into plain TeX first
+        output_unicode: Emit Unicode characters instead of Typst symbol names
+        handle_text_mode: How to treat `\\text{...}` contents: "verbatim", "convert" or "strip"
+        custom_tex_macros: Custom TeX macro definitions
+        replace_unknown_commands: Typst fallbacks for unknown commands (name
+            without backslash); a ``None`` value drops the command silently
+        symbol_map: Typst translations overriding the built-in ones of TeX symbols
+        environment_map: Typst functions standing in for TeX environments
+        skip_empty: Do not convert empty strings; put ``empty_replacement`` at
+            their positions instead
+        empty_replacement: Value returned for skipped empty strings (default ``None``)
+        max_input_length: Raise ``ValueError`` listing all values longer than this
+            many bytes
+        dedupe: Convert each distinct value once and copy the result to every
+            row holding it, e.g. for categorical columns
+
+    Returns:
+        pandas Series of converted Typst strings
+
+    Raises:
+        ImportError: If the extension was built without the ``pandas`` feature
+
+    Examples:
+        >>> tex2typst_series(pd.Series([r"\\alpha", r"\\beta"])).to_list()
+        ['alpha', 'beta']
+    """
+    if not hasattr(_tex2typst_core, "tex2typst_series"):
+        raise ImportError(
+            "tex2typst_series requires the extension to be built with the 'pandas' feature"
+        )
+    return _tex2typst_core.tex2typst_series(
+        s,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        skip_empty=skip_empty,
+        empty_replacement=empty_replacement,
+        max_input_length=max_input_length,
+        dedupe=dedupe,
+    )
+
+
+def tex2typst_arrow(
+    array: Any,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    keep_original_on_error: bool = False,
+) -> Any:
+    """
+    Convert a pyarrow string array of LaTeX/TeX to Typst format.
+
+    The values are read in place through the Arrow C data interface, without
+    building a Python list, and converted in one batch with the GIL released.
+    Requires the extension to be built with the ``arrow`` feature and pyarrow 14
+    or newer. Conversion options are the same as for :func:`tex2typst`.
+
+    Args:
+        array: pyarrow ``string`` or ``large_string`` Array or ChunkedArray
+        keep_original_on_error: Return the original TeX for values that fail to
+            convert instead of raising
+
+    Returns:
+        pyarrow string array of the same length with nulls kept as nulls, or a
+        ChunkedArray with the same chunks for ChunkedArray input
+
+    Raises:
+        ImportError: If the extension was built without the ``arrow`` feature
+        TypeError: If ``array`` is not an Arrow string array
+        ValueError: If a value is not valid UTF-8 or fails to convert
+
+    Examples:
+        >>> tex2typst_arrow(pa.array([r"\\alpha", None])).to_pylist()
+        ['alpha', None]
+    """
+    if not hasattr(_tex2typst_core, "tex2typst_arrow"):
+        raise ImportError(
+            "tex2typst_arrow requires the extension to be built with the 'arrow' feature"
+        )
+    return _tex2typst_core.tex2typst_arrow(
+        array,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        keep_original_on_error=keep_original_on_error,
+    )
+
+
+def convert_series(
+    obj: Any,
+    *,
+    non_strict: Optional[bool] = None,
+    prefer_shorthands: Optional[bool] = None,
+    keep_spaces: Optional[bool] = None,
+    frac_to_slash: Optional[bool] = None,
+    infty_to_oo: Optional[bool] = None,
+    optimize: Optional[bool] = None,
+    ignore_unknown_commands: Optional[bool] = None,
+    handle_physics: Optional[bool] = None,
+    output_unicode: Optional[bool] = None,
+    handle_text_mode: Optional[Literal["verbatim", "convert", "strip"]] = None,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+    replace_unknown_commands: Optional[Dict[str, Optional[str]]] = None,
+    symbol_map: Optional[Dict[str, str]] = None,
+    environment_map: Optional[Dict[str, str]] = None,
+    keep_original_on_error: bool = False,
+) -> Any:
+    """
+    Convert a dataframe column of LaTeX/TeX strings to Typst format.
+
+    Works with pandas and polars Series, or any iterable of strings, without
+    needing either library at build time. Missing values (``None``, NaN,
+    ``pd.NA``) stay ``None``. When the extension is built with the ``arrow``
+    feature and ``obj`` exports ``__arrow_c_stream__``, the values are read in
+    place and a pyarrow array is returned. Conversion options are the same as
+    for :func:`tex2typst`.
+
+    Args:
+        obj: pandas or polars Series, or an iterable of strings
+        keep_original_on_error: Return the original TeX for values that fail to
+            convert instead of raising
+
+    Returns:
+        list of Typst strings with ``None`` for missing values, or a pyarrow
+        string array for Arrow input with the ``arrow`` feature
+
+    Raises:
+        TypeError: If a value is neither a string nor missing
+        ValueError: If a value fails to convert
+
+    Examples:
+        >>> convert_series(pd.Series([r"\\alpha", float("nan")]))
+        ['alpha', None]
+    """
+    return _tex2typst_core.convert_series(
+        obj,
+        non_strict=non_strict,
+        prefer_shorthands=prefer_shorthands,
+        keep_spaces=keep_spaces,
+        frac_to_slash=frac_to_slash,
+        infty_to_oo=infty_to_oo,
+        optimize=optimize,
+        ignore_unknown_commands=ignore_unknown_commands,
+        handle_physics=handle_physics,
+        output_unicode=output_unicode,
+        handle_text_mode=handle_text_mode,
+        custom_tex_macros=custom_tex_macros,
+        replace_unknown_commands=replace_unknown_commands,
+        symbol_map=symbol_map,
+        environment_map=environment_map,
+        keep_original_on_error=keep_original_on_error,
+    )
+
+
+def parse_tex(
+    tex: str,
+    *,
+    custom_tex_macros: Optional[Dict[str, str]] = None,
+) -> Dict[str, Any]:
+    """
+    Parse LaTeX/TeX math and return the intermediate AST.
+
+    The node schema is passed through as-is from the upstream tex2typst
+    library and is unstable: it may change whenever the bundled JS is upgraded.
+
+    Args:
+        tex: LaTeX/TeX math string to parse
+        custom_tex_macros: Custom TeX macro definitions
+
+    Returns:
+        Root node of the parsed tree as nested dicts/lists
+
+    Raises:
+        ValueError: If parsing fails or the input or tree is nested too deeply
+
+    Examples:
+        >>> parse_tex(r"\\frac{a}{b}")["type"]
+        'funcCall'
+    """
+    return _tex2typst_core.parse_tex(tex, custom_tex_macros=custom_tex_macros)
+
+
+def parse_typst(typst: str) -> Dict[str, Any]:
+    """
+    Parse Typst math and return the intermediate AST.
+
+    The node schema is passed through as-is from the upstream tex2typst
+    library and is unstable: it may change whenever the bundled JS is upgraded.
+
+    Args:
+        typst: Typst math string to parse
+
+    Returns:
+        Root node of the parsed tree as nested dicts/lists
+
+    Raises:
+        ValueError: If parsing fails or the tree is nested too deeply
+
+    Examples:
+        >>> parse_typst("frac(a, b)")["type"]
+        'funcCall'
+    """
+    return _tex2typst_core.parse_typst(typst)
+
+
+def tex2typst_ast(tex: str) -> Dict[str, Any]:
+    """
+    Convert LaTeX/TeX math and return the Typst tree instead of its text.
+
+    The JS bundle must define a global ``tex2typstAST(tex)`` function returning
+    the converted tree, e.g. through :func:`register_js_extension`. The bundled
+    build does not, and then this raises ``NotImplementedError``. The schema is
+    whatever that function returns.
+
+    Args:
+        tex: LaTeX/TeX math string to convert
+
+    Returns:
+        Root node of the converted tree as nested dicts/lists
+
+    Raises:
+        NotImplementedError: If the bundle does not define ``tex2typstAST``
+        ValueError: If the conversion fails or the tree is nested too deeply
+    """
+    return _tex2typst_core.tex2typst_ast(tex)
+
+
+def typst2tex_ast(typst: str) -> Dict[str, Any]:
+    """
+    Convert Typst math and return the TeX tree instead of its text.
+
+    The JS bundle must define a global ``typst2texAST(typst)`` function returning
+    the converted tree, e.g. through :func:`register_js_extension`. The bundled
+    build does not, and then this raises ``NotImplementedError``.
+
+    Args:
+        typst: Typst math string to convert
+
+    Returns:
+        Root node of the converted tree as nested dicts/lists
+
+    Raises:
+        NotImplementedError: If the bundle does not define ``typst2texAST``
+        ValueError: If the conversion fails or the tree is nested too deeply
+    """
+    return _tex2typst_core.typst2tex_ast(typst)
+
+
+def parse_preamble(text: str) -> Dict[str, Any]:
+    """
+    Harvest macro definitions from a LaTeX preamble.
+
+    ``\\newcommand``, ``\\renewcommand``, ``\\providecommand`` (and their
+    starred forms) and ``\\def`` with undelimited parameters are recognized;
+    ``%`` comments are ignored. Declarations that cannot be represented, such as
+    ``\\NewDocumentCommand`` or optional arguments with defaults, are listed in
+    ``warnings`` instead of being dropped silently.
+
+    Args:
+        text: Preamble source
+
+    Returns:
+        Dict with ``macros`` (name to body), ``num_args`` (name to argument
+        count) and ``warnings`` (list of messages)
+
+    Examples:
+        >>> parse_preamble(r"\\newcommand{\\R}{\\mathbb{R}}")["macros"]
+        {'\\\\R': '\\\\mathbb{R}'}
+    """
+    return _tex2typst_core.parse_preamble(text)
+
+
+def load_macros_from_file(path: Union[str, "os.PathLike[str]"]) -> Dict[str, str]:
+    """
+    Load macro definitions from a JSON or TOML file.
+
+    The file maps macro names to their TeX expansions, e.g. ``{"\\\\R":
+    "\\\\mathbb{R}"}`` in JSON or ``"\\\\R" = "\\\\mathbb{R}"`` in TOML, and
+    the format is chosen by the ``.json`` or ``.toml`` extension. TOML needs
+    Python 3.11+ or the ``tomli`` package.
+
+    Args:
+        path: Path of the macro file
+
+    Returns:
+        Dict suitable for passing as ``custom_tex_macros``
+
+    Raises:
+        FileNotFoundError: If the file does not exist
+        ValueError: If the extension is unsupported or the file does not parse
+            as a table of macros
+        KeyError: If a macro maps to something other than a string
+
+    Examples:
+        >>> macros = load_macros_from_file("macros.json")
+        >>> tex2typst(r"x \\in \\R", custom_tex_macros=macros)
+        'x in RR'
+    """
+    return _tex2typst_core.load_macros_from_file(path)
+
+
+def save_macros_to_file(macros: Dict[str, str], path: Union[str, "os.PathLike[str]"]) -> None:
+    """
+    Write macro definitions to a JSON or TOML file.
+
+    The format is chosen by the ``.json`` or ``.toml`` extension and entries
+    are written in dict order; :func:`load_macros_from_file` reads the file back.
+
+    Args:
+        macros: Macro names mapped to their TeX expansions
+        path: Path of the file to write
+
+    Raises:
+        ValueError: If the extension is unsupported
+        KeyError: If a macro maps to something other than a string
+    """
+    _tex2typst_core.save_macros_to_file(macros, path)
+
+
+def detect_format(s: str) -> Literal["tex", "typst", "ambiguous"]:
+    """
+    Guess whether a math string is written in TeX or Typst.
+
+    Backslash commands, ``^{...}`` groups and braces count as TeX evidence;
+    calls like ``frac(``, bare symbol names, ``->`` style shorthands, string
+    literals and ``#`` count as Typst evidence. A format wins only when its
+    evidence is at least twice the other's.
+
+    Args:
+        s: Math string to classify
+
+    Returns:
+        "tex", "typst", or "ambiguous" when the evidence is missing or mixed
+
+    Examples:
+        >>> detect_format(r"\\frac{1}{2}")
+        'tex'
+        >>> detect_format("frac(1, 2)")
+        'typst'
+        >>> detect_format("x + y")
+        'ambiguous'
+    """
+    return _tex2typst_core.detect_format(s)
+
+
+def convert_auto(
+    s: str,
+    target: Literal["typst", "tex"] = "typst",
+    *,
+    strict_detection: bool = False,
+    **options: Any,
+) -> str:
+    """
+    Convert to ``target`` after detecting the input format with :func:`detect_format`.
+
+    Input that already matches the target is returned unchanged. Results are not cached.
+
+    Args:
+        s: TeX or Typst math string
+        target: Output format, "typst" or "tex"
+        strict_detection: Raise instead of guessing when the input is ambiguous;
+            otherwise ambiguous input is converted as if it were in the other format
+        **options: Options of :func:`tex2typst` or :func:`typst2tex`, matching the target
+
+    Returns:
+        String in the target format
+
+    Raises:
+        ValueError: If ``strict_detection`` is set and the input is ambiguous,
+            or if the conversion fails
+
+    Examples:
+        >>> convert_auto(r"\\frac{1}{2}")
+        '1/2'
+        >>> convert_auto("frac(1, 2)")
+        'frac(1, 2)'
+    """
+    return _tex2typst_core.convert_auto(
+        s, target, strict_detection=strict_detection, **options
+    )
+
+
+def get_js_bundle_version() -> str:
+    """
+    Get the version of the upstream tex2typst JavaScript library in this build.
+
+    Returns:
+        Version string such as ``"0.5.6"``, or ``"unknown"`` if the bundle does
+        not record it
+    """
+    return _tex2typst_core.get_js_bundle_version()
+
+
+def list_global_functions() -> List[str]:
+    """
+    List the global functions the JavaScript bundle defines in this thread's
+    engine, e.g. to check that a development bundle exports ``tex2typstAST``
+    before calling it.
+
+    Functions added by :func:`register_js_extension` are included; built-ins such
+    as ``parseInt`` are not.
+
+    Returns:
+        Sorted function names, such as ``["tex2typst", "typst2tex", ...]``
+    """
+    return _tex2typst_core.list_global_functions()
+
+
+def get_converter_info() -> Dict[str, Any]:
+    """
+    Report component versions and the state of this thread's converter.
+
+    Does not start the JavaScript engine if this thread has not used it yet.
+
+    Returns:
+        dict with ``rust_crate_version``, ``js_bundle_version``,
+        ``quickjs_version``, ``rquickjs_version``,
+        ``thread_converter_initialized`` and ``thread_conversion_count`` (inputs
+        this thread has sent to the JS converter, excluding cache hits)
+    """
+    return _tex2typst_core.get_converter_info()
+
+
+class VersionInfo(NamedTuple):
+    """Versions of the components of this build, returned by :func:`version_info`"""
+
+    rust: str
+    js_bundle: str
+    quickjs: str
+    rquickjs: str
+
+
+def version_info() -> VersionInfo:
+    """
+    Report the versions of the Rust crate, the JavaScript bundle and QuickJS.
+
+    Like :func:`get_converter_info`, does not start the JavaScript engine.
+
+    Returns:
+        VersionInfo with ``rust``, ``js_bundle`` (``"unknown"`` if the bundle does
+        not record it), ``quickjs`` and ``rquickjs``
+
+    Example:
+        >>> version_info().rust == __version__
+        True
+    """
+    info = _tex2typst_core.get_converter_info()
+    return VersionInfo(
+        rust=info["rust_crate_version"],
+        js_bundle=info["js_bundle_version"],
+        quickjs=info["quickjs_version"],
+        rquickjs=info["rquickjs_version"],
+    )
+
+
+def __getattr__(name: str) -> Any:
+    # ``__js_bundle_version__`` is read on access, so it follows load_bundle()
+    if name == "__js_bundle_version__":
+        return version_info().js_bundle
+    raise AttributeError(f"module {__name__!r} has no attribute {name!r}")
+
+
+def check_installation(*, raise_on_failure: bool = False) -> Dict[str, Any]:
+    """
+    Self-test this installation; include the result in bug reports.
+
+    Starts this thread's converter, checks that the bundle defines the
+    ``tex2typst`` and ``typst2tex`` functions and runs a few known conversions
+    in both directions, such as ``\\frac{1}{2}`` to ``frac(1, 2)``. Failed checks
+    are recorded in the result instead of raised.
+
+    Args:
+        raise_on_failure: Raise :class:`EngineError` naming the failed checks
+            instead of returning (default: False)
+
+    Returns:
+        dict with ``ok`` (whether every check passed), ``module_path`` (the
+        extension module file), ``versions`` (``rust_crate_version``,
+        ``js_bundle_version``, ``quickjs_version`` and ``rquickjs_version``) and
+        ``checks``, a list of dicts with ``name``, ``ok`` and ``error`` (None for
+        a check that passed)
+
+    Example:
+        >>> check_installation()["ok"]
+        True
+    """
+    return _tex2typst_core.check_installation(raise_on_failure=raise_on_failure)
+
+
+def get_stats() -> Dict[str, Any]:
+    """
+    Report cumulative conversion statistics of all threads.
+
+    Counters are kept by the extension since import or the last
+    :func:`reset_stats`. A call is one request handled by a JavaScript engine:
+    a single string or a whole batch. Results served by the module-level LRU
+    cache never reach the engine and are not counted.
+
+    Returns:
+        dict with ``tex2typst_conversions`` and ``typst2tex_conversions``
+        (inputs, counting every batch item), ``calls``, ``total_time_seconds``,
+        ``max_call_time_seconds``, ``engines_initialized``,
+        ``js_init_time_seconds``, ``cache_hits`` and ``cache_misses`` (of all
+        :class:`Converter` caches) and ``js_memory_used_bytes`` (this thread's
+        engine, ``None`` if it has not started)
+
+    Example:
+        >>> reset_stats()
+        >>> _ = tex2typst([r"\\alpha", r"\\beta"])
+        >>> stats = get_stats()
+        >>> stats["tex2typst_conversions"], stats["calls"]
+        (2, 1)
+    """
+    return _tex2typst_core.get_stats()
+
+
+def reset_stats() -> None:
+    """Zero the counters reported by :func:`get_stats`."""
+    _tex2typst_core.reset_stats()
+
+
+def set_js_memory_limit(limit_bytes: Optional[int]) -> None:
+    """
+    Cap the memory each JavaScript engine may allocate for conversions.
+
+    The limit counts bytes beyond what loading the converter bundle took, so a
+    fresh engine always starts. It applies to all threads, including engines
+    already running, from their next call. A conversion exceeding it raises
+    :class:`MemoryError`; the engine is then discarded and the thread's next
+    conversion transparently starts a new one. A :class:`Converter` whose engine
+    ran out of memory raises :class:`EngineError` and has to be recreated.
+
+    Args:
+        limit_bytes: Bytes of headroom, or ``None`` to lift the limit
+
+    Example:
+        >>> set_js_memory_limit(64 * 1024 * 1024)
+        >>> set_js_memory_limit(None)
+    """
+    _tex2typst_core.set_js_memory_limit(limit_bytes)
+
+
+def get_js_memory_limit() -> Optional[int]:
+    """Return the limit set by :func:`set_js_memory_limit`, ``None`` if there is none."""
+    return _tex2typst_core.get_js_memory_limit()
+
+
+def set_js_gc_threshold(threshold_bytes: Optional[int]) -> None:
+    """
+    Set after how many allocated bytes each JavaScript engine collects garbage.
+
+    After each automatic collection QuickJS moves the threshold to half again the
+    memory still in use. It applies to all threads, including engines already
+    running, from their next call. The default is QuickJS's own, 256 KiB.
+
+    Args:
+        threshold_bytes: Bytes before the first automatic collection, or ``None``
+            to turn automatic collection off
+
+    Example:
+        >>> set_js_gc_threshold(4 * 1024 * 1024)
+        >>> set_js_gc_threshold(256 * 1024)
+    """
+    _tex2typst_core.set_js_gc_threshold(threshold_bytes)
+
+
+def get_js_gc_threshold() -> Optional[int]:
+    """Return the threshold set by :func:`set_js_gc_threshold`, ``None`` if automatic collection is off."""
+    return _tex2typst_core.get_js_gc_threshold()
+
+
+def set_js_gc_interval(every_n_items: int) -> None:
+    """
+    Collect garbage every ``every_n_items`` items a batch converts.
+
+    Reference cycles left behind by conversions, e.g. by JS extensions, are only
+    freed by a collection. This bounds the memory of long batches even with
+    automatic collection turned off. The default is every 10000 items.
+
+    Args:
+        every_n_items: Items between collections, or ``0`` to never collect in
+            batches
+
+    Example:
+        >>> set_js_gc_interval(1000)
+        >>> set_js_gc_interval(10000)
+    """
+    _tex2typst_core.set_js_gc_interval(every_n_items)
+
+
+def get_js_gc_interval() -> int:
+    """Return the interval set by :func:`set_js_gc_interval`."""
+    return _tex2typst_core.get_js_gc_interval()
+
+
+def run_gc() -> None:
+    """
+    Collect the garbage of the calling thread's JavaScript engine now.
+
+    Does nothing if the thread has not converted anything yet.
+
+    Example:
+        >>> run_gc()
+    """
+    _tex2typst_core.run_gc()
+
+
+def memory_usage() -> Optional[Dict[str, int]]:
+    """
+    Return allocation statistics of the calling thread's JavaScript engine.
+
+    Compare them before and after :func:`run_gc` to see what a collection
+    reclaimed.
+
+    Returns:
+        Dict with ``malloc_size`` (bytes allocated), ``memory_used_size`` (bytes
+        in use including allocator overhead), ``malloc_count``, ``obj_count`` and
+        ``str_count``, or ``None`` if the thread has not converted anything yet
+
+    Example:
+        >>> _ = tex2typst("x")
+        >>> memory_usage()["malloc_size"] > 0
+        True
+    """
+    return _tex2typst_core.memory_usage()
+
+
+def warmup() -> float:
+    """
+    Start the calling thread's JavaScript engine now.
+
+    The first conversion on a thread otherwise pays for creating the engine and
+    evaluating the converter bundle, tens of milliseconds. Call this where that
+    cost is harmless, e.g. while a worker thread starts serving requests.
+
+    Returns:
+        Seconds spent starting the engine, ``0.0`` if it was already running
+
+    Example:
+        >>> _ = warmup()
+        >>> warmup()
+        0.0
+    """
+    return _tex2typst_core.warmup()
+
+
+def warmup_background() -> "Future[float]":
+    """
+    Start a JavaScript engine on a new daemon thread without waiting for it.
+
+    Engines are per thread, so this does not speed up the calling thread; use
+    :func:`warmup` there. It takes the one-time, process-wide setup (reading and
+    hashing the converter bundle) off the first request.
+
+    Returns:
+        :class:`concurrent.futures.Future` resolving to the seconds spent
+        starting the engine, or to the error that prevented it
+    """
+    future: "Future[float]" = Future()
+
+    def run() -> None:
+        try:
+            future.set_result(_tex2typst_core.warmup())
+        except BaseException as e:
+            future.set_exception(e)
+
+    threading.Thread(target=run, name="tex2typst-warmup", daemon=True).start()
+    return future
+
+
+def get_pending_warnings() -> List[str]:
+    """
+    Messages the JS engines on this thread passed to ``console.log``,
+    ``console.warn`` and the other ``console`` methods since the last
+    :func:`clear_warnings`, oldest first.
+
+    The bundle reports non-fatal issues this way, which would otherwise go
+    unnoticed. Only the most recent 1000 messages are kept, and messages of a
+    :class:`WorkerConverter` stay on its worker thread.
+
+    Example:
+        >>> clear_warnings()
+        >>> _ = tex2typst(r"\\alpha")
+        >>> get_pending_warnings()
+        []
+    """
+    return _tex2typst_core.get_pending_warnings()
+
+
+def clear_warnings() -> None:
+    """Forget the messages reported by :func:`get_pending_warnings`."""
+    _tex2typst_core.clear_warnings()
+
+
+def list_supported_commands() -> List[str]:
+    """
+    Sorted TeX commands the converter recognizes, such as ``\\frac``.
+
+    Built by checking every name the converter bundle and the extensions from
+    :func:`register_js_extension` mention, which takes a fraction of a second
+    once; the list is kept until the bundle or extensions change.
+
+    Example:
+        >>> r"\\frac" in list_supported_commands()
+        True
+    """
+    return _tex2typst_core.list_supported_commands()
+
+
+def find_unsupported_commands(
+    tex: str, *, custom_tex_macros: Optional[Dict[str, str]] = None
+) -> List[str]:
+    """
+    Commands in ``tex`` the converter does not recognize, in order of first use,
+    e.g. to screen documents before a long conversion job.
+
+    Only a backslash followed by letters counts as a command. Macros of
+    ``custom_tex_macros`` (or the default set by :func:`set_default_options`) are
+    not reported. The result is the one ``report_unknown=True`` gives, without
+    converting ``tex``.
+
+    Example:
+        >>> find_unsupported_commands(r"\\frac{1}{2} + \\pdv{f}{x}")
+        ['\\\\pdv']
+    """
+    return _tex2typst_core.find_unsupported_commands(
+        tex, custom_tex_macros=custom_tex_macros
+    )
+
+
+def set_converter_init_hook(callback: Callable[[], None]) -> None:
+    """
+    Call ``callback`` each time the calling thread's converter starts.
+
+    The thread's JS engine starts lazily on its first conversion and restarts
+    after :func:`load_bundle`, :func:`clear_js_extensions` or a fork; the hook
+    runs right after that, with no arguments, e.g. to log or to set up state
+    through :class:`Converter`. It may convert itself. An engine already running
+    does not call it, and the hook applies to the calling thread only,
+    replacing any earlier one there.
+
+    Args:
+        callback: Called with no arguments; its exceptions propagate from the
+            conversion that started the engine
+
+    Raises:
+        TypeError: If ``callback`` is not callable
+
+    Example:
+        >>> starts = []
+        >>> set_converter_init_hook(lambda: starts.append(threading.get_ident()))
+    """
+    _tex2typst_core.set_converter_init_hook(callback)
+
+
+def clear_converter_init_hook() -> None:
+    """Remove the calling thread's hook set by :func:`set_converter_init_hook`."""
+    _tex2typst_core.clear_converter_init_hook()
+
+
+def register_js_extension(source: str) -> None:
+    """
+    Evaluate JavaScript after the converter bundle in every JS engine.
+
+    Extensions run in registration order, right after the bundle, so they can
+    replace the global ``tex2typst``/``typst2tex`` functions or patch the bundle's
+    tables. The calling thread's converter is restarted immediately; those of other
+    threads restart on their next call. :class:`Converter` and
+    :class:`WorkerConverter` instances keep the engine they were created with.
+    Cached results are cleared.
+
+    Args:
+        source: JavaScript source evaluated as a script
+
+    Raises:
+        EngineError: If the source raises, with the JS stack trace; it is not
+            registered then
+
+    Example:
+        >>> register_js_extension(
+        ...     "const base = tex2typst;"
+        ...     "tex2typst = (tex, options) => base(tex, options).toUpperCase();"
+        ... )
+    """
+    _tex2typst_core.register_js_extension(source)
+    clear_cache()
+
+
+def clear_js_extensions() -> None:
+    """
+    Remove all extensions added by :func:`register_js_extension`.
+
+    Module-level converters restart without them on their next call, and cached
+    results are cleared.
+    """
+    _tex2typst_core.clear_js_extensions()
+    clear_cache()
+
+
+def load_bundle(
+    path_or_source: Union[str, "os.PathLike[str]"], *, is_path: bool = True
+) -> None:
+    """
+    Use another tex2typst.js bundle, such as a patched fork, instead of the
+    embedded one.
+
+    The bundle is started right away in a new converter for the calling thread;
+    converters of other threads restart with it on their next call.
+    :class:`Converter` and :class:`WorkerConverter` instances keep the engine they
+    were created with. Extensions from :func:`register_js_extension` run after
+    the new bundle. Cached results are cleared.
+
+    Args:
+        path_or_source: Path of the bundle file, or its JavaScript source if
+            ``is_path`` is false
+        is_path: Whether ``path_or_source`` is a path
+
+    Raises:
+        FileNotFoundError: If the bundle file does not exist
+        EngineError: If the bundle fails to evaluate or does not define global
+            ``tex2typst`` and ``typst2tex`` functions; the active bundle is kept
+
+    Example:
+        >>> load_bundle("vendor/tex2typst-fork.bundle.js")
+    """
+    _tex2typst_core.load_bundle(path_or_source, is_path=is_path)
+    clear_cache()
+
+
+def tex2typst_from_js_file(
+    js_path: Union[str, "os.PathLike[str]"], **kwargs: Any
+) -> Converter:
+    """
+    Create a :class:`Converter` running the bundle file at ``js_path``, such as a
+    development build, in a fresh JS context.
+
+    Unlike :func:`load_bundle`, the module-level functions and other converters
+    keep their bundle. Same as :meth:`Converter.from_js_file`; ``kwargs`` are the
+    arguments of the :class:`Converter` constructor.
+
+    Raises:
+        FileNotFoundError: If the bundle file does not exist
+        EngineError: If the bundle fails to evaluate or does not define global
+            ``tex2typst`` and ``typst2tex`` functions
+
+    Example:
+        >>> conv = tex2typst_from_js_file("js/tex2typst.bundle.js")
+    """
+    return Converter.from_js_file(js_path, **kwargs)
+
+
+def reset_bundle() -> None:
+    """Go back to the embedded bundle, undoing :func:`load_bundle`."""
+    _tex2typst_core.reset_bundle()
+    clear_cache()
+
+
+def bundle_sha256() -> str:
+    """
+    Hex SHA-256 of the active JS bundle, the embedded one or the one installed by
+    :func:`load_bundle`.
+    """
+    return _tex2typst_core.bundle_sha256()
+
+
+def clear_cache() -> None:
+    """
+    Clear all cached conversion results.
+
+    Example:
+        >>> clear_cache()
+    """
+    _tex2typst_cached.cache_clear()
+    _typst2tex_cached.cache_clear()
+
+
+def cache_info() -> Dict[str, object]:
+    """
+    Get cache statistics.
+
+    Returns:
+        Dictionary with cache info for tex2typst and typst2tex
+
+    Example:
+        >>> info = cache_info()
+        >>> print(f"tex2typst hits: {info['tex2typst'].hits}")
+        >>> print(f"tex2typst misses: {info['tex2typst'].misses}")
+        >>> print(f"Cache size: {info['tex2typst'].currsize}/{info['tex2typst'].maxsize}")
+    """
+    return {
+        "tex2typst": _tex2typst_cached.cache_info(),
+        "typst2tex": _typst2tex_cached.cache_info(),
+    }
+
+
+def enable_disk_cache(
+    path: Union[str, "os.PathLike[str]"], max_size_mb: float = 1024
+) -> None:
+    """
+    Keep conversion results in a directory on disk, across runs and processes.
+
+    Every conversion that reaches the JS engine is looked up by the SHA-256 of
+    its input, options and the JS bundle, so upgrading the bundle never serves
+    stale results. Each process appends to its own shard file in ``path`` and
+    reads all shards when the cache is enabled, so several processes can share
+    one directory. The in-memory LRU cache still sits in front of it.
+
+    Args:
+        path: Cache directory, created if missing
+        max_size_mb: Once the shard files reach this size, new results are no
+            longer written (existing entries are still served)
+
+    Example:
+        >>> enable_disk_cache("~/.cache/tex2typst")
+    """
+    _tex2typst_core.enable_disk_cache(os.path.expanduser(path), max_size_mb)
+
+
+def disable_disk_cache() -> None:
+    """Flush pending entries and stop using the disk cache."""
+    _tex2typst_core.disable_disk_cache()
+
+
+def disk_cache_stats() -> Dict[str, Any]:
+    """
+    Get disk cache statistics.
+
+    Returns:
+        Dictionary with ``enabled``, ``path``, ``entries``, ``size_bytes``,
+        ``max_size_bytes``, ``hits`` and ``misses``
+    """
+    return _tex2typst_core.disk_cache_stats()
+
+
+def set_default_options(**kwargs: Any) -> None:
+    """
+    Set process-wide default options for all conversion functions.
+
+    Defaults apply to ``tex2typst``, ``typst2tex`` and their list forms, and are
+    merged beneath explicit per-call kwargs: a per-call ``None`` uses the
+    default, while an explicit value (including ``False``) overrides it.
+    Passing ``None`` here removes the default for that option. The conversion
+    cache is cleared because cached results may no longer apply.
+
+    Raises:
+        TypeError: If an option name is unknown or a value has the wrong type
+
+    Example:
+        >>> set_default_options(non_strict=True, infty_to_oo=True)
+        >>> tex2typst(r"\\infty")
+        'oo'
+    """
+    _tex2typst_core.set_default_options(**kwargs)
+    clear_cache()
+
+
+def get_default_options() -> Dict[str, Any]:
+    """
+    Get the process-wide default options.
+
+    Returns:
+        Dictionary mapping option names to their default values
+    """
+    return _tex2typst_core.get_default_options()
+
+
+def get_supported_options() -> Dict[str, Dict[str, Any]]:
+    """
+    List every keyword option of :func:`tex2typst` and :func:`typst2tex`.
+
+    The metadata is static; no JavaScript runs. Defaults are those applied when
+    the option is not passed.
+
+    Returns:
+        Dictionary mapping option names to dicts with ``type``, ``default``,
+        ``description`` and ``functions`` (the functions accepting the option),
+        plus ``choices`` for options limited to a set of strings
+
+    Examples:
+        >>> get_supported_options()["wrap"]["choices"]
+        ['inline', 'block', 'none', 'auto']
+    """
+    return _tex2typst_core.get_supported_options()
+
+
+def reset_default_options() -> None:
+    """
+    Remove all process-wide default options and clear the conversion cache.
+    """
+    _tex2typst_core.reset_default_options()
+    clear_cache()
+
+
+def merge_options(*option_dicts: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Merge option dicts left to right, later dicts winning.
+
+    Every name is checked against the keyword options of :func:`tex2typst` and
+    :func:`typst2tex` and every value against its type, so typos and wrongly
+    typed values fail here instead of at conversion time. ``custom_tex_macros``,
+    ``custom_typst_macros`` and ``replace_unknown_commands`` are merged key by
+    key; a ``None`` value removes the option from the result.
+
+    Args:
+        *option_dicts: Option dicts, from lowest to highest precedence
+
+    Returns:
+        New dict that can be passed as ``**options``
+
+    Raises:
+        TypeError: For unknown option names, wrongly typed values or non-dict
+            arguments
+        ValueError: For values outside an option's choices
+
+    Examples:
+        >>> project = {"frac_to_slash": False, "custom_tex_macros": {"\\\\R": "\\\\mathbb{R}"}}
+        >>> merge_options(project, {"custom_tex_macros": {"\\\\N": "\\\\mathbb{N}"}})
+        {'frac_to_slash': False, 'custom_tex_macros': {'\\\\R': '\\\\mathbb{R}', '\\\\N': '\\\\mathbb{N}'}}
+        >>> merge_options({"nonstrict": True})
+        Traceback (most recent call last):
+        TypeError: Unknown option 'nonstrict', did you mean 'non_strict'?
+    """
+    return _tex2typst_core.merge_options(*option_dicts)
+
+
+__all__ = [
+    "tex2typst",
+    "typst2tex",
+    "tex2typst_with_info",
+    "tex2typst_batch_with_info",
+    "tex2typst_batch_mixed",
+    "tex2typst_batch_progress",
+    "tex2typst_aligned",
+    "explain_conversion",
+    "roundtrip_check",
+    "roundtrip_diff",
+    "BatchResult",
+    "ConversionInfo",
+    "TexParseError",
+    "EngineError",
+    "Converter",
+    "WorkerConverter",
+    "parse_preamble",
+    "load_macros_from_file",
+    "save_macros_to_file",
+    "convert_refs",
+    "strip_original_comments",
+    "convert_typst_document",
+    "convert_latex_document",
+    "convert_markdown",
+    "DocumentReport",
+    "Span",
+    "num_to_typst",
+    "format_typst_math",
+    "typst_equivalent",
+    "typst_equivalent_batch",
+    "diff_typst",
+    "normalize_tex",
+    "num_to_tex",
+    "detect_format",
+    "convert_auto",
+    "convert_jsonl",
+    "tex2typst_file",
+    "tex2typst_iter",
+    "tex2typst_series",
+    "tex2typst_arrow",
+    "convert_series",
+    "parse_tex",
+    "parse_typst",
+    "tex2typst_ast",
+    "typst2tex_ast",
+    "get_js_bundle_version",
+    "list_global_functions",
+    "get_converter_info",
+    "version_info",
+    "VersionInfo",
+    "check_installation",
+    "get_stats",
+    "reset_stats",
+    "set_js_memory_limit",
+    "get_js_memory_limit",
+    "set_js_gc_threshold",
+    "get_js_gc_threshold",
+    "set_js_gc_interval",
+    "get_js_gc_interval",
+    "run_gc",
+    "memory_usage",
+    "warmup",
+    "warmup_background",
+    "eager_init",
+    "get_pending_warnings",
+    "clear_warnings",
+    "list_supported_commands",
+    "find_unsupported_commands",
+    "set_converter_init_hook",
+    "clear_converter_init_hook",
+    "register_js_extension",
+    "clear_js_extensions",
+    "load_bundle",
+    "tex2typst_from_js_file",
+    "reset_bundle",
+    "bundle_sha256",
+    "clear_cache",
+    "cache_info",
+    "enable_disk_cache",
+    "disable_disk_cache",
+    "disk_cache_stats",
+    "set_default_options",
+    "get_default_options",
+    "get_supported_options",
+    "reset_default_options",
+    "merge_options",
+    "__version__",
+    "__rust_version__",
+    "__js_bundle_version__",
+]
+
+#: Whether importing the module started the importing thread's JavaScript engine,
+#: requested by setting the ``TEX2TYPST_EAGER_INIT`` environment variable to ``1``
+eager_init = os.environ.get("TEX2TYPST_EAGER_INIT", "").lower() in ("1", "true", "yes")
+if eager_init:
+    warmup()
@@ -0,0 +1,1968 @@
+"""Type stubs for tex2typst module."""
+
+import os
+from concurrent.futures import Future
+from types import TracebackType
+from typing import Any, Callable, Iterable, Iterator, Literal, NamedTuple, overload
+
+__version__: str
+__rust_version__: str
+__js_bundle_version__: str
+
+__all__ = [
+    "tex2typst",
+    "typst2tex",
+    "tex2typst_with_info",
+    "tex2typst_batch_with_info",
+    "tex2typst_batch_mixed",
+    "tex2typst_batch_progress",
+    "tex2typst_aligned",
+    "explain_conversion",
+    "roundtrip_check",
+    "roundtrip_diff",
+    "BatchResult",
+    "ConversionInfo",
+    "TexParseError",
+    "EngineError",
+    "Converter",
+    "WorkerConverter",
+    "parse_preamble",
+    "load_macros_from_file",
+    "save_macros_to_file",
+    "convert_refs",
+    "strip_original_comments",
+    "convert_typst_document",
+    "convert_latex_document",
+    "convert_markdown",
+    "DocumentReport",
+    "Span",
+    "num_to_typst",
+    "format_typst_math",
+    "typst_equivalent",
+    "typst_equivalent_batch",
+    "diff_typst",
+    "normalize_tex",
+    "num_to_tex",
+    "detect_format",
+    "convert_auto",
+    "convert_jsonl",
+    "tex2typst_file",
+    "tex2typst_iter",
+    "tex2typst_series",
+    "tex2typst_arrow",
+    "convert_series",
+    "parse_tex",
+    "parse_typst",
+    "tex2typst_ast",
+    "typst2tex_ast",
+    "get_js_bundle_version",
+    "list_global_functions",
+    "get_converter_info",
+    "version_info",
+    "VersionInfo",
+    "check_installation",
+    "get_stats",
+    "reset_stats",
+    "warmup",
+    "warmup_background",
+    "eager_init",
+    "get_pending_warnings",
+    "clear_warnings",
+    "list_supported_commands",
+    "find_unsupported_commands",
+    "set_converter_init_hook",
+    "clear_converter_init_hook",
+    "register_js_extension",
+    "clear_js_extensions",
+    "load_bundle",
+    "tex2typst_from_js_file",
+    "reset_bundle",
+    "bundle_sha256",
+    "clear_cache",
+    "cache_info",
+    "enable_disk_cache",
+    "disable_disk_cache",
+    "disk_cache_stats",
+    "set_default_options",
+    "get_default_options",
+    "get_supported_options",
+    "reset_default_options",
+    "merge_options",
+    "__version__",
+    "__rust_version__",
+    "__js_bundle_version__",
+]
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: None = None,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> str:
+    """
+    Convert LaTeX/TeX math to Typst format.
+
+    This function uses a thread-local lazy singleton pattern - the converter is
+    initialized only on the first call within each thread, avoiding import-time overhead.
+
+    Args:
+        tex: LaTeX/TeX math string to convert
+        non_strict: Allow non-strict parsing (default: library default)
+        prefer_shorthands: Prefer shorthand notation (default: library default)
+        keep_spaces: Preserve spaces in output (default: library default)
+        frac_to_slash: Convert fractions to slash notation (default: library default)
+        infty_to_oo: Convert infinity symbol to oo (default: library default)
+        optimize: Optimize output (default: library default)
+        ignore_unknown_commands: Pass commands the converter does not recognize
+            through as `\\cmd` (default: library default)
+        handle_physics: Rewrite macros of the `physics` package (`\\dv`, `\\abs`,
+            `\\qty`, ...) into plain TeX first (default: False)
+        output_unicode: Emit Unicode characters (`α`, `×`, ...) instead of Typst
+            symbol names (default: False)
+        handle_text_mode: How to treat `\\text{...}` contents: "verbatim", "convert" or "strip" (default: library default)
+        custom_tex_macros: Custom TeX macro definitions as dict mapping macro names to expansions
+        replace_unknown_commands: Typst fallbacks for unknown commands, keyed by name without
+            backslash; a None value drops the command (default: library default)
+        symbol_map: Typst translations overriding those of built-in TeX symbols, keyed
+            by command name, e.g. `{"phi": "phi.alt"}` (default: None)
+        environment_map: Typst functions standing in for TeX environments, keyed by
+            environment name; cells become arguments, rows separated by `;`
+            (default: None)
+        trim_input: Strip leading/trailing whitespace before conversion (default: False)
+        fail_on_unknown: Raise `TexParseError` listing commands the converter does
+            not recognize (default: False)
+        preamble: LaTeX preamble whose `\\newcommand`/`\\def` definitions are applied
+            beneath `custom_tex_macros` (default: None)
+        max_input_length: Raise `ValueError` for input longer than this many bytes (default: None)
+        wrap: Surround the output with `$...$` ("inline"), `$ ... $` ("block") or
+            nothing ("none"); "auto" follows the stripped delimiters (default: None)
+        auto_strip_delimiters: Remove one pair of `$...$`, `$$...$$`, `\\(...\\)` or
+            `\\[...\\]` around the input; unbalanced delimiters raise `ValueError`
+            (default: False)
+        chem: Translate mhchem `\\ce{...}` formulas (subscripts, charges, arrows and
+            coefficients); unsupported constructs raise `ValueError` (default: False)
+        handle_mhchem: Same as `chem` (default: False)
+        siunitx: Convert `\\SI`/`\\qty`, `\\si`/`\\unit` and `\\num`; unknown unit
+            macros are listed in a `UserWarning` (default: False)
+        handle_siunitx: Same as `siunitx` (default: False)
+        units_style: "plain" math, or "unify"/"metro" package calls for siunitx
+            output; requires `siunitx=True` (default: "plain")
+        labels: Remove `\\label{...}` and attach it to block-wrapped output as
+            `<name>` (default: False)
+        tags: Remove `\\tag{...}`; block-wrapped output becomes a `math.equation`
+            numbered with the tag (default: False)
+        normalize_unicode: NFC-normalize the input and replace Unicode math
+            characters such as `−`, `′`, `α` and `×` with TeX (default: False)
+        unicode_report: Also return the `(character, tex)` substitutions made by
+            `normalize_unicode` (default: False)
+        strip_comments: Remove `%` comments (not `\\%`) before conversion; a comment
+            also swallows its line break (default: False)
+        collapse_redundant_braces: Drop grouping parentheses Typst ignores, as in
+            `x^(2)` and `(a)/(b)` (default: False)
+        normalize_output: Canonicalize the spacing of the Typst output with
+            `format_typst_math` (default: False)
+        keep_original: With "comment", also return a Typst comment quoting the
+            input (default: None)
+        max_nesting_depth: Raise `TexParseError` for input nested deeper than this;
+            None disables the check. Input too deep for the JS engine raises
+            `RecursionError` (default: 512)
+        js_options: Further options for the JS converter under their JS names,
+            passed as they are; `int` values become JS numbers (default: None)
+
+    Returns:
+        Converted Typst string
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.tex2typst(r"\\frac{1}{2}")
+        '1/2'
+        >>> tex2typst.tex2typst(r"\\alpha + \\beta")
+        'alpha + beta'
+        >>> tex2typst.tex2typst(r"\\frac{1}{2}", frac_to_slash=False)
+        'frac(1, 2)'
+        >>> tex2typst.tex2typst(r"\\infty", infty_to_oo=True)
+        'oo'
+        >>> tex2typst.tex2typst(r"\\myop", custom_tex_macros={"\\\\myop": "\\\\operatorname{myop}"})
+        'op("myop")'
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[True],
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: None = None,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> tuple[str, list[str]]:
+    """
+    Convert LaTeX/TeX math and also return the commands the converter did not
+    recognize, e.g. `("pdv f x", ["\\pdv"])`.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: Literal["comment"],
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> tuple[str, str]:
+    """
+    Convert LaTeX/TeX math and also return a Typst comment quoting the input,
+    e.g. `("x^2", "// tex: x^2")`; block-wrapped output gets `/* tex: ... */`.
+    Remove the comments again with `strip_original_comments`.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[True],
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[False] = False,
+    keep_original: Literal["comment"] | None = None,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> tuple[Any, ...]:
+    """
+    Convert LaTeX/TeX math and also return the substitutions made by
+    `normalize_unicode`, e.g. `("x - y", [("\u2212", "-")])`; with `report_unknown`
+    the unknown commands come first.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: Literal[True],
+    keep_original: Literal["comment"] | None = None,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> tuple[Any, float]:
+    """
+    Convert LaTeX/TeX math and also return the wall time of the call in seconds,
+    e.g. `("1/2", 0.00012)`; the LRU cache is bypassed.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[False] = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], None] | None = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> list[str | None]:
+    """
+    Convert multiple LaTeX/TeX strings to Typst format (with caching).
+
+    With `keep_original_on_error=True`, items that fail to convert are returned
+    unchanged instead of raising. With `skip_empty=True`, empty strings are not
+    converted and `empty_replacement` is returned at their positions.
+    `pre_process` and `post_process` are called on every input and converted
+    output from inside the batch; an exception aborts with the item index.
+    `progress_callback` receives `(completed, total)` every `callback_every_n`
+    items and when the batch is done. A nonzero `chunk_size` converts the list in
+    sub-batches of that many items. `dedupe=True` converts each distinct input once.
+    With `atomic=True`, any failure raises and no results are returned, even with
+    `keep_original_on_error=True`.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[True],
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], None] | None = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> tuple[list[str | None], list[int]]:
+    """Convert multiple strings and also return the indices of failed items."""
+    ...
+
+@overload
+def tex2typst(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[False] = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[False] = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: Literal[False] = False,
+    return_summary: Literal[True],
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], None] | None = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> BatchResult:
+    """
+    Convert multiple LaTeX/TeX strings to Typst format and summarize the batch.
+
+    The `BatchResult` holds the results, the `(index, message)` of every failed
+    item (with `keep_original_on_error=True`; otherwise the first failure raises),
+    the elapsed time, the throughput and the JS engines started.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: Literal[True],
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], None] | None = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> tuple[Any, ...]:
+    """
+    Convert multiple strings and also return a dict mapping item index to the
+    commands the converter did not recognize, followed by the substitutions
+    dict when `unicode_report` is set.
+    """
+    ...
+
+@overload
+def tex2typst(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: Literal[True],
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: Literal[False] = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], None] | None = None,
+    callback_every_n: int = 100,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> tuple[Any, ...]:
+    """
+    Convert multiple strings and also return a dict mapping item index to the
+    substitutions made by `normalize_unicode`, as the last tuple element.
+    """
+    ...
+
+@overload
+def typst2tex(
+    typst: str,
+    *,
+    block_math_mode: bool | None = None,
+    custom_typst_macros: dict[str, str] | None = None,
+    trim_input: bool = False,
+    max_input_length: int | None = None,
+    delimiters: Literal["dollars", "latex", "none"] | None = None,
+    trailing_newline: bool = False,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> str:
+    """
+    Convert Typst math to LaTeX/TeX format.
+
+    This function uses a thread-local lazy singleton pattern - the converter is
+    initialized only on the first call within each thread, avoiding import-time overhead.
+
+    Args:
+        typst: Typst math string to convert
+        block_math_mode: Use block math mode (default: library default)
+        custom_typst_macros: Typst identifiers replaced before conversion (default: None)
+        trim_input: Strip leading/trailing whitespace before conversion (default: False)
+        max_input_length: Raise `ValueError` for input longer than this many bytes (default: None)
+        delimiters: Surround the output with `\\(...\\)` ("latex") or `$...$` ("dollars"),
+            in block math mode with `\\[...\\]` or `$$...$$`; "none" leaves it bare
+            (default: None)
+        trailing_newline: End delimited display math with a line break (default: False)
+        js_options: Further options for the JS converter under their JS names (default: None)
+
+    Returns:
+        Converted LaTeX/TeX string
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.typst2tex("1/2")
+        '\\\\frac{1}{2}'
+        >>> tex2typst.typst2tex("alpha + beta")
+        '\\\\alpha + \\\\beta'
+        >>> tex2typst.typst2tex("x", block_math_mode=False)
+        'x'
+        >>> tex2typst.typst2tex("x^2", block_math_mode=False, delimiters="latex")
+        '\\\\(x^2\\\\)'
+    """
+    ...
+
+@overload
+def typst2tex(
+    typst: list[str],
+    *,
+    block_math_mode: bool | None = None,
+    custom_typst_macros: dict[str, str] | None = None,
+    trim_input: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    max_input_length: int | None = None,
+    delimiters: Literal["dollars", "latex", "none"] | None = None,
+    trailing_newline: bool = False,
+    dedupe: bool = False,
+    return_summary: Literal[False] = False,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> list[str | None]:
+    """
+    Convert multiple Typst strings to LaTeX/TeX format (with caching).
+
+    With `skip_empty=True`, empty strings are not converted and
+    `empty_replacement` is returned at their positions. `pre_process` and
+    `post_process` are called on every input and converted output.
+    `dedupe=True` converts each distinct input once.
+    """
+    ...
+
+@overload
+def typst2tex(
+    typst: list[str],
+    *,
+    block_math_mode: bool | None = None,
+    custom_typst_macros: dict[str, str] | None = None,
+    trim_input: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    max_input_length: int | None = None,
+    delimiters: Literal["dollars", "latex", "none"] | None = None,
+    trailing_newline: bool = False,
+    dedupe: bool = False,
+    return_summary: Literal[True],
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_item", "per_batch"] = False,
+) -> BatchResult:
+    """
+    Convert multiple Typst strings to LaTeX/TeX format and summarize the batch.
+
+    Any failure raises, so the `BatchResult` has no `errors`.
+    """
+    ...
+
+class TexParseError(ValueError):
+    """Raised by `fail_on_unknown=True` when TeX input uses unrecognized commands."""
+
+class EngineError(RuntimeError):
+    """Raised when the JS engine cannot load the bundle or it lacks the converter functions."""
+
+class BatchResult:
+    """Outcome of a batch conversion with `return_summary=True`."""
+
+    @property
+    def results(self) -> list[str | None]:
+        """Converted items in input order; failed items hold their input."""
+        ...
+    @property
+    def errors(self) -> list[tuple[int, str]]:
+        """`(index, message)` of every item that failed to convert."""
+        ...
+    @property
+    def elapsed(self) -> float:
+        """Wall time of the whole call in seconds."""
+        ...
+    @property
+    def items_per_second(self) -> float:
+        """Items handled per second of `elapsed`."""
+        ...
+    @property
+    def engine_inits(self) -> int:
+        """JS engines started during the call, on any thread."""
+        ...
+    def __len__(self) -> int: ...
+
+class Span:
+    """One math segment of a converted document."""
+
+    @property
+    def start(self) -> int:
+        """Byte offset of the segment in the input, delimiters included."""
+        ...
+    @property
+    def end(self) -> int:
+        """Byte offset just past the segment."""
+        ...
+    @property
+    def kind(self) -> str:
+        """
+        "inline", "display", "env:NAME" for a LaTeX environment or
+        "math.equation" (reported, not converted).
+        """
+        ...
+    @property
+    def original(self) -> str:
+        """Input text of the segment."""
+        ...
+    @property
+    def converted(self) -> str | None:
+        """Replacement text of the segment, None if it was not converted."""
+        ...
+    @property
+    def error(self) -> str | None:
+        """Why the segment failed to convert."""
+        ...
+
+class DocumentReport:
+    """Output of a document conversion with every math segment of the input."""
+
+    @property
+    def output(self) -> str | None:
+        """Converted document, None with `dry_run=True`."""
+        ...
+    @property
+    def spans(self) -> list[Span]:
+        """Math segments in input order."""
+        ...
+
+class ConversionInfo:
+    """Result of a conversion together with metadata about the emitted Typst."""
+
+    @property
+    def typst(self) -> str:
+        """Converted Typst string."""
+        ...
+    @property
+    def symbols(self) -> list[str]:
+        """Distinct symbol and function names in the output, in order of first use."""
+        ...
+    @property
+    def required_packages(self) -> list[str]:
+        """Typst packages that must be imported for the output to compile."""
+        ...
+    @property
+    def labels(self) -> list[tuple[str, str]]:
+        """`(tex_name, typst_label)` of each `\\label` removed with `labels=True`."""
+        ...
+
+    @property
+    def tag(self) -> str | None:
+        """Typst markup of the `\\tag{...}` removed with `tags=True`, e.g. `(3a)`."""
+        ...
+
+def tex2typst_with_info(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> ConversionInfo:
+    """
+    Convert LaTeX/TeX math to Typst and report symbols and required packages.
+
+    Examples:
+        >>> import tex2typst
+        >>> info = tex2typst.tex2typst_with_info(r"\\alpha + \\beta")
+        >>> info.symbols
+        ['alpha', 'beta']
+    """
+    ...
+
+def tex2typst_batch_with_info(
+    tex: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    max_input_length: int | None = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> list[ConversionInfo | None]:
+    """
+    Batch variant of tex2typst_with_info.
+
+    With `skip_empty=True`, empty strings yield `None`, or the info for
+    `empty_replacement` when one is given.
+    """
+    ...
+
+def tex2typst_batch_mixed(tex: list[str], **kwargs: Any) -> list[str | Exception | None]:
+    """
+    Convert a list like `tex2typst`, returning each failed item's exception in
+    its place instead of raising; `skip_empty` items hold `empty_replacement`.
+    """
+    ...
+
+def tex2typst_batch_progress(
+    tex: list[str], progress_bar: Any | None = None, **kwargs: Any
+) -> list[str | None] | tuple[Any, ...] | BatchResult:
+    """
+    Convert a list like `tex2typst`, calling `progress_bar.update(n)` (e.g. on a
+    `tqdm` bar) after every `chunk_size` items, 100 by default.
+    """
+    ...
+
+def tex2typst_aligned(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> str:
+    """
+    Convert an aligned multi-line equation such as `a &= b \\\\ &= c`.
+
+    Rows are split on `\\\\` and cells on `&` outside braces and nested environments;
+    each cell is converted on its own and the rows are joined into `&`-aligned Typst
+    math, one per line. An enclosing `align`, `aligned`, `split` or `eqnarray`
+    environment is unwrapped first.
+    """
+    ...
+
+def explain_conversion(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> str:
+    """
+    Convert LaTeX/TeX math to Typst annotated with `/* ... */` comments.
+
+    Bundles without annotation support yield the plain conversion after a
+    `/* input: ... */` comment holding the original TeX.
+    """
+    ...
+
+def roundtrip_check(tex: str, *, max_input_length: int | None = None) -> bool:
+    """
+    Check that TeX survives a TeX -> Typst -> TeX round trip, ignoring insignificant
+    whitespace; conversion errors return False.
+    """
+    ...
+
+def roundtrip_diff(tex: str, *, max_input_length: int | None = None) -> dict[str, Any]:
+    """
+    Report a TeX -> Typst -> TeX round trip as a dict with "input", "intermediate",
+    "output", "equivalent", "diff" and "error".
+    """
+    ...
+
+def convert_refs(text: str, labels: dict[str, str] | None = None) -> str:
+    """
+    Rewrite `\\ref`, `\\eqref` and `\\autoref` in LaTeX prose to Typst `@label`
+    references, using `labels` (e.g. `dict(info.labels)`) where given.
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.convert_refs(r"see \\eqref{eq:euler}")
+        'see @eq:euler'
+    """
+    ...
+
+def strip_original_comments(text: str) -> str:
+    """
+    Remove the `// tex: ...` and `/* tex: ... */` comments added by
+    `keep_original="comment"`, leaving other comments alone.
+    """
+    ...
+
+@overload
+def convert_typst_document(
+    text: str,
+    *,
+    delimiters: Literal["latex", "dollars"] = "latex",
+    errors: Literal["raise", "keep"] = "raise",
+    report: Literal[False] = False,
+    dry_run: Literal[False] = False,
+    custom_typst_macros: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str:
+    """
+    Convert the `$...$` math of a Typst document to LaTeX with `typst2tex`,
+    leaving the markup around it; dollars in raw text, strings, comments and code
+    are skipped and `math.equation(...)` calls reported with a `UserWarning`.
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.convert_typst_document("Let $x^2$ be")
+        'Let \\\\(x^2\\\\) be'
+    """
+    ...
+
+@overload
+def convert_typst_document(
+    text: str,
+    *,
+    delimiters: Literal["latex", "dollars"] = "latex",
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    custom_typst_macros: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> DocumentReport:
+    """
+    Convert the math of a Typst document and return a `DocumentReport` with a
+    `Span` for every math segment; `dry_run=True` leaves out the output.
+    """
+    ...
+
+@overload
+def convert_latex_document(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: Literal[False] = False,
+    dry_run: Literal[False] = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str:
+    """
+    Convert the math of a LaTeX document to Typst with `tex2typst`, leaving the
+    markup around it: `$...$`, `\\(...\\)`, `$$...$$`, `\\[...\\]` and math
+    environments such as `align`; comments, `\\verb` and verbatim environments
+    are skipped.
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.convert_latex_document(r"Let \\(x^2\\) be")
+        'Let $x^2$ be'
+    """
+    ...
+
+@overload
+def convert_latex_document(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> DocumentReport:
+    """
+    Convert the math of a LaTeX document and return a `DocumentReport` with a
+    `Span` for every math segment; `dry_run=True` leaves out the output.
+    """
+    ...
+
+@overload
+def convert_markdown(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: Literal[False] = False,
+    dry_run: Literal[False] = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str:
+    """
+    Convert the `$...$` and `$$...$$` math of a Markdown document to Typst with
+    `tex2typst`, leaving the markup around it; inline math follows Pandoc's rules
+    and code spans and fenced code blocks are skipped.
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.convert_markdown(r"Let $\\alpha$ be")
+        'Let $alpha$ be'
+    """
+    ...
+
+@overload
+def convert_markdown(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> DocumentReport:
+    """
+    Convert the math of a Markdown document and return a `DocumentReport` with a
+    `Span` for every math segment; `dry_run=True` leaves out the output.
+    """
+    ...
+
+def num_to_typst(n: float) -> str:
+    """
+    Format a number as Typst math, e.g. `1.5e-10` as `1.5 times 10^(-10)`;
+    `inf` becomes `oo` and `nan` the string `"NaN"`.
+    """
+    ...
+
+def format_typst_math(typst: str) -> str:
+    """
+    Canonicalize the spacing of Typst math: collapsed whitespace, single spaces
+    around relations, none inside brackets or around `^`, `_` and `/`, and no
+    redundant `lr(...)`. Idempotent.
+    """
+    ...
+
+def typst_equivalent(a: str, b: str, *, numbers_by_value: bool = True) -> bool:
+    """
+    Whether two Typst math strings have the same canonical token stream; strings
+    compare exactly, numbers by value unless `numbers_by_value=False`.
+    """
+    ...
+
+def typst_equivalent_batch(
+    list_a: list[str], list_b: list[str], *, numbers_by_value: bool = True
+) -> list[bool]:
+    """`typst_equivalent` for each pair; raises `ValueError` on a length mismatch."""
+    ...
+
+def diff_typst(a: str, b: str, *, numbers_by_value: bool = True) -> str | None:
+    """
+    Word-diff of the canonical token streams (`[-only in a-]{+only in b+}`), or
+    `None` if the strings are equivalent.
+    """
+    ...
+
+def normalize_tex(tex: str, *, expand_macros: dict[str, str] | None = None) -> str:
+    """
+    Canonical form of TeX math for hashing: comments, insignificant whitespace
+    and redundant braces removed, `expand_macros` (keyed like
+    `custom_tex_macros`) expanded. The result converts like the input with
+    `strip_comments=True`.
+    """
+    ...
+
+def num_to_tex(n: float) -> str:
+    """
+    Format a number as TeX math, e.g. `1.5e-10` as `1.5 \\times 10^{-10}`;
+    `inf` becomes `\\infty` and `nan` `\\mathrm{NaN}`.
+    """
+    ...
+
+class Converter:
+    """
+    Converter with its own JS engine, default options and LRU result cache.
+
+    Keyword options given to the constructor are defaults for every call and may
+    be overridden per call. Usable only from the thread that created it. Use as a
+    context manager or call `close()` to drop the JS engine deterministically.
+
+    Examples:
+        >>> conv = Converter(cache_size=64, frac_to_slash=False)
+        >>> conv.tex2typst(r"\\frac{1}{2}")
+        'frac(1, 2)'
+    """
+
+    def __init__(
+        self,
+        config: dict[str, Any] | None = None,
+        *,
+        cache_size: int = 128,
+        **options: Any,
+    ) -> None: ...
+    @classmethod
+    def from_js_file(
+        cls,
+        js_path: str | os.PathLike[str],
+        config: dict[str, Any] | None = None,
+        *,
+        cache_size: int = 128,
+        **options: Any,
+    ) -> Converter:
+        """
+        Create a converter running the bundle file at `js_path`, e.g. a development
+        build, instead of the active bundle; pickled copies load the file again.
+        """
+        ...
+    def tex2typst(self, tex: str, **options: Any) -> str:
+        """Convert LaTeX/TeX to Typst; accepts the same options as `tex2typst`."""
+        ...
+    def typst2tex(self, typst: str, **options: Any) -> str:
+        """Convert Typst to LaTeX/TeX; accepts the same options as `typst2tex`."""
+        ...
+    def tex2typst_batch_generator(
+        self, items: Iterable[str], **options: Any
+    ) -> Iterator[str]:
+        """
+        Convert `items` lazily, one per `next()`, with the options of `tex2typst`.
+
+        Results go through the cache; a failed item raises `ValueError` naming its
+        position and ends the iteration.
+        """
+        ...
+    def cache_info(self) -> dict[str, int]:
+        """Return `hits`, `misses`, `size` and `capacity` of the result cache."""
+        ...
+    def reload_js_bundle(self, new_js_path: str | os.PathLike[str] | None = None) -> None:
+        """
+        Evaluate the bundle at `new_js_path`, or the embedded one, again in this
+        converter's JS context and clear its result cache.
+        """
+        ...
+    def close(self) -> None:
+        """Drop the JS runtime; later calls raise `RuntimeError`. Idempotent."""
+        ...
+    @property
+    def closed(self) -> bool:
+        """Whether `close()` has been called."""
+        ...
+    def __repr__(self) -> str:
+        """E.g. `<Converter js_version="0.5.6" cache_hits=42 cache_misses=7>`."""
+        ...
+    def __reduce__(self) -> tuple[type[Converter], tuple[dict[str, Any]]]:
+        """Pickle the construction options only; the copy gets a fresh JS engine."""
+        ...
+    def __enter__(self) -> Converter: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class WorkerConverter:
+    """
+    Converter backed by one dedicated worker thread that owns the JS engine.
+
+    All Python threads share the same warm engine; calls are queued to the worker
+    and block with the GIL released until the result arrives. Use as a context
+    manager or call `close()` when done; later calls raise `RuntimeError`.
+
+    Examples:
+        >>> with WorkerConverter() as conv:
+        ...     conv.convert(r"\\alpha")
+        'alpha'
+    """
+
+    def __init__(self) -> None: ...
+    def convert(self, tex: str, **options: Any) -> str:
+        """Convert a LaTeX/TeX string; accepts the same options as `tex2typst`."""
+        ...
+    def convert_many(self, tex_list: list[str], **options: Any) -> list[str]:
+        """Convert a list of strings as a single job on the worker thread."""
+        ...
+    def close(self) -> None:
+        """Stop the worker after queued jobs finish and join its thread."""
+        ...
+    @property
+    def closed(self) -> bool:
+        """Whether `close()` has been called."""
+        ...
+    def __enter__(self) -> WorkerConverter: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+def parse_preamble(text: str) -> dict[str, Any]:
+    """
+    Harvest `\\newcommand`, `\\renewcommand`, `\\providecommand` and `\\def`
+    definitions from a LaTeX preamble.
+
+    Returns a dict with `macros` (name to body), `num_args` (name to argument
+    count) and `warnings` listing unsupported declarations.
+    """
+    ...
+
+def load_macros_from_file(path: str | os.PathLike[str]) -> dict[str, str]:
+    """
+    Load macro names and TeX expansions from a `.json` or `.toml` file, for use
+    as `custom_tex_macros`.
+
+    Raises `FileNotFoundError` for a missing file, `ValueError` for an
+    unsupported extension or malformed content and `KeyError` for non-string
+    expansions.
+    """
+    ...
+
+def save_macros_to_file(macros: dict[str, str], path: str | os.PathLike[str]) -> None:
+    """
+    Write macros to a `.json` or `.toml` file readable by `load_macros_from_file`.
+    """
+    ...
+
+def detect_format(s: str) -> Literal["tex", "typst", "ambiguous"]:
+    """
+    Guess whether a math string is written in TeX or Typst.
+
+    Returns "ambiguous" when the heuristics find no evidence or mixed evidence.
+    """
+    ...
+
+def convert_auto(
+    s: str,
+    target: Literal["typst", "tex"] = "typst",
+    *,
+    strict_detection: bool = False,
+    **options: Any,
+) -> str:
+    """
+    Convert to `target`, detecting the input format first.
+
+    Input already in the target format is returned unchanged. With
+    `strict_detection=True`, ambiguous input raises `ValueError` instead of
+    being converted as if it were in the other format.
+    """
+    ...
+
+def convert_jsonl(
+    input_path: str | os.PathLike[str],
+    output_path: str | os.PathLike[str],
+    *,
+    tex_field: str = "tex",
+    out_field: str = "typst",
+    errors: Literal["skip", "null", "raise"] = "skip",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> dict[str, Any]:
+    """
+    Stream a JSON Lines file, converting ``tex_field`` of each record into ``out_field``.
+
+    Unknown fields are preserved untouched and the GIL is released while the
+    file is processed. Returns a summary with ``converted`` and ``failed``
+    counts and the first error messages.
+    """
+    ...
+
+def tex2typst_file(
+    input_path: str | os.PathLike[str],
+    output_path: str | os.PathLike[str],
+    *,
+    errors: Literal["keep", "empty", "raise"] = "keep",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> int:
+    """
+    Convert a file with one TeX formula per line into a file of Typst lines.
+
+    Blank lines stay blank so the line numbers match. Failed lines are copied
+    (``"keep"``), blanked (``"empty"``) or raise ``ValueError`` (``"raise"``).
+    Returns the number of lines converted successfully.
+    """
+    ...
+
+def tex2typst_iter(
+    iterable: Iterable[str],
+    *,
+    chunk_size: int = 1024,
+    errors: Literal["raise", "keep", "null"] = "raise",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+) -> Iterator[str | None]:
+    """
+    Lazily convert an iterable of TeX strings to Typst, `chunk_size` items at a time.
+
+    Failed items raise ``ValueError`` after the items before them were yielded
+    (``"raise"``), are yielded unchanged (``"keep"``) or as ``None`` (``"null"``).
+    """
+    ...
+
+def tex2typst_series(
+    s: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    max_input_length: int | None = None,
+    dedupe: bool = False,
+) -> Any:
+    """
+    Convert a pandas Series of LaTeX/TeX strings to a Series of Typst strings.
+
+    The result keeps the index and name of the input. Only available when the
+    extension is built with the ``pandas`` feature. With `skip_empty=True`,
+    empty strings become `empty_replacement`. `dedupe=True` converts each
+    distinct value once.
+    """
+    ...
+
+def tex2typst_arrow(
+    array: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    keep_original_on_error: bool = False,
+) -> Any:
+    """
+    Convert a pyarrow string Array or ChunkedArray of LaTeX/TeX to Typst.
+
+    Values are read in place through the Arrow C data interface; nulls stay null.
+    Only available when the extension is built with the ``arrow`` feature.
+    """
+    ...
+
+def convert_series(
+    obj: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    keep_original_on_error: bool = False,
+) -> Any:
+    """
+    Convert a pandas or polars Series, or an iterable of strings, to Typst.
+
+    Missing values stay ``None``. Returns a list, or a pyarrow array for input
+    exporting ``__arrow_c_stream__`` when built with the ``arrow`` feature.
+    """
+    ...
+
+def parse_tex(
+    tex: str, *, custom_tex_macros: dict[str, str] | None = None
+) -> dict[str, Any]:
+    """
+    Parse LaTeX/TeX math and return the intermediate AST.
+
+    The node schema is passed through as-is from the upstream library and is
+    unstable across bundle upgrades.
+
+    Examples:
+        >>> import tex2typst
+        >>> node = tex2typst.parse_tex(r"\\frac{a}{b}")
+        >>> len(node["args"])
+        2
+    """
+    ...
+
+def parse_typst(typst: str) -> dict[str, Any]:
+    """
+    Parse Typst math and return the intermediate AST.
+
+    The node schema is passed through as-is from the upstream library and is
+    unstable across bundle upgrades.
+    """
+    ...
+
+def tex2typst_ast(tex: str) -> dict[str, Any]:
+    """
+    Convert LaTeX/TeX math and return the Typst tree from the bundle's global
+    `tex2typstAST` function; raises `NotImplementedError` if it is not defined,
+    as in the bundled build.
+    """
+    ...
+
+def typst2tex_ast(typst: str) -> dict[str, Any]:
+    """
+    Convert Typst math and return the TeX tree from the bundle's global
+    `typst2texAST` function; raises `NotImplementedError` if it is not defined,
+    as in the bundled build.
+    """
+    ...
+
+def get_js_bundle_version() -> str:
+    """Version of the upstream tex2typst JS library in this build, or "unknown"."""
+    ...
+
+def list_global_functions() -> list[str]:
+    """
+    Sorted names of the global functions the JS bundle and registered extensions
+    define in this thread's engine; built-ins are left out.
+    """
+    ...
+
+def get_converter_info() -> dict[str, Any]:
+    """
+    Report component versions and the state of this thread's converter.
+
+    Does not start the JavaScript engine if this thread has not used it yet.
+
+    Returns:
+        dict with "rust_crate_version", "js_bundle_version", "quickjs_version",
+        "rquickjs_version", "thread_converter_initialized" and
+        "thread_conversion_count"
+    """
+    ...
+
+class VersionInfo(NamedTuple):
+    """Versions of the components of this build."""
+
+    rust: str
+    js_bundle: str
+    quickjs: str
+    rquickjs: str
+
+def version_info() -> VersionInfo:
+    """
+    Report the versions of the Rust crate, the JavaScript bundle and QuickJS
+    without starting the JavaScript engine.
+    """
+    ...
+
+def check_installation(*, raise_on_failure: bool = False) -> dict[str, Any]:
+    """
+    Self-test this installation: start the converter and run known conversions
+    in both directions.
+
+    Returns:
+        dict with "ok", "module_path", "versions" and "checks" (dicts with
+        "name", "ok" and "error"); with `raise_on_failure`, a failed check
+        raises `EngineError` instead
+    """
+    ...
+
+def get_stats() -> dict[str, Any]:
+    """
+    Report cumulative conversion statistics of all threads since import or
+    `reset_stats()`.
+
+    Returns:
+        dict with "tex2typst_conversions", "typst2tex_conversions", "calls",
+        "total_time_seconds", "max_call_time_seconds", "engines_initialized",
+        "js_init_time_seconds", "cache_hits", "cache_misses" and
+        "js_memory_used_bytes" (None if this thread's engine has not started)
+    """
+    ...
+
+def reset_stats() -> None:
+    """Zero the counters reported by `get_stats()`."""
+    ...
+
+def set_js_memory_limit(limit_bytes: int | None) -> None:
+    """
+    Cap the bytes each JS engine may allocate beyond what loading the bundle took;
+    a conversion exceeding it raises `MemoryError` and the engine is replaced.
+    """
+    ...
+
+def get_js_memory_limit() -> int | None:
+    """Return the limit set by `set_js_memory_limit()`, None if there is none."""
+    ...
+
+def set_js_gc_threshold(threshold_bytes: int | None) -> None:
+    """
+    Collect garbage automatically once each JS engine has allocated
+    `threshold_bytes`; None turns automatic collection off.
+    """
+    ...
+
+def get_js_gc_threshold() -> int | None:
+    """Return the threshold set by `set_js_gc_threshold()`, None if automatic collection is off."""
+    ...
+
+def set_js_gc_interval(every_n_items: int) -> None:
+    """Collect garbage every `every_n_items` batch items; 0 never does."""
+    ...
+
+def get_js_gc_interval() -> int:
+    """Return the interval set by `set_js_gc_interval()`."""
+    ...
+
+def run_gc() -> None:
+    """Collect the garbage of the calling thread's JS engine now."""
+    ...
+
+def memory_usage() -> dict[str, int] | None:
+    """
+    Return allocation statistics of the calling thread's JS engine, None if it
+    has not converted anything yet.
+    """
+    ...
+
+def warmup() -> float:
+    """
+    Start the calling thread's JS engine now rather than on its first conversion.
+
+    Returns:
+        Seconds spent starting it, 0.0 if it was already running
+    """
+    ...
+
+def warmup_background() -> Future[float]:
+    """
+    Start a JS engine on a new daemon thread without waiting for it, taking the
+    process-wide setup off the first request.
+
+    Returns:
+        Future resolving to the seconds spent starting the engine
+    """
+    ...
+
+eager_init: bool
+"""Whether importing the module ran `warmup()`, set by `TEX2TYPST_EAGER_INIT=1`"""
+
+def get_pending_warnings() -> list[str]:
+    """
+    Messages the JS engines on this thread passed to `console` methods since the
+    last `clear_warnings()`, oldest first. Only the most recent 1000 are kept.
+    """
+    ...
+
+def clear_warnings() -> None:
+    """Forget the messages reported by `get_pending_warnings()`."""
+    ...
+
+def list_supported_commands() -> list[str]:
+    """
+    Sorted TeX commands (e.g. `\\frac`) the bundle and JS extensions recognize;
+    collected once per bundle or extension change.
+    """
+    ...
+
+def find_unsupported_commands(
+    tex: str, *, custom_tex_macros: dict[str, str] | None = None
+) -> list[str]:
+    """
+    Commands in `tex` the converter does not recognize, in order of first use,
+    skipping `custom_tex_macros`; matches `report_unknown` without converting.
+    """
+    ...
+
+def set_converter_init_hook(callback: Callable[[], None]) -> None:
+    """
+    Call `callback` with no arguments each time the calling thread's converter
+    starts: on its first conversion and after a bundle change, extension reset or
+    fork. Per thread; replaces any earlier hook. Raises `TypeError` if `callback`
+    is not callable.
+    """
+    ...
+
+def clear_converter_init_hook() -> None:
+    """Remove the calling thread's converter init hook."""
+    ...
+
+def register_js_extension(source: str) -> None:
+    """
+    Evaluate JavaScript after the converter bundle in every new JS engine, in
+    registration order; the calling thread's converter restarts immediately and
+    those of other threads on their next call. Raises `EngineError` with the JS
+    stack trace if the source fails.
+    """
+    ...
+
+def clear_js_extensions() -> None:
+    """Remove all JS extensions; module-level converters restart without them."""
+    ...
+
+def load_bundle(path_or_source: str | os.PathLike[str], *, is_path: bool = True) -> None:
+    """
+    Use another tex2typst.js bundle instead of the embedded one for all converters
+    started from now on; raises `EngineError` if it does not define the global
+    `tex2typst` and `typst2tex` functions.
+    """
+    ...
+
+def tex2typst_from_js_file(js_path: str | os.PathLike[str], **kwargs: Any) -> Converter:
+    """
+    Create a `Converter` running the bundle file at `js_path` in a fresh JS
+    context, leaving the module-level functions on their bundle.
+    """
+    ...
+
+def reset_bundle() -> None:
+    """Go back to the embedded JS bundle."""
+    ...
+
+def bundle_sha256() -> str:
+    """Hex SHA-256 of the active JS bundle."""
+    ...
+
+def clear_cache() -> None:
+    """Clear all cached conversion results."""
+    ...
+
+def cache_info() -> dict[str, object]:
+    """Get cache statistics for tex2typst and typst2tex."""
+    ...
+
+def enable_disk_cache(path: str | os.PathLike[str], max_size_mb: float = 1024) -> None:
+    """
+    Cache conversion results in `path` across runs and processes, keyed by the
+    SHA-256 of input, options and JS bundle. New entries stop being written once
+    the cache reaches `max_size_mb`.
+    """
+    ...
+
+def disable_disk_cache() -> None:
+    """Flush pending entries and stop using the disk cache."""
+    ...
+
+def disk_cache_stats() -> dict[str, Any]:
+    """Return `enabled`, `path`, `entries`, `size_bytes`, `max_size_bytes`, `hits` and `misses`."""
+    ...
+
+def set_default_options(**kwargs: Any) -> None:
+    """
+    Set process-wide default options merged beneath explicit per-call kwargs.
+
+    Examples:
+        >>> import tex2typst
+        >>> tex2typst.set_default_options(infty_to_oo=True)
+        >>> tex2typst.tex2typst(r"\\infty")
+        'oo'
+        >>> tex2typst.tex2typst(r"\\infty", infty_to_oo=False)
+        'infinity'
+    """
+    ...
+
+def get_default_options() -> dict[str, Any]:
+    """Get the process-wide default options."""
+    ...
+
+def get_supported_options() -> dict[str, dict[str, Any]]:
+    """List every keyword option with its type, default and description."""
+    ...
+
+def reset_default_options() -> None:
+    """Remove all process-wide default options."""
+    ...
+
+def merge_options(*option_dicts: dict[str, Any]) -> dict[str, Any]:
+    """
+    Merge option dicts left to right (later dicts win) after validating every
+    name and value; macro dicts are merged key by key and `None` removes an option.
+    """
+    ...
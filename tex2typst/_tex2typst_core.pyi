@@ -0,0 +1,656 @@
+"""Type stubs for the native extension module behind the tex2typst package.
+
+The public API with its documentation lives in `tex2typst/__init__.pyi`; these
+stubs describe the functions and classes the package wraps.
+"""
+
+import os
+from types import TracebackType
+from typing import Any, Callable, Iterable, Iterator, Literal
+
+__version__: str
+
+class TexParseError(ValueError):
+    """Raised by `fail_on_unknown=True` when TeX input uses unrecognized commands."""
+
+class EngineError(RuntimeError):
+    """Raised when the JS engine cannot load the bundle or it lacks the converter functions."""
+
+class BatchResult:
+    @property
+    def results(self) -> list[str | None]: ...
+    @property
+    def errors(self) -> list[tuple[int, str]]: ...
+    @property
+    def elapsed(self) -> float: ...
+    @property
+    def items_per_second(self) -> float: ...
+    @property
+    def engine_inits(self) -> int: ...
+    def __len__(self) -> int: ...
+
+class Span:
+    @property
+    def start(self) -> int: ...
+    @property
+    def end(self) -> int: ...
+    @property
+    def kind(self) -> str: ...
+    @property
+    def original(self) -> str: ...
+    @property
+    def converted(self) -> str | None: ...
+    @property
+    def error(self) -> str | None: ...
+
+class DocumentReport:
+    @property
+    def output(self) -> str | None: ...
+    @property
+    def spans(self) -> list[Span]: ...
+
+class ConversionInfo:
+    @property
+    def typst(self) -> str: ...
+    @property
+    def symbols(self) -> list[str]: ...
+    @property
+    def required_packages(self) -> list[str]: ...
+    @property
+    def labels(self) -> list[tuple[str, str]]: ...
+    @property
+    def tag(self) -> str | None: ...
+
+class Converter:
+    def __init__(
+        self,
+        config: dict[str, Any] | None = None,
+        *,
+        cache_size: int | None = None,
+        **options: Any,
+    ) -> None: ...
+    @classmethod
+    def from_js_file(
+        cls,
+        js_path: str | os.PathLike[str],
+        config: dict[str, Any] | None = None,
+        *,
+        cache_size: int | None = None,
+        **options: Any,
+    ) -> Converter: ...
+    def tex2typst(self, tex: str, **options: Any) -> str: ...
+    def typst2tex(self, typst: str, **options: Any) -> str: ...
+    def tex2typst_batch_generator(
+        self, items: Iterable[str], **options: Any
+    ) -> Iterator[str]: ...
+    def cache_info(self) -> dict[str, int]: ...
+    def reload_js_bundle(self, new_js_path: str | os.PathLike[str] | None = None) -> None: ...
+    def close(self) -> None: ...
+    @property
+    def closed(self) -> bool: ...
+    def __reduce__(self) -> tuple[type[Converter], tuple[dict[str, Any]]]: ...
+    def __enter__(self) -> Converter: ...
+    def __exit__(
+        self,
+        _exc_type: type[BaseException] | None = None,
+        _exc_value: BaseException | None = None,
+        _traceback: TracebackType | None = None,
+    ) -> bool: ...
+
+class WorkerConverter:
+    def __init__(self) -> None: ...
+    def convert(self, tex: str, **options: Any) -> str: ...
+    def convert_many(self, tex_list: list[str], **options: Any) -> list[str]: ...
+    def close(self) -> None: ...
+    @property
+    def closed(self) -> bool: ...
+    def __enter__(self) -> WorkerConverter: ...
+    def __exit__(
+        self,
+        _exc_type: type[BaseException] | None = None,
+        _exc_value: BaseException | None = None,
+        _traceback: TracebackType | None = None,
+    ) -> bool: ...
+
+def bundle_sha256() -> str: ...
+
+def check_installation(*, raise_on_failure: bool = False) -> dict[str, Any]: ...
+
+def clear_converter_init_hook() -> None: ...
+
+def clear_js_extensions() -> None: ...
+
+def clear_warnings() -> None: ...
+
+def convert_auto(
+    s: str,
+    target: Literal["typst", "tex"] = "typst",
+    *,
+    strict_detection: bool = False,
+    **options: Any,
+) -> str: ...
+
+def convert_jsonl(
+    input_path: str | os.PathLike[str],
+    output_path: str | os.PathLike[str],
+    *,
+    tex_field: str = "tex",
+    out_field: str = "typst",
+    errors: Literal["skip", "null", "raise"] = "skip",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> dict[str, Any]: ...
+
+def convert_refs(text: str, labels: dict[str, str] | None = None) -> str: ...
+def strip_original_comments(text: str) -> str: ...
+def convert_typst_document(
+    text: str,
+    *,
+    delimiters: Literal["latex", "dollars"] = "latex",
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    custom_typst_macros: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str | DocumentReport: ...
+def convert_latex_document(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str | DocumentReport: ...
+def convert_markdown(
+    text: str,
+    *,
+    errors: Literal["raise", "keep"] = "raise",
+    report: bool = False,
+    dry_run: bool = False,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    js_options: dict[str, Any] | None = None,
+) -> str | DocumentReport: ...
+
+def convert_series(
+    obj: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    keep_original_on_error: bool = False,
+) -> Any: ...
+
+def detect_format(s: str) -> Literal["tex", "typst", "ambiguous"]: ...
+
+def diff_typst(a: str, b: str, *, numbers_by_value: bool = True) -> str | None: ...
+
+def disable_disk_cache() -> None: ...
+
+def disk_cache_stats() -> dict[str, Any]: ...
+
+def enable_disk_cache(
+    path: str | os.PathLike[str],
+    max_size_mb: float = 1024,
+) -> None: ...
+
+def explain_conversion(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> str: ...
+
+def find_unsupported_commands(
+    tex: str,
+    *,
+    custom_tex_macros: dict[str, str] | None = None,
+) -> list[str]: ...
+
+def format_typst_math(typst: str) -> str: ...
+
+def get_converter_info() -> dict[str, Any]: ...
+
+def get_default_options() -> dict[str, Any]: ...
+
+def get_js_bundle_version() -> str: ...
+def list_global_functions() -> list[str]: ...
+
+def get_js_gc_interval() -> int: ...
+
+def get_js_gc_threshold() -> int | None: ...
+
+def get_js_memory_limit() -> int | None: ...
+
+def get_pending_warnings() -> list[str]: ...
+
+def get_stats() -> dict[str, Any]: ...
+
+def get_supported_options() -> dict[str, dict[str, Any]]: ...
+
+def list_supported_commands() -> list[str]: ...
+
+def load_bundle(
+    path_or_source: str | os.PathLike[str],
+    *,
+    is_path: bool = True,
+) -> None: ...
+
+def load_macros_from_file(path: str | os.PathLike[str]) -> dict[str, str]: ...
+
+def memory_usage() -> dict[str, int] | None: ...
+
+def merge_options(*option_dicts: dict[str, Any]) -> dict[str, Any]: ...
+
+def normalize_tex(tex: str, *, expand_macros: dict[str, str] | None = None) -> str: ...
+
+def num_to_tex(n: float) -> str: ...
+
+def num_to_typst(n: float) -> str: ...
+
+def parse_preamble(text: str) -> dict[str, Any]: ...
+
+def parse_tex(
+    tex: str,
+    *,
+    custom_tex_macros: dict[str, str] | None = None,
+) -> dict[str, Any]: ...
+
+def parse_typst(typst: str) -> dict[str, Any]: ...
+
+def register_js_extension(source: str) -> None: ...
+
+def reset_bundle() -> None: ...
+
+def reset_default_options() -> None: ...
+
+def reset_stats() -> None: ...
+
+def roundtrip_check(tex: str, *, max_input_length: int | None = None) -> bool: ...
+
+def roundtrip_diff(
+    tex: str,
+    *,
+    max_input_length: int | None = None,
+) -> dict[str, Any]: ...
+
+def run_gc() -> None: ...
+
+def save_macros_to_file(
+    macros: dict[str, str],
+    path: str | os.PathLike[str],
+) -> None: ...
+
+def set_converter_init_hook(callback: Callable[[], None]) -> None: ...
+
+def set_default_options(**kwargs: Any) -> None: ...
+
+def set_js_gc_interval(every_n_items: int) -> None: ...
+
+def set_js_gc_threshold(threshold_bytes: int | None) -> None: ...
+
+def set_js_memory_limit(limit_bytes: int | None) -> None: ...
+
+def tex2typst(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    timing: bool = False,
+    keep_original: Literal["comment"] | None = None,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> str | tuple[Any, ...]: ...
+
+def tex2typst_aligned(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> str: ...
+
+# Only defined when the extension is built with the `arrow` feature
+def tex2typst_arrow(
+    array: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    keep_original_on_error: bool = False,
+) -> Any: ...
+
+def tex2typst_ast(tex: str) -> dict[str, Any]: ...
+
+def tex2typst_batch(
+    tex_list: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    keep_original_on_error: bool = False,
+    return_failed_indices: bool = False,
+    return_summary: bool = False,
+    return_exceptions: bool = False,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    trim_input: bool = False,
+    report_unknown: bool = False,
+    fail_on_unknown: bool = False,
+    preamble: str | None = None,
+    max_input_length: int | None = None,
+    wrap: Literal["inline", "block", "none", "auto"] | None = None,
+    auto_strip_delimiters: bool = False,
+    chem: bool = False,
+    handle_mhchem: bool = False,
+    siunitx: bool = False,
+    handle_siunitx: bool = False,
+    units_style: Literal["plain", "unify", "metro"] | None = None,
+    labels: bool = False,
+    tags: bool = False,
+    normalize_unicode: bool = False,
+    unicode_report: bool = False,
+    strip_comments: bool = False,
+    collapse_redundant_braces: bool = False,
+    normalize_output: bool = False,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    progress_callback: Callable[[int, int], object] | None = None,
+    callback_every_n: int = 100,
+    progress_bar: Any | None = None,
+    chunk_size: int = 0,
+    dedupe: bool = False,
+    atomic: bool = False,
+    max_nesting_depth: int | None = 512,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_batch", "per_item"] | None = None,
+) -> (
+    list[str] | list[str | None] | list[str | Exception | None] | tuple[Any, ...] | BatchResult
+): ...
+
+def tex2typst_batch_with_info(
+    tex_list: list[str],
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    max_input_length: int | None = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> list[ConversionInfo | None]: ...
+
+def tex2typst_file(
+    input_path: str | os.PathLike[str],
+    output_path: str | os.PathLike[str],
+    *,
+    errors: Literal["keep", "empty", "raise"] = "keep",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+) -> int: ...
+
+def tex2typst_iter(
+    iterable: Iterable[str],
+    *,
+    chunk_size: int = 1024,
+    errors: Literal["raise", "keep", "null"] = "raise",
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+) -> Iterator[str | None]: ...
+
+# Only defined when the extension is built with the `pandas` feature
+def tex2typst_series(
+    s: Any,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    max_input_length: int | None = None,
+    dedupe: bool = False,
+) -> Any: ...
+
+def tex2typst_with_info(
+    tex: str,
+    *,
+    non_strict: bool | None = None,
+    prefer_shorthands: bool | None = None,
+    keep_spaces: bool | None = None,
+    frac_to_slash: bool | None = None,
+    infty_to_oo: bool | None = None,
+    optimize: bool | None = None,
+    ignore_unknown_commands: bool | None = None,
+    handle_physics: bool | None = None,
+    output_unicode: bool | None = None,
+    handle_text_mode: Literal["verbatim", "convert", "strip"] | None = None,
+    custom_tex_macros: dict[str, str] | None = None,
+    replace_unknown_commands: dict[str, str | None] | None = None,
+    symbol_map: dict[str, str] | None = None,
+    environment_map: dict[str, str] | None = None,
+    max_input_length: int | None = None,
+    labels: bool = False,
+    tags: bool = False,
+) -> ConversionInfo: ...
+
+def typst2tex(
+    typst: str,
+    *,
+    block_math_mode: bool | None = None,
+    custom_typst_macros: dict[str, str] | None = None,
+    trim_input: bool = False,
+    max_input_length: int | None = None,
+    delimiters: Literal["dollars", "latex", "none"] | None = None,
+    trailing_newline: bool = False,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool = False,
+) -> str: ...
+
+def typst2tex_ast(typst: str) -> dict[str, Any]: ...
+
+def typst2tex_batch(
+    typst_list: list[str],
+    *,
+    block_math_mode: bool | None = None,
+    custom_typst_macros: dict[str, str] | None = None,
+    skip_empty: bool = False,
+    empty_replacement: str | None = None,
+    trim_input: bool = False,
+    max_input_length: int | None = None,
+    delimiters: Literal["dollars", "latex", "none"] | None = None,
+    trailing_newline: bool = False,
+    pre_process: Callable[[str], str] | None = None,
+    post_process: Callable[[str], str] | None = None,
+    dedupe: bool = False,
+    return_summary: bool = False,
+    js_options: dict[str, Any] | None = None,
+    isolated: bool | Literal["per_batch", "per_item"] | None = None,
+) -> list[str] | list[str | None] | BatchResult: ...
+
+def typst_equivalent(a: str, b: str, *, numbers_by_value: bool = True) -> bool: ...
+
+def typst_equivalent_batch(
+    list_a: list[str],
+    list_b: list[str],
+    *,
+    numbers_by_value: bool = True,
+) -> list[bool]: ...
+
+def warmup() -> float: ...